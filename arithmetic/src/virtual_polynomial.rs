@@ -9,7 +9,7 @@
 
 use crate::{errors::ArithErrors, multilinear_polynomial::random_zero_mle_list, random_mle_list};
 use ark_ff::PrimeField;
-use ark_poly::{DenseMultilinearExtension, MultilinearExtension, Polynomial};
+use ark_poly::{DenseMultilinearExtension, Polynomial};
 use ark_serialize::CanonicalSerialize;
 use ark_std::{
     end_timer,
@@ -18,6 +18,7 @@ use ark_std::{
 };
 use rayon::prelude::*;
 use std::{cmp::max, collections::HashMap, marker::PhantomData, ops::Add, sync::Arc};
+use transcript::{Encode, Encoder};
 
 #[rustfmt::skip]
 /// A virtual polynomial is a sum of products of multilinear polynomials;
@@ -46,6 +47,7 @@ use std::{cmp::max, collections::HashMap, marker::PhantomData, ops::Add, sync::A
 ///     \]
 /// - raw_pointers_lookup_table maps fi to i
 ///
+#[allow(clippy::doc_overindented_list_items)]
 #[derive(Clone, Debug, Default, PartialEq)]
 pub struct VirtualPolynomial<F: PrimeField> {
     /// Aux information about the multilinear polynomial
@@ -71,6 +73,13 @@ pub struct VPAuxInfo<F: PrimeField> {
     pub phantom: PhantomData<F>,
 }
 
+impl<F: PrimeField> Encode for VPAuxInfo<F> {
+    fn encode<E: Encoder>(&self, encoder: &mut E) {
+        encoder.encode_u64(self.max_degree as u64);
+        encoder.encode_u64(self.num_variables as u64);
+    }
+}
+
 impl<F: PrimeField> Add for &VirtualPolynomial<F> {
     type Output = VirtualPolynomial<F>;
     fn add(self, other: &VirtualPolynomial<F>) -> Self::Output {
@@ -265,7 +274,7 @@ impl<F: PrimeField> VirtualPolynomial<F> {
                 rng.gen_range(num_multiplicands_range.0..num_multiplicands_range.1);
             let (product, product_sum) = random_mle_list(nv, num_multiplicands, rng);
             let coefficient = F::rand(rng);
-            poly.add_mle_list(product.into_iter(), coefficient)?;
+            poly.add_mle_list(product, coefficient)?;
             sum += product_sum * coefficient;
         }
 
@@ -287,7 +296,7 @@ impl<F: PrimeField> VirtualPolynomial<F> {
                 rng.gen_range(num_multiplicands_range.0..num_multiplicands_range.1);
             let product = random_zero_mle_list(nv, num_multiplicands, rng);
             let coefficient = F::rand(rng);
-            poly.add_mle_list(product.into_iter(), coefficient)?;
+            poly.add_mle_list(product, coefficient)?;
         }
 
         Ok(poly)
@@ -4,13 +4,13 @@
 // You should have received a copy of the MIT License
 // along with the HyperPlonk library. If not, see <https://mit-license.org/>.
 
-use std::{fs::File, io, time::Instant};
+use std::{fs::File, time::Instant};
 
 use ark_bls12_381::{Bls12_381, Fr};
-use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, Write};
+use ark_serialize::{Validate, Write};
 use ark_std::test_rng;
 use hyperplonk::{
-    prelude::{CustomizedGates, HyperPlonkErrors, MockCircuit},
+    prelude::{load_from_file, save_to_file, CustomizedGates, HyperPlonkErrors, MockCircuit},
     HyperPlonkSNARK,
 };
 use subroutines::{
@@ -56,14 +56,12 @@ fn main() -> Result<(), HyperPlonkErrors> {
     Ok(())
 }
 
-fn read_srs() -> Result<MultilinearUniversalParams<Bls12_381>, io::Error> {
-    let mut f = File::open("srs.params")?;
-    Ok(MultilinearUniversalParams::<Bls12_381>::deserialize_compressed_unchecked(&mut f).unwrap())
+fn read_srs() -> Result<MultilinearUniversalParams<Bls12_381>, HyperPlonkErrors> {
+    load_from_file("srs.params", Validate::No)
 }
 
 fn write_srs(pcs_srs: &MultilinearUniversalParams<Bls12_381>) {
-    let mut f = File::create("srs.params").unwrap();
-    pcs_srs.serialize_uncompressed(&mut f).unwrap();
+    save_to_file(pcs_srs, "srs.params").unwrap();
 }
 
 fn bench_vanilla_plonk(
@@ -130,30 +128,28 @@ fn bench_mock_circuit_zkp_helper(
     // generate pk and vks
     let start = Instant::now();
     for _ in 0..repetition {
-        let (_pk, _vk) = <PolyIOP<Fr> as HyperPlonkSNARK<
-            Bls12_381,
-            MultilinearKzgPCS<Bls12_381>,
-        >>::preprocess(&index, pcs_srs)?;
+        let (_pk, _vk) =
+            <PolyIOP<Fr> as HyperPlonkSNARK<Fr, MultilinearKzgPCS<Bls12_381>>>::preprocess(
+                &index, pcs_srs,
+            )?;
     }
     println!(
         "key extraction for {} variables: {} us",
         nv,
         start.elapsed().as_micros() / repetition as u128
     );
-    let (pk, vk) =
-        <PolyIOP<Fr> as HyperPlonkSNARK<Bls12_381, MultilinearKzgPCS<Bls12_381>>>::preprocess(
-            &index, pcs_srs,
-        )?;
+    let (pk, vk) = <PolyIOP<Fr> as HyperPlonkSNARK<Fr, MultilinearKzgPCS<Bls12_381>>>::preprocess(
+        &index, pcs_srs,
+    )?;
     //==========================================================
     // generate a proof
     let start = Instant::now();
     for _ in 0..repetition {
-        let _proof =
-            <PolyIOP<Fr> as HyperPlonkSNARK<Bls12_381, MultilinearKzgPCS<Bls12_381>>>::prove(
-                &pk,
-                &circuit.public_inputs,
-                &circuit.witnesses,
-            )?;
+        let _proof = <PolyIOP<Fr> as HyperPlonkSNARK<Fr, MultilinearKzgPCS<Bls12_381>>>::prove(
+            &pk,
+            &circuit.public_inputs,
+            &circuit.witnesses,
+        )?;
     }
     let t = start.elapsed().as_micros() / repetition as u128;
     println!(
@@ -163,7 +159,7 @@ fn bench_mock_circuit_zkp_helper(
     );
     file.write_all(format!("{} {}\n", nv, t).as_ref()).unwrap();
 
-    let proof = <PolyIOP<Fr> as HyperPlonkSNARK<Bls12_381, MultilinearKzgPCS<Bls12_381>>>::prove(
+    let proof = <PolyIOP<Fr> as HyperPlonkSNARK<Fr, MultilinearKzgPCS<Bls12_381>>>::prove(
         &pk,
         &circuit.public_inputs,
         &circuit.witnesses,
@@ -172,12 +168,11 @@ fn bench_mock_circuit_zkp_helper(
     // verify a proof
     let start = Instant::now();
     for _ in 0..repetition {
-        let verify =
-            <PolyIOP<Fr> as HyperPlonkSNARK<Bls12_381, MultilinearKzgPCS<Bls12_381>>>::verify(
-                &vk,
-                &circuit.public_inputs,
-                &proof,
-            )?;
+        let verify = <PolyIOP<Fr> as HyperPlonkSNARK<Fr, MultilinearKzgPCS<Bls12_381>>>::verify(
+            &vk,
+            &circuit.public_inputs,
+            &proof,
+        )?;
         assert!(verify);
     }
     println!(
@@ -0,0 +1,184 @@
+// Copyright (c) 2023 Espresso Systems (espressosys.com)
+// This file is part of the HyperPlonk library.
+
+// You should have received a copy of the MIT License
+// along with the HyperPlonk library. If not, see <https://mit-license.org/>.
+
+//! Blinding rows for HyperPlonk circuits.
+//!
+//! A circuit of `2^n` constraints is embedded in a hypercube of `n + 1`
+//! variables: the constraints are the lower half and the upper half holds
+//! blinding rows. On blinding rows
+//!   - the selectors are zero and the gate is multiplied by the active row
+//!     polynomial `1 - x_n`, so the gate does not constrain them;
+//!   - the rows are wired in pairs `(2^n + 2k, 2^n + 2k + 1)` in every column
+//!     and the prover fills each pair with a fresh random value.
+//!
+//! The witness polynomials thus carry `2^(n-1)` random values per column, and
+//! the wired pairs also randomize the fractional and product polynomials of
+//! the permutation check.
+//!
+//! Blinding rows alone are not zero-knowledge: the commitments and openings
+//! of a non-hiding PCS are deterministic functions of the polynomials, and
+//! the round messages of the zero check and of the permutation check are
+//! computed from the witness. Zero-knowledge keys of `preprocess_zk` thus
+//! combine the blinding rows with a hiding PCS and with committed random masks
+//! on both zero checks, see `prove_zk`.
+
+use crate::{
+    errors::HyperPlonkErrors,
+    permutation::field_to_index,
+    selectors::SelectorColumn,
+    structs::{HyperPlonkIndex, HyperPlonkParams},
+    witness::WitnessColumn,
+};
+use ark_ff::PrimeField;
+use ark_poly::DenseMultilinearExtension;
+use ark_std::rand::{CryptoRng, RngCore};
+use std::sync::Arc;
+
+/// Embed a circuit into the lower half of a hypercube with blinding rows.
+pub(crate) fn blind_index<F: PrimeField>(
+    index: &HyperPlonkIndex<F>,
+) -> Result<HyperPlonkIndex<F>, HyperPlonkErrors> {
+    if index.params.blinded {
+        return Err(HyperPlonkErrors::InvalidParameters(
+            "the index is already blinded".to_string(),
+        ));
+    }
+    // the multiplicities and fractional polynomials of the lookup checks are
+    // not blinded
+    if !index.params.lookups.is_empty() {
+        return Err(HyperPlonkErrors::InvalidParameters(
+            "blinding rows do not support lookup gates".to_string(),
+        ));
+    }
+    if !index.params.range_checks.is_empty() {
+        return Err(HyperPlonkErrors::InvalidParameters(
+            "blinding rows do not support range check gates".to_string(),
+        ));
+    }
+    let num_rows = index.params.num_constraints;
+    if num_rows < 2 {
+        return Err(HyperPlonkErrors::InvalidParameters(
+            "blinding rows require at least 2 constraints".to_string(),
+        ));
+    }
+    let num_cells = index.num_witness_columns() * num_rows;
+    if index.permutation.len() < num_cells {
+        return Err(HyperPlonkErrors::InvalidParameters(format!(
+            "permutation length {} is smaller than the number of cells {}",
+            index.permutation.len(),
+            num_cells
+        )));
+    }
+
+    // cell (col, row) moves from col * 2^n + row to col * 2^(n+1) + row
+    let mut permutation = Vec::with_capacity(2 * num_cells);
+    for col in 0..index.num_witness_columns() {
+        for sigma in index.permutation[col * num_rows..(col + 1) * num_rows].iter() {
+            let j = field_to_index(sigma)
+                .filter(|&j| j < num_cells)
+                .ok_or_else(|| {
+                    HyperPlonkErrors::InvalidParameters(
+                        "permutation maps a cell outside of the witness columns".to_string(),
+                    )
+                })?;
+            permutation.push(F::from((2 * (j - j % num_rows) + j % num_rows) as u64));
+        }
+        // blinding rows are swapped in pairs
+        let offset = col * 2 * num_rows + num_rows;
+        for row in 0..num_rows {
+            permutation.push(F::from((offset + (row ^ 1)) as u64));
+        }
+    }
+
+    let selectors = index
+        .selectors
+        .iter()
+        .map(|s| SelectorColumn([s.0.as_slice(), &vec![F::zero(); num_rows]].concat()))
+        .collect();
+
+    Ok(HyperPlonkIndex {
+        params: HyperPlonkParams {
+            blinded: true,
+            ..index.params.clone()
+        },
+        permutation,
+        selectors,
+        lookup_selectors: vec![],
+        tables: vec![],
+    })
+}
+
+/// Append the blinding rows to the witness columns: every pair of blinding
+/// rows gets a fresh random value.
+pub(crate) fn blind_witnesses<F: PrimeField, R: RngCore + CryptoRng>(
+    witnesses: &[WitnessColumn<F>],
+    rng: &mut R,
+) -> Vec<WitnessColumn<F>> {
+    witnesses
+        .iter()
+        .map(|w| {
+            let mut column = w.0.clone();
+            for _ in 0..w.0.len() / 2 {
+                let r = F::rand(rng);
+                column.push(r);
+                column.push(r);
+            }
+            WitnessColumn(column)
+        })
+        .collect()
+}
+
+/// The active row polynomial `1 - x_n`: one on the constraints and zero on
+/// the blinding rows.
+pub(crate) fn active_rows_mle<F: PrimeField>(num_vars: usize) -> Arc<DenseMultilinearExtension<F>> {
+    let half = 1 << (num_vars - 1);
+    Arc::new(DenseMultilinearExtension::from_evaluations_vec(
+        num_vars,
+        [vec![F::one(); half], vec![F::zero(); half]].concat(),
+    ))
+}
+
+/// Evaluate the active row polynomial at `point`.
+pub(crate) fn eval_active_rows<F: PrimeField>(point: &[F]) -> F {
+    point.last().map_or(F::one(), |x| F::one() - x)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{mock::MockCircuit, permutation::check_permutation, prelude::CustomizedGates};
+    use arithmetic::evaluate_opt;
+    use ark_bls12_381::Fr;
+    use ark_std::{rand::SeedableRng, UniformRand};
+    use rand_chacha::ChaCha20Rng;
+
+    #[test]
+    fn test_blinding() -> Result<(), HyperPlonkErrors> {
+        let mut rng = ChaCha20Rng::seed_from_u64(0);
+        let circuit = MockCircuit::<Fr>::new(8, &CustomizedGates::vanilla_plonk_gate());
+        let blinded = blind_index(&circuit.index)?;
+        assert_eq!(blinded.num_variables(), 4);
+        assert_eq!(blinded.params.num_constraints, 8);
+        assert_eq!(blinded.permutation.len(), 3 * 16);
+        assert!(blinded.selectors.iter().all(|s| s.0.len() == 16));
+        assert!(blind_index(&blinded).is_err());
+
+        let witnesses = blind_witnesses(&circuit.witnesses, &mut rng);
+        assert!(witnesses.iter().all(|w| w.0.len() == 16));
+        assert!(check_permutation(&blinded.permutation, &witnesses)?.is_empty());
+        // two blindings differ
+        let witnesses2 = blind_witnesses(&circuit.witnesses, &mut rng);
+        assert_ne!(witnesses[0].0[8], witnesses2[0].0[8]);
+        assert_eq!(witnesses[0].0[..8], witnesses2[0].0[..8]);
+
+        let point: Vec<Fr> = (0..4).map(|_| Fr::rand(&mut rng)).collect();
+        assert_eq!(
+            evaluate_opt(&active_rows_mle(4), &point),
+            eval_active_rows(&point)
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,398 @@
+// Copyright (c) 2023 Espresso Systems (espressosys.com)
+// This file is part of the HyperPlonk library.
+
+// You should have received a copy of the MIT License
+// along with the HyperPlonk library. If not, see <https://mit-license.org/>.
+
+//! A constraint system builder that outputs a `HyperPlonkIndex` together with
+//! the matching witness columns.
+
+use crate::{
+    custom_gate::CustomizedGates,
+    errors::HyperPlonkErrors,
+    permutation::{Cell, CopyConstraints},
+    selectors::SelectorColumn,
+    structs::{HyperPlonkIndex, HyperPlonkParams},
+    witness::WitnessColumn,
+};
+use ark_ff::PrimeField;
+use ark_std::log2;
+
+/// A variable of the circuit, i.e. a value that can be wired into gates.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Variable(usize);
+
+/// A finalized circuit: the index to preprocess, the witness columns and the
+/// (padded) public input to prove against.
+#[derive(Clone, Debug)]
+pub struct Circuit<F: PrimeField> {
+    /// the public input, padded with zeros to a power of two
+    pub public_inputs: Vec<F>,
+    /// the witness columns, one value per row
+    pub witnesses: Vec<WitnessColumn<F>>,
+    /// the index to preprocess: the parameters, the wire permutation and the
+    /// selector columns
+    pub index: HyperPlonkIndex<F>,
+}
+
+/// A builder for circuits over a single customized gate.
+///
+/// Each call to `add_gate` adds one row: an assignment of the selectors and a
+/// variable for each witness column. The same variable used at several places,
+/// or two variables asserted equal, become copy constraints, which are turned
+/// into the wire permutation by `finalize` with `CopyConstraints`.
+///
+/// The rows are laid out as
+/// - one row per public input, holding the public input in the first witness
+///   column and zeros elsewhere, with all selectors set to zero;
+/// - the gates, in the order they were added;
+/// - zero rows up to the next power of two.
+///
+/// The public input rows and the padding rows are only valid if the gate
+/// vanishes when all the selectors are zero, which `finalize` checks.
+#[derive(Clone, Debug)]
+pub struct CircuitBuilder<F: PrimeField> {
+    gate: CustomizedGates,
+    /// assigned value of each variable
+    values: Vec<F>,
+    /// union-find forest over the variables, merged by `assert_equal`
+    parents: Vec<usize>,
+    public_inputs: Vec<Variable>,
+    selector_rows: Vec<Vec<F>>,
+    wire_rows: Vec<Vec<Variable>>,
+}
+
+impl<F: PrimeField> CircuitBuilder<F> {
+    /// Create an empty circuit for the given gate.
+    pub fn new(gate: CustomizedGates) -> Self {
+        Self {
+            gate,
+            values: vec![],
+            parents: vec![],
+            public_inputs: vec![],
+            selector_rows: vec![],
+            wire_rows: vec![],
+        }
+    }
+
+    /// Allocate a new variable with the given value.
+    pub fn alloc(&mut self, value: F) -> Variable {
+        self.values.push(value);
+        self.parents.push(self.parents.len());
+        Variable(self.values.len() - 1)
+    }
+
+    /// Allocate a new public input variable with the given value.
+    pub fn alloc_public_input(&mut self, value: F) -> Variable {
+        let var = self.alloc(value);
+        self.public_inputs.push(var);
+        var
+    }
+
+    /// The value assigned to a variable.
+    pub fn value(&self, var: Variable) -> Result<F, HyperPlonkErrors> {
+        self.check_var(var)?;
+        Ok(self.values[var.0])
+    }
+
+    /// The number of gates added so far.
+    pub fn num_gates(&self) -> usize {
+        self.wire_rows.len()
+    }
+
+    /// Add a gate with the given selector values, wired to the given
+    /// variables, one per witness column.
+    pub fn add_gate(
+        &mut self,
+        selectors: &[F],
+        wires: &[Variable],
+    ) -> Result<(), HyperPlonkErrors> {
+        if selectors.len() != self.gate.num_selector_columns() {
+            return Err(HyperPlonkErrors::InvalidParameters(format!(
+                "number of selectors is not correct: got {}, expect {}",
+                selectors.len(),
+                self.gate.num_selector_columns()
+            )));
+        }
+        if wires.len() != self.gate.num_witness_columns() {
+            return Err(HyperPlonkErrors::InvalidParameters(format!(
+                "number of wires is not correct: got {}, expect {}",
+                wires.len(),
+                self.gate.num_witness_columns()
+            )));
+        }
+        for &var in wires.iter() {
+            self.check_var(var)?;
+        }
+
+        self.selector_rows.push(selectors.to_vec());
+        self.wire_rows.push(wires.to_vec());
+        Ok(())
+    }
+
+    /// Constrain two variables to be equal.
+    pub fn assert_equal(&mut self, a: Variable, b: Variable) -> Result<(), HyperPlonkErrors> {
+        if self.value(a)? != self.value(b)? {
+            return Err(HyperPlonkErrors::InvalidParameters(format!(
+                "variables {} and {} are asserted equal but have different values",
+                a.0, b.0
+            )));
+        }
+        let (ra, rb) = (self.find(a.0), self.find(b.0));
+        self.parents[ra] = rb;
+        Ok(())
+    }
+
+    /// Lay out the circuit and build the index, the witness columns and the
+    /// public input.
+    pub fn finalize(mut self) -> Result<Circuit<F>, HyperPlonkErrors> {
+        let num_selectors = self.gate.num_selector_columns();
+        let num_witnesses = self.gate.num_witness_columns();
+
+        // the public input length must be a power of two; pad with zeros
+        let num_pub_input = self.public_inputs.len().max(1).next_power_of_two();
+        while self.public_inputs.len() < num_pub_input {
+            let var = self.alloc(F::zero());
+            self.public_inputs.push(var);
+        }
+        // the multilinear polynomials need at least one variable
+        let num_rows = (num_pub_input + self.num_gates())
+            .max(2)
+            .next_power_of_two();
+        let nv = log2(num_rows) as usize;
+
+        // the variable of each cell, if any, and the selector rows
+        let mut cells: Vec<Vec<Option<Variable>>> = vec![vec![None; num_rows]; num_witnesses];
+        let mut selectors = vec![SelectorColumn(vec![F::zero(); num_rows]); num_selectors];
+        for (row, &var) in self.public_inputs.iter().enumerate() {
+            cells[0][row] = Some(var);
+        }
+        for (i, (wires, selector_row)) in self
+            .wire_rows
+            .iter()
+            .zip(self.selector_rows.iter())
+            .enumerate()
+        {
+            let row = num_pub_input + i;
+            for (col, &var) in wires.iter().enumerate() {
+                cells[col][row] = Some(var);
+            }
+            for (col, &q) in selector_row.iter().enumerate() {
+                selectors[col].0[row] = q;
+            }
+        }
+
+        let witnesses: Vec<WitnessColumn<F>> = cells
+            .iter()
+            .map(|col| {
+                WitnessColumn(
+                    col.iter()
+                        .map(|var| var.map_or(F::zero(), |v| self.values[v.0]))
+                        .collect(),
+                )
+            })
+            .collect();
+
+        // the public input rows and the padding rows must satisfy the gate
+        let zero_selectors = vec![F::zero(); num_selectors];
+        for row in (0..num_pub_input).chain(num_pub_input + self.num_gates()..num_rows) {
+            let wires: Vec<F> = witnesses.iter().map(|w| w.0[row]).collect();
+            let monomials = self.gate.evaluate_monomials(&zero_selectors, &wires);
+            if !monomials.iter().sum::<F>().is_zero() {
+                return Err(HyperPlonkErrors::InvalidParameters(format!(
+                    "the gate does not vanish on the public input or padding row {}",
+                    row
+                )));
+            }
+        }
+
+        // all the cells holding a same class of equal variables are copies
+        // of the first one
+        let mut copy_constraints = CopyConstraints::new(nv, num_witnesses);
+        let mut first_cells: Vec<Option<Cell>> = vec![None; self.values.len()];
+        for (col, cells) in cells.iter().enumerate() {
+            for (row, var) in cells.iter().enumerate() {
+                if let Some(var) = var {
+                    let cell = Cell::new(col, row);
+                    match first_cells[self.find(var.0)] {
+                        Some(first) => copy_constraints.assert_equal(first, cell)?,
+                        None => first_cells[self.find(var.0)] = Some(cell),
+                    }
+                }
+            }
+        }
+        let permutation = copy_constraints.build_permutation();
+
+        let public_inputs = witnesses[0].0[..num_pub_input].to_vec();
+        let params = HyperPlonkParams {
+            num_constraints: num_rows,
+            num_pub_input,
+            gate_func: self.gate,
+            blinded: false,
+            lookups: vec![],
+            range_checks: vec![],
+        };
+        Ok(Circuit {
+            public_inputs,
+            witnesses,
+            index: HyperPlonkIndex {
+                params,
+                permutation,
+                selectors,
+                lookup_selectors: vec![],
+                tables: vec![],
+            },
+        })
+    }
+
+    fn check_var(&self, var: Variable) -> Result<(), HyperPlonkErrors> {
+        if var.0 >= self.values.len() {
+            return Err(HyperPlonkErrors::InvalidParameters(format!(
+                "variable {} is not allocated",
+                var.0
+            )));
+        }
+        Ok(())
+    }
+
+    /// Find the representative of a variable, with path halving.
+    fn find(&mut self, mut x: usize) -> usize {
+        while self.parents[x] != x {
+            self.parents[x] = self.parents[self.parents[x]];
+            x = self.parents[x];
+        }
+        x
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{permutation::check_permutation, HyperPlonkSNARK};
+    use arithmetic::identity_permutation;
+    use ark_bls12_381::{Bls12_381, Fr};
+    use ark_std::{test_rng, One, Zero};
+    use subroutines::{
+        pcs::{prelude::MultilinearKzgPCS, PolynomialCommitmentScheme},
+        poly_iop::PolyIOP,
+    };
+
+    type Kzg = MultilinearKzgPCS<Bls12_381>;
+
+    /// `x^3 + x + 5 = out` with a public `out`, over the vanilla plonk gate
+    /// `q_1 w_1 + q_2 w_2 + q_3 w_3 + q_M w_1 w_2 + q_C = 0`.
+    fn cubic_circuit(x: u64, out: u64) -> Result<CircuitBuilder<Fr>, HyperPlonkErrors> {
+        let mut builder = CircuitBuilder::new(CustomizedGates::vanilla_plonk_gate());
+        let out = builder.alloc_public_input(Fr::from(out));
+        let x = builder.alloc(Fr::from(x));
+        let x2 = builder.alloc(builder.value(x)? * builder.value(x)?);
+        let x3 = builder.alloc(builder.value(x2)? * builder.value(x)?);
+        let x3_plus_x = builder.alloc(builder.value(x3)? + builder.value(x)?);
+        let zero = Fr::zero();
+        let one = Fr::one();
+
+        builder.add_gate(&[zero, zero, -one, one, zero], &[x, x, x2])?;
+        builder.add_gate(&[zero, zero, -one, one, zero], &[x2, x, x3])?;
+        builder.add_gate(&[one, one, -one, zero, zero], &[x3, x, x3_plus_x])?;
+        builder.add_gate(
+            &[one, zero, -one, zero, Fr::from(5u64)],
+            &[x3_plus_x, x, out],
+        )?;
+        Ok(builder)
+    }
+
+    fn prove_and_verify(circuit: &Circuit<Fr>) -> Result<bool, HyperPlonkErrors> {
+        let mut rng = test_rng();
+        let pcs_srs = Kzg::gen_srs_for_testing(&mut rng, 8)?;
+        let (pk, vk) =
+            <PolyIOP<Fr> as HyperPlonkSNARK<Fr, Kzg>>::preprocess(&circuit.index, &pcs_srs)?;
+        let proof = <PolyIOP<Fr> as HyperPlonkSNARK<Fr, Kzg>>::prove(
+            &pk,
+            &circuit.public_inputs,
+            &circuit.witnesses,
+        )?;
+        <PolyIOP<Fr> as HyperPlonkSNARK<Fr, Kzg>>::verify(&vk, &circuit.public_inputs, &proof)
+    }
+
+    #[test]
+    fn test_builder_layout() -> Result<(), HyperPlonkErrors> {
+        let circuit = cubic_circuit(3, 35)?.finalize()?;
+
+        // 1 public input row and 4 gates, padded to 8 rows
+        assert_eq!(circuit.index.params.num_constraints, 8);
+        assert_eq!(circuit.index.params.num_pub_input, 1);
+        assert_eq!(circuit.public_inputs, vec![Fr::from(35u64)]);
+        assert_eq!(circuit.witnesses.len(), 3);
+        assert_eq!(circuit.index.selectors.len(), 5);
+        // over the merged domain, with the 3 columns padded to 4
+        assert_eq!(circuit.index.permutation.len(), 4 * 8);
+        assert_ne!(circuit.index.permutation, identity_permutation(5, 1));
+
+        // `out` lives in (col 0, row 0) and (col 2, row 4): the two cells
+        // form a cycle
+        assert_eq!(circuit.index.permutation[0], Fr::from(2 * 8 + 4u64));
+        assert_eq!(circuit.index.permutation[2 * 8 + 4], Fr::from(0u64));
+
+        // the permutation preserves the witness values
+        assert!(check_permutation(&circuit.index.permutation, &circuit.witnesses)?.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_builder_e2e() -> Result<(), HyperPlonkErrors> {
+        let _trace = crate::trace_guard();
+        assert!(prove_and_verify(&cubic_circuit(3, 35)?.finalize()?)?);
+
+        // equal variables allocated separately, and tied with `assert_equal`
+        let mut builder = cubic_circuit(3, 35)?;
+        let y = builder.alloc(Fr::from(35u64));
+        let out = builder.alloc_public_input(Fr::from(35u64));
+        builder.assert_equal(y, out)?;
+        let zero = Fr::zero();
+        builder.add_gate(&[Fr::one(), zero, -Fr::one(), zero, zero], &[y, y, y])?;
+        let circuit = builder.finalize()?;
+        assert_eq!(
+            circuit.public_inputs,
+            vec![Fr::from(35u64), Fr::from(35u64)]
+        );
+        assert!(prove_and_verify(&circuit)?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_builder_bad_witness() -> Result<(), HyperPlonkErrors> {
+        let _trace = crate::trace_guard();
+        // breaking a copy constraint must not yield a valid proof
+        let mut circuit = cubic_circuit(3, 35)?.finalize()?;
+        // the second use of `x2`, in the second gate
+        circuit.witnesses[0].0[2] += Fr::one();
+        assert!(!prove_and_verify(&circuit).unwrap_or(false));
+
+        // wrong public output
+        assert!(!prove_and_verify(&cubic_circuit(3, 36)?.finalize()?).unwrap_or(false));
+        Ok(())
+    }
+
+    #[test]
+    fn test_builder_errors() -> Result<(), HyperPlonkErrors> {
+        let mut builder = CircuitBuilder::<Fr>::new(CustomizedGates::vanilla_plonk_gate());
+        let a = builder.alloc(Fr::one());
+        let b = builder.alloc(Fr::zero());
+        assert!(builder.add_gate(&[Fr::zero(); 4], &[a, a, a]).is_err());
+        assert!(builder.add_gate(&[Fr::zero(); 5], &[a, a]).is_err());
+        assert!(builder
+            .add_gate(&[Fr::zero(); 5], &[a, a, Variable(2)])
+            .is_err());
+        assert!(builder.assert_equal(a, b).is_err());
+
+        // `w_1 - q w_2` does not vanish on a public input row holding a
+        // non-zero `w_1`
+        let gate = CustomizedGates {
+            gates: vec![(1, None, vec![0]), (-1, Some(0), vec![1])],
+        };
+        let mut builder = CircuitBuilder::<Fr>::new(gate);
+        builder.alloc_public_input(Fr::one());
+        assert!(builder.finalize().is_err());
+        Ok(())
+    }
+}
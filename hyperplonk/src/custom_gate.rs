@@ -4,6 +4,7 @@
 // You should have received a copy of the MIT License
 // along with the HyperPlonk library. If not, see <https://mit-license.org/>.
 
+use ark_ff::PrimeField;
 use ark_std::cmp::max;
 
 /// Customized gate is a list of tuples of
@@ -71,6 +72,29 @@ impl CustomizedGates {
         res + 1
     }
 
+    /// Evaluate each monomial of the gate on one row, given the selector and
+    /// the witness values of this row. The gate is satisfied on the row if
+    /// the monomials sum to zero.
+    pub(crate) fn evaluate_monomials<F: PrimeField>(&self, selectors: &[F], wires: &[F]) -> Vec<F> {
+        self.gates
+            .iter()
+            .map(|(coeff, q, wit)| {
+                let mut cur_monomial = if *coeff < 0 {
+                    -F::from((-coeff) as u64)
+                } else {
+                    F::from(*coeff as u64)
+                };
+                if let Some(p) = q {
+                    cur_monomial *= selectors[*p];
+                }
+                for wit_index in wit.iter() {
+                    cur_monomial *= wires[*wit_index];
+                }
+                cur_monomial
+            })
+            .collect()
+    }
+
     /// Return a vanilla plonk gate:
     /// ``` ignore
     ///   q_L w_1 + q_R w_2 + q_O w_3 + q_M w1w2 + q_C = 0
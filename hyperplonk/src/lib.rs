@@ -6,27 +6,41 @@
 
 //! Main module for the HyperPlonk SNARK.
 
-use ark_ec::pairing::Pairing;
+use ark_ff::PrimeField;
+use ark_std::rand::{CryptoRng, RngCore};
 use errors::HyperPlonkErrors;
-use subroutines::{pcs::prelude::PolynomialCommitmentScheme, poly_iop::prelude::PermutationCheck};
+use subroutines::{
+    pcs::prelude::{HidingPolynomialCommitmentScheme, PolynomialCommitmentScheme},
+    poly_iop::prelude::{LookupCheck, PermutationCheck, RangeCheck},
+};
 use witness::WitnessColumn;
 
+mod blinding;
+mod circuit;
 mod custom_gate;
 mod errors;
+mod lookup;
 mod mock;
+mod permutation;
+mod persistence;
 pub mod prelude;
+mod range_check;
+mod satisfaction;
 mod selectors;
+mod serialization;
 mod snark;
 mod structs;
 mod utils;
 mod witness;
 
 /// A trait for HyperPlonk SNARKs.
-/// A HyperPlonk is derived from ZeroChecks and PermutationChecks.
-pub trait HyperPlonkSNARK<E, PCS>: PermutationCheck<E, PCS>
+/// A HyperPlonk is derived from ZeroChecks, PermutationChecks, LookupChecks
+/// and RangeChecks.
+pub trait HyperPlonkSNARK<F, PCS>:
+    PermutationCheck<F, PCS> + LookupCheck<F, PCS> + RangeCheck<F, PCS>
 where
-    E: Pairing,
-    PCS: PolynomialCommitmentScheme<E>,
+    F: PrimeField,
+    PCS: PolynomialCommitmentScheme<F>,
 {
     type Index;
     type ProvingKey;
@@ -49,6 +63,27 @@ where
         pcs_srs: &PCS::SRS,
     ) -> Result<(Self::ProvingKey, Self::VerifyingKey), HyperPlonkErrors>;
 
+    /// Generate the preprocessed polynomials of a zero-knowledge circuit.
+    ///
+    /// The circuit is extended with random blinding rows, so the polynomials
+    /// have one more variable than with `preprocess` and proving is about
+    /// twice as slow. `pcs_srs` must support one more variable, and enough
+    /// variables to commit to the masks of the zero checks. Proofs for the
+    /// resulting keys are generated with `prove_zk`.
+    ///
+    /// Inputs:
+    /// - `index`: HyperPlonk index
+    /// - `pcs_srs`: Polynomial commitment structured reference string
+    ///
+    /// Outputs:
+    /// - The zero-knowledge HyperPlonk proving key and verifying key
+    fn preprocess_zk(
+        index: &Self::Index,
+        pcs_srs: &PCS::SRS,
+    ) -> Result<(Self::ProvingKey, Self::VerifyingKey), HyperPlonkErrors>
+    where
+        PCS: HidingPolynomialCommitmentScheme<F>;
+
     /// Generate HyperPlonk SNARK proof.
     ///
     /// Inputs:
@@ -58,12 +93,38 @@ where
     ///
     /// Outputs:
     /// - The HyperPlonk SNARK proof.
+    ///
+    /// Returns an error for a zero-knowledge proving key, which needs the
+    /// randomness of `prove_zk`.
     fn prove(
         pk: &Self::ProvingKey,
-        pub_input: &[E::ScalarField],
-        witnesses: &[WitnessColumn<E::ScalarField>],
+        pub_input: &[F],
+        witnesses: &[WitnessColumn<F>],
     ) -> Result<Self::Proof, HyperPlonkErrors>;
 
+    /// Generate a zero-knowledge HyperPlonk SNARK proof for a proving key of
+    /// `preprocess_zk`.
+    ///
+    /// The blinding rows, the blindings of the commitments and the masks of
+    /// the zero checks are drawn from `rng`.
+    ///
+    /// Inputs:
+    /// - `pk`: zero-knowledge circuit proving key
+    /// - `pub_input`: online public input
+    /// - `witness`: witness assignment
+    /// - `rng`: a cryptographically secure random number generator
+    ///
+    /// Outputs:
+    /// - The zero-knowledge HyperPlonk SNARK proof.
+    fn prove_zk<R: RngCore + CryptoRng>(
+        pk: &Self::ProvingKey,
+        pub_input: &[F],
+        witnesses: &[WitnessColumn<F>],
+        rng: &mut R,
+    ) -> Result<Self::Proof, HyperPlonkErrors>
+    where
+        PCS: HidingPolynomialCommitmentScheme<F>;
+
     /// Verify the HyperPlonk proof.
     ///
     /// Inputs:
@@ -75,7 +136,27 @@ where
     /// - Return a boolean on whether the verification is successful
     fn verify(
         vk: &Self::VerifyingKey,
-        pub_input: &[E::ScalarField],
+        pub_input: &[F],
         proof: &Self::Proof,
     ) -> Result<bool, HyperPlonkErrors>;
 }
+
+/// Run the tests that print traces one at a time.
+///
+/// The indentation of the ark-std timers is global: it overflows when the
+/// timers of parallel tests interleave, or when the timers of the proofs that
+/// fail on purpose are never ended. With `print-trace`, the guard holds a
+/// global lock and resets the indentation; otherwise it does nothing.
+#[cfg(test)]
+pub(crate) fn trace_guard() -> Option<std::sync::MutexGuard<'static, ()>> {
+    #[cfg(feature = "print-trace")]
+    {
+        static LOCK: std::sync::Mutex<()> = std::sync::Mutex::new(());
+        // a failed test poisons the lock, which is still usable
+        let guard = LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        ark_std::perf_trace::inner::NUM_INDENT.store(0, std::sync::atomic::Ordering::Relaxed);
+        Some(guard)
+    }
+    #[cfg(not(feature = "print-trace"))]
+    None
+}
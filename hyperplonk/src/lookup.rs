@@ -0,0 +1,94 @@
+// Copyright (c) 2023 Espresso Systems (espressosys.com)
+// This file is part of the HyperPlonk library.
+
+// You should have received a copy of the MIT License
+// along with the HyperPlonk library. If not, see <https://mit-license.org/>.
+
+//! Lookup gates, i.e. the "HyperPlonk+" extension of the paper.
+//!
+//! A lookup gate asserts that, on every row where its lookup selector is one,
+//! the values of some witness columns are entries of a fixed table column.
+//! The table columns and the lookup selectors are preprocessed and committed
+//! to by the indexer, and each gate is proven with a `LookupCheck`.
+
+use crate::{errors::HyperPlonkErrors, structs::HyperPlonkIndex};
+use ark_ff::PrimeField;
+use ark_poly::DenseMultilinearExtension;
+use ark_std::log2;
+
+/// A lookup gate: the witness columns `witnesses` are looked up into the
+/// table column `table`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct LookupGate {
+    /// the indices of the witness columns looked up
+    pub witnesses: Vec<usize>,
+    /// the index of the table column
+    pub table: usize,
+}
+
+/// A column of table entries of length `#constraints`
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct TableColumn<F: PrimeField>(pub(crate) Vec<F>);
+
+impl<F: PrimeField> TableColumn<F> {
+    /// the number of variables of the multilinear polynomial that presents a
+    /// column.
+    pub fn get_nv(&self) -> usize {
+        log2(self.0.len()) as usize
+    }
+
+    /// Append a new entry to the table column
+    pub fn append(&mut self, new_element: F) {
+        self.0.push(new_element)
+    }
+}
+
+impl<F: PrimeField> From<&TableColumn<F>> for DenseMultilinearExtension<F> {
+    fn from(table: &TableColumn<F>) -> Self {
+        let nv = table.get_nv();
+        Self::from_evaluations_slice(nv, table.0.as_ref())
+    }
+}
+
+/// Check that the lookup gates of an index refer to existing witness columns
+/// and table columns, and that there is one lookup selector per gate, all
+/// columns being of length `#constraints`.
+pub(crate) fn check_lookup_index<F: PrimeField>(
+    index: &HyperPlonkIndex<F>,
+) -> Result<(), HyperPlonkErrors> {
+    let params = &index.params;
+    for (i, lookup) in params.lookups.iter().enumerate() {
+        if lookup.witnesses.is_empty()
+            || lookup
+                .witnesses
+                .iter()
+                .any(|&w| w >= params.num_witness_columns())
+        {
+            return Err(HyperPlonkErrors::InvalidParameters(format!(
+                "{}-th lookup gate has invalid witness columns {:?}",
+                i, lookup.witnesses
+            )));
+        }
+    }
+    if index.lookup_selectors.len() != params.num_lookups()
+        || index.tables.len() != params.num_table_columns()
+    {
+        return Err(HyperPlonkErrors::InvalidParameters(format!(
+            "got {} lookup selectors and {} tables, expect {} and {}",
+            index.lookup_selectors.len(),
+            index.tables.len(),
+            params.num_lookups(),
+            params.num_table_columns()
+        )));
+    }
+    let num_rows = 1 << params.num_variables();
+    if index.lookup_selectors.iter().any(|s| s.0.len() != num_rows)
+        || index.tables.iter().any(|t| t.0.len() != num_rows)
+    {
+        return Err(HyperPlonkErrors::InvalidParameters(format!(
+            "lookup selectors and tables must have length {}",
+            num_rows
+        )));
+    }
+    Ok(())
+}
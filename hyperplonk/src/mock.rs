@@ -10,6 +10,7 @@ use ark_std::{log2, test_rng};
 
 use crate::{
     custom_gate::CustomizedGates,
+    satisfaction::check_satisfaction,
     selectors::SelectorColumn,
     structs::{HyperPlonkIndex, HyperPlonkParams},
     witness::WitnessColumn,
@@ -99,6 +100,9 @@ impl<F: PrimeField> MockCircuit<F> {
             num_constraints,
             num_pub_input: public_inputs.len(),
             gate_func: gate.clone(),
+            blinded: false,
+            lookups: vec![],
+            range_checks: vec![],
         };
 
         let permutation = identity_permutation(merged_nv as usize, 1);
@@ -106,6 +110,8 @@ impl<F: PrimeField> MockCircuit<F> {
             params,
             permutation,
             selectors,
+            lookup_selectors: vec![],
+            tables: vec![],
         };
 
         Self {
@@ -115,30 +121,10 @@ impl<F: PrimeField> MockCircuit<F> {
         }
     }
 
+    /// Whether the witness satisfies the circuit, see `check_satisfaction`.
     pub fn is_satisfied(&self) -> bool {
-        for current_row in 0..self.num_variables() {
-            let mut cur = F::zero();
-            for (coeff, q, wit) in self.index.params.gate_func.gates.iter() {
-                let mut cur_monomial = if *coeff < 0 {
-                    -F::from((-coeff) as u64)
-                } else {
-                    F::from(*coeff as u64)
-                };
-                cur_monomial = match q {
-                    Some(p) => cur_monomial * self.index.selectors[*p].0[current_row],
-                    None => cur_monomial,
-                };
-                for wit_index in wit.iter() {
-                    cur_monomial *= self.witnesses[*wit_index].0[current_row];
-                }
-                cur += cur_monomial;
-            }
-            if !cur.is_zero() {
-                return false;
-            }
-        }
-
-        true
+        check_satisfaction(&self.index, &self.public_inputs, &self.witnesses)
+            .is_ok_and(|report| report.is_satisfied())
     }
 }
 
@@ -147,12 +133,15 @@ mod test {
     use super::*;
     use crate::{errors::HyperPlonkErrors, HyperPlonkSNARK};
     use ark_bls12_381::{Bls12_381, Fr};
+    use ark_poly::DenseMultilinearExtension;
+    use std::sync::Arc;
     use subroutines::{
         pcs::{
-            prelude::{MultilinearKzgPCS, MultilinearUniversalParams},
+            prelude::{Commitment, GeminiPCS, MultilinearKzgPCS, ZeromorphPCS},
             PolynomialCommitmentScheme,
         },
         poly_iop::PolyIOP,
+        BatchProof,
     };
 
     const SUPPORTED_SIZE: usize = 20;
@@ -181,56 +170,69 @@ mod test {
         }
     }
 
-    fn test_mock_circuit_zkp_helper(
+    fn test_mock_circuit_zkp_helper<PCS>(
         nv: usize,
         gate: &CustomizedGates,
-        pcs_srs: &MultilinearUniversalParams<Bls12_381>,
-    ) -> Result<(), HyperPlonkErrors> {
+        pcs_srs: &PCS::SRS,
+    ) -> Result<(), HyperPlonkErrors>
+    where
+        PCS: PolynomialCommitmentScheme<
+            Fr,
+            Polynomial = Arc<DenseMultilinearExtension<Fr>>,
+            Point = Vec<Fr>,
+            Evaluation = Fr,
+            Commitment = Commitment<Bls12_381>,
+            BatchProof = BatchProof<Bls12_381, PCS>,
+        >,
+    {
         let circuit = MockCircuit::<Fr>::new(1 << nv, gate);
         assert!(circuit.is_satisfied());
 
         let index = circuit.index;
         // generate pk and vks
-        let (pk, vk) =
-            <PolyIOP<Fr> as HyperPlonkSNARK<Bls12_381, MultilinearKzgPCS<Bls12_381>>>::preprocess(
-                &index, pcs_srs,
-            )?;
+        let (pk, vk) = <PolyIOP<Fr> as HyperPlonkSNARK<Fr, PCS>>::preprocess(&index, pcs_srs)?;
         // generate a proof and verify
-        let proof =
-            <PolyIOP<Fr> as HyperPlonkSNARK<Bls12_381, MultilinearKzgPCS<Bls12_381>>>::prove(
-                &pk,
-                &circuit.public_inputs,
-                &circuit.witnesses,
-            )?;
+        let proof = <PolyIOP<Fr> as HyperPlonkSNARK<Fr, PCS>>::prove(
+            &pk,
+            &circuit.public_inputs,
+            &circuit.witnesses,
+        )?;
 
         let verify =
-            <PolyIOP<Fr> as HyperPlonkSNARK<Bls12_381, MultilinearKzgPCS<Bls12_381>>>::verify(
-                &vk,
-                &circuit.public_inputs,
-                &proof,
-            )?;
+            <PolyIOP<Fr> as HyperPlonkSNARK<Fr, PCS>>::verify(&vk, &circuit.public_inputs, &proof)?;
         assert!(verify);
         Ok(())
     }
 
     #[test]
     fn test_mock_circuit_zkp() -> Result<(), HyperPlonkErrors> {
+        let _trace = crate::trace_guard();
         let mut rng = test_rng();
         let pcs_srs =
             MultilinearKzgPCS::<Bls12_381>::gen_srs_for_testing(&mut rng, SUPPORTED_SIZE)?;
         for nv in MIN_NUM_VARS..MAX_NUM_VARS {
             let vanilla_gate = CustomizedGates::vanilla_plonk_gate();
-            test_mock_circuit_zkp_helper(nv, &vanilla_gate, &pcs_srs)?;
+            test_mock_circuit_zkp_helper::<MultilinearKzgPCS<Bls12_381>>(
+                nv,
+                &vanilla_gate,
+                &pcs_srs,
+            )?;
         }
         for nv in MIN_NUM_VARS..MAX_NUM_VARS {
             let tubro_gate = CustomizedGates::jellyfish_turbo_plonk_gate();
-            test_mock_circuit_zkp_helper(nv, &tubro_gate, &pcs_srs)?;
+            test_mock_circuit_zkp_helper::<MultilinearKzgPCS<Bls12_381>>(
+                nv,
+                &tubro_gate,
+                &pcs_srs,
+            )?;
         }
         let nv = 5;
         for num_witness in 2..10 {
             for degree in CUSTOM_DEGREE {
                 let mock_gate = CustomizedGates::mock_gate(num_witness, degree);
-                test_mock_circuit_zkp_helper(nv, &mock_gate, &pcs_srs)?;
+                test_mock_circuit_zkp_helper::<MultilinearKzgPCS<Bls12_381>>(
+                    nv, &mock_gate, &pcs_srs,
+                )?;
             }
         }
 
@@ -239,26 +241,62 @@ mod test {
 
     #[test]
     fn test_mock_circuit_e2e() -> Result<(), HyperPlonkErrors> {
+        let _trace = crate::trace_guard();
         let mut rng = test_rng();
         let pcs_srs =
             MultilinearKzgPCS::<Bls12_381>::gen_srs_for_testing(&mut rng, SUPPORTED_SIZE)?;
         let nv = MAX_NUM_VARS;
 
         let turboplonk_gate = CustomizedGates::jellyfish_turbo_plonk_gate();
-        test_mock_circuit_zkp_helper(nv, &turboplonk_gate, &pcs_srs)?;
+        test_mock_circuit_zkp_helper::<MultilinearKzgPCS<Bls12_381>>(
+            nv,
+            &turboplonk_gate,
+            &pcs_srs,
+        )?;
 
         Ok(())
     }
 
     #[test]
     fn test_mock_long_selector_e2e() -> Result<(), HyperPlonkErrors> {
+        let _trace = crate::trace_guard();
         let mut rng = test_rng();
         let pcs_srs =
             MultilinearKzgPCS::<Bls12_381>::gen_srs_for_testing(&mut rng, SUPPORTED_SIZE)?;
         let nv = MAX_NUM_VARS;
 
         let long_selector_gate = CustomizedGates::super_long_selector_gate();
-        test_mock_circuit_zkp_helper(nv, &long_selector_gate, &pcs_srs)?;
+        test_mock_circuit_zkp_helper::<MultilinearKzgPCS<Bls12_381>>(
+            nv,
+            &long_selector_gate,
+            &pcs_srs,
+        )?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_mock_circuit_e2e_zeromorph() -> Result<(), HyperPlonkErrors> {
+        let _trace = crate::trace_guard();
+        let mut rng = test_rng();
+        let pcs_srs = ZeromorphPCS::<Bls12_381>::gen_srs_for_testing(&mut rng, MAX_NUM_VARS)?;
+        let nv = MAX_NUM_VARS;
+
+        let turboplonk_gate = CustomizedGates::jellyfish_turbo_plonk_gate();
+        test_mock_circuit_zkp_helper::<ZeromorphPCS<Bls12_381>>(nv, &turboplonk_gate, &pcs_srs)?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_mock_circuit_e2e_gemini() -> Result<(), HyperPlonkErrors> {
+        let _trace = crate::trace_guard();
+        let mut rng = test_rng();
+        let pcs_srs = GeminiPCS::<Bls12_381>::gen_srs_for_testing(&mut rng, MIN_NUM_VARS)?;
+        let nv = MIN_NUM_VARS;
+
+        let turboplonk_gate = CustomizedGates::jellyfish_turbo_plonk_gate();
+        test_mock_circuit_zkp_helper::<GeminiPCS<Bls12_381>>(nv, &turboplonk_gate, &pcs_srs)?;
 
         Ok(())
     }
@@ -0,0 +1,389 @@
+// Copyright (c) 2023 Espresso Systems (espressosys.com)
+// This file is part of the HyperPlonk library.
+
+// You should have received a copy of the MIT License
+// along with the HyperPlonk library. If not, see <https://mit-license.org/>.
+
+//! Compile copy constraints into the wire permutation of a `HyperPlonkIndex`.
+//!
+//! The witness columns are merged into a single table over
+//! `num_vars + log2(num_witness_columns)` variables, which is the domain
+//! `HyperPlonkParams::eval_id_oracle` assumes: the cell at `(col, row)` has
+//! index `col * 2^num_vars + row`. The permutation maps every cell to the next
+//! cell of its cycle, where the cycles are the classes of cells constrained to
+//! be equal; unconstrained cells are fixed points.
+
+use crate::{errors::HyperPlonkErrors, witness::WitnessColumn};
+use ark_ff::PrimeField;
+use ark_std::log2;
+
+/// A cell of the witness table.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Cell {
+    /// the witness column
+    pub col: usize,
+    /// the row, i.e. the constraint
+    pub row: usize,
+}
+
+impl Cell {
+    /// Create a new cell.
+    pub fn new(col: usize, row: usize) -> Self {
+        Self { col, row }
+    }
+}
+
+/// A set of copy constraints, i.e. equalities between cells, over a table of
+/// `2^num_vars` rows and `num_witness_columns` columns.
+#[derive(Clone, Debug)]
+pub struct CopyConstraints {
+    num_vars: usize,
+    num_witness_columns: usize,
+    /// union-find forest over the cells of the merged domain
+    parents: Vec<usize>,
+    /// size of the tree rooted at each cell
+    sizes: Vec<usize>,
+}
+
+impl CopyConstraints {
+    /// Create an empty set of copy constraints.
+    pub fn new(num_vars: usize, num_witness_columns: usize) -> Self {
+        let domain_size = 1 << (num_vars + log2(num_witness_columns) as usize);
+        Self {
+            num_vars,
+            num_witness_columns,
+            parents: (0..domain_size).collect(),
+            sizes: vec![1; domain_size],
+        }
+    }
+
+    /// The index of a cell in the merged domain.
+    pub fn cell_index(&self, cell: Cell) -> Result<usize, HyperPlonkErrors> {
+        if cell.col >= self.num_witness_columns || cell.row >= 1 << self.num_vars {
+            return Err(HyperPlonkErrors::InvalidParameters(format!(
+                "cell ({}, {}) is out of the {} x {} table",
+                cell.col,
+                cell.row,
+                self.num_witness_columns,
+                1 << self.num_vars
+            )));
+        }
+        Ok((cell.col << self.num_vars) + cell.row)
+    }
+
+    /// Constrain two cells to hold the same value.
+    pub fn assert_equal(&mut self, a: Cell, b: Cell) -> Result<(), HyperPlonkErrors> {
+        let ra = self.find(self.cell_index(a)?);
+        let rb = self.find(self.cell_index(b)?);
+        if ra == rb {
+            return Ok(());
+        }
+        // union by size
+        let (small, large) = if self.sizes[ra] < self.sizes[rb] {
+            (ra, rb)
+        } else {
+            (rb, ra)
+        };
+        self.parents[small] = large;
+        self.sizes[large] += self.sizes[small];
+        Ok(())
+    }
+
+    /// Build the permutation over the merged domain: each class of equal
+    /// cells, in increasing index order, becomes a cycle.
+    pub fn build_permutation<F: PrimeField>(&self) -> Vec<F> {
+        let domain_size = self.parents.len();
+        let roots: Vec<usize> = (0..domain_size).map(|i| self.root(i)).collect();
+
+        // the cells of each class, indexed by the root
+        let mut classes: Vec<Vec<usize>> = vec![vec![]; domain_size];
+        for (i, &root) in roots.iter().enumerate() {
+            classes[root].push(i);
+        }
+
+        let mut permutation: Vec<F> = vec![F::zero(); domain_size];
+        for class in classes.iter() {
+            for (i, &cell) in class.iter().enumerate() {
+                permutation[cell] = F::from(class[(i + 1) % class.len()] as u64);
+            }
+        }
+        permutation
+    }
+
+    /// Find the root of a cell, with path halving.
+    fn find(&mut self, mut x: usize) -> usize {
+        while self.parents[x] != x {
+            self.parents[x] = self.parents[self.parents[x]];
+            x = self.parents[x];
+        }
+        x
+    }
+
+    /// Find the root of a cell without compressing the path.
+    fn root(&self, mut x: usize) -> usize {
+        while self.parents[x] != x {
+            x = self.parents[x];
+        }
+        x
+    }
+}
+
+/// Build the permutation for a list of wire equalities over a table of
+/// `2^num_vars` rows and `num_witness_columns` columns.
+pub fn build_permutation<F: PrimeField>(
+    num_vars: usize,
+    num_witness_columns: usize,
+    equalities: &[(Cell, Cell)],
+) -> Result<Vec<F>, HyperPlonkErrors> {
+    let mut copy_constraints = CopyConstraints::new(num_vars, num_witness_columns);
+    for &(a, b) in equalities.iter() {
+        copy_constraints.assert_equal(a, b)?;
+    }
+    Ok(copy_constraints.build_permutation())
+}
+
+/// A cell whose value differs from the value of its image under the
+/// permutation.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CopyViolation<F: PrimeField> {
+    /// the violating cell
+    pub cell: Cell,
+    /// its value
+    pub value: F,
+    /// the image of the cell under the permutation
+    pub target: Cell,
+    /// the value of the image
+    pub target_value: F,
+}
+
+/// Check a witness against a permutation, and return every cell whose value
+/// differs from the value of its image.
+///
+/// Returns an error if the permutation is not a permutation of the cells of
+/// the witness columns.
+pub fn check_permutation<F: PrimeField>(
+    permutation: &[F],
+    witnesses: &[WitnessColumn<F>],
+) -> Result<Vec<CopyViolation<F>>, HyperPlonkErrors> {
+    if witnesses.is_empty() {
+        return Err(HyperPlonkErrors::InvalidParameters(
+            "empty witness columns".to_string(),
+        ));
+    }
+    let num_rows = witnesses[0].0.len();
+    if !num_rows.is_power_of_two() || witnesses.iter().any(|w| w.0.len() != num_rows) {
+        return Err(HyperPlonkErrors::InvalidParameters(
+            "witness columns must have the same power of two length".to_string(),
+        ));
+    }
+    let num_vars = log2(num_rows) as usize;
+    let num_cells = witnesses.len() * num_rows;
+    if permutation.len() < num_cells {
+        return Err(HyperPlonkErrors::InvalidParameters(format!(
+            "permutation length {} is smaller than the number of cells {}",
+            permutation.len(),
+            num_cells
+        )));
+    }
+
+    let to_cell = |i: usize| Cell::new(i >> num_vars, i & (num_rows - 1));
+    let mut seen = vec![false; num_cells];
+    let mut violations = vec![];
+    for (i, sigma) in permutation.iter().take(num_cells).enumerate() {
+        // the image must be a cell of the table, hit exactly once
+        let j = field_to_index(sigma)
+            .filter(|&j| j < num_cells && !seen[j])
+            .ok_or_else(|| {
+                HyperPlonkErrors::InvalidParameters(format!(
+                    "permutation maps cell {} outside of the table or onto a cell twice",
+                    i
+                ))
+            })?;
+        seen[j] = true;
+
+        let (cell, target) = (to_cell(i), to_cell(j));
+        let value = witnesses[cell.col].0[cell.row];
+        let target_value = witnesses[target.col].0[target.row];
+        if value != target_value {
+            violations.push(CopyViolation {
+                cell,
+                value,
+                target,
+                target_value,
+            });
+        }
+    }
+    Ok(violations)
+}
+
+/// Convert a field element into an index, if it fits in a `u64`.
+pub(crate) fn field_to_index<F: PrimeField>(x: &F) -> Option<usize> {
+    let bigint = x.into_bigint();
+    let limbs = bigint.as_ref();
+    if limbs[1..].iter().any(|&l| l != 0) {
+        return None;
+    }
+    usize::try_from(limbs[0]).ok()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        custom_gate::CustomizedGates,
+        selectors::SelectorColumn,
+        structs::{HyperPlonkIndex, HyperPlonkParams},
+        HyperPlonkSNARK,
+    };
+    use arithmetic::identity_permutation;
+    use ark_bls12_381::{Bls12_381, Fr};
+    use ark_ff::Field;
+    use ark_std::{test_rng, One};
+    use subroutines::{
+        pcs::{prelude::MultilinearKzgPCS, PolynomialCommitmentScheme},
+        poly_iop::PolyIOP,
+    };
+
+    type Kzg = MultilinearKzgPCS<Bls12_381>;
+
+    #[test]
+    fn test_build_permutation() -> Result<(), HyperPlonkErrors> {
+        // no equality: the identity over the merged domain, where the 3
+        // columns are padded to 4
+        let permutation = build_permutation::<Fr>(2, 3, &[])?;
+        assert_eq!(permutation, identity_permutation(4, 1));
+
+        // (0, 0) = (1, 2) = (2, 3), and (0, 1) = (0, 3)
+        let equalities = [
+            (Cell::new(2, 3), Cell::new(0, 0)),
+            (Cell::new(1, 2), Cell::new(2, 3)),
+            (Cell::new(0, 1), Cell::new(0, 3)),
+        ];
+        let permutation = build_permutation::<Fr>(2, 3, &equalities)?;
+        let mut expected: Vec<Fr> = identity_permutation(4, 1);
+        expected[0] = Fr::from(6u64);
+        expected[6] = Fr::from(11u64);
+        expected[11] = Fr::from(0u64);
+        expected[1] = Fr::from(3u64);
+        expected[3] = Fr::from(1u64);
+        assert_eq!(permutation, expected);
+
+        // out of the table
+        assert!(build_permutation::<Fr>(2, 3, &[(Cell::new(3, 0), Cell::new(0, 0))]).is_err());
+        assert!(build_permutation::<Fr>(2, 3, &[(Cell::new(0, 4), Cell::new(0, 0))]).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_check_permutation() -> Result<(), HyperPlonkErrors> {
+        let equalities = [
+            (Cell::new(0, 0), Cell::new(1, 1)),
+            (Cell::new(1, 1), Cell::new(1, 3)),
+        ];
+        let permutation = build_permutation::<Fr>(2, 2, &equalities)?;
+        let mut witnesses = vec![
+            WitnessColumn(vec![
+                Fr::from(7u64),
+                Fr::from(1u64),
+                Fr::from(2u64),
+                Fr::from(3u64),
+            ]),
+            WitnessColumn(vec![
+                Fr::from(4u64),
+                Fr::from(7u64),
+                Fr::from(5u64),
+                Fr::from(7u64),
+            ]),
+        ];
+        assert!(check_permutation(&permutation, &witnesses)?.is_empty());
+
+        // breaking (1, 3) breaks the two edges of the cycle through it
+        witnesses[1].0[3] = Fr::from(8u64);
+        let violations = check_permutation(&permutation, &witnesses)?;
+        assert_eq!(violations.len(), 2);
+        assert_eq!(violations[0].cell, Cell::new(1, 1));
+        assert_eq!(violations[0].target, Cell::new(1, 3));
+        assert_eq!(violations[0].target_value, Fr::from(8u64));
+        assert_eq!(violations[1].cell, Cell::new(1, 3));
+        assert_eq!(violations[1].target, Cell::new(0, 0));
+
+        // not a permutation
+        let mut bad_permutation = permutation.clone();
+        bad_permutation[0] = Fr::from(1u64);
+        assert!(check_permutation(&bad_permutation, &witnesses).is_err());
+        bad_permutation[0] = Fr::from(8u64);
+        assert!(check_permutation(&bad_permutation, &witnesses).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_copy_constraints_e2e() -> Result<(), HyperPlonkErrors> {
+        let _trace = crate::trace_guard();
+        // the chain a_{i+1} = a_i^5, with the gate `q w_1^5 - w_2 = 0` and
+        // the copy constraints (1, i) = (0, i + 1)
+        let nv = 3;
+        let num_rows = 1 << nv;
+        let gate_func = CustomizedGates {
+            gates: vec![(1, Some(0), vec![0, 0, 0, 0, 0]), (-1, None, vec![1])],
+        };
+        let mut w1 = vec![Fr::from(2u64)];
+        let mut w2 = vec![];
+        for i in 0..num_rows {
+            w2.push(w1[i].pow([5u64]));
+            if i + 1 < num_rows {
+                w1.push(w2[i]);
+            }
+        }
+        let equalities: Vec<(Cell, Cell)> = (0..num_rows - 1)
+            .map(|i| (Cell::new(1, i), Cell::new(0, i + 1)))
+            .collect();
+        let permutation = build_permutation(nv, 2, &equalities)?;
+        let index = HyperPlonkIndex {
+            params: HyperPlonkParams {
+                num_constraints: num_rows,
+                num_pub_input: 1,
+                gate_func,
+                blinded: false,
+                lookups: vec![],
+                range_checks: vec![],
+            },
+            permutation,
+            selectors: vec![SelectorColumn(vec![Fr::one(); num_rows])],
+            lookup_selectors: vec![],
+            tables: vec![],
+        };
+
+        let mut rng = test_rng();
+        let pcs_srs = Kzg::gen_srs_for_testing(&mut rng, 8)?;
+        let (pk, vk) = <PolyIOP<Fr> as HyperPlonkSNARK<Fr, Kzg>>::preprocess(&index, &pcs_srs)?;
+        let witnesses = vec![WitnessColumn(w1.clone()), WitnessColumn(w2)];
+        assert!(check_permutation(&index.permutation, &witnesses)?.is_empty());
+        let proof = <PolyIOP<Fr> as HyperPlonkSNARK<Fr, Kzg>>::prove(&pk, &w1[..1], &witnesses)?;
+        assert!(<PolyIOP<Fr> as HyperPlonkSNARK<Fr, Kzg>>::verify(
+            &vk,
+            &w1[..1],
+            &proof
+        )?);
+
+        // restart the chain at row 4: every row satisfies the gate, but the
+        // copy constraint (1, 3) = (0, 4) is broken
+        let mut bad_witnesses = witnesses;
+        bad_witnesses[0].0[4] = Fr::from(3u64);
+        for i in 4..num_rows {
+            bad_witnesses[1].0[i] = bad_witnesses[0].0[i].pow([5u64]);
+            if i + 1 < num_rows {
+                bad_witnesses[0].0[i + 1] = bad_witnesses[1].0[i];
+            }
+        }
+        let violations = check_permutation(&index.permutation, &bad_witnesses)?;
+        assert_eq!(violations.len(), 2);
+        assert_eq!(violations[0].cell, Cell::new(0, 4));
+        assert_eq!(violations[1].cell, Cell::new(1, 3));
+        let res = <PolyIOP<Fr> as HyperPlonkSNARK<Fr, Kzg>>::prove(&pk, &w1[..1], &bad_witnesses)
+            .and_then(|proof| {
+                <PolyIOP<Fr> as HyperPlonkSNARK<Fr, Kzg>>::verify(&vk, &w1[..1], &proof)
+            });
+        assert!(!res.unwrap_or(false));
+        Ok(())
+    }
+}
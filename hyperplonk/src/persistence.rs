@@ -0,0 +1,183 @@
+// Copyright (c) 2023 Espresso Systems (espressosys.com)
+// This file is part of the HyperPlonk library.
+
+// You should have received a copy of the MIT License
+// along with the HyperPlonk library. If not, see <https://mit-license.org/>.
+
+//! Saving and loading keys and parameters to and from files.
+//!
+//! A file holds the uncompressed serialization of the value, which is faster
+//! to load than the compressed one, followed by its 32-byte SHA3-256
+//! checksum. The checksum is always checked before deserializing, so that a
+//! corrupted file is rejected rather than read as garbage.
+
+use crate::{
+    errors::HyperPlonkErrors,
+    structs::{HyperPlonkProvingKey, HyperPlonkVerifyingKey},
+};
+use ark_ff::PrimeField;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, Compress, Validate};
+use ark_std::{end_timer, start_timer};
+use sha3::{Digest, Sha3_256};
+use std::{fs, path::Path};
+use subroutines::pcs::PolynomialCommitmentScheme;
+
+const CHECKSUM_SIZE: usize = 32;
+
+/// Write `value` to the file at `path`, replacing it if it exists.
+pub fn save_to_file<S: CanonicalSerialize>(
+    value: &S,
+    path: impl AsRef<Path>,
+) -> Result<(), HyperPlonkErrors> {
+    let timer = start_timer!(|| format!("save to {}", path.as_ref().display()));
+    let mut bytes = Vec::with_capacity(value.serialized_size(Compress::No) + CHECKSUM_SIZE);
+    value.serialize_uncompressed(&mut bytes)?;
+    let checksum = Sha3_256::digest(&bytes);
+    bytes.extend_from_slice(&checksum);
+    fs::write(path, bytes).map_err(ark_serialize::SerializationError::from)?;
+    end_timer!(timer);
+    Ok(())
+}
+
+/// Read a value written by `save_to_file` from the file at `path`.
+///
+/// With `Validate::No`, group elements are not checked to be on the curve
+/// and in the subgroup, which is much faster but only safe for files that
+/// were written by a trusted party; the checksum is checked in both cases.
+pub fn load_from_file<S: CanonicalDeserialize>(
+    path: impl AsRef<Path>,
+    validate: Validate,
+) -> Result<S, HyperPlonkErrors> {
+    let path = path.as_ref();
+    let timer = start_timer!(|| format!("load from {}", path.display()));
+    let bytes = fs::read(path).map_err(ark_serialize::SerializationError::from)?;
+    if bytes.len() < CHECKSUM_SIZE {
+        return Err(HyperPlonkErrors::InvalidParameters(format!(
+            "{} is too short to hold a checksum",
+            path.display()
+        )));
+    }
+    let (body, checksum) = bytes.split_at(bytes.len() - CHECKSUM_SIZE);
+    if Sha3_256::digest(body).as_slice() != checksum {
+        return Err(HyperPlonkErrors::InvalidParameters(format!(
+            "checksum mismatch in {}",
+            path.display()
+        )));
+    }
+    let res = S::deserialize_with_mode(body, Compress::No, validate)?;
+    end_timer!(timer);
+    Ok(res)
+}
+
+impl<F: PrimeField, PCS: PolynomialCommitmentScheme<F>> HyperPlonkProvingKey<F, PCS> {
+    /// Save the proving key, including the trimmed PCS prover parameters, so
+    /// that it can be loaded instead of running `preprocess` again.
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), HyperPlonkErrors> {
+        save_to_file(self, path)
+    }
+
+    /// Load a proving key written by `save`, checking every group element.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, HyperPlonkErrors> {
+        load_from_file(path, Validate::Yes)
+    }
+
+    /// Load a proving key written by `save`, skipping the curve and subgroup
+    /// checks of the group elements. Only use it for trusted files.
+    pub fn load_unchecked(path: impl AsRef<Path>) -> Result<Self, HyperPlonkErrors> {
+        load_from_file(path, Validate::No)
+    }
+}
+
+impl<F: PrimeField, PCS: PolynomialCommitmentScheme<F>> HyperPlonkVerifyingKey<F, PCS> {
+    /// Save the verifying key.
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), HyperPlonkErrors> {
+        save_to_file(self, path)
+    }
+
+    /// Load a verifying key written by `save`, checking every group element.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, HyperPlonkErrors> {
+        load_from_file(path, Validate::Yes)
+    }
+
+    /// Load a verifying key written by `save`, skipping the curve and
+    /// subgroup checks of the group elements. Only use it for trusted files.
+    pub fn load_unchecked(path: impl AsRef<Path>) -> Result<Self, HyperPlonkErrors> {
+        load_from_file(path, Validate::No)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{custom_gate::CustomizedGates, mock::MockCircuit, HyperPlonkSNARK};
+    use ark_bls12_381::{Bls12_381, Fr};
+    use ark_std::test_rng;
+    use std::path::PathBuf;
+    use subroutines::{
+        pcs::prelude::{MultilinearKzgPCS, MultilinearUniversalParams},
+        poly_iop::PolyIOP,
+    };
+
+    type Pcs = MultilinearKzgPCS<Bls12_381>;
+
+    fn temp_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("hyperplonk-{}-{}", std::process::id(), name))
+    }
+
+    #[test]
+    fn test_save_and_load_keys() -> Result<(), HyperPlonkErrors> {
+        let _trace = crate::trace_guard();
+        let mut rng = test_rng();
+        let pcs_srs = Pcs::gen_srs_for_testing(&mut rng, 6)?;
+        let circuit = MockCircuit::<Fr>::new(16, &CustomizedGates::vanilla_plonk_gate());
+        let (pk, vk) =
+            <PolyIOP<Fr> as HyperPlonkSNARK<Fr, Pcs>>::preprocess(&circuit.index, &pcs_srs)?;
+
+        let srs_path = temp_path("srs.params");
+        save_to_file(&pcs_srs, &srs_path)?;
+        let srs2: MultilinearUniversalParams<Bls12_381> = load_from_file(&srs_path, Validate::No)?;
+        assert_eq!(srs2.h_mask, pcs_srs.h_mask);
+        fs::remove_file(&srs_path).unwrap();
+
+        let pk_path = temp_path("pk");
+        let vk_path = temp_path("vk");
+        pk.save(&pk_path)?;
+        vk.save(&vk_path)?;
+        for (pk2, vk2) in [
+            (
+                HyperPlonkProvingKey::<Fr, Pcs>::load(&pk_path)?,
+                HyperPlonkVerifyingKey::<Fr, Pcs>::load(&vk_path)?,
+            ),
+            (
+                HyperPlonkProvingKey::<Fr, Pcs>::load_unchecked(&pk_path)?,
+                HyperPlonkVerifyingKey::<Fr, Pcs>::load_unchecked(&vk_path)?,
+            ),
+        ] {
+            let proof = <PolyIOP<Fr> as HyperPlonkSNARK<Fr, Pcs>>::prove(
+                &pk2,
+                &circuit.public_inputs,
+                &circuit.witnesses,
+            )?;
+            assert!(<PolyIOP<Fr> as HyperPlonkSNARK<Fr, Pcs>>::verify(
+                &vk2,
+                &circuit.public_inputs,
+                &proof
+            )?);
+        }
+
+        // a flipped bit is caught by the checksum, even without validation
+        let mut bytes = fs::read(&pk_path).unwrap();
+        bytes[100] ^= 1;
+        fs::write(&pk_path, &bytes).unwrap();
+        assert!(HyperPlonkProvingKey::<Fr, Pcs>::load_unchecked(&pk_path).is_err());
+
+        // a truncated file
+        fs::write(&vk_path, [0u8; 16]).unwrap();
+        assert!(HyperPlonkVerifyingKey::<Fr, Pcs>::load(&vk_path).is_err());
+        // a missing file
+        fs::remove_file(&pk_path).unwrap();
+        fs::remove_file(&vk_path).unwrap();
+        assert!(HyperPlonkVerifyingKey::<Fr, Pcs>::load(&vk_path).is_err());
+        Ok(())
+    }
+}
@@ -5,6 +5,17 @@
 // along with the HyperPlonk library. If not, see <https://mit-license.org/>.
 
 pub use crate::{
-    custom_gate::CustomizedGates, errors::HyperPlonkErrors, mock::MockCircuit,
-    selectors::SelectorColumn, witness::WitnessColumn, HyperPlonkSNARK,
+    circuit::{Circuit, CircuitBuilder, Variable},
+    custom_gate::CustomizedGates,
+    errors::HyperPlonkErrors,
+    lookup::{LookupGate, TableColumn},
+    mock::MockCircuit,
+    permutation::{build_permutation, check_permutation, Cell, CopyConstraints, CopyViolation},
+    persistence::{load_from_file, save_to_file},
+    range_check::RangeCheckGate,
+    satisfaction::{check_satisfaction, GateViolation, PublicInputViolation, SatisfactionReport},
+    selectors::SelectorColumn,
+    serialization::SERIALIZATION_VERSION,
+    witness::WitnessColumn,
+    HyperPlonkSNARK,
 };
@@ -0,0 +1,62 @@
+// Copyright (c) 2023 Espresso Systems (espressosys.com)
+// This file is part of the HyperPlonk library.
+
+// You should have received a copy of the MIT License
+// along with the HyperPlonk library. If not, see <https://mit-license.org/>.
+
+//! Range check gates.
+//!
+//! A range check gate asserts that every value of a witness column lies in
+//! `[0, 2^num_bits)`. It is proven with a `RangeCheck` on a public range
+//! table, so it needs no preprocessed column, and costs a few lookups per row
+//! instead of one constraint per bit of a decomposition gate.
+
+use crate::{errors::HyperPlonkErrors, structs::HyperPlonkParams};
+use ark_ff::PrimeField;
+
+/// A range check gate: the values of the witness column `witness` are in
+/// `[0, 2^num_bits)`.
+///
+/// The values are decomposed into limbs of `min(num_bits, n)` bits for a
+/// circuit of `n` variables, so that the range table has as many entries as
+/// the circuit has rows.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct RangeCheckGate {
+    /// the index of the witness column
+    pub witness: usize,
+    /// the number of bits of the range
+    pub num_bits: usize,
+}
+
+impl RangeCheckGate {
+    /// The number of bits of the limbs for a circuit of `num_vars` variables.
+    pub(crate) fn limb_bits(&self, num_vars: usize) -> usize {
+        self.num_bits.min(num_vars)
+    }
+
+    /// The number of limbs committed by the prover, i.e. all but the top one.
+    pub(crate) fn num_committed_limbs(&self, num_vars: usize) -> usize {
+        self.num_bits.div_ceil(self.limb_bits(num_vars)) - 1
+    }
+}
+
+/// Check that the range check gates of the parameters refer to existing
+/// witness columns, with ranges that fit in the field, in a circuit of at
+/// least one variable.
+pub(crate) fn check_range_checks<F: PrimeField>(
+    params: &HyperPlonkParams,
+) -> Result<(), HyperPlonkErrors> {
+    for (i, range_check) in params.range_checks.iter().enumerate() {
+        if range_check.witness >= params.num_witness_columns()
+            || params.num_variables() == 0
+            || range_check.num_bits == 0
+            || range_check.num_bits >= F::MODULUS_BIT_SIZE as usize
+        {
+            return Err(HyperPlonkErrors::InvalidParameters(format!(
+                "{}-th range check gate is invalid: {:?}",
+                i, range_check
+            )));
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,511 @@
+// Copyright (c) 2023 Espresso Systems (espressosys.com)
+// This file is part of the HyperPlonk library.
+
+// You should have received a copy of the MIT License
+// along with the HyperPlonk library. If not, see <https://mit-license.org/>.
+
+//! Row-level diagnostics of a witness against a HyperPlonk index.
+
+use crate::{
+    errors::HyperPlonkErrors,
+    permutation::{check_permutation, CopyViolation},
+    structs::{HyperPlonkIndex, HyperPlonkParams},
+    witness::WitnessColumn,
+};
+use ark_ff::{BigInteger, PrimeField};
+use std::{collections::HashSet, fmt};
+
+/// A row on which the customized gate does not vanish.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GateViolation<F: PrimeField> {
+    /// the row
+    pub row: usize,
+    /// the value of each monomial of the gate, in the order of
+    /// `CustomizedGates`
+    pub monomials: Vec<F>,
+    /// the sum of the monomials, i.e. the value of the gate
+    pub value: F,
+}
+
+/// A public input that does not match the first witness column.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PublicInputViolation<F: PrimeField> {
+    /// the index of the public input, which is also the row
+    pub index: usize,
+    /// the public input
+    pub public_input: F,
+    /// the value of the first witness column on that row
+    pub witness: F,
+}
+
+/// A looked up value that is not an entry of the table of its lookup gate.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LookupViolation<F: PrimeField> {
+    /// the index of the lookup gate
+    pub lookup: usize,
+    /// the row
+    pub row: usize,
+    /// the witness column looked up
+    pub witness: usize,
+    /// the value missing from the table
+    pub value: F,
+}
+
+/// A value out of the range of its range check gate.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RangeCheckViolation<F: PrimeField> {
+    /// the index of the range check gate
+    pub range_check: usize,
+    /// the row
+    pub row: usize,
+    /// the value of at least `num_bits` bits
+    pub value: F,
+}
+
+/// The result of `check_satisfaction`: every failing row, broken wire
+/// equality, mismatching public input, value missing from a lookup table and
+/// value out of range.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SatisfactionReport<F: PrimeField> {
+    pub gate_violations: Vec<GateViolation<F>>,
+    pub copy_violations: Vec<CopyViolation<F>>,
+    pub public_input_violations: Vec<PublicInputViolation<F>>,
+    pub lookup_violations: Vec<LookupViolation<F>>,
+    pub range_check_violations: Vec<RangeCheckViolation<F>>,
+}
+
+impl<F: PrimeField> SatisfactionReport<F> {
+    /// Whether the witness satisfies the circuit.
+    pub fn is_satisfied(&self) -> bool {
+        self.gate_violations.is_empty()
+            && self.copy_violations.is_empty()
+            && self.public_input_violations.is_empty()
+            && self.lookup_violations.is_empty()
+            && self.range_check_violations.is_empty()
+    }
+}
+
+impl<F: PrimeField> fmt::Display for SatisfactionReport<F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_satisfied() {
+            return write!(f, "the circuit is satisfied");
+        }
+        write!(
+            f,
+            "the circuit is not satisfied: {} failing rows, {} broken wire equalities, {} wrong public inputs, \
+             {} values missing from lookup tables, {} values out of range",
+            self.gate_violations.len(),
+            self.copy_violations.len(),
+            self.public_input_violations.len(),
+            self.lookup_violations.len(),
+            self.range_check_violations.len()
+        )?;
+        for v in self.gate_violations.iter() {
+            write!(f, "\n  row {}: gate = {}, monomials = [", v.row, v.value)?;
+            for (i, m) in v.monomials.iter().enumerate() {
+                if i > 0 {
+                    write!(f, ", ")?;
+                }
+                write!(f, "{}", m)?;
+            }
+            write!(f, "]")?;
+        }
+        for v in self.copy_violations.iter() {
+            write!(
+                f,
+                "\n  cell ({}, {}) = {} is wired to cell ({}, {}) = {}",
+                v.cell.col, v.cell.row, v.value, v.target.col, v.target.row, v.target_value
+            )?;
+        }
+        for v in self.public_input_violations.iter() {
+            write!(
+                f,
+                "\n  public input {} = {} but the witness is {}",
+                v.index, v.public_input, v.witness
+            )?;
+        }
+        for v in self.lookup_violations.iter() {
+            write!(
+                f,
+                "\n  lookup {}: cell ({}, {}) = {} is not in the table",
+                v.lookup, v.witness, v.row, v.value
+            )?;
+        }
+        for v in self.range_check_violations.iter() {
+            write!(
+                f,
+                "\n  range check {}: row {} = {} is out of range",
+                v.range_check, v.row, v.value
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Check a witness against an index: evaluate the customized gate on every
+/// row, check the copy constraints, check that the public input is the
+/// prefix of the first witness column, look up the witnesses of every lookup
+/// gate in its table, and check the ranges of the range check gates.
+///
+/// Returns an error if the dimensions of the inputs do not match the index,
+/// and a report of every violation otherwise.
+pub fn check_satisfaction<F: PrimeField>(
+    index: &HyperPlonkIndex<F>,
+    pub_input: &[F],
+    witnesses: &[WitnessColumn<F>],
+) -> Result<SatisfactionReport<F>, HyperPlonkErrors> {
+    let selectors: Vec<&[F]> = index.selectors.iter().map(|s| s.0.as_ref()).collect();
+    let lookup_selectors: Vec<&[F]> = index
+        .lookup_selectors
+        .iter()
+        .map(|s| s.0.as_ref())
+        .collect();
+    let tables: Vec<&[F]> = index.tables.iter().map(|t| t.0.as_ref()).collect();
+    check_satisfaction_internal(
+        &index.params,
+        &selectors,
+        &index.permutation,
+        &lookup_selectors,
+        &tables,
+        pub_input,
+        witnesses,
+    )
+}
+
+/// `check_satisfaction` over the columns of an index, so that it can also run
+/// on the preprocessed polynomials of a proving key.
+///
+/// The columns cover the whole hypercube of `params`, but the gate is only
+/// checked on the first `num_constraints` rows; for zero-knowledge keys the
+/// rows above are blinding rows.
+pub(crate) fn check_satisfaction_internal<F: PrimeField>(
+    params: &HyperPlonkParams,
+    selectors: &[&[F]],
+    permutation: &[F],
+    lookup_selectors: &[&[F]],
+    tables: &[&[F]],
+    pub_input: &[F],
+    witnesses: &[WitnessColumn<F>],
+) -> Result<SatisfactionReport<F>, HyperPlonkErrors> {
+    let num_rows = 1 << params.num_variables();
+    if selectors.len() != params.num_selector_columns()
+        || selectors.iter().any(|s| s.len() != num_rows)
+    {
+        return Err(HyperPlonkErrors::InvalidParameters(format!(
+            "expect {} selector columns of length {}",
+            params.num_selector_columns(),
+            num_rows
+        )));
+    }
+    if witnesses.len() != params.num_witness_columns()
+        || witnesses.iter().any(|w| w.0.len() != num_rows)
+    {
+        return Err(HyperPlonkErrors::InvalidParameters(format!(
+            "expect {} witness columns of length {}",
+            params.num_witness_columns(),
+            num_rows
+        )));
+    }
+    if lookup_selectors.len() != params.num_lookups()
+        || tables.len() != params.num_table_columns()
+        || lookup_selectors
+            .iter()
+            .chain(tables.iter())
+            .any(|c| c.len() != num_rows)
+        || params
+            .lookups
+            .iter()
+            .any(|l| l.witnesses.iter().any(|&w| w >= witnesses.len()))
+        || params
+            .range_checks
+            .iter()
+            .any(|r| r.witness >= witnesses.len())
+    {
+        return Err(HyperPlonkErrors::InvalidParameters(format!(
+            "expect {} lookup selectors and {} tables of length {}, and lookups and range checks \
+             on existing witness columns",
+            params.num_lookups(),
+            params.num_table_columns(),
+            num_rows
+        )));
+    }
+    if pub_input.len() > params.num_constraints {
+        return Err(HyperPlonkErrors::InvalidParameters(format!(
+            "public input length {} is greater than num constraints {}",
+            pub_input.len(),
+            params.num_constraints
+        )));
+    }
+
+    let mut gate_violations = vec![];
+    for row in 0..params.num_constraints {
+        let row_selectors: Vec<F> = selectors.iter().map(|s| s[row]).collect();
+        let row_witnesses: Vec<F> = witnesses.iter().map(|w| w.0[row]).collect();
+        let monomials = params
+            .gate_func
+            .evaluate_monomials(&row_selectors, &row_witnesses);
+        let value: F = monomials.iter().sum();
+        if !value.is_zero() {
+            gate_violations.push(GateViolation {
+                row,
+                monomials,
+                value,
+            });
+        }
+    }
+
+    let copy_violations = check_permutation(permutation, witnesses)?;
+
+    let public_input_violations = pub_input
+        .iter()
+        .zip(witnesses[0].0.iter())
+        .enumerate()
+        .filter(|(_, (pi, w))| pi != w)
+        .map(|(index, (&public_input, &witness))| PublicInputViolation {
+            index,
+            public_input,
+            witness,
+        })
+        .collect();
+
+    // the lookups cover the whole hypercube, like the lookup checks
+    let mut lookup_violations = vec![];
+    for (lookup, (gate, selector)) in params.lookups.iter().zip(lookup_selectors).enumerate() {
+        let table: HashSet<F> = tables[gate.table].iter().copied().collect();
+        for (row, _) in selector.iter().enumerate().filter(|(_, q)| !q.is_zero()) {
+            for &witness in gate.witnesses.iter() {
+                let value = witnesses[witness].0[row];
+                if !table.contains(&value) {
+                    lookup_violations.push(LookupViolation {
+                        lookup,
+                        row,
+                        witness,
+                        value,
+                    });
+                }
+            }
+        }
+    }
+
+    // so do the range checks
+    let mut range_check_violations = vec![];
+    for (range_check, gate) in params.range_checks.iter().enumerate() {
+        for (row, value) in witnesses[gate.witness].0.iter().enumerate() {
+            if value.into_bigint().num_bits() as usize > gate.num_bits {
+                range_check_violations.push(RangeCheckViolation {
+                    range_check,
+                    row,
+                    value: *value,
+                });
+            }
+        }
+    }
+
+    Ok(SatisfactionReport {
+        gate_violations,
+        copy_violations,
+        public_input_violations,
+        lookup_violations,
+        range_check_violations,
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        custom_gate::CustomizedGates,
+        lookup::{LookupGate, TableColumn},
+        mock::MockCircuit,
+        permutation::{build_permutation, Cell},
+        range_check::RangeCheckGate,
+        selectors::SelectorColumn,
+    };
+    use ark_bls12_381::Fr;
+    use ark_std::One;
+
+    #[test]
+    fn test_check_satisfaction() -> Result<(), HyperPlonkErrors> {
+        let mut circuit = MockCircuit::<Fr>::new(8, &CustomizedGates::vanilla_plonk_gate());
+        let report =
+            check_satisfaction(&circuit.index, &circuit.public_inputs, &circuit.witnesses)?;
+        assert!(report.is_satisfied());
+
+        // the last row, which `is_satisfied` used to skip, is broken
+        circuit.witnesses[2].0[7] += Fr::one();
+        let report =
+            check_satisfaction(&circuit.index, &circuit.public_inputs, &circuit.witnesses)?;
+        assert!(!report.is_satisfied());
+        assert!(!circuit.is_satisfied());
+        assert_eq!(report.gate_violations.len(), 1);
+        let violation = &report.gate_violations[0];
+        assert_eq!(violation.row, 7);
+        assert_eq!(violation.monomials.len(), 5);
+        // the monomial `q_O w_3` is off by `q_O`
+        assert_eq!(violation.value, circuit.index.selectors[2].0[7]);
+
+        // wrong public input, and a broken wire equality
+        let mut bad_pi = circuit.public_inputs.clone();
+        bad_pi[1] = Fr::from(42u64);
+        circuit.index.permutation = build_permutation(3, 3, &[(Cell::new(0, 0), Cell::new(1, 5))])?;
+        let report = check_satisfaction(&circuit.index, &bad_pi, &circuit.witnesses)?;
+        assert_eq!(report.public_input_violations.len(), 1);
+        assert_eq!(report.public_input_violations[0].index, 1);
+        assert_eq!(
+            report.public_input_violations[0].public_input,
+            Fr::from(42u64)
+        );
+        assert_eq!(report.copy_violations.len(), 2);
+        assert_eq!(report.copy_violations[0].target, Cell::new(1, 5));
+        let message = report.to_string();
+        assert!(message
+            .contains("1 failing rows, 2 broken wire equalities, 1 wrong public inputs, 0 values"));
+        assert!(message.contains("row 7"));
+        assert!(message.contains("cell (0, 0)"));
+
+        // wrong dimensions
+        assert!(check_satisfaction(&circuit.index, &bad_pi, &circuit.witnesses[1..]).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_check_lookups() -> Result<(), HyperPlonkErrors> {
+        let mut circuit = MockCircuit::<Fr>::new(8, &CustomizedGates::vanilla_plonk_gate());
+        // the second witness column is looked up into a copy of itself on the
+        // odd rows
+        circuit.index.params.lookups = vec![LookupGate {
+            witnesses: vec![1],
+            table: 0,
+        }];
+        circuit.index.lookup_selectors = vec![SelectorColumn(
+            (0..8).map(|i| Fr::from((i % 2) as u64)).collect(),
+        )];
+        circuit.index.tables = vec![TableColumn(circuit.witnesses[1].0.clone())];
+        assert!(circuit.is_satisfied());
+
+        // the entry of an even row is not looked up
+        circuit.index.tables[0].0[4] = Fr::from(42u64);
+        assert!(circuit.is_satisfied());
+        // but that of an odd row is
+        let value = circuit.witnesses[1].0[5];
+        circuit.index.tables[0].0[5] = Fr::from(42u64);
+        let report =
+            check_satisfaction(&circuit.index, &circuit.public_inputs, &circuit.witnesses)?;
+        assert!(!report.is_satisfied());
+        assert!(!circuit.is_satisfied());
+        assert!(report.gate_violations.is_empty());
+        assert_eq!(
+            report.lookup_violations,
+            vec![LookupViolation {
+                lookup: 0,
+                row: 5,
+                witness: 1,
+                value,
+            }]
+        );
+        let message = report.to_string();
+        assert!(message.contains("1 values missing from lookup tables, 0 values"));
+        assert!(message.contains("lookup 0: cell (1, 5)"));
+
+        // a lookup gate without a table
+        circuit.index.tables.clear();
+        assert!(
+            check_satisfaction(&circuit.index, &circuit.public_inputs, &circuit.witnesses).is_err()
+        );
+        assert!(!circuit.is_satisfied());
+        Ok(())
+    }
+
+    #[test]
+    fn test_check_range_checks() -> Result<(), HyperPlonkErrors> {
+        // q_1 * W_1 - 4 * q_2 * W_2 - q_3 * W_3 = 0, with W_3 in 2 bits
+        let gate_func = CustomizedGates {
+            gates: vec![
+                (1, Some(0), vec![0]),
+                (-4, Some(1), vec![1]),
+                (-1, Some(2), vec![2]),
+            ],
+        };
+        let mut circuit = MockCircuit::<Fr>::new(4, &gate_func);
+        circuit.index.selectors = vec![SelectorColumn(vec![Fr::one(); 4]); 3];
+        circuit.witnesses = [[1u64, 5, 14, 3], [0, 1, 3, 0], [1, 1, 2, 3]]
+            .iter()
+            .map(|w| WitnessColumn(w.iter().map(|&v| Fr::from(v)).collect()))
+            .collect();
+        circuit.public_inputs = vec![Fr::one()];
+        circuit.index.params.num_pub_input = 1;
+        circuit.index.params.range_checks = vec![RangeCheckGate {
+            witness: 2,
+            num_bits: 2,
+        }];
+        assert!(circuit.is_satisfied());
+
+        // 4 * 1 + 4 = 8 satisfies the gate, but not the range of W_3
+        circuit.witnesses[0].0[1] = Fr::from(8u64);
+        circuit.witnesses[2].0[1] = Fr::from(4u64);
+        let report =
+            check_satisfaction(&circuit.index, &circuit.public_inputs, &circuit.witnesses)?;
+        assert!(!report.is_satisfied());
+        assert!(!circuit.is_satisfied());
+        assert!(report.gate_violations.is_empty());
+        assert_eq!(
+            report.range_check_violations,
+            vec![RangeCheckViolation {
+                range_check: 0,
+                row: 1,
+                value: Fr::from(4u64),
+            }]
+        );
+        let message = report.to_string();
+        assert!(message.contains("1 values out of range"));
+        assert!(message.contains("range check 0: row 1 = 4"));
+
+        // and neither does -1 = p - 1
+        circuit.witnesses[0].0[1] = Fr::from(3u64);
+        circuit.witnesses[2].0[1] = -Fr::one();
+        let report =
+            check_satisfaction(&circuit.index, &circuit.public_inputs, &circuit.witnesses)?;
+        assert!(report.gate_violations.is_empty());
+        assert_eq!(report.range_check_violations.len(), 1);
+
+        // a range check on a missing witness column
+        circuit.index.params.range_checks[0].witness = 3;
+        assert!(
+            check_satisfaction(&circuit.index, &circuit.public_inputs, &circuit.witnesses).is_err()
+        );
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "extensive_sanity_checks")]
+    fn test_prove_checks_satisfaction() -> Result<(), HyperPlonkErrors> {
+        let _trace = crate::trace_guard();
+        use crate::HyperPlonkSNARK;
+        use ark_bls12_381::Bls12_381;
+        use ark_std::test_rng;
+        use subroutines::{
+            pcs::{prelude::MultilinearKzgPCS, PolynomialCommitmentScheme},
+            poly_iop::PolyIOP,
+        };
+
+        let mut circuit = MockCircuit::<Fr>::new(8, &CustomizedGates::vanilla_plonk_gate());
+        let mut rng = test_rng();
+        let pcs_srs = MultilinearKzgPCS::<Bls12_381>::gen_srs_for_testing(&mut rng, 8)?;
+        let (pk, _) =
+            <PolyIOP<Fr> as HyperPlonkSNARK<Fr, MultilinearKzgPCS<Bls12_381>>>::preprocess(
+                &circuit.index,
+                &pcs_srs,
+            )?;
+
+        circuit.witnesses[1].0[6] += Fr::one();
+        match <PolyIOP<Fr> as HyperPlonkSNARK<Fr, MultilinearKzgPCS<Bls12_381>>>::prove(
+            &pk,
+            &circuit.public_inputs,
+            &circuit.witnesses,
+        ) {
+            Err(e) => assert!(e.to_string().contains("row 6")),
+            Ok(_) => panic!("proved an unsatisfied circuit"),
+        }
+        Ok(())
+    }
+}
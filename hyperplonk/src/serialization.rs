@@ -0,0 +1,1033 @@
+// Copyright (c) 2023 Espresso Systems (espressosys.com)
+// This file is part of the HyperPlonk library.
+
+// You should have received a copy of the MIT License
+// along with the HyperPlonk library. If not, see <https://mit-license.org/>.
+
+//! Canonical serialization of HyperPlonk proofs, keys and parameters.
+//!
+//! Proofs, proving keys, verifying keys, and parameters and gates serialized
+//! on their own start with an 8-byte header:
+//!   - the magic bytes `b"HPLK"`
+//!   - the kind of the artifact: 0 for a proof, 1 for a proving key, 2 for a
+//!     verifying key, 3 for parameters and 4 for a gate
+//!   - the format version as a little-endian `u16`, see
+//!     `SERIALIZATION_VERSION`
+//!   - 1 if the body is compressed and 0 otherwise
+//!
+//! followed by the fields of the struct in declaration order. The parameters
+//! and the gate embedded in a key have no header of their own, and are read
+//! in the version of the key. When the format
+//! changes, `SERIALIZATION_VERSION` is bumped and the readers keep a branch
+//! for every older version, so that stored artifacts remain readable.
+//!
+//! Versions:
+//!   1. the initial format
+//!   2. `HyperPlonkParams` ends with the `blinded` flag; version 1 keys
+//!      are read as keys without blinding rows
+//!   3. `HyperPlonkParams` ends with the lookup gates, keys end with the lookup
+//!      selector and table polynomials and commitments, and proofs end with
+//!      the lookup check proofs; older artifacts are read as circuits without
+//!      lookup gates
+//!   4. `HyperPlonkParams` ends with the range check gates, and proofs end with
+//!      the range check proofs; older artifacts are read as circuits without
+//!      range check gates
+//!   5. proofs end with the masks of the zero check and of the permutation
+//!      check of zero-knowledge proofs; older proofs are read as proofs that
+//!      are not zero-knowledge
+//!
+//! On deserialization with `Validate::Yes`, group elements are checked to be
+//! on the curve and in the prime order subgroup, and the lengths of the
+//! preprocessed polynomials and commitments are checked against the
+//! parameters of the key.
+
+use crate::{
+    custom_gate::CustomizedGates,
+    lookup::LookupGate,
+    range_check::RangeCheckGate,
+    structs::{HyperPlonkParams, HyperPlonkProof, HyperPlonkProvingKey, HyperPlonkVerifyingKey},
+};
+use ark_ff::PrimeField;
+use ark_poly::{DenseMultilinearExtension, MultilinearExtension};
+use ark_serialize::{
+    CanonicalDeserialize, CanonicalSerialize, Compress, Read, SerializationError, Valid, Validate,
+    Write,
+};
+use std::sync::Arc;
+use subroutines::{
+    pcs::PolynomialCommitmentScheme,
+    poly_iop::prelude::{LookupCheck, PermutationCheck, RangeCheck},
+};
+
+/// The current version of the serialization format of proofs and keys.
+pub const SERIALIZATION_VERSION: u16 = 5;
+
+const MAGIC: [u8; 4] = *b"HPLK";
+const HEADER_SIZE: usize = 8;
+
+/// The kind of a serialized artifact, stored in its header.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum ArtifactKind {
+    Proof = 0,
+    ProvingKey = 1,
+    VerifyingKey = 2,
+    Params = 3,
+    Gate = 4,
+}
+
+fn write_header<W: Write>(
+    mut writer: W,
+    kind: ArtifactKind,
+    compress: Compress,
+) -> Result<(), SerializationError> {
+    writer.write_all(&MAGIC)?;
+    writer.write_all(&[kind as u8])?;
+    writer.write_all(&SERIALIZATION_VERSION.to_le_bytes())?;
+    writer.write_all(&[(compress == Compress::Yes) as u8])?;
+    Ok(())
+}
+
+/// Read and check a header, and return the format version.
+fn read_header<R: Read>(
+    mut reader: R,
+    kind: ArtifactKind,
+    compress: Compress,
+) -> Result<u16, SerializationError> {
+    let mut header = [0u8; HEADER_SIZE];
+    reader.read_exact(&mut header)?;
+    if header[..4] != MAGIC || header[4] != kind as u8 {
+        return Err(SerializationError::InvalidData);
+    }
+    let version = u16::from_le_bytes([header[5], header[6]]);
+    if version == 0 || version > SERIALIZATION_VERSION {
+        return Err(SerializationError::InvalidData);
+    }
+    // the body must be read in the mode it was written in
+    if header[7] != (compress == Compress::Yes) as u8 {
+        return Err(SerializationError::UnexpectedFlags);
+    }
+    Ok(version)
+}
+
+/// Check that there are `num` polynomials of `num_vars` variables.
+fn check_oracles<F: ark_ff::PrimeField>(
+    oracles: &[Arc<DenseMultilinearExtension<F>>],
+    num: usize,
+    num_vars: usize,
+) -> Result<(), SerializationError> {
+    if oracles.len() != num
+        || oracles
+            .iter()
+            .any(|p| p.num_vars() != num_vars || p.evaluations.len() != 1 << num_vars)
+    {
+        return Err(SerializationError::InvalidData);
+    }
+    Ok(())
+}
+
+/// Read a vector of lookup data in the format of `version`, without
+/// validation. Lookups were added in version 3, so older artifacts have none.
+fn read_lookup_field<R: Read, T: CanonicalDeserialize>(
+    reader: R,
+    compress: Compress,
+    version: u16,
+) -> Result<Vec<T>, SerializationError> {
+    match version {
+        1 | 2 => Ok(vec![]),
+        _ => Vec::deserialize_with_mode(reader, compress, Validate::No),
+    }
+}
+
+/// Read a vector of range check data in the format of `version`, without
+/// validation. Range checks were added in version 4, so older artifacts have
+/// none.
+fn read_range_check_field<R: Read, T: CanonicalDeserialize>(
+    reader: R,
+    compress: Compress,
+    version: u16,
+) -> Result<Vec<T>, SerializationError> {
+    match version {
+        1..=3 => Ok(vec![]),
+        _ => Vec::deserialize_with_mode(reader, compress, Validate::No),
+    }
+}
+
+/// Read an optional mask in the format of `version`, without validation.
+/// Masks were added in version 5, so older proofs have none.
+fn read_mask_field<R: Read, T: CanonicalDeserialize>(
+    reader: R,
+    compress: Compress,
+    version: u16,
+) -> Result<Option<T>, SerializationError> {
+    match version {
+        1..=4 => Ok(None),
+        _ => Option::deserialize_with_mode(reader, compress, Validate::No),
+    }
+}
+
+// ===========================================================================
+// CustomizedGates
+// ===========================================================================
+
+// `i64` has no canonical serialization, so the coefficients are written as
+// their two's complement `u64`.
+impl CanonicalSerialize for CustomizedGates {
+    fn serialize_with_mode<W: Write>(
+        &self,
+        mut writer: W,
+        compress: Compress,
+    ) -> Result<(), SerializationError> {
+        write_header(&mut writer, ArtifactKind::Gate, compress)?;
+        write_gate(self, writer, compress)
+    }
+
+    fn serialized_size(&self, compress: Compress) -> usize {
+        HEADER_SIZE + gate_size(self, compress)
+    }
+}
+
+/// Write a gate without header.
+fn write_gate<W: Write>(
+    gate: &CustomizedGates,
+    mut writer: W,
+    compress: Compress,
+) -> Result<(), SerializationError> {
+    gate.gates
+        .len()
+        .serialize_with_mode(&mut writer, compress)?;
+    for (coeff, selector, wires) in gate.gates.iter() {
+        (*coeff as u64).serialize_with_mode(&mut writer, compress)?;
+        selector.serialize_with_mode(&mut writer, compress)?;
+        wires.serialize_with_mode(&mut writer, compress)?;
+    }
+    Ok(())
+}
+
+fn gate_size(gate: &CustomizedGates, compress: Compress) -> usize {
+    gate.gates.len().serialized_size(compress)
+        + gate
+            .gates
+            .iter()
+            .map(|(coeff, selector, wires)| {
+                (*coeff as u64).serialized_size(compress)
+                    + selector.serialized_size(compress)
+                    + wires.serialized_size(compress)
+            })
+            .sum::<usize>()
+}
+
+impl Valid for CustomizedGates {
+    /// A gate has at least one monomial, every selector is used by exactly one
+    /// monomial, and the wires of each monomial are sorted.
+    fn check(&self) -> Result<(), SerializationError> {
+        if self.gates.is_empty() {
+            return Err(SerializationError::InvalidData);
+        }
+        let mut used = vec![false; self.num_selector_columns()];
+        for (_coeff, selector, wires) in self.gates.iter() {
+            if let Some(q) = selector {
+                match used.get_mut(*q) {
+                    Some(u) if !*u => *u = true,
+                    _ => return Err(SerializationError::InvalidData),
+                }
+            }
+            if wires.windows(2).any(|w| w[0] > w[1]) {
+                return Err(SerializationError::InvalidData);
+            }
+        }
+        Ok(())
+    }
+}
+
+impl CanonicalDeserialize for CustomizedGates {
+    fn deserialize_with_mode<R: Read>(
+        mut reader: R,
+        compress: Compress,
+        validate: Validate,
+    ) -> Result<Self, SerializationError> {
+        read_header(&mut reader, ArtifactKind::Gate, compress)?;
+        let res = read_gate(&mut reader, compress)?;
+        if validate == Validate::Yes {
+            res.check()?;
+        }
+        Ok(res)
+    }
+}
+
+/// Read a gate without header and without validation. The format of gates
+/// has not changed since version 1.
+fn read_gate<R: Read>(
+    mut reader: R,
+    compress: Compress,
+) -> Result<CustomizedGates, SerializationError> {
+    let len = usize::deserialize_with_mode(&mut reader, compress, Validate::No)?;
+    let mut gates = vec![];
+    for _ in 0..len {
+        let coeff = u64::deserialize_with_mode(&mut reader, compress, Validate::No)? as i64;
+        let selector = Option::deserialize_with_mode(&mut reader, compress, Validate::No)?;
+        let wires = Vec::deserialize_with_mode(&mut reader, compress, Validate::No)?;
+        gates.push((coeff, selector, wires));
+    }
+    Ok(CustomizedGates { gates })
+}
+
+// ===========================================================================
+// HyperPlonkParams
+// ===========================================================================
+
+impl CanonicalSerialize for HyperPlonkParams {
+    fn serialize_with_mode<W: Write>(
+        &self,
+        mut writer: W,
+        compress: Compress,
+    ) -> Result<(), SerializationError> {
+        write_header(&mut writer, ArtifactKind::Params, compress)?;
+        write_params(self, writer, compress)
+    }
+
+    fn serialized_size(&self, compress: Compress) -> usize {
+        HEADER_SIZE + params_size(self, compress)
+    }
+}
+
+/// Write parameters without header.
+fn write_params<W: Write>(
+    params: &HyperPlonkParams,
+    mut writer: W,
+    compress: Compress,
+) -> Result<(), SerializationError> {
+    params
+        .num_constraints
+        .serialize_with_mode(&mut writer, compress)?;
+    params
+        .num_pub_input
+        .serialize_with_mode(&mut writer, compress)?;
+    write_gate(&params.gate_func, &mut writer, compress)?;
+    params.blinded.serialize_with_mode(&mut writer, compress)?;
+    params
+        .lookups
+        .len()
+        .serialize_with_mode(&mut writer, compress)?;
+    for lookup in params.lookups.iter() {
+        lookup
+            .witnesses
+            .serialize_with_mode(&mut writer, compress)?;
+        lookup.table.serialize_with_mode(&mut writer, compress)?;
+    }
+    params
+        .range_checks
+        .len()
+        .serialize_with_mode(&mut writer, compress)?;
+    for range_check in params.range_checks.iter() {
+        range_check
+            .witness
+            .serialize_with_mode(&mut writer, compress)?;
+        range_check
+            .num_bits
+            .serialize_with_mode(&mut writer, compress)?;
+    }
+    Ok(())
+}
+
+fn params_size(params: &HyperPlonkParams, compress: Compress) -> usize {
+    params.num_constraints.serialized_size(compress)
+        + params.num_pub_input.serialized_size(compress)
+        + gate_size(&params.gate_func, compress)
+        + params.blinded.serialized_size(compress)
+        + params.lookups.len().serialized_size(compress)
+        + params
+            .lookups
+            .iter()
+            .map(|lookup| {
+                lookup.witnesses.serialized_size(compress) + lookup.table.serialized_size(compress)
+            })
+            .sum::<usize>()
+        + params.range_checks.len().serialized_size(compress)
+        + params
+            .range_checks
+            .iter()
+            .map(|range_check| {
+                range_check.witness.serialized_size(compress)
+                    + range_check.num_bits.serialized_size(compress)
+            })
+            .sum::<usize>()
+}
+
+impl Valid for HyperPlonkParams {
+    /// The number of constraints and of public inputs are powers of two, the
+    /// public inputs fit in the first witness column, every lookup gate looks
+    /// up some existing witness columns, and every range check gate checks an
+    /// existing witness column.
+    fn check(&self) -> Result<(), SerializationError> {
+        if !self.num_constraints.is_power_of_two()
+            || !self.num_pub_input.is_power_of_two()
+            || self.num_pub_input > self.num_constraints
+        {
+            return Err(SerializationError::InvalidData);
+        }
+        self.gate_func.check()?;
+        if self.lookups.iter().any(|lookup| {
+            lookup.witnesses.is_empty()
+                || lookup
+                    .witnesses
+                    .iter()
+                    .any(|&w| w >= self.num_witness_columns())
+        }) {
+            return Err(SerializationError::InvalidData);
+        }
+        if self.range_checks.iter().any(|range_check| {
+            range_check.witness >= self.num_witness_columns() || range_check.num_bits == 0
+        }) {
+            return Err(SerializationError::InvalidData);
+        }
+        Ok(())
+    }
+}
+
+impl CanonicalDeserialize for HyperPlonkParams {
+    fn deserialize_with_mode<R: Read>(
+        mut reader: R,
+        compress: Compress,
+        validate: Validate,
+    ) -> Result<Self, SerializationError> {
+        let version = read_header(&mut reader, ArtifactKind::Params, compress)?;
+        let res = read_params(&mut reader, compress, version)?;
+        if validate == Validate::Yes {
+            res.check()?;
+        }
+        Ok(res)
+    }
+}
+
+/// Read parameters in the format of `version`, without validation.
+fn read_params<R: Read>(
+    mut reader: R,
+    compress: Compress,
+    version: u16,
+) -> Result<HyperPlonkParams, SerializationError> {
+    Ok(HyperPlonkParams {
+        num_constraints: usize::deserialize_with_mode(&mut reader, compress, Validate::No)?,
+        num_pub_input: usize::deserialize_with_mode(&mut reader, compress, Validate::No)?,
+        gate_func: read_gate(&mut reader, compress)?,
+        blinded: match version {
+            1 => false,
+            _ => bool::deserialize_with_mode(&mut reader, compress, Validate::No)?,
+        },
+        lookups: match version {
+            1 | 2 => vec![],
+            _ => {
+                let len = usize::deserialize_with_mode(&mut reader, compress, Validate::No)?;
+                let mut lookups = vec![];
+                for _ in 0..len {
+                    lookups.push(LookupGate {
+                        witnesses: Vec::deserialize_with_mode(&mut reader, compress, Validate::No)?,
+                        table: usize::deserialize_with_mode(&mut reader, compress, Validate::No)?,
+                    });
+                }
+                lookups
+            },
+        },
+        range_checks: match version {
+            1..=3 => vec![],
+            _ => {
+                let len = usize::deserialize_with_mode(&mut reader, compress, Validate::No)?;
+                let mut range_checks = vec![];
+                for _ in 0..len {
+                    range_checks.push(RangeCheckGate {
+                        witness: usize::deserialize_with_mode(&mut reader, compress, Validate::No)?,
+                        num_bits: usize::deserialize_with_mode(
+                            &mut reader,
+                            compress,
+                            Validate::No,
+                        )?,
+                    });
+                }
+                range_checks
+            },
+        },
+    })
+}
+
+// ===========================================================================
+// HyperPlonkProof
+// ===========================================================================
+
+impl<F, PC, PCS> CanonicalSerialize for HyperPlonkProof<F, PC, PCS>
+where
+    F: PrimeField,
+    PC: PermutationCheck<F, PCS> + LookupCheck<F, PCS> + RangeCheck<F, PCS>,
+    PCS: PolynomialCommitmentScheme<F>,
+{
+    fn serialize_with_mode<W: Write>(
+        &self,
+        mut writer: W,
+        compress: Compress,
+    ) -> Result<(), SerializationError> {
+        write_header(&mut writer, ArtifactKind::Proof, compress)?;
+        self.witness_commits
+            .serialize_with_mode(&mut writer, compress)?;
+        self.batch_openings
+            .serialize_with_mode(&mut writer, compress)?;
+        self.zero_check_proof
+            .serialize_with_mode(&mut writer, compress)?;
+        self.perm_check_proof
+            .serialize_with_mode(&mut writer, compress)?;
+        self.lookup_check_proofs
+            .serialize_with_mode(&mut writer, compress)?;
+        self.range_check_proofs
+            .serialize_with_mode(&mut writer, compress)?;
+        self.zero_check_mask
+            .serialize_with_mode(&mut writer, compress)?;
+        self.perm_check_mask
+            .serialize_with_mode(&mut writer, compress)
+    }
+
+    fn serialized_size(&self, compress: Compress) -> usize {
+        HEADER_SIZE
+            + self.witness_commits.serialized_size(compress)
+            + self.batch_openings.serialized_size(compress)
+            + self.zero_check_proof.serialized_size(compress)
+            + self.perm_check_proof.serialized_size(compress)
+            + self.lookup_check_proofs.serialized_size(compress)
+            + self.range_check_proofs.serialized_size(compress)
+            + self.zero_check_mask.serialized_size(compress)
+            + self.perm_check_mask.serialized_size(compress)
+    }
+}
+
+impl<F, PC, PCS> Valid for HyperPlonkProof<F, PC, PCS>
+where
+    F: PrimeField,
+    PC: PermutationCheck<F, PCS> + LookupCheck<F, PCS> + RangeCheck<F, PCS>,
+    PCS: PolynomialCommitmentScheme<F>,
+{
+    /// A proof does not carry the parameters of the circuit; the number of
+    /// witness commitments, of lookup check proofs and of range check proofs,
+    /// and the presence of the masks, is checked against the verifying key by
+    /// `verify`.
+    fn check(&self) -> Result<(), SerializationError> {
+        self.witness_commits.check()?;
+        self.batch_openings.check()?;
+        self.zero_check_proof.check()?;
+        self.perm_check_proof.check()?;
+        self.lookup_check_proofs.check()?;
+        self.range_check_proofs.check()?;
+        self.zero_check_mask.check()?;
+        self.perm_check_mask.check()
+    }
+}
+
+impl<F, PC, PCS> CanonicalDeserialize for HyperPlonkProof<F, PC, PCS>
+where
+    F: PrimeField,
+    PC: PermutationCheck<F, PCS> + LookupCheck<F, PCS> + RangeCheck<F, PCS>,
+    PCS: PolynomialCommitmentScheme<F>,
+{
+    fn deserialize_with_mode<R: Read>(
+        mut reader: R,
+        compress: Compress,
+        validate: Validate,
+    ) -> Result<Self, SerializationError> {
+        let version = read_header(&mut reader, ArtifactKind::Proof, compress)?;
+        let res = match version {
+            1..=5 => Self {
+                witness_commits: Vec::deserialize_with_mode(&mut reader, compress, Validate::No)?,
+                batch_openings: PCS::BatchProof::deserialize_with_mode(
+                    &mut reader,
+                    compress,
+                    Validate::No,
+                )?,
+                zero_check_proof: CanonicalDeserialize::deserialize_with_mode(
+                    &mut reader,
+                    compress,
+                    Validate::No,
+                )?,
+                perm_check_proof: PC::PermutationProof::deserialize_with_mode(
+                    &mut reader,
+                    compress,
+                    Validate::No,
+                )?,
+                lookup_check_proofs: read_lookup_field(&mut reader, compress, version)?,
+                range_check_proofs: read_range_check_field(&mut reader, compress, version)?,
+                zero_check_mask: read_mask_field(&mut reader, compress, version)?,
+                perm_check_mask: read_mask_field(&mut reader, compress, version)?,
+            },
+            _ => return Err(SerializationError::InvalidData),
+        };
+        if validate == Validate::Yes {
+            res.check()?;
+        }
+        Ok(res)
+    }
+}
+
+// ===========================================================================
+// HyperPlonkProvingKey
+// ===========================================================================
+
+impl<F, PCS> CanonicalSerialize for HyperPlonkProvingKey<F, PCS>
+where
+    F: PrimeField,
+    PCS: PolynomialCommitmentScheme<F>,
+{
+    fn serialize_with_mode<W: Write>(
+        &self,
+        mut writer: W,
+        compress: Compress,
+    ) -> Result<(), SerializationError> {
+        write_header(&mut writer, ArtifactKind::ProvingKey, compress)?;
+        write_params(&self.params, &mut writer, compress)?;
+        self.permutation_oracles
+            .serialize_with_mode(&mut writer, compress)?;
+        self.selector_oracles
+            .serialize_with_mode(&mut writer, compress)?;
+        self.selector_commitments
+            .serialize_with_mode(&mut writer, compress)?;
+        self.permutation_commitments
+            .serialize_with_mode(&mut writer, compress)?;
+        self.lookup_selector_oracles
+            .serialize_with_mode(&mut writer, compress)?;
+        self.table_oracles
+            .serialize_with_mode(&mut writer, compress)?;
+        self.lookup_selector_commitments
+            .serialize_with_mode(&mut writer, compress)?;
+        self.table_commitments
+            .serialize_with_mode(&mut writer, compress)?;
+        self.pcs_param.serialize_with_mode(&mut writer, compress)
+    }
+
+    fn serialized_size(&self, compress: Compress) -> usize {
+        HEADER_SIZE
+            + params_size(&self.params, compress)
+            + self.permutation_oracles.serialized_size(compress)
+            + self.selector_oracles.serialized_size(compress)
+            + self.selector_commitments.serialized_size(compress)
+            + self.permutation_commitments.serialized_size(compress)
+            + self.lookup_selector_oracles.serialized_size(compress)
+            + self.table_oracles.serialized_size(compress)
+            + self.lookup_selector_commitments.serialized_size(compress)
+            + self.table_commitments.serialized_size(compress)
+            + self.pcs_param.serialized_size(compress)
+    }
+}
+
+impl<F, PCS> Valid for HyperPlonkProvingKey<F, PCS>
+where
+    F: PrimeField,
+    PCS: PolynomialCommitmentScheme<F>,
+{
+    /// There is one selector polynomial per selector column, one permutation
+    /// polynomial per witness column, one lookup selector polynomial per
+    /// lookup gate and one table polynomial per table column, all over the
+    /// number of variables of the parameters, and one commitment per
+    /// polynomial.
+    fn check(&self) -> Result<(), SerializationError> {
+        self.params.check()?;
+        let nv = self.params.num_variables();
+        check_oracles(
+            &self.selector_oracles,
+            self.params.num_selector_columns(),
+            nv,
+        )?;
+        check_oracles(
+            &self.permutation_oracles,
+            self.params.num_witness_columns(),
+            nv,
+        )?;
+        check_oracles(&self.lookup_selector_oracles, self.params.num_lookups(), nv)?;
+        check_oracles(&self.table_oracles, self.params.num_table_columns(), nv)?;
+        if self.selector_commitments.len() != self.selector_oracles.len()
+            || self.permutation_commitments.len() != self.permutation_oracles.len()
+            || self.lookup_selector_commitments.len() != self.lookup_selector_oracles.len()
+            || self.table_commitments.len() != self.table_oracles.len()
+        {
+            return Err(SerializationError::InvalidData);
+        }
+        self.selector_commitments.check()?;
+        self.permutation_commitments.check()?;
+        self.lookup_selector_commitments.check()?;
+        self.table_commitments.check()?;
+        self.pcs_param.check()
+    }
+}
+
+impl<F, PCS> CanonicalDeserialize for HyperPlonkProvingKey<F, PCS>
+where
+    F: PrimeField,
+    PCS: PolynomialCommitmentScheme<F>,
+{
+    fn deserialize_with_mode<R: Read>(
+        mut reader: R,
+        compress: Compress,
+        validate: Validate,
+    ) -> Result<Self, SerializationError> {
+        let version = read_header(&mut reader, ArtifactKind::ProvingKey, compress)?;
+        let res = match version {
+            1..=5 => Self {
+                params: read_params(&mut reader, compress, version)?,
+                permutation_oracles: Vec::deserialize_with_mode(
+                    &mut reader,
+                    compress,
+                    Validate::No,
+                )?,
+                selector_oracles: Vec::deserialize_with_mode(&mut reader, compress, Validate::No)?,
+                selector_commitments: Vec::deserialize_with_mode(
+                    &mut reader,
+                    compress,
+                    Validate::No,
+                )?,
+                permutation_commitments: Vec::deserialize_with_mode(
+                    &mut reader,
+                    compress,
+                    Validate::No,
+                )?,
+                lookup_selector_oracles: read_lookup_field(&mut reader, compress, version)?,
+                table_oracles: read_lookup_field(&mut reader, compress, version)?,
+                lookup_selector_commitments: read_lookup_field(&mut reader, compress, version)?,
+                table_commitments: read_lookup_field(&mut reader, compress, version)?,
+                pcs_param: PCS::ProverParam::deserialize_with_mode(
+                    &mut reader,
+                    compress,
+                    Validate::No,
+                )?,
+            },
+            _ => return Err(SerializationError::InvalidData),
+        };
+        if validate == Validate::Yes {
+            res.check()?;
+        }
+        Ok(res)
+    }
+}
+
+// ===========================================================================
+// HyperPlonkVerifyingKey
+// ===========================================================================
+
+impl<F, PCS> CanonicalSerialize for HyperPlonkVerifyingKey<F, PCS>
+where
+    F: PrimeField,
+    PCS: PolynomialCommitmentScheme<F>,
+{
+    fn serialize_with_mode<W: Write>(
+        &self,
+        mut writer: W,
+        compress: Compress,
+    ) -> Result<(), SerializationError> {
+        write_header(&mut writer, ArtifactKind::VerifyingKey, compress)?;
+        write_params(&self.params, &mut writer, compress)?;
+        self.pcs_param.serialize_with_mode(&mut writer, compress)?;
+        self.selector_commitments
+            .serialize_with_mode(&mut writer, compress)?;
+        self.perm_commitments
+            .serialize_with_mode(&mut writer, compress)?;
+        self.lookup_selector_commitments
+            .serialize_with_mode(&mut writer, compress)?;
+        self.table_commitments
+            .serialize_with_mode(&mut writer, compress)
+    }
+
+    fn serialized_size(&self, compress: Compress) -> usize {
+        HEADER_SIZE
+            + params_size(&self.params, compress)
+            + self.pcs_param.serialized_size(compress)
+            + self.selector_commitments.serialized_size(compress)
+            + self.perm_commitments.serialized_size(compress)
+            + self.lookup_selector_commitments.serialized_size(compress)
+            + self.table_commitments.serialized_size(compress)
+    }
+}
+
+impl<F, PCS> Valid for HyperPlonkVerifyingKey<F, PCS>
+where
+    F: PrimeField,
+    PCS: PolynomialCommitmentScheme<F>,
+{
+    /// There is one commitment per selector column, one per witness column,
+    /// one per lookup gate and one per table column.
+    fn check(&self) -> Result<(), SerializationError> {
+        self.params.check()?;
+        if self.selector_commitments.len() != self.params.num_selector_columns()
+            || self.perm_commitments.len() != self.params.num_witness_columns()
+            || self.lookup_selector_commitments.len() != self.params.num_lookups()
+            || self.table_commitments.len() != self.params.num_table_columns()
+        {
+            return Err(SerializationError::InvalidData);
+        }
+        self.pcs_param.check()?;
+        self.selector_commitments.check()?;
+        self.perm_commitments.check()?;
+        self.lookup_selector_commitments.check()?;
+        self.table_commitments.check()
+    }
+}
+
+impl<F, PCS> CanonicalDeserialize for HyperPlonkVerifyingKey<F, PCS>
+where
+    F: PrimeField,
+    PCS: PolynomialCommitmentScheme<F>,
+{
+    fn deserialize_with_mode<R: Read>(
+        mut reader: R,
+        compress: Compress,
+        validate: Validate,
+    ) -> Result<Self, SerializationError> {
+        let version = read_header(&mut reader, ArtifactKind::VerifyingKey, compress)?;
+        let res = match version {
+            1..=5 => Self {
+                params: read_params(&mut reader, compress, version)?,
+                pcs_param: PCS::VerifierParam::deserialize_with_mode(
+                    &mut reader,
+                    compress,
+                    Validate::No,
+                )?,
+                selector_commitments: Vec::deserialize_with_mode(
+                    &mut reader,
+                    compress,
+                    Validate::No,
+                )?,
+                perm_commitments: Vec::deserialize_with_mode(&mut reader, compress, Validate::No)?,
+                lookup_selector_commitments: read_lookup_field(&mut reader, compress, version)?,
+                table_commitments: read_lookup_field(&mut reader, compress, version)?,
+            },
+            _ => return Err(SerializationError::InvalidData),
+        };
+        if validate == Validate::Yes {
+            res.check()?;
+        }
+        Ok(res)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{errors::HyperPlonkErrors, mock::MockCircuit, HyperPlonkSNARK};
+    use ark_bls12_381::{Bls12_381, Fr};
+    use ark_std::test_rng;
+    use subroutines::{pcs::prelude::MultilinearKzgPCS, poly_iop::PolyIOP};
+    use transcript::IOPTranscript;
+
+    type Snark = PolyIOP<Fr>;
+    type Pcs = MultilinearKzgPCS<Bls12_381>;
+    type Proof = HyperPlonkProof<Fr, PolyIOP<Fr>, Pcs>;
+
+    /// Serialize, deserialize with validation and serialize again, and return
+    /// the deserialized value. Proofs and keys are not `PartialEq` for every
+    /// PCS, so the bytes are compared instead.
+    fn round_trip<S: CanonicalSerialize + CanonicalDeserialize>(
+        s: &S,
+        compress: Compress,
+    ) -> Result<S, SerializationError> {
+        let mut bytes = vec![];
+        s.serialize_with_mode(&mut bytes, compress)?;
+        assert_eq!(bytes.len(), s.serialized_size(compress));
+        let t = S::deserialize_with_mode(&bytes[..], compress, Validate::Yes)?;
+        let mut bytes2 = vec![];
+        t.serialize_with_mode(&mut bytes2, compress)?;
+        assert_eq!(bytes, bytes2);
+        Ok(t)
+    }
+
+    #[test]
+    fn test_serialization() -> Result<(), HyperPlonkErrors> {
+        let _trace = crate::trace_guard();
+        let mut rng = test_rng();
+        let pcs_srs = Pcs::gen_srs_for_testing(&mut rng, 6)?;
+        let circuit = MockCircuit::<Fr>::new(16, &CustomizedGates::vanilla_plonk_gate());
+        let (pk, vk) = <Snark as HyperPlonkSNARK<Fr, Pcs>>::preprocess(&circuit.index, &pcs_srs)?;
+        let proof = <Snark as HyperPlonkSNARK<Fr, Pcs>>::prove(
+            &pk,
+            &circuit.public_inputs,
+            &circuit.witnesses,
+        )?;
+
+        for compress in [Compress::Yes, Compress::No] {
+            let gate = CustomizedGates::jellyfish_turbo_plonk_gate();
+            assert_eq!(round_trip(&gate, compress)?, gate);
+            assert_eq!(
+                round_trip(&circuit.index.params, compress)?,
+                circuit.index.params
+            );
+            let proof2 = round_trip(&proof, compress)?;
+            let pk2 = round_trip(&pk, compress)?;
+            let vk2 = round_trip(&vk, compress)?;
+            assert_eq!(
+                vk2.digest::<IOPTranscript<Fr>>()?,
+                vk.digest::<IOPTranscript<Fr>>()?
+            );
+
+            // the deserialized proof verifies, and so does a proof from the
+            // deserialized proving key
+            assert!(<Snark as HyperPlonkSNARK<Fr, Pcs>>::verify(
+                &vk2,
+                &circuit.public_inputs,
+                &proof2
+            )?);
+            let proof3 = <Snark as HyperPlonkSNARK<Fr, Pcs>>::prove(
+                &pk2,
+                &circuit.public_inputs,
+                &circuit.witnesses,
+            )?;
+            assert!(<Snark as HyperPlonkSNARK<Fr, Pcs>>::verify(
+                &vk2,
+                &circuit.public_inputs,
+                &proof3
+            )?);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_read_version_1() -> Result<(), HyperPlonkErrors> {
+        let _trace = crate::trace_guard();
+        let mut rng = test_rng();
+        let pcs_srs = Pcs::gen_srs_for_testing(&mut rng, 6)?;
+        let circuit = MockCircuit::<Fr>::new(16, &CustomizedGates::vanilla_plonk_gate());
+        let (_, vk) = <Snark as HyperPlonkSNARK<Fr, Pcs>>::preprocess(&circuit.index, &pcs_srs)?;
+
+        // a version 1 key is a version 5 key without the blinding flag,
+        // the lookup gates and the range check gates at the end of the
+        // parameters, which are one byte and two empty vectors, and without
+        // the lookup commitments at the end of the key, which are two empty
+        // vectors
+        let mut bytes = vec![];
+        vk.serialize_compressed(&mut bytes)?;
+        bytes[5..7].copy_from_slice(&1u16.to_le_bytes());
+        let params_end = HEADER_SIZE + vk.params.compressed_size();
+        bytes.drain(params_end - 17..params_end);
+        bytes.truncate(bytes.len() - 16);
+        let vk1 = HyperPlonkVerifyingKey::<Fr, Pcs>::deserialize_compressed(&bytes[..])?;
+        assert_eq!(vk1.params, vk.params);
+        assert_eq!(
+            vk1.digest::<IOPTranscript<Fr>>()?,
+            vk.digest::<IOPTranscript<Fr>>()?
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_read_proof_version_4() -> Result<(), HyperPlonkErrors> {
+        let _trace = crate::trace_guard();
+        let mut rng = test_rng();
+        let pcs_srs = Pcs::gen_srs_for_testing(&mut rng, 6)?;
+        let circuit = MockCircuit::<Fr>::new(16, &CustomizedGates::vanilla_plonk_gate());
+        let (pk, vk) = <Snark as HyperPlonkSNARK<Fr, Pcs>>::preprocess(&circuit.index, &pcs_srs)?;
+        let proof = <Snark as HyperPlonkSNARK<Fr, Pcs>>::prove(
+            &pk,
+            &circuit.public_inputs,
+            &circuit.witnesses,
+        )?;
+
+        // a version 4 proof is a version 5 proof without the masks at the
+        // end, which are two `None` of one byte each
+        let mut bytes = vec![];
+        proof.serialize_compressed(&mut bytes)?;
+        let mut bytes4 = bytes.clone();
+        bytes4[5..7].copy_from_slice(&4u16.to_le_bytes());
+        bytes4.truncate(bytes4.len() - 2);
+        let proof4 = Proof::deserialize_compressed(&bytes4[..])?;
+        assert!(proof4.zero_check_mask.is_none() && proof4.perm_check_mask.is_none());
+        let mut bytes5 = vec![];
+        proof4.serialize_compressed(&mut bytes5)?;
+        assert_eq!(bytes5, bytes);
+        assert!(<Snark as HyperPlonkSNARK<Fr, Pcs>>::verify(
+            &vk,
+            &circuit.public_inputs,
+            &proof4
+        )?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_read_params_older_versions() -> Result<(), HyperPlonkErrors> {
+        let circuit = MockCircuit::<Fr>::new(16, &CustomizedGates::vanilla_plonk_gate());
+        let params = &circuit.index.params;
+        let mut bytes = vec![];
+        params.serialize_compressed(&mut bytes)?;
+
+        // version 3 parameters end before the range check gates, an empty
+        // vector
+        let mut bytes3 = bytes.clone();
+        bytes3[5..7].copy_from_slice(&3u16.to_le_bytes());
+        bytes3.truncate(bytes3.len() - 8);
+        assert_eq!(
+            HyperPlonkParams::deserialize_compressed(&bytes3[..])?,
+            *params
+        );
+
+        // version 1 parameters also end before the blinding flag and
+        // the lookup gates, which are one byte and an empty vector
+        let mut bytes1 = bytes;
+        bytes1[5..7].copy_from_slice(&1u16.to_le_bytes());
+        bytes1.truncate(bytes1.len() - 17);
+        assert_eq!(
+            HyperPlonkParams::deserialize_compressed(&bytes1[..])?,
+            *params
+        );
+
+        // a gate is not read as parameters
+        let mut gate_bytes = vec![];
+        params.gate_func.serialize_compressed(&mut gate_bytes)?;
+        assert!(HyperPlonkParams::deserialize_compressed(&gate_bytes[..]).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_deserialization_rejects_invalid_data() -> Result<(), HyperPlonkErrors> {
+        let _trace = crate::trace_guard();
+        let mut rng = test_rng();
+        let pcs_srs = Pcs::gen_srs_for_testing(&mut rng, 6)?;
+        let circuit = MockCircuit::<Fr>::new(16, &CustomizedGates::vanilla_plonk_gate());
+        let (pk, vk) = <Snark as HyperPlonkSNARK<Fr, Pcs>>::preprocess(&circuit.index, &pcs_srs)?;
+        let proof = <Snark as HyperPlonkSNARK<Fr, Pcs>>::prove(
+            &pk,
+            &circuit.public_inputs,
+            &circuit.witnesses,
+        )?;
+
+        let mut bytes = vec![];
+        proof.serialize_compressed(&mut bytes)?;
+
+        // wrong compression mode, wrong kind, unknown version
+        assert!(Proof::deserialize_uncompressed(&bytes[..]).is_err());
+        assert!(HyperPlonkVerifyingKey::<Fr, Pcs>::deserialize_compressed(&bytes[..]).is_err());
+        let mut future = bytes.clone();
+        future[5..7].copy_from_slice(&(SERIALIZATION_VERSION + 1).to_le_bytes());
+        assert!(Proof::deserialize_compressed(&future[..]).is_err());
+
+        // a witness commitment that is not on the curve: the last byte of the
+        // first uncompressed point, after the header and the vector length
+        let mut bad_point = vec![];
+        proof.serialize_uncompressed(&mut bad_point)?;
+        bad_point[HEADER_SIZE + 8 + 95] ^= 1;
+        assert!(Proof::deserialize_uncompressed(&bad_point[..]).is_err());
+        assert!(Proof::deserialize_uncompressed_unchecked(&bad_point[..]).is_ok());
+
+        // a verifying key missing a commitment
+        let mut short_vk = vk.clone();
+        short_vk.perm_commitments.pop();
+        let mut bytes = vec![];
+        short_vk.serialize_compressed(&mut bytes)?;
+        assert!(HyperPlonkVerifyingKey::<Fr, Pcs>::deserialize_compressed(&bytes[..]).is_err());
+
+        // a proving key with a selector over the wrong number of variables
+        let mut bad_pk = pk.clone();
+        bad_pk.selector_oracles[0] = Arc::new(DenseMultilinearExtension::from_evaluations_vec(
+            2,
+            vec![Fr::from(1u64); 4],
+        ));
+        let mut bytes = vec![];
+        bad_pk.serialize_compressed(&mut bytes)?;
+        assert!(HyperPlonkProvingKey::<Fr, Pcs>::deserialize_compressed(&bytes[..]).is_err());
+
+        // a gate with a repeated selector
+        let gate = CustomizedGates {
+            gates: vec![(1, Some(0), vec![0]), (1, Some(0), vec![1])],
+        };
+        let mut bytes = vec![];
+        gate.serialize_compressed(&mut bytes)?;
+        assert!(CustomizedGates::deserialize_compressed(&bytes[..]).is_err());
+        Ok(())
+    }
+}
@@ -5,56 +5,85 @@
 // along with the HyperPlonk library. If not, see <https://mit-license.org/>.
 
 use crate::{
+    blinding::{active_rows_mle, blind_index, blind_witnesses, eval_active_rows},
     errors::HyperPlonkErrors,
-    structs::{HyperPlonkIndex, HyperPlonkProof, HyperPlonkProvingKey, HyperPlonkVerifyingKey},
-    utils::{build_f, eval_f, eval_perm_gate, prover_sanity_check, PcsAccumulator},
+    lookup::check_lookup_index,
+    range_check::check_range_checks,
+    structs::{
+        HyperPlonkIndex, HyperPlonkMask, HyperPlonkParams, HyperPlonkProof, HyperPlonkProvingKey,
+        HyperPlonkVerifyingKey,
+    },
+    utils::{
+        append_statement, build_f, eval_f, eval_perm_gate, prover_sanity_check, PcsAccumulator,
+    },
     witness::WitnessColumn,
     HyperPlonkSNARK,
 };
 use arithmetic::{evaluate_opt, gen_eval_point, VPAuxInfo};
-use ark_ec::pairing::Pairing;
+use ark_ff::PrimeField;
 use ark_poly::DenseMultilinearExtension;
-use ark_std::{end_timer, log2, start_timer, One, Zero};
+use ark_std::{
+    end_timer, log2,
+    rand::{CryptoRng, RngCore},
+    start_timer,
+};
 use rayon::iter::IntoParallelRefIterator;
 #[cfg(feature = "parallel")]
 use rayon::iter::ParallelIterator;
 use std::{marker::PhantomData, sync::Arc};
 use subroutines::{
-    pcs::prelude::{Commitment, PolynomialCommitmentScheme},
+    pcs::prelude::{HidingPolynomialCommitmentScheme, PolynomialCommitmentScheme},
     poly_iop::{
-        prelude::{PermutationCheck, ZeroCheck},
+        prelude::{
+            IOPProof, LookupCheck, PermutationCheck, ProductCheckProof, RangeCheck, SumCheckMask,
+            SumCheckMaskClaims, ZeroCheck, ZkProductCheckProof, ZkSumCheckProof,
+        },
         PolyIOP,
     },
-    BatchProof,
 };
-use transcript::IOPTranscript;
+use transcript::Transcript;
 
-impl<E, PCS> HyperPlonkSNARK<E, PCS> for PolyIOP<E::ScalarField>
+impl<F, PCS, T> HyperPlonkSNARK<F, PCS> for PolyIOP<F, T>
 where
-    E: Pairing,
+    F: PrimeField,
     // Ideally we want to access polynomial as PCS::Polynomial, instead of instantiating it here.
     // But since PCS::Polynomial can be both univariate or multivariate in our implementation
     // we cannot bound PCS::Polynomial with a property trait bound.
     PCS: PolynomialCommitmentScheme<
-        E,
-        Polynomial = Arc<DenseMultilinearExtension<E::ScalarField>>,
-        Point = Vec<E::ScalarField>,
-        Evaluation = E::ScalarField,
-        Commitment = Commitment<E>,
-        BatchProof = BatchProof<E, PCS>,
+        F,
+        Polynomial = Arc<DenseMultilinearExtension<F>>,
+        Point = Vec<F>,
+        Evaluation = F,
     >,
+    T: Transcript<F>,
 {
-    type Index = HyperPlonkIndex<E::ScalarField>;
-    type ProvingKey = HyperPlonkProvingKey<E, PCS>;
-    type VerifyingKey = HyperPlonkVerifyingKey<E, PCS>;
-    type Proof = HyperPlonkProof<E, Self, PCS>;
+    type Index = HyperPlonkIndex<F>;
+    type ProvingKey = HyperPlonkProvingKey<F, PCS>;
+    type VerifyingKey = HyperPlonkVerifyingKey<F, PCS>;
+    type Proof = HyperPlonkProof<F, Self, PCS>;
 
     fn preprocess(
         index: &Self::Index,
         pcs_srs: &PCS::SRS,
     ) -> Result<(Self::ProvingKey, Self::VerifyingKey), HyperPlonkErrors> {
+        check_lookup_index(index)?;
+        check_range_checks::<F>(&index.params)?;
         let num_vars = index.num_variables();
-        let supported_ml_degree = num_vars;
+        let mut supported_ml_degree = num_vars;
+        // the encodings of the masks of a zero-knowledge circuit may have more
+        // variables than the circuit
+        if index.params.blinded {
+            let (gate_mask_degree, perm_mask_degree) = zk_mask_degrees(&index.params);
+            supported_ml_degree = supported_ml_degree
+                .max(SumCheckMask::<F>::encoding_num_vars(
+                    num_vars,
+                    gate_mask_degree,
+                ))
+                .max(SumCheckMask::<F>::encoding_num_vars(
+                    num_vars,
+                    perm_mask_degree,
+                ));
+        }
 
         // extract PCS prover and verifier keys from SRS
         let (pcs_prover_param, pcs_verifier_param) =
@@ -75,7 +104,7 @@ where
         }
 
         // build selector oracles and commit to it
-        let selector_oracles: Vec<Arc<DenseMultilinearExtension<E::ScalarField>>> = index
+        let selector_oracles: Vec<Arc<DenseMultilinearExtension<F>>> = index
             .selectors
             .iter()
             .map(|s| Arc::new(DenseMultilinearExtension::from(s)))
@@ -86,6 +115,27 @@ where
             .map(|poly| PCS::commit(&pcs_prover_param, poly))
             .collect::<Result<Vec<_>, _>>()?;
 
+        // build lookup selector and table oracles and commit to them
+        let lookup_selector_oracles: Vec<Arc<DenseMultilinearExtension<F>>> = index
+            .lookup_selectors
+            .iter()
+            .map(|s| Arc::new(DenseMultilinearExtension::from(s)))
+            .collect();
+        let table_oracles: Vec<Arc<DenseMultilinearExtension<F>>> = index
+            .tables
+            .iter()
+            .map(|t| Arc::new(DenseMultilinearExtension::from(t)))
+            .collect();
+
+        let lookup_selector_commitments = lookup_selector_oracles
+            .par_iter()
+            .map(|poly| PCS::commit(&pcs_prover_param, poly))
+            .collect::<Result<Vec<_>, _>>()?;
+        let table_commitments = table_oracles
+            .par_iter()
+            .map(|poly| PCS::commit(&pcs_prover_param, poly))
+            .collect::<Result<Vec<_>, _>>()?;
+
         Ok((
             Self::ProvingKey {
                 params: index.params.clone(),
@@ -93,6 +143,10 @@ where
                 selector_oracles,
                 selector_commitments: selector_commitments.clone(),
                 permutation_commitments: perm_comms.clone(),
+                lookup_selector_oracles,
+                table_oracles,
+                lookup_selector_commitments: lookup_selector_commitments.clone(),
+                table_commitments: table_commitments.clone(),
                 pcs_param: pcs_prover_param,
             },
             Self::VerifyingKey {
@@ -100,10 +154,22 @@ where
                 pcs_param: pcs_verifier_param,
                 selector_commitments,
                 perm_commitments: perm_comms,
+                lookup_selector_commitments,
+                table_commitments,
             },
         ))
     }
 
+    fn preprocess_zk(
+        index: &Self::Index,
+        pcs_srs: &PCS::SRS,
+    ) -> Result<(Self::ProvingKey, Self::VerifyingKey), HyperPlonkErrors>
+    where
+        PCS: HidingPolynomialCommitmentScheme<F>,
+    {
+        <Self as HyperPlonkSNARK<F, PCS>>::preprocess(&blind_index(index)?, pcs_srs)
+    }
+
     /// Generate HyperPlonk SNARK proof.
     ///
     /// Inputs:
@@ -116,6 +182,8 @@ where
     ///
     /// Steps:
     ///
+    /// 0. Append the verifying key digest and the public input to transcript
+    ///
     /// 1. Commit Witness polynomials `w_i(x)` and append commitment to
     ///    transcript
     ///
@@ -131,7 +199,9 @@ where
     /// in vanilla plonk, and obtain a ZeroCheckSubClaim
     ///
     /// 3. Run permutation check on `\{w_i(x)\}` and `permutation_oracle`, and
-    ///    obtain a PermCheckSubClaim.
+    ///    obtain a PermCheckSubClaim. Then run a lookup check on the witnesses
+    ///    and the table of each lookup gate, and a range check on the witness
+    ///    of each range check gate.
     ///
     /// 4. Generate evaluations and corresponding proofs
     /// - 4.1. (deferred) batch opening prod(x) at
@@ -148,214 +218,59 @@ where
     ///   - 4.3.1. (deferred) wi_poly(zero_check_point)
     ///   - 4.3.2. (deferred) selector_poly(zero_check_point)
     ///
-    /// - 4.4. public input consistency checks
+    /// - 4.4. lookup check evaluations and proofs, for each lookup gate
+    ///   - 4.4.1. (deferred) h_f(x), the lookup selector and the looked up
+    ///     wi_poly at the witness point
+    ///   - 4.4.2. (deferred) the table, m(x) and h_t(x) at the table point
+    ///
+    /// - 4.5. range check evaluations and proofs, for each range check gate
+    ///   - 4.5.1. (deferred) h_f(x), the range checked wi_poly and the
+    ///     committed limbs at the witness point
+    ///   - 4.5.2. (deferred) m(x) and h_t(x) at the table point
+    ///
+    /// - 4.6. public input consistency checks
     ///   - pi_poly(r_pi) where r_pi is sampled from transcript
     ///
     /// - 5. deferred batch opening
     fn prove(
         pk: &Self::ProvingKey,
-        pub_input: &[E::ScalarField],
-        witnesses: &[WitnessColumn<E::ScalarField>],
+        pub_input: &[F],
+        witnesses: &[WitnessColumn<F>],
     ) -> Result<Self::Proof, HyperPlonkErrors> {
-        let start = start_timer!(|| "hyperplonk proving");
-        let mut transcript = IOPTranscript::<E::ScalarField>::new(b"hyperplonk");
-
-        prover_sanity_check(&pk.params, pub_input, witnesses)?;
-
-        // witness assignment of length 2^n
-        let num_vars = pk.params.num_variables();
-
-        // online public input of length 2^\ell
-        let ell = log2(pk.params.num_pub_input) as usize;
-
-        // We use accumulators to store the polynomials and their eval points.
-        // They are batch opened at a later stage.
-        let mut pcs_acc = PcsAccumulator::<E, PCS>::new(num_vars);
-
-        // =======================================================================
-        // 1. Commit Witness polynomials `w_i(x)` and append commitment to
-        // transcript
-        // =======================================================================
-        let step = start_timer!(|| "commit witnesses");
-
-        let witness_polys: Vec<Arc<DenseMultilinearExtension<E::ScalarField>>> = witnesses
-            .iter()
-            .map(|w| Arc::new(DenseMultilinearExtension::from(w)))
-            .collect();
-
-        let witness_commits = witness_polys
-            .par_iter()
-            .map(|x| PCS::commit(&pk.pcs_param, x).unwrap())
-            .collect::<Vec<_>>();
-        for w_com in witness_commits.iter() {
-            transcript.append_serializable_element(b"w", w_com)?;
-        }
-
-        end_timer!(step);
-        // =======================================================================
-        // 2 Run ZeroCheck on
-        //
-        //     `f(q_0(x),...q_l(x), w_0(x),...w_d(x))`
-        //
-        // where `f` is the constraint polynomial i.e.,
-        //
-        //     f(q_l, q_r, q_m, q_o, w_a, w_b, w_c)
-        //     = q_l w_a(x) + q_r w_b(x) + q_m w_a(x)w_b(x) - q_o w_c(x)
-        //
-        // in vanilla plonk, and obtain a ZeroCheckSubClaim
-        // =======================================================================
-        let step = start_timer!(|| "ZeroCheck on f");
-
-        let fx = build_f(
-            &pk.params.gate_func,
-            pk.params.num_variables(),
-            &pk.selector_oracles,
-            &witness_polys,
-        )?;
-
-        let zero_check_proof = <Self as ZeroCheck<E::ScalarField>>::prove(&fx, &mut transcript)?;
-        end_timer!(step);
-        // =======================================================================
-        // 3. Run permutation check on `\{w_i(x)\}` and `permutation_oracle`, and
-        // obtain a PermCheckSubClaim.
-        // =======================================================================
-        let step = start_timer!(|| "Permutation check on w_i(x)");
-
-        let (perm_check_proof, prod_x, frac_poly) = <Self as PermutationCheck<E, PCS>>::prove(
-            &pk.pcs_param,
-            &witness_polys,
-            &witness_polys,
-            &pk.permutation_oracles,
-            &mut transcript,
-        )?;
-        let perm_check_point = &perm_check_proof.zero_check_proof.point;
-
-        end_timer!(step);
-        // =======================================================================
-        // 4. Generate evaluations and corresponding proofs
-        // - permcheck
-        //  1. (deferred) batch opening prod(x) at
-        //   - [perm_check_point]
-        //   - [perm_check_point[2..n], 0]
-        //   - [perm_check_point[2..n], 1]
-        //   - [1,...1, 0]
-        //  2. (deferred) batch opening frac(x) at
-        //   - [perm_check_point]
-        //   - [perm_check_point[2..n], 0]
-        //   - [perm_check_point[2..n], 1]
-        //  3. (deferred) batch opening s_id(x) at
-        //   - [perm_check_point]
-        //  4. (deferred) batch opening perms(x) at
-        //   - [perm_check_point]
-        //  5. (deferred) batch opening witness_i(x) at
-        //   - [perm_check_point]
-        //
-        // - zero check evaluations and proofs
-        //   - 4.3.1. (deferred) wi_poly(zero_check_point)
-        //   - 4.3.2. (deferred) selector_poly(zero_check_point)
-        //
-        // - 4.4. (deferred) public input consistency checks
-        //   - pi_poly(r_pi) where r_pi is sampled from transcript
-        // =======================================================================
-        let step = start_timer!(|| "opening and evaluations");
-
-        // (perm_check_point[2..n], 0)
-        let perm_check_point_0 = [
-            &[E::ScalarField::zero()],
-            &perm_check_point[0..num_vars - 1],
-        ]
-        .concat();
-        // (perm_check_point[2..n], 1)
-        let perm_check_point_1 =
-            [&[E::ScalarField::one()], &perm_check_point[0..num_vars - 1]].concat();
-        // (1, ..., 1, 0)
-        let prod_final_query_point = [
-            vec![E::ScalarField::zero()],
-            vec![E::ScalarField::one(); num_vars - 1],
-        ]
-        .concat();
-
-        // prod(x)'s points
-        pcs_acc.insert_poly_and_points(&prod_x, &perm_check_proof.prod_x_comm, perm_check_point);
-        pcs_acc.insert_poly_and_points(&prod_x, &perm_check_proof.prod_x_comm, &perm_check_point_0);
-        pcs_acc.insert_poly_and_points(&prod_x, &perm_check_proof.prod_x_comm, &perm_check_point_1);
-        pcs_acc.insert_poly_and_points(
-            &prod_x,
-            &perm_check_proof.prod_x_comm,
-            &prod_final_query_point,
-        );
-
-        // frac(x)'s points
-        pcs_acc.insert_poly_and_points(&frac_poly, &perm_check_proof.frac_comm, perm_check_point);
-        pcs_acc.insert_poly_and_points(
-            &frac_poly,
-            &perm_check_proof.frac_comm,
-            &perm_check_point_0,
-        );
-        pcs_acc.insert_poly_and_points(
-            &frac_poly,
-            &perm_check_proof.frac_comm,
-            &perm_check_point_1,
-        );
-
-        // perms(x)'s points
-        for (perm, pcom) in pk
-            .permutation_oracles
-            .iter()
-            .zip(pk.permutation_commitments.iter())
-        {
-            pcs_acc.insert_poly_and_points(perm, pcom, perm_check_point);
+        if pk.params.blinded {
+            return Err(HyperPlonkErrors::InvalidProver(
+                "a zero-knowledge proving key needs randomness, use `prove_zk`".to_string(),
+            ));
         }
+        prover_sanity_check(&pk.params, pub_input, witnesses)?;
+        prove_internal(pk, pub_input, witnesses)
+    }
 
-        // witnesses' points
-        // TODO: refactor so it remains correct even if the order changed
-        for (wpoly, wcom) in witness_polys.iter().zip(witness_commits.iter()) {
-            pcs_acc.insert_poly_and_points(wpoly, wcom, perm_check_point);
-        }
-        for (wpoly, wcom) in witness_polys.iter().zip(witness_commits.iter()) {
-            pcs_acc.insert_poly_and_points(wpoly, wcom, &zero_check_proof.point);
+    /// Generate a zero-knowledge HyperPlonk SNARK proof, see `prove`.
+    ///
+    /// The witness columns are first extended with random blinding rows
+    /// drawn from `rng`. Then the witnesses, the product and fractional
+    /// polynomials of the permutation check and the masks are committed to
+    /// with random blindings, the zero check and the zero check of the
+    /// permutation check are masked, and the batch opening is hiding. The
+    /// masks are opened at the points of their claims on their own.
+    fn prove_zk<R: RngCore + CryptoRng>(
+        pk: &Self::ProvingKey,
+        pub_input: &[F],
+        witnesses: &[WitnessColumn<F>],
+        rng: &mut R,
+    ) -> Result<Self::Proof, HyperPlonkErrors>
+    where
+        PCS: HidingPolynomialCommitmentScheme<F>,
+    {
+        if !pk.params.blinded {
+            return Err(HyperPlonkErrors::InvalidProver(
+                "the proving key is not zero-knowledge, use `prove` or `preprocess_zk`".to_string(),
+            ));
         }
-
-        //   - 4.3.2. (deferred) selector_poly(zero_check_point)
-        pk.selector_oracles
-            .iter()
-            .zip(pk.selector_commitments.iter())
-            .for_each(|(poly, com)| {
-                pcs_acc.insert_poly_and_points(poly, com, &zero_check_proof.point)
-            });
-
-        // - 4.4. public input consistency checks
-        //   - pi_poly(r_pi) where r_pi is sampled from transcript
-        let r_pi = transcript.get_and_append_challenge_vectors(b"r_pi", ell)?;
-        // padded with zeros
-        let r_pi_padded = [r_pi, vec![E::ScalarField::zero(); num_vars - ell]].concat();
-        // Evaluate witness_poly[0] at r_pi||0s which is equal to public_input evaluated
-        // at r_pi. Assumes that public_input is a power of 2
-        pcs_acc.insert_poly_and_points(&witness_polys[0], &witness_commits[0], &r_pi_padded);
-        end_timer!(step);
-
-        // =======================================================================
-        // 5. deferred batch opening
-        // =======================================================================
-        let step = start_timer!(|| "deferred batch openings prod(x)");
-        let batch_openings = pcs_acc.multi_open(&pk.pcs_param, &mut transcript)?;
-        end_timer!(step);
-
-        end_timer!(start);
-
-        Ok(HyperPlonkProof {
-            // PCS commit for witnesses
-            witness_commits,
-            // batch_openings,
-            batch_openings,
-            // =======================================================================
-            // IOP proofs
-            // =======================================================================
-            // the custom gate zerocheck proof
-            zero_check_proof,
-            // the permutation check proof for copy constraints
-            perm_check_proof,
-        })
+        prover_sanity_check(&pk.params, pub_input, witnesses)?;
+        let witnesses = blind_witnesses(witnesses, rng);
+        prove_zk_internal(pk, pub_input, &witnesses, rng)
     }
 
     /// Verify the HyperPlonk proof.
@@ -368,6 +283,8 @@ where
     /// Outputs:
     /// - Return a boolean on whether the verification is successful
     ///
+    /// 0. Append the verifying key digest and the public input to transcript
+    ///
     /// 1. Verify zero_check_proof on
     ///
     ///     `f(q_0(x),...q_l(x), w_0(x),...w_d(x))`
@@ -379,22 +296,26 @@ where
     /// ```
     /// in vanilla plonk, and obtain a ZeroCheckSubClaim
     ///
-    /// 2. Verify perm_check_proof on `\{w_i(x)\}` and `permutation_oracles`
+    /// 2. Verify perm_check_proof on `\{w_i(x)\}` and `permutation_oracles`,
+    ///    the lookup_check_proofs of the lookup gates and the
+    ///    range_check_proofs of the range check gates
     ///
     /// 3. check subclaim validity
     ///
     /// 4. Verify the opening against the commitment:
     /// - check permutation check evaluations
     /// - check zero check evaluations
+    /// - check lookup check evaluations
+    /// - check range check evaluations
     /// - public input consistency checks
     fn verify(
         vk: &Self::VerifyingKey,
-        pub_input: &[E::ScalarField],
+        pub_input: &[F],
         proof: &Self::Proof,
     ) -> Result<bool, HyperPlonkErrors> {
         let start = start_timer!(|| "hyperplonk verification");
 
-        let mut transcript = IOPTranscript::<E::ScalarField>::new(b"hyperplonk");
+        let mut transcript = T::new(b"hyperplonk");
 
         let num_selectors = vk.params.num_selector_columns();
         let num_witnesses = vk.params.num_witness_columns();
@@ -415,17 +336,71 @@ where
             )));
         }
 
+        // proof shape, since a deserialized proof is not checked against the
+        // circuit
+        // each lookup gate opens h_f(x), the lookup selector, the looked up
+        // witnesses, the table, m(x) and h_t(x)
+        let num_lookup_evals: usize = vk
+            .params
+            .lookups
+            .iter()
+            .map(|l| 5 + l.witnesses.len())
+            .sum();
+        // each range check gate opens h_f(x), the range checked witness, the
+        // committed limbs, m(x) and h_t(x)
+        let num_range_check_evals: usize = vk
+            .params
+            .range_checks
+            .iter()
+            .map(|r| 4 + r.num_committed_limbs(num_vars))
+            .sum();
+        let num_evals =
+            8 + 3 * num_witnesses + num_selectors + num_lookup_evals + num_range_check_evals;
+        let evals = PCS::batch_evaluations(&proof.batch_openings);
+        if proof.witness_commits.len() != num_witnesses
+            || proof.lookup_check_proofs.len() != vk.params.num_lookups()
+            || proof.range_check_proofs.len() != vk.params.num_range_checks()
+            || evals.len() != num_evals
+        {
+            return Err(HyperPlonkErrors::InvalidProof(format!(
+                "Proof shape is not correct: got {} witness commitments, {} lookup check proofs, \
+                 {} range check proofs and {} evaluations, expect {}, {}, {} and {}",
+                proof.witness_commits.len(),
+                proof.lookup_check_proofs.len(),
+                proof.range_check_proofs.len(),
+                evals.len(),
+                num_witnesses,
+                vk.params.num_lookups(),
+                vk.params.num_range_checks(),
+                num_evals
+            )));
+        }
+
+        // a zero-knowledge key only accepts masked proofs, and the other keys
+        // only accept proofs without masks
+        if proof.zero_check_mask.is_some() != vk.params.blinded
+            || proof.perm_check_mask.is_some() != vk.params.blinded
+        {
+            return Err(HyperPlonkErrors::InvalidProof(format!(
+                "Proof masks do not match the verifying key: expect a proof that is {}zero-knowledge",
+                if vk.params.blinded { "" } else { "not " }
+            )));
+        }
+
+        // bind the transcript to the circuit and the public input
+        append_statement(&mut transcript, &vk.digest::<T>()?, pub_input)?;
+
         // Extract evaluations from openings
-        let prod_evals = &proof.batch_openings.f_i_eval_at_point_i[0..4];
-        let frac_evals = &proof.batch_openings.f_i_eval_at_point_i[4..7];
-        let perm_evals = &proof.batch_openings.f_i_eval_at_point_i[7..7 + num_witnesses];
-        let witness_perm_evals =
-            &proof.batch_openings.f_i_eval_at_point_i[7 + num_witnesses..7 + 2 * num_witnesses];
-        let witness_gate_evals =
-            &proof.batch_openings.f_i_eval_at_point_i[7 + 2 * num_witnesses..7 + 3 * num_witnesses];
-        let selector_evals = &proof.batch_openings.f_i_eval_at_point_i
-            [7 + 3 * num_witnesses..7 + 3 * num_witnesses + num_selectors];
-        let pi_eval = proof.batch_openings.f_i_eval_at_point_i.last().unwrap();
+        let prod_evals = &evals[0..4];
+        let frac_evals = &evals[4..7];
+        let perm_evals = &evals[7..7 + num_witnesses];
+        let witness_perm_evals = &evals[7 + num_witnesses..7 + 2 * num_witnesses];
+        let witness_gate_evals = &evals[7 + 2 * num_witnesses..7 + 3 * num_witnesses];
+        let selector_evals = &evals[7 + 3 * num_witnesses..7 + 3 * num_witnesses + num_selectors];
+        let lookup_offset = 7 + 3 * num_witnesses + num_selectors;
+        let lookup_evals = &evals[lookup_offset..lookup_offset + num_lookup_evals];
+        let range_check_evals = &evals[lookup_offset + num_lookup_evals..num_evals - 1];
+        let pi_eval = evals.last().unwrap();
 
         // =======================================================================
         // 1. Verify zero_check_proof on `f(q_0(x),...q_l(x), w_0(x),...w_d(x))`
@@ -438,8 +413,8 @@ where
         // =======================================================================
         let step = start_timer!(|| "verify zero check");
         // Zero check and perm check have different AuxInfo
-        let zero_check_aux_info = VPAuxInfo::<E::ScalarField> {
-            max_degree: vk.params.gate_func.degree(),
+        let zero_check_aux_info = VPAuxInfo::<F> {
+            max_degree: vk.params.gate_func.degree() + vk.params.blinded as usize,
             num_variables: num_vars,
             phantom: PhantomData,
         };
@@ -448,16 +423,33 @@ where
             transcript.append_serializable_element(b"w", w_com)?;
         }
 
-        let zero_check_sub_claim = <Self as ZeroCheck<E::ScalarField>>::verify(
-            &proof.zero_check_proof,
-            &zero_check_aux_info,
-            &mut transcript,
-        )?;
+        let (zero_check_sub_claim, zero_check_mask_claims) = match &proof.zero_check_mask {
+            Some(mask) => {
+                let (sub_claim, mask_claims) = <Self as ZeroCheck<F>>::verify_zk(
+                    &mask.zk_sum_check_proof(&proof.zero_check_proof),
+                    &zero_check_aux_info,
+                    &mask.mask_comm,
+                    &mut transcript,
+                )?;
+                (sub_claim, Some(mask_claims))
+            },
+            None => (
+                <Self as ZeroCheck<F>>::verify(
+                    &proof.zero_check_proof,
+                    &zero_check_aux_info,
+                    &mut transcript,
+                )?,
+                None,
+            ),
+        };
 
         let zero_check_point = zero_check_sub_claim.point;
 
         // check zero check subclaim
-        let f_eval = eval_f(&vk.params.gate_func, selector_evals, witness_gate_evals)?;
+        let mut f_eval = eval_f(&vk.params.gate_func, selector_evals, witness_gate_evals)?;
+        if vk.params.blinded {
+            f_eval *= eval_active_rows(&zero_check_point);
+        }
         if f_eval != zero_check_sub_claim.expected_evaluation {
             return Err(HyperPlonkErrors::InvalidProof(
                 "zero check evaluation failed".to_string(),
@@ -471,17 +463,37 @@ where
         let step = start_timer!(|| "verify permutation check");
 
         // Zero check and perm check have different AuxInfo
-        let perm_check_aux_info = VPAuxInfo::<E::ScalarField> {
+        let perm_check_aux_info = VPAuxInfo::<F> {
             // Prod(x) has a max degree of witnesses.len() + 1
             max_degree: proof.witness_commits.len() + 1,
             num_variables: num_vars,
             phantom: PhantomData,
         };
-        let perm_check_sub_claim = <Self as PermutationCheck<E, PCS>>::verify(
-            &proof.perm_check_proof,
-            &perm_check_aux_info,
-            &mut transcript,
-        )?;
+        let (perm_check_sub_claim, perm_check_mask_claims) = match &proof.perm_check_mask {
+            Some(mask) => {
+                let zk_proof = ZkProductCheckProof {
+                    zero_check_proof: mask
+                        .zk_sum_check_proof(&proof.perm_check_proof.zero_check_proof),
+                    prod_x_comm: proof.perm_check_proof.prod_x_comm.clone(),
+                    frac_comm: proof.perm_check_proof.frac_comm.clone(),
+                    mask_comm: mask.mask_comm.clone(),
+                };
+                let (sub_claim, mask_claims) = <Self as PermutationCheck<F, PCS>>::verify_zk(
+                    &zk_proof,
+                    &perm_check_aux_info,
+                    &mut transcript,
+                )?;
+                (sub_claim, Some(mask_claims))
+            },
+            None => (
+                <Self as PermutationCheck<F, PCS>>::verify(
+                    &proof.perm_check_proof,
+                    &perm_check_aux_info,
+                    &mut transcript,
+                )?,
+                None,
+            ),
+        };
 
         let perm_check_point = perm_check_sub_claim
             .product_check_sub_claim
@@ -519,6 +531,108 @@ where
                 "evaluation failed".to_string(),
             ));
         }
+        // the grand product must be one, otherwise the witness does not satisfy
+        // the copy constraints
+        if prod_evals[3] != perm_check_sub_claim.product_check_sub_claim.final_query.1 {
+            return Err(HyperPlonkErrors::InvalidVerifier(
+                "product evaluation failed".to_string(),
+            ));
+        }
+
+        end_timer!(step);
+        // =======================================================================
+        // 2.5. Verify lookup_check_proofs on the lookup gates
+        // =======================================================================
+        let step = start_timer!(|| "verify lookup checks");
+
+        // the table side has a max degree of 2 for h_t(x) * (beta + t(x))
+        let table_aux_info = VPAuxInfo::<F> {
+            max_degree: 2,
+            num_variables: num_vars,
+            phantom: PhantomData,
+        };
+        let mut lookup_check_sub_claims = Vec::with_capacity(vk.params.num_lookups());
+        let mut offset = 0;
+        for (lookup, lookup_proof) in vk
+            .params
+            .lookups
+            .iter()
+            .zip(proof.lookup_check_proofs.iter())
+        {
+            // h_f(x) * prod_i (beta + w_i(x)) has a max degree of
+            // witnesses.len() + 1
+            let lookup_aux_info = VPAuxInfo::<F> {
+                max_degree: lookup.witnesses.len() + 1,
+                num_variables: num_vars,
+                phantom: PhantomData,
+            };
+            let sub_claim = <Self as LookupCheck<F, PCS>>::verify(
+                lookup_proof,
+                &lookup_aux_info,
+                &table_aux_info,
+                &mut transcript,
+            )?;
+
+            // check evaluation subclaims, with the evaluations in the order
+            // h_f, q, w_i, t, m, h_t
+            let evals = &lookup_evals[offset..offset + 5 + lookup.witnesses.len()];
+            let k = lookup.witnesses.len();
+            let witness_eval =
+                sub_claim.witness_evaluation(&evals[2..2 + k], evals[1], evals[0])?;
+            let table_eval =
+                sub_claim.table_evaluation(evals[2 + k], evals[3 + k], evals[4 + k])?;
+            if witness_eval != sub_claim.witness_sub_claim.expected_evaluation
+                || table_eval != sub_claim.table_sub_claim.expected_evaluation
+            {
+                return Err(HyperPlonkErrors::InvalidVerifier(
+                    "lookup evaluation failed".to_string(),
+                ));
+            }
+            offset += 5 + k;
+            lookup_check_sub_claims.push(sub_claim);
+        }
+
+        end_timer!(step);
+        // =======================================================================
+        // 2.6. Verify range_check_proofs on the range check gates
+        // =======================================================================
+        let step = start_timer!(|| "verify range checks");
+
+        let mut range_check_sub_claims = Vec::with_capacity(vk.params.num_range_checks());
+        let mut offset = 0;
+        for (range_check, range_check_proof) in vk
+            .params
+            .range_checks
+            .iter()
+            .zip(proof.range_check_proofs.iter())
+        {
+            let sub_claim = <Self as RangeCheck<F, PCS>>::verify(
+                range_check_proof,
+                1,
+                num_vars,
+                range_check.num_bits,
+                range_check.limb_bits(num_vars),
+                &mut transcript,
+            )?;
+
+            // check evaluation subclaims, with the evaluations in the order
+            // h_f, w, the committed limbs, m, h_t
+            let k = range_check.num_committed_limbs(num_vars);
+            let evals = &range_check_evals[offset..offset + 4 + k];
+            let witness_eval =
+                sub_claim.witness_evaluation(&evals[1..2], &evals[2..2 + k], evals[0])?;
+            let table_eval = sub_claim.table_evaluation(evals[2 + k], evals[3 + k])?;
+            let lookup_sub_claim = &sub_claim.lookup_check_sub_claim;
+            if witness_eval != lookup_sub_claim.witness_sub_claim.expected_evaluation
+                || table_eval != lookup_sub_claim.table_sub_claim.expected_evaluation
+            {
+                return Err(HyperPlonkErrors::InvalidVerifier(
+                    "range check evaluation failed".to_string(),
+                ));
+            }
+            offset += 4 + k;
+            range_check_sub_claims.push(sub_claim);
+        }
 
         end_timer!(step);
         // =======================================================================
@@ -530,60 +644,107 @@ where
         let mut comms = vec![];
         let mut points = vec![];
 
-        let perm_check_point_0 = [
-            &[E::ScalarField::zero()],
-            &perm_check_point[0..num_vars - 1],
-        ]
-        .concat();
-        let perm_check_point_1 =
-            [&[E::ScalarField::one()], &perm_check_point[0..num_vars - 1]].concat();
-        let prod_final_query_point = [
-            vec![E::ScalarField::zero()],
-            vec![E::ScalarField::one(); num_vars - 1],
-        ]
-        .concat();
+        let perm_check_point_0 = [&[F::zero()], &perm_check_point[0..num_vars - 1]].concat();
+        let perm_check_point_1 = [&[F::one()], &perm_check_point[0..num_vars - 1]].concat();
+        let prod_final_query_point = [vec![F::zero()], vec![F::one(); num_vars - 1]].concat();
 
         // prod(x)'s points
-        comms.push(proof.perm_check_proof.prod_x_comm);
-        comms.push(proof.perm_check_proof.prod_x_comm);
-        comms.push(proof.perm_check_proof.prod_x_comm);
-        comms.push(proof.perm_check_proof.prod_x_comm);
+        comms.push(proof.perm_check_proof.prod_x_comm.clone());
+        comms.push(proof.perm_check_proof.prod_x_comm.clone());
+        comms.push(proof.perm_check_proof.prod_x_comm.clone());
+        comms.push(proof.perm_check_proof.prod_x_comm.clone());
         points.push(perm_check_point.clone());
         points.push(perm_check_point_0.clone());
         points.push(perm_check_point_1.clone());
         points.push(prod_final_query_point);
         // frac(x)'s points
-        comms.push(proof.perm_check_proof.frac_comm);
-        comms.push(proof.perm_check_proof.frac_comm);
-        comms.push(proof.perm_check_proof.frac_comm);
+        comms.push(proof.perm_check_proof.frac_comm.clone());
+        comms.push(proof.perm_check_proof.frac_comm.clone());
+        comms.push(proof.perm_check_proof.frac_comm.clone());
         points.push(perm_check_point.clone());
         points.push(perm_check_point_0);
         points.push(perm_check_point_1);
 
         // perms' points
-        for &pcom in vk.perm_commitments.iter() {
-            comms.push(pcom);
+        for pcom in vk.perm_commitments.iter() {
+            comms.push(pcom.clone());
             points.push(perm_check_point.clone());
         }
 
         // witnesses' points
         // TODO: merge points
-        for &wcom in proof.witness_commits.iter() {
-            comms.push(wcom);
+        for wcom in proof.witness_commits.iter() {
+            comms.push(wcom.clone());
             points.push(perm_check_point.clone());
         }
-        for &wcom in proof.witness_commits.iter() {
-            comms.push(wcom);
+        for wcom in proof.witness_commits.iter() {
+            comms.push(wcom.clone());
             points.push(zero_check_point.clone());
         }
 
         // selector_poly(zero_check_point)
-        for &com in vk.selector_commitments.iter() {
-            comms.push(com);
+        for com in vk.selector_commitments.iter() {
+            comms.push(com.clone());
             points.push(zero_check_point.clone());
         }
 
-        // - 4.4. public input consistency checks
+        // lookup polynomials' points
+        for (i, (lookup, sub_claim)) in vk
+            .params
+            .lookups
+            .iter()
+            .zip(lookup_check_sub_claims.iter())
+            .enumerate()
+        {
+            let lookup_proof = &proof.lookup_check_proofs[i];
+            let witness_point = &sub_claim.witness_sub_claim.point;
+            let table_point = &sub_claim.table_sub_claim.point;
+
+            comms.push(lookup_proof.witness_frac_comm.clone());
+            points.push(witness_point.clone());
+            comms.push(vk.lookup_selector_commitments[i].clone());
+            points.push(witness_point.clone());
+            for &w in lookup.witnesses.iter() {
+                comms.push(proof.witness_commits[w].clone());
+                points.push(witness_point.clone());
+            }
+
+            comms.push(vk.table_commitments[lookup.table].clone());
+            points.push(table_point.clone());
+            comms.push(lookup_proof.multiplicity_comm.clone());
+            points.push(table_point.clone());
+            comms.push(lookup_proof.table_frac_comm.clone());
+            points.push(table_point.clone());
+        }
+
+        // range check polynomials' points
+        for ((range_check, range_check_proof), sub_claim) in vk
+            .params
+            .range_checks
+            .iter()
+            .zip(proof.range_check_proofs.iter())
+            .zip(range_check_sub_claims.iter())
+        {
+            let lookup_proof = &range_check_proof.lookup_check_proof;
+            let witness_point = &sub_claim.lookup_check_sub_claim.witness_sub_claim.point;
+            let table_point = &sub_claim.lookup_check_sub_claim.table_sub_claim.point;
+
+            comms.push(lookup_proof.witness_frac_comm.clone());
+            points.push(witness_point.clone());
+            comms.push(proof.witness_commits[range_check.witness].clone());
+            points.push(witness_point.clone());
+            for com in range_check_proof.limb_comms.iter() {
+                comms.push(com.clone());
+                points.push(witness_point.clone());
+            }
+
+            comms.push(lookup_proof.multiplicity_comm.clone());
+            points.push(table_point.clone());
+            comms.push(lookup_proof.table_frac_comm.clone());
+            points.push(table_point.clone());
+        }
+
+        // - 4.6. public input consistency checks
         //   - pi_poly(r_pi) where r_pi is sampled from transcript
         let r_pi = transcript.get_and_append_challenge_vectors(b"r_pi", ell)?;
 
@@ -597,14 +758,31 @@ where
                 pi_eval, expect_pi_eval,
             )));
         }
-        let r_pi_padded = [r_pi, vec![E::ScalarField::zero(); num_vars - ell]].concat();
+        let r_pi_padded = [r_pi, vec![F::zero(); num_vars - ell]].concat();
 
-        comms.push(proof.witness_commits[0]);
+        comms.push(proof.witness_commits[0].clone());
         points.push(r_pi_padded);
-        assert_eq!(comms.len(), proof.batch_openings.f_i_eval_at_point_i.len());
+        assert_eq!(comms.len(), evals.len());
         end_timer!(pi_step);
         end_timer!(step);
 
+        // the encodings of the masks do not have the number of variables of
+        // the circuit, so they are opened on their own
+        let step = start_timer!(|| "verify mask openings");
+        for (mask, mask_claims) in [
+            (&proof.zero_check_mask, zero_check_mask_claims),
+            (&proof.perm_check_mask, perm_check_mask_claims),
+        ] {
+            if let (Some(mask), Some(mask_claims)) = (mask, mask_claims) {
+                if !verify_mask_openings(&vk.pcs_param, mask, &mask_claims)? {
+                    end_timer!(step);
+                    end_timer!(start);
+                    return Ok(false);
+                }
+            }
+        }
+        end_timer!(step);
+
         let step = start_timer!(|| "PCS batch verify");
         // check proof
         let res = PCS::batch_verify(
@@ -621,20 +799,720 @@ where
     }
 }
 
+/// Generate HyperPlonk SNARK proof for a proving key that is not
+/// zero-knowledge, see `prove`.
+#[allow(clippy::type_complexity)]
+fn prove_internal<F, PCS, T>(
+    pk: &HyperPlonkProvingKey<F, PCS>,
+    pub_input: &[F],
+    witnesses: &[WitnessColumn<F>],
+) -> Result<HyperPlonkProof<F, PolyIOP<F, T>, PCS>, HyperPlonkErrors>
+where
+    F: PrimeField,
+    PCS: PolynomialCommitmentScheme<
+        F,
+        Polynomial = Arc<DenseMultilinearExtension<F>>,
+        Point = Vec<F>,
+        Evaluation = F,
+    >,
+    T: Transcript<F>,
+{
+    let start = start_timer!(|| "hyperplonk proving");
+    let mut transcript = T::new(b"hyperplonk");
+
+    // check the witness row by row, so that an invalid witness fails here
+    // with a readable report rather than as a rejected proof
+    #[cfg(feature = "extensive_sanity_checks")]
+    check_witness(pk, pub_input, witnesses)?;
+
+    // bind the transcript to the circuit and the public input
+    append_statement(&mut transcript, &pk.vk_digest::<T>()?, pub_input)?;
+
+    // witness assignment of length 2^n
+    let num_vars = pk.params.num_variables();
+
+    // online public input of length 2^\ell
+    let ell = log2(pk.params.num_pub_input) as usize;
+
+    // We use accumulators to store the polynomials and their eval points.
+    // They are batch opened at a later stage.
+    let mut pcs_acc = PcsAccumulator::<F, PCS>::new(num_vars);
+
+    // =======================================================================
+    // 1. Commit Witness polynomials `w_i(x)` and append commitment to
+    // transcript
+    // =======================================================================
+    let step = start_timer!(|| "commit witnesses");
+
+    let witness_polys: Vec<Arc<DenseMultilinearExtension<F>>> = witnesses
+        .iter()
+        .map(|w| Arc::new(DenseMultilinearExtension::from(w)))
+        .collect();
+
+    let witness_commits = witness_polys
+        .par_iter()
+        .map(|x| PCS::commit(&pk.pcs_param, x).unwrap())
+        .collect::<Vec<_>>();
+    for w_com in witness_commits.iter() {
+        transcript.append_serializable_element(b"w", w_com)?;
+    }
+
+    end_timer!(step);
+    // =======================================================================
+    // 2 Run ZeroCheck on
+    //
+    //     `f(q_0(x),...q_l(x), w_0(x),...w_d(x))`
+    //
+    // where `f` is the constraint polynomial i.e.,
+    //
+    //     f(q_l, q_r, q_m, q_o, w_a, w_b, w_c)
+    //     = q_l w_a(x) + q_r w_b(x) + q_m w_a(x)w_b(x) - q_o w_c(x)
+    //
+    // in vanilla plonk, and obtain a ZeroCheckSubClaim
+    // =======================================================================
+    let step = start_timer!(|| "ZeroCheck on f");
+
+    let fx = build_f(
+        &pk.params.gate_func,
+        pk.params.num_variables(),
+        &pk.selector_oracles,
+        &witness_polys,
+    )?;
+
+    let zero_check_proof = <PolyIOP<F, T> as ZeroCheck<F>>::prove(&fx, &mut transcript)?;
+    end_timer!(step);
+    // =======================================================================
+    // 3. Run permutation check on `\{w_i(x)\}` and `permutation_oracle`, and
+    // obtain a PermCheckSubClaim.
+    // =======================================================================
+    let step = start_timer!(|| "Permutation check on w_i(x)");
+
+    let (perm_check_proof, prod_x, frac_poly) = <PolyIOP<F, T> as PermutationCheck<F, PCS>>::prove(
+        &pk.pcs_param,
+        &witness_polys,
+        &witness_polys,
+        &pk.permutation_oracles,
+        &mut transcript,
+    )?;
+
+    end_timer!(step);
+    // =======================================================================
+    // 3.5. Run lookup check on the witnesses and the table of each lookup
+    // gate, restricted to the rows of its lookup selector.
+    // =======================================================================
+    let step = start_timer!(|| "Lookup checks on w_i(x)");
+
+    let mut lookup_check_proofs = Vec::with_capacity(pk.params.num_lookups());
+    let mut lookup_polys = Vec::with_capacity(pk.params.num_lookups());
+    for (lookup, selector) in pk
+        .params
+        .lookups
+        .iter()
+        .zip(pk.lookup_selector_oracles.iter())
+    {
+        let fxs: Vec<_> = lookup
+            .witnesses
+            .iter()
+            .map(|&w| witness_polys[w].clone())
+            .collect();
+        let (proof, multiplicity, witness_frac, table_frac) =
+            <PolyIOP<F, T> as LookupCheck<F, PCS>>::prove(
+                &pk.pcs_param,
+                &fxs,
+                Some(selector),
+                &pk.table_oracles[lookup.table],
+                &mut transcript,
+            )?;
+        lookup_check_proofs.push(proof);
+        lookup_polys.push((multiplicity, witness_frac, table_frac));
+    }
+
+    end_timer!(step);
+    // =======================================================================
+    // 3.6. Run range check on the witness of each range check gate.
+    // =======================================================================
+    let step = start_timer!(|| "Range checks on w_i(x)");
+
+    let mut range_check_proofs = Vec::with_capacity(pk.params.num_range_checks());
+    let mut range_check_polys = Vec::with_capacity(pk.params.num_range_checks());
+    for range_check in pk.params.range_checks.iter() {
+        let (proof, limbs, multiplicity, witness_frac, table_frac) =
+            <PolyIOP<F, T> as RangeCheck<F, PCS>>::prove(
+                &pk.pcs_param,
+                &[witness_polys[range_check.witness].clone()],
+                range_check.num_bits,
+                range_check.limb_bits(num_vars),
+                &mut transcript,
+            )?;
+        range_check_proofs.push(proof);
+        range_check_polys.push((limbs, multiplicity, witness_frac, table_frac));
+    }
+
+    end_timer!(step);
+    // =======================================================================
+    // 4. Generate evaluations and corresponding proofs
+    // - permcheck
+    //  1. (deferred) batch opening prod(x) at
+    //   - [perm_check_point]
+    //   - [perm_check_point[2..n], 0]
+    //   - [perm_check_point[2..n], 1]
+    //   - [1,...1, 0]
+    //  2. (deferred) batch opening frac(x) at
+    //   - [perm_check_point]
+    //   - [perm_check_point[2..n], 0]
+    //   - [perm_check_point[2..n], 1]
+    //  3. (deferred) batch opening s_id(x) at
+    //   - [perm_check_point]
+    //  4. (deferred) batch opening perms(x) at
+    //   - [perm_check_point]
+    //  5. (deferred) batch opening witness_i(x) at
+    //   - [perm_check_point]
+    //
+    // - zero check evaluations and proofs
+    //   - 4.3.1. (deferred) wi_poly(zero_check_point)
+    //   - 4.3.2. (deferred) selector_poly(zero_check_point)
+    //
+    // - 4.4. (deferred) lookup check evaluations and proofs
+    //
+    // - 4.5. (deferred) range check evaluations and proofs
+    //
+    // - 4.6. (deferred) public input consistency checks
+    //   - pi_poly(r_pi) where r_pi is sampled from transcript
+    // =======================================================================
+    let step = start_timer!(|| "opening and evaluations");
+
+    insert_perm_and_gate_openings(
+        &mut pcs_acc,
+        pk,
+        &witness_polys,
+        &witness_commits,
+        &perm_check_proof,
+        &prod_x,
+        &frac_poly,
+        &zero_check_proof.point,
+    );
+
+    // - 4.4. lookup check evaluations
+    for (i, lookup) in pk.params.lookups.iter().enumerate() {
+        let proof = &lookup_check_proofs[i];
+        let (multiplicity, witness_frac, table_frac) = &lookup_polys[i];
+        let witness_point = &proof.witness_sum_check_proof.point;
+        let table_point = &proof.table_sum_check_proof.point;
+
+        //   - 4.4.1. (deferred) h_f(x), the lookup selector and the looked up
+        //     witnesses at the witness point
+        pcs_acc.insert_poly_and_points(witness_frac, &proof.witness_frac_comm, witness_point);
+        pcs_acc.insert_poly_and_points(
+            &pk.lookup_selector_oracles[i],
+            &pk.lookup_selector_commitments[i],
+            witness_point,
+        );
+        for &w in lookup.witnesses.iter() {
+            pcs_acc.insert_poly_and_points(&witness_polys[w], &witness_commits[w], witness_point);
+        }
+
+        //   - 4.4.2. (deferred) the table, m(x) and h_t(x) at the table point
+        pcs_acc.insert_poly_and_points(
+            &pk.table_oracles[lookup.table],
+            &pk.table_commitments[lookup.table],
+            table_point,
+        );
+        pcs_acc.insert_poly_and_points(multiplicity, &proof.multiplicity_comm, table_point);
+        pcs_acc.insert_poly_and_points(table_frac, &proof.table_frac_comm, table_point);
+    }
+
+    // - 4.5. range check evaluations
+    for (i, range_check) in pk.params.range_checks.iter().enumerate() {
+        let proof = &range_check_proofs[i].lookup_check_proof;
+        let limb_comms = &range_check_proofs[i].limb_comms;
+        let (limbs, multiplicity, witness_frac, table_frac) = &range_check_polys[i];
+        let witness_point = &proof.witness_sum_check_proof.point;
+        let table_point = &proof.table_sum_check_proof.point;
+
+        //   - 4.5.1. (deferred) h_f(x), the range checked witness and the
+        //     committed limbs at the witness point
+        pcs_acc.insert_poly_and_points(witness_frac, &proof.witness_frac_comm, witness_point);
+        pcs_acc.insert_poly_and_points(
+            &witness_polys[range_check.witness],
+            &witness_commits[range_check.witness],
+            witness_point,
+        );
+        for (limb, com) in limbs.iter().zip(limb_comms.iter()) {
+            pcs_acc.insert_poly_and_points(limb, com, witness_point);
+        }
+
+        //   - 4.5.2. (deferred) m(x) and h_t(x) at the table point
+        pcs_acc.insert_poly_and_points(multiplicity, &proof.multiplicity_comm, table_point);
+        pcs_acc.insert_poly_and_points(table_frac, &proof.table_frac_comm, table_point);
+    }
+
+    // - 4.6. public input consistency checks
+    //   - pi_poly(r_pi) where r_pi is sampled from transcript
+    let r_pi = transcript.get_and_append_challenge_vectors(b"r_pi", ell)?;
+    // padded with zeros
+    let r_pi_padded = [r_pi, vec![F::zero(); num_vars - ell]].concat();
+    // Evaluate witness_poly[0] at r_pi||0s which is equal to public_input evaluated
+    // at r_pi. Assumes that public_input is a power of 2
+    pcs_acc.insert_poly_and_points(&witness_polys[0], &witness_commits[0], &r_pi_padded);
+    end_timer!(step);
+
+    // =======================================================================
+    // 5. deferred batch opening
+    // =======================================================================
+    let step = start_timer!(|| "deferred batch openings prod(x)");
+    let batch_openings = pcs_acc.multi_open(&pk.pcs_param, &mut transcript)?;
+    end_timer!(step);
+
+    end_timer!(start);
+
+    Ok(HyperPlonkProof {
+        // PCS commit for witnesses
+        witness_commits,
+        // batch_openings,
+        batch_openings,
+        // =======================================================================
+        // IOP proofs
+        // =======================================================================
+        // the custom gate zerocheck proof
+        zero_check_proof,
+        // the permutation check proof for copy constraints
+        perm_check_proof,
+        // the lookup check proofs for lookup gates
+        lookup_check_proofs,
+        // the range check proofs for range check gates
+        range_check_proofs,
+        // the proof is not zero-knowledge
+        zero_check_mask: None,
+        perm_check_mask: None,
+    })
+}
+
+/// Generate a zero-knowledge HyperPlonk SNARK proof for witness columns that
+/// include the blinding rows of a zero-knowledge proving key.
+///
+/// The steps are those of `prove_internal` without the lookup and range
+/// checks, which zero-knowledge keys do not support. The commitments and the
+/// batch opening are hiding, and the zero check and the zero check of the
+/// permutation check run on polynomials masked with committed random masks,
+/// which are opened at the points of their claims.
+#[allow(clippy::type_complexity)]
+fn prove_zk_internal<F, PCS, T, R>(
+    pk: &HyperPlonkProvingKey<F, PCS>,
+    pub_input: &[F],
+    witnesses: &[WitnessColumn<F>],
+    rng: &mut R,
+) -> Result<HyperPlonkProof<F, PolyIOP<F, T>, PCS>, HyperPlonkErrors>
+where
+    F: PrimeField,
+    PCS: HidingPolynomialCommitmentScheme<
+        F,
+        Polynomial = Arc<DenseMultilinearExtension<F>>,
+        Point = Vec<F>,
+        Evaluation = F,
+    >,
+    T: Transcript<F>,
+    R: RngCore + CryptoRng,
+{
+    let start = start_timer!(|| "hyperplonk zk proving");
+    let mut transcript = T::new(b"hyperplonk");
+
+    // check the witness row by row, so that an invalid witness fails here
+    // with a readable report rather than as a rejected proof
+    #[cfg(feature = "extensive_sanity_checks")]
+    check_witness(pk, pub_input, witnesses)?;
+
+    // bind the transcript to the circuit and the public input
+    append_statement(&mut transcript, &pk.vk_digest::<T>()?, pub_input)?;
+
+    // witness assignment of length 2^n
+    let num_vars = pk.params.num_variables();
+
+    // online public input of length 2^\ell
+    let ell = log2(pk.params.num_pub_input) as usize;
+
+    let (gate_mask_degree, perm_mask_degree) = zk_mask_degrees(&pk.params);
+
+    // We use accumulators to store the polynomials and their eval points.
+    // They are batch opened at a later stage.
+    let mut pcs_acc = PcsAccumulator::<F, PCS>::new(num_vars);
+
+    // =======================================================================
+    // 1. Commit Witness polynomials `w_i(x)` with random blindings and
+    // append commitment to transcript
+    // =======================================================================
+    let step = start_timer!(|| "hiding commit witnesses");
+
+    let witness_polys: Vec<Arc<DenseMultilinearExtension<F>>> = witnesses
+        .iter()
+        .map(|w| Arc::new(DenseMultilinearExtension::from(w)))
+        .collect();
+
+    let (witness_commits, witness_blindings): (Vec<_>, Vec<_>) = witness_polys
+        .iter()
+        .map(|x| PCS::commit_with_rng(&pk.pcs_param, x, rng))
+        .collect::<Result<Vec<_>, _>>()?
+        .into_iter()
+        .unzip();
+    for w_com in witness_commits.iter() {
+        transcript.append_serializable_element(b"w", w_com)?;
+    }
+
+    end_timer!(step);
+    // =======================================================================
+    // 2 Run a zero-knowledge ZeroCheck on
+    //
+    //     `f(q_0(x),...q_l(x), w_0(x),...w_d(x))`
+    //
+    // restricted to the constraint rows, with a committed random mask
+    // =======================================================================
+    let step = start_timer!(|| "zk ZeroCheck on f");
+
+    let mut fx = build_f(
+        &pk.params.gate_func,
+        pk.params.num_variables(),
+        &pk.selector_oracles,
+        &witness_polys,
+    )?;
+    // the gate does not apply to the blinding rows
+    fx.mul_by_mle(active_rows_mle(num_vars), F::one())?;
+
+    let gate_mask = SumCheckMask::rand(num_vars, gate_mask_degree, rng)?;
+    let gate_mask_poly = gate_mask.to_mle();
+    let (gate_mask_comm, gate_mask_blinding) =
+        PCS::commit_with_rng(&pk.pcs_param, &gate_mask_poly, rng)?;
+    let zk_zero_check_proof = <PolyIOP<F, T> as ZeroCheck<F>>::prove_zk(
+        &fx,
+        &gate_mask,
+        &gate_mask_comm,
+        &mut transcript,
+    )?;
+    end_timer!(step);
+    // =======================================================================
+    // 3. Run a zero-knowledge permutation check on `\{w_i(x)\}` and
+    // `permutation_oracle`.
+    // =======================================================================
+    let step = start_timer!(|| "zk Permutation check on w_i(x)");
+
+    let (zk_perm_check_proof, perm_oracles) =
+        <PolyIOP<F, T> as PermutationCheck<F, PCS>>::prove_zk(
+            &pk.pcs_param,
+            &witness_polys,
+            &witness_polys,
+            &pk.permutation_oracles,
+            &mut transcript,
+            rng,
+        )?;
+
+    end_timer!(step);
+    // =======================================================================
+    // 4. Generate evaluations and corresponding proofs, see `prove`
+    // =======================================================================
+    let step = start_timer!(|| "opening and evaluations");
+
+    let (zero_check_proof, zero_check_mask) = open_mask(
+        &pk.pcs_param,
+        zk_zero_check_proof,
+        gate_mask_degree,
+        &gate_mask_poly,
+        gate_mask_comm,
+        &gate_mask_blinding,
+    )?;
+    let (perm_zero_check_proof, perm_check_mask) = open_mask(
+        &pk.pcs_param,
+        zk_perm_check_proof.zero_check_proof,
+        perm_mask_degree,
+        &perm_oracles.mask,
+        zk_perm_check_proof.mask_comm,
+        &perm_oracles.mask_blinding,
+    )?;
+    let perm_check_proof = ProductCheckProof {
+        zero_check_proof: perm_zero_check_proof,
+        prod_x_comm: zk_perm_check_proof.prod_x_comm,
+        frac_comm: zk_perm_check_proof.frac_comm,
+    };
+
+    insert_perm_and_gate_openings(
+        &mut pcs_acc,
+        pk,
+        &witness_polys,
+        &witness_commits,
+        &perm_check_proof,
+        &perm_oracles.prod_x,
+        &perm_oracles.frac_poly,
+        &zero_check_proof.point,
+    );
+    // the blindings in the order of the openings, where the preprocessed
+    // polynomials are public
+    let mut blindings = vec![perm_oracles.prod_x_blinding; 4];
+    blindings.extend(vec![perm_oracles.frac_blinding; 3]);
+    blindings.extend(vec![PCS::Blinding::default(); pk.permutation_oracles.len()]);
+    blindings.extend(witness_blindings.iter().cloned());
+    blindings.extend(witness_blindings.iter().cloned());
+    blindings.extend(vec![PCS::Blinding::default(); pk.selector_oracles.len()]);
+
+    // - 4.6. public input consistency checks
+    //   - pi_poly(r_pi) where r_pi is sampled from transcript
+    let r_pi = transcript.get_and_append_challenge_vectors(b"r_pi", ell)?;
+    // padded with zeros
+    let r_pi_padded = [r_pi, vec![F::zero(); num_vars - ell]].concat();
+    pcs_acc.insert_poly_and_points(&witness_polys[0], &witness_commits[0], &r_pi_padded);
+    blindings.push(witness_blindings[0].clone());
+    end_timer!(step);
+
+    // =======================================================================
+    // 5. deferred hiding batch opening
+    // =======================================================================
+    let step = start_timer!(|| "deferred hiding batch openings");
+    let batch_openings =
+        pcs_acc.multi_open_with_blindings(&pk.pcs_param, &blindings, &mut transcript)?;
+    end_timer!(step);
+
+    end_timer!(start);
+
+    Ok(HyperPlonkProof {
+        witness_commits,
+        batch_openings,
+        zero_check_proof,
+        perm_check_proof,
+        lookup_check_proofs: vec![],
+        range_check_proofs: vec![],
+        zero_check_mask: Some(zero_check_mask),
+        perm_check_mask: Some(perm_check_mask),
+    })
+}
+
+/// Insert the openings of the permutation check and of the zero check into
+/// `pcs_acc`, in the order in which `verify` assembles them:
+///   - prod(x) at the permutation check point, at its shifts by 0 and 1, and
+///     at `[0, 1, ..., 1]`
+///   - frac(x) at the permutation check point and at its shifts by 0 and 1
+///   - the permutation oracles and the witnesses at the permutation check
+///     point
+///   - the witnesses and the selectors at the zero check point
+#[allow(clippy::too_many_arguments)]
+fn insert_perm_and_gate_openings<F, PCS, T>(
+    pcs_acc: &mut PcsAccumulator<F, PCS>,
+    pk: &HyperPlonkProvingKey<F, PCS>,
+    witness_polys: &[Arc<DenseMultilinearExtension<F>>],
+    witness_commits: &[PCS::Commitment],
+    perm_check_proof: &ProductCheckProof<F, PCS, PolyIOP<F, T>>,
+    prod_x: &Arc<DenseMultilinearExtension<F>>,
+    frac_poly: &Arc<DenseMultilinearExtension<F>>,
+    zero_check_point: &PCS::Point,
+) where
+    F: PrimeField,
+    PCS: PolynomialCommitmentScheme<
+        F,
+        Polynomial = Arc<DenseMultilinearExtension<F>>,
+        Point = Vec<F>,
+        Evaluation = F,
+    >,
+    T: Transcript<F>,
+{
+    let num_vars = pcs_acc.num_var;
+    let perm_check_point = &perm_check_proof.zero_check_proof.point;
+
+    // (perm_check_point[2..n], 0)
+    let perm_check_point_0 = [&[F::zero()], &perm_check_point[0..num_vars - 1]].concat();
+    // (perm_check_point[2..n], 1)
+    let perm_check_point_1 = [&[F::one()], &perm_check_point[0..num_vars - 1]].concat();
+    // (1, ..., 1, 0)
+    let prod_final_query_point = [vec![F::zero()], vec![F::one(); num_vars - 1]].concat();
+
+    // prod(x)'s points
+    pcs_acc.insert_poly_and_points(prod_x, &perm_check_proof.prod_x_comm, perm_check_point);
+    pcs_acc.insert_poly_and_points(prod_x, &perm_check_proof.prod_x_comm, &perm_check_point_0);
+    pcs_acc.insert_poly_and_points(prod_x, &perm_check_proof.prod_x_comm, &perm_check_point_1);
+    pcs_acc.insert_poly_and_points(
+        prod_x,
+        &perm_check_proof.prod_x_comm,
+        &prod_final_query_point,
+    );
+
+    // frac(x)'s points
+    pcs_acc.insert_poly_and_points(frac_poly, &perm_check_proof.frac_comm, perm_check_point);
+    pcs_acc.insert_poly_and_points(frac_poly, &perm_check_proof.frac_comm, &perm_check_point_0);
+    pcs_acc.insert_poly_and_points(frac_poly, &perm_check_proof.frac_comm, &perm_check_point_1);
+
+    // perms(x)'s points
+    for (perm, pcom) in pk
+        .permutation_oracles
+        .iter()
+        .zip(pk.permutation_commitments.iter())
+    {
+        pcs_acc.insert_poly_and_points(perm, pcom, perm_check_point);
+    }
+
+    // witnesses' points
+    // TODO: refactor so it remains correct even if the order changed
+    for (wpoly, wcom) in witness_polys.iter().zip(witness_commits.iter()) {
+        pcs_acc.insert_poly_and_points(wpoly, wcom, perm_check_point);
+    }
+    for (wpoly, wcom) in witness_polys.iter().zip(witness_commits.iter()) {
+        pcs_acc.insert_poly_and_points(wpoly, wcom, zero_check_point);
+    }
+
+    //   - 4.3.2. (deferred) selector_poly(zero_check_point)
+    pk.selector_oracles
+        .iter()
+        .zip(pk.selector_commitments.iter())
+        .for_each(|(poly, com)| pcs_acc.insert_poly_and_points(poly, com, zero_check_point));
+}
+
+/// The degrees of the masks of the custom gate zerocheck and of the zerocheck
+/// of the permutation check of a zero-knowledge circuit, i.e. the degrees of
+/// the zerocheck polynomials times the eq polynomial: the gate is multiplied by
+/// the active rows, and Q(x) of the permutation check has a max degree of
+/// witnesses.len() + 1.
+fn zk_mask_degrees(params: &HyperPlonkParams) -> (usize, usize) {
+    (
+        params.gate_func.degree() + 2,
+        params.num_witness_columns() + 2,
+    )
+}
+
+/// Open the encoding of the mask of a zero-knowledge zerocheck at the points
+/// of its claims, and split the zerocheck proof into the masked sum check
+/// proof and the mask of a HyperPlonk proof.
+fn open_mask<F, PCS>(
+    pcs_param: &PCS::ProverParam,
+    proof: ZkSumCheckProof<F>,
+    mask_degree: usize,
+    mask_poly: &PCS::Polynomial,
+    mask_comm: PCS::Commitment,
+    mask_blinding: &PCS::Blinding,
+) -> Result<(IOPProof<F>, HyperPlonkMask<F, PCS>), HyperPlonkErrors>
+where
+    F: PrimeField,
+    PCS: HidingPolynomialCommitmentScheme<F, Point = Vec<F>>,
+{
+    let mask_claims = SumCheckMaskClaims::new(
+        mask_degree,
+        &proof.sum_check_proof.point,
+        proof.mask_evaluations.clone(),
+    );
+    let mask_openings = mask_claims
+        .points
+        .iter()
+        .map(|point| {
+            PCS::open_with_blinding(pcs_param, mask_poly, mask_blinding, point)
+                .map(|(opening, _)| opening)
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok((
+        proof.sum_check_proof,
+        HyperPlonkMask {
+            mask_comm,
+            mask_sum: proof.mask_sum,
+            mask_evaluations: proof.mask_evaluations,
+            mask_openings,
+        },
+    ))
+}
+
+/// Verify the openings of the encoding of a mask against its claims.
+fn verify_mask_openings<F, PCS>(
+    pcs_param: &PCS::VerifierParam,
+    mask: &HyperPlonkMask<F, PCS>,
+    mask_claims: &SumCheckMaskClaims<F>,
+) -> Result<bool, HyperPlonkErrors>
+where
+    F: PrimeField,
+    PCS: PolynomialCommitmentScheme<F, Point = Vec<F>>,
+{
+    if mask.mask_openings.len() != mask_claims.points.len() {
+        return Err(HyperPlonkErrors::InvalidProof(format!(
+            "Mask openings length is not correct: got {}, expect {}",
+            mask.mask_openings.len(),
+            mask_claims.points.len()
+        )));
+    }
+    for ((point, eval), opening) in mask_claims
+        .points
+        .iter()
+        .zip(mask_claims.evaluations.iter())
+        .zip(mask.mask_openings.iter())
+    {
+        if !PCS::verify(pcs_param, &mask.mask_comm, point, eval, opening)? {
+            return Ok(false);
+        }
+    }
+    Ok(true)
+}
+
+/// Check the witness row by row, so that an invalid witness fails with a
+/// readable report rather than as a rejected proof.
+#[cfg(feature = "extensive_sanity_checks")]
+fn check_witness<F, PCS>(
+    pk: &HyperPlonkProvingKey<F, PCS>,
+    pub_input: &[F],
+    witnesses: &[WitnessColumn<F>],
+) -> Result<(), HyperPlonkErrors>
+where
+    F: PrimeField,
+    PCS: PolynomialCommitmentScheme<F>,
+{
+    let selectors: Vec<&[F]> = pk
+        .selector_oracles
+        .iter()
+        .map(|s| s.evaluations.as_slice())
+        .collect();
+    let permutation: Vec<F> = pk
+        .permutation_oracles
+        .iter()
+        .flat_map(|p| p.evaluations.iter().copied())
+        .collect();
+    let lookup_selectors: Vec<&[F]> = pk
+        .lookup_selector_oracles
+        .iter()
+        .map(|s| s.evaluations.as_slice())
+        .collect();
+    let tables: Vec<&[F]> = pk
+        .table_oracles
+        .iter()
+        .map(|t| t.evaluations.as_slice())
+        .collect();
+    let report = crate::satisfaction::check_satisfaction_internal(
+        &pk.params,
+        &selectors,
+        &permutation,
+        &lookup_selectors,
+        &tables,
+        pub_input,
+        witnesses,
+    )?;
+    if !report.is_satisfied() {
+        return Err(HyperPlonkErrors::InvalidProver(report.to_string()));
+    }
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::{
-        custom_gate::CustomizedGates, selectors::SelectorColumn, structs::HyperPlonkParams,
+        custom_gate::CustomizedGates,
+        lookup::{LookupGate, TableColumn},
+        mock::MockCircuit,
+        range_check::RangeCheckGate,
+        selectors::SelectorColumn,
+        structs::HyperPlonkParams,
         witness::WitnessColumn,
     };
     use arithmetic::{identity_permutation, random_permutation};
-    use ark_bls12_381::Bls12_381;
-    use ark_std::test_rng;
-    use subroutines::pcs::prelude::MultilinearKzgPCS;
+    use ark_bls12_381::{Bls12_381, Fr};
+    use ark_ed_on_bls12_381_bandersnatch::{EdwardsProjective, Fr as BandersnatchFr};
+    use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+    use ark_std::{rand::SeedableRng, test_rng, One, Zero};
+    use rand_chacha::ChaCha20Rng;
+    use subroutines::pcs::prelude::{
+        BasefoldPCS, HidingMultilinearKzgPCS, HyraxPCS, LigeroPCS, MultilinearKzgPCS,
+    };
+    use transcript::{diff, record, IOPTranscript, RecordingTranscript};
 
     #[test]
     fn test_hyperplonk_e2e() -> Result<(), HyperPlonkErrors> {
+        let _trace = crate::trace_guard();
         // Example:
         //     q_L(X) * W_1(X)^5 - W_2(X) = 0
         // is represented as
@@ -651,14 +1529,474 @@ mod tests {
         let gates = CustomizedGates {
             gates: vec![(1, Some(0), vec![0, 0, 0, 0, 0]), (-1, None, vec![1])],
         };
-        test_hyperplonk_helper::<Bls12_381>(gates)
+        test_hyperplonk_helper::<Fr, MultilinearKzgPCS<Bls12_381>>(gates)
     }
 
-    fn test_hyperplonk_helper<E: Pairing>(
-        gate_func: CustomizedGates,
-    ) -> Result<(), HyperPlonkErrors> {
+    #[test]
+    fn test_hyperplonk_hyrax_e2e() -> Result<(), HyperPlonkErrors> {
+        let _trace = crate::trace_guard();
+        // the same circuit as `test_hyperplonk_e2e`, committed with Hyrax over
+        // Bandersnatch, which has no pairing
+        let gates = CustomizedGates {
+            gates: vec![(1, Some(0), vec![0, 0, 0, 0, 0]), (-1, None, vec![1])],
+        };
+        test_hyperplonk_helper::<BandersnatchFr, HyraxPCS<EdwardsProjective>>(gates)
+    }
+
+    #[test]
+    fn test_hyperplonk_hyrax_pallas_e2e() -> Result<(), HyperPlonkErrors> {
+        let _trace = crate::trace_guard();
+        // the same circuit as `test_hyperplonk_e2e`, committed with Hyrax over
+        // Pallas, a cycle curve with no pairing
+        let gates = CustomizedGates {
+            gates: vec![(1, Some(0), vec![0, 0, 0, 0, 0]), (-1, None, vec![1])],
+        };
+        test_hyperplonk_helper::<pallas::Fr, HyraxPCS<pallas::Projective>>(gates)
+    }
+
+    #[test]
+    fn test_hyperplonk_ligero_e2e() -> Result<(), HyperPlonkErrors> {
+        let _trace = crate::trace_guard();
+        // the same circuit as `test_hyperplonk_e2e`, committed with Ligero
+        let gates = CustomizedGates {
+            gates: vec![(1, Some(0), vec![0, 0, 0, 0, 0]), (-1, None, vec![1])],
+        };
+        test_hyperplonk_helper::<Fr, LigeroPCS<Fr>>(gates)
+    }
+
+    #[test]
+    fn test_hyperplonk_basefold_e2e() -> Result<(), HyperPlonkErrors> {
+        let _trace = crate::trace_guard();
+        // the same circuit as `test_hyperplonk_e2e`, committed with Basefold
+        let gates = CustomizedGates {
+            gates: vec![(1, Some(0), vec![0, 0, 0, 0, 0]), (-1, None, vec![1])],
+        };
+        test_hyperplonk_helper::<Fr, BasefoldPCS<Fr>>(gates)
+    }
+
+    #[test]
+    fn test_hyperplonk_transcript_divergence() -> Result<(), HyperPlonkErrors> {
+        let _trace = crate::trace_guard();
+        type Recording = PolyIOP<Fr, RecordingTranscript<Fr, IOPTranscript<Fr>>>;
+        type Kzg = MultilinearKzgPCS<Bls12_381>;
+
+        let mut rng = test_rng();
+        let pcs_srs = Kzg::gen_srs_for_testing(&mut rng, 8)?;
+        let circuit = MockCircuit::<Fr>::new(1 << 4, &CustomizedGates::vanilla_plonk_gate());
+        let (pk, vk) =
+            <Recording as HyperPlonkSNARK<Fr, Kzg>>::preprocess(&circuit.index, &pcs_srs)?;
+
+        let (proof, prover_log) = record(|| {
+            <Recording as HyperPlonkSNARK<Fr, Kzg>>::prove(
+                &pk,
+                &circuit.public_inputs,
+                &circuit.witnesses,
+            )
+        });
+        let proof = proof?;
+
+        // an honest verifier replays the prover's transcript
+        let (res, verifier_log) = record(|| {
+            <Recording as HyperPlonkSNARK<Fr, Kzg>>::verify(&vk, &circuit.public_inputs, &proof)
+        });
+        assert!(res?);
+        assert!(diff(&prover_log, &verifier_log).is_none());
+
+        // a verifier with another public input diverges at the public input
+        let mut bad_pi = circuit.public_inputs.clone();
+        bad_pi[0] += Fr::one();
+        let (res, verifier_log) =
+            record(|| <Recording as HyperPlonkSNARK<Fr, Kzg>>::verify(&vk, &bad_pi, &proof));
+        assert!(!res.unwrap_or(false));
+        let divergence = diff(&prover_log, &verifier_log).unwrap();
+        assert_eq!(divergence.label(), Some(&b"pi"[..]));
+        assert!(divergence
+            .verifier
+            .unwrap()
+            .location
+            .file()
+            .ends_with("utils.rs"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_hyperplonk_zk_e2e() -> Result<(), HyperPlonkErrors> {
+        let _trace = crate::trace_guard();
+        type HidingKzg = HidingMultilinearKzgPCS<Bls12_381>;
+        type Snark = PolyIOP<Fr>;
+        type Proof = HyperPlonkProof<Fr, Snark, HidingKzg>;
+
+        let mut rng = ChaCha20Rng::seed_from_u64(0);
+        let pcs_srs = HidingKzg::gen_srs_for_testing(&mut rng, 8)?;
+
+        // q_L(X) * W_1(X)^5 - W_2(X) = 0, whose second monomial has no
+        // selector and only vanishes on the blinding rows thanks to the
+        // active row polynomial
+        let index = HyperPlonkIndex {
+            params: HyperPlonkParams {
+                num_constraints: 4,
+                num_pub_input: 2,
+                gate_func: CustomizedGates {
+                    gates: vec![(1, Some(0), vec![0, 0, 0, 0, 0]), (-1, None, vec![1])],
+                },
+                blinded: false,
+                lookups: vec![],
+                range_checks: vec![],
+            },
+            permutation: identity_permutation(2, 2),
+            selectors: vec![SelectorColumn(vec![Fr::one(); 4])],
+            lookup_selectors: vec![],
+            tables: vec![],
+        };
+        let w1 = WitnessColumn((0..4u64).map(Fr::from).collect());
+        let w2 = WitnessColumn((0..4u64).map(|i| Fr::from(i.pow(5))).collect());
+        let pi = w1.0[..2].to_vec();
+        let witnesses = vec![w1, w2];
+
+        let (pk, vk) = <Snark as HyperPlonkSNARK<Fr, HidingKzg>>::preprocess_zk(&index, &pcs_srs)?;
+        assert!(vk.params.blinded);
+        assert_eq!(vk.params.num_variables(), 3);
+        // a zero-knowledge key cannot prove without randomness
+        assert!(<Snark as HyperPlonkSNARK<Fr, HidingKzg>>::prove(&pk, &pi, &witnesses).is_err());
+
+        let proof1 =
+            <Snark as HyperPlonkSNARK<Fr, HidingKzg>>::prove_zk(&pk, &pi, &witnesses, &mut rng)?;
+        let proof2 =
+            <Snark as HyperPlonkSNARK<Fr, HidingKzg>>::prove_zk(&pk, &pi, &witnesses, &mut rng)?;
+        assert!(<Snark as HyperPlonkSNARK<Fr, HidingKzg>>::verify(
+            &vk, &pi, &proof1
+        )?);
+        assert!(<Snark as HyperPlonkSNARK<Fr, HidingKzg>>::verify(
+            &vk, &pi, &proof2
+        )?);
+
+        // the same witness is committed to differently
+        assert!(proof1
+            .witness_commits
+            .iter()
+            .zip(proof2.witness_commits.iter())
+            .all(|(c1, c2)| c1 != c2));
+
+        // the round messages are masked: the first message of an unmasked
+        // zero check sums to zero, and the one of a masked zero check sums to
+        // the sum of the mask times a challenge
+        for proof in [&proof1, &proof2] {
+            for (sum_check_proof, mask) in [
+                (&proof.zero_check_proof, &proof.zero_check_mask),
+                (
+                    &proof.perm_check_proof.zero_check_proof,
+                    &proof.perm_check_mask,
+                ),
+            ] {
+                let mask = mask.as_ref().unwrap();
+                let first = &sum_check_proof.proofs[0].evaluations;
+                assert!(!mask.mask_sum.is_zero());
+                assert!(!(first[0] + first[1]).is_zero());
+                assert_eq!(mask.mask_evaluations.len(), 3);
+                assert_eq!(mask.mask_openings.len(), 3);
+            }
+        }
+        // the batch opening is masked as well
+        assert!(!proof1.batch_openings.sum_check_proof.mask_sum.is_zero());
+        assert_ne!(
+            proof1.batch_openings.mask_commitment,
+            proof2.batch_openings.mask_commitment
+        );
+
+        // tampered masks are rejected
+        let rejects = |proof: &Proof| {
+            !<Snark as HyperPlonkSNARK<Fr, HidingKzg>>::verify(&vk, &pi, proof).unwrap_or(false)
+        };
+        let mut bad_proof = proof1.clone();
+        bad_proof.zero_check_mask.as_mut().unwrap().mask_evaluations[0] += Fr::one();
+        assert!(rejects(&bad_proof));
+        let mut bad_proof = proof1.clone();
+        bad_proof.perm_check_mask.as_mut().unwrap().mask_sum += Fr::one();
+        assert!(rejects(&bad_proof));
+        // the openings of the masks are checked against their commitments
+        let mut bad_proof = proof1.clone();
+        bad_proof
+            .zero_check_mask
+            .as_mut()
+            .unwrap()
+            .mask_openings
+            .swap(0, 1);
+        assert!(rejects(&bad_proof));
+        let mut bad_proof = proof1.clone();
+        bad_proof.perm_check_mask.as_mut().unwrap().mask_openings =
+            proof2.perm_check_mask.clone().unwrap().mask_openings;
+        assert!(rejects(&bad_proof));
+        let mut bad_proof = proof1.clone();
+        bad_proof
+            .perm_check_mask
+            .as_mut()
+            .unwrap()
+            .mask_openings
+            .pop();
+        assert!(rejects(&bad_proof));
+        let mut bad_proof = proof1.clone();
+        bad_proof.zero_check_mask = proof2.zero_check_mask.clone();
+        assert!(rejects(&bad_proof));
+        // a proof without masks is rejected by a zero-knowledge key
+        let mut bad_proof = proof1.clone();
+        bad_proof.perm_check_mask = None;
+        assert!(rejects(&bad_proof));
+        bad_proof.zero_check_mask = None;
+        assert!(rejects(&bad_proof));
+
+        // wrong public input
+        let mut bad_pi = pi.clone();
+        bad_pi[1] += Fr::one();
+        assert!(
+            !<Snark as HyperPlonkSNARK<Fr, HidingKzg>>::verify(&vk, &bad_pi, &proof1)
+                .unwrap_or(false)
+        );
+
+        // a zero-knowledge proof is rejected by the key of the circuit without
+        // blinding rows, which does not prove with masks
+        let (plain_pk, plain_vk) =
+            <Snark as HyperPlonkSNARK<Fr, HidingKzg>>::preprocess(&index, &pcs_srs)?;
+        assert!(
+            !<Snark as HyperPlonkSNARK<Fr, HidingKzg>>::verify(&plain_vk, &pi, &proof1)
+                .unwrap_or(false)
+        );
+        assert!(<Snark as HyperPlonkSNARK<Fr, HidingKzg>>::prove_zk(
+            &plain_pk, &pi, &witnesses, &mut rng
+        )
+        .is_err());
+
+        // the masks survive serialization
+        let mut bytes = vec![];
+        proof1.serialize_compressed(&mut bytes)?;
+        let proof3 = Proof::deserialize_compressed(&bytes[..])?;
+        assert!(proof3.zero_check_mask.is_some() && proof3.perm_check_mask.is_some());
+        let mut bytes3 = vec![];
+        proof3.serialize_compressed(&mut bytes3)?;
+        assert_eq!(bytes3, bytes);
+        assert!(<Snark as HyperPlonkSNARK<Fr, HidingKzg>>::verify(
+            &vk, &pi, &proof3
+        )?);
+
+        // a circuit with copy constraints
+        let circuit = MockCircuit::<Fr>::new(1 << 3, &CustomizedGates::vanilla_plonk_gate());
+        let (pk, vk) =
+            <Snark as HyperPlonkSNARK<Fr, HidingKzg>>::preprocess_zk(&circuit.index, &pcs_srs)?;
+        let proof = <Snark as HyperPlonkSNARK<Fr, HidingKzg>>::prove_zk(
+            &pk,
+            &circuit.public_inputs,
+            &circuit.witnesses,
+            &mut rng,
+        )?;
+        assert!(<Snark as HyperPlonkSNARK<Fr, HidingKzg>>::verify(
+            &vk,
+            &circuit.public_inputs,
+            &proof
+        )?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_hyperplonk_lookup_e2e() -> Result<(), HyperPlonkErrors> {
+        let _trace = crate::trace_guard();
+        type Kzg = MultilinearKzgPCS<Bls12_381>;
+        type Snark = PolyIOP<Fr>;
+
+        let mut rng = test_rng();
+        let pcs_srs = Kzg::gen_srs_for_testing(&mut rng, 8)?;
+
+        // q_L(X) * W_1(X)^5 - W_2(X) = 0, where
+        // - both lookups of W_1 are into the table [3, 2, 1, 0]
+        // - W_2 is looked up into the table [1, 32, 243, 243] on all rows but
+        //   the first one
+        let mut index = HyperPlonkIndex {
+            params: HyperPlonkParams {
+                num_constraints: 4,
+                num_pub_input: 2,
+                gate_func: CustomizedGates {
+                    gates: vec![(1, Some(0), vec![0, 0, 0, 0, 0]), (-1, None, vec![1])],
+                },
+                blinded: false,
+                lookups: vec![
+                    LookupGate {
+                        witnesses: vec![0, 0],
+                        table: 0,
+                    },
+                    LookupGate {
+                        witnesses: vec![1],
+                        table: 1,
+                    },
+                ],
+                range_checks: vec![],
+            },
+            permutation: identity_permutation(2, 2),
+            selectors: vec![SelectorColumn(vec![Fr::one(); 4])],
+            lookup_selectors: vec![
+                SelectorColumn(vec![Fr::one(); 4]),
+                SelectorColumn(vec![Fr::zero(), Fr::one(), Fr::one(), Fr::one()]),
+            ],
+            tables: vec![
+                TableColumn([3u64, 2, 1, 0].into_iter().map(Fr::from).collect()),
+                TableColumn([1u64, 32, 243, 243].into_iter().map(Fr::from).collect()),
+            ],
+        };
+        let w1 = WitnessColumn((0..4u64).map(Fr::from).collect());
+        let w2 = WitnessColumn((0..4u64).map(|i| Fr::from(i.pow(5))).collect());
+        let pi = w1.0[..2].to_vec();
+        let witnesses = vec![w1, w2];
+
+        let (pk, vk) = <Snark as HyperPlonkSNARK<Fr, Kzg>>::preprocess(&index, &pcs_srs)?;
+        let proof = <Snark as HyperPlonkSNARK<Fr, Kzg>>::prove(&pk, &pi, &witnesses)?;
+        assert!(<Snark as HyperPlonkSNARK<Fr, Kzg>>::verify(
+            &vk, &pi, &proof
+        )?);
+
+        // the proof and the keys survive serialization
+        let mut bytes = vec![];
+        proof.serialize_compressed(&mut bytes)?;
+        let proof2 = HyperPlonkProof::<Fr, Snark, Kzg>::deserialize_compressed(&bytes[..])?;
+        let mut bytes = vec![];
+        vk.serialize_compressed(&mut bytes)?;
+        let vk2 = HyperPlonkVerifyingKey::<Fr, Kzg>::deserialize_compressed(&bytes[..])?;
+        assert!(<Snark as HyperPlonkSNARK<Fr, Kzg>>::verify(
+            &vk2, &pi, &proof2
+        )?);
+
+        // bad path 1: a proof for one table is rejected for another table
+        let mut other_index = index.clone();
+        other_index.tables[0].0[0] = Fr::from(5u64);
+        let (_, other_vk) =
+            <Snark as HyperPlonkSNARK<Fr, Kzg>>::preprocess(&other_index, &pcs_srs)?;
+        assert!(
+            !<Snark as HyperPlonkSNARK<Fr, Kzg>>::verify(&other_vk, &pi, &proof).unwrap_or(false)
+        );
+
+        // bad path 2: a witness that is not in the table on a looked up row
+        index.lookup_selectors[1] = SelectorColumn(vec![Fr::one(); 4]);
+        let (bad_pk, _) = <Snark as HyperPlonkSNARK<Fr, Kzg>>::preprocess(&index, &pcs_srs)?;
+        assert!(<Snark as HyperPlonkSNARK<Fr, Kzg>>::prove(&bad_pk, &pi, &witnesses).is_err());
+
+        // bad path 3: an index without a table for its lookup gate
+        index.tables.pop();
+        assert!(<Snark as HyperPlonkSNARK<Fr, Kzg>>::preprocess(&index, &pcs_srs).is_err());
+
+        // lookup gates are not supported by zero-knowledge keys
+        assert!(blind_index(&other_index).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_hyperplonk_range_check_e2e() -> Result<(), HyperPlonkErrors> {
+        let _trace = crate::trace_guard();
+        type Kzg = MultilinearKzgPCS<Bls12_381>;
+        type Snark = PolyIOP<Fr>;
+
         let mut rng = test_rng();
-        let pcs_srs = MultilinearKzgPCS::<E>::gen_srs_for_testing(&mut rng, 16)?;
+        let pcs_srs = Kzg::gen_srs_for_testing(&mut rng, 8)?;
+
+        // q_1(X) * W_1(X) - 4 * q_2(X) * W_2(X) - q_3(X) * W_3(X) = 0, where
+        // W_1, W_2 and W_3 are in the ranges of 10, 8 and 2 bits
+        let index = HyperPlonkIndex {
+            params: HyperPlonkParams {
+                num_constraints: 8,
+                num_pub_input: 2,
+                gate_func: CustomizedGates {
+                    gates: vec![
+                        (1, Some(0), vec![0]),
+                        (-4, Some(1), vec![1]),
+                        (-1, Some(2), vec![2]),
+                    ],
+                },
+                blinded: false,
+                lookups: vec![],
+                range_checks: vec![
+                    RangeCheckGate {
+                        witness: 0,
+                        num_bits: 10,
+                    },
+                    RangeCheckGate {
+                        witness: 1,
+                        num_bits: 8,
+                    },
+                    RangeCheckGate {
+                        witness: 2,
+                        num_bits: 2,
+                    },
+                ],
+            },
+            permutation: identity_permutation(3, 3),
+            selectors: vec![SelectorColumn(vec![Fr::one(); 8]); 3],
+            lookup_selectors: vec![],
+            tables: vec![],
+        };
+        let high = [0u64, 255, 17, 100, 3, 200, 128, 1];
+        let low = [0u64, 3, 1, 2, 3, 0, 2, 1];
+        let build_witnesses = |high: &[u64], low: &[u64]| {
+            vec![
+                WitnessColumn(
+                    high.iter()
+                        .zip(low.iter())
+                        .map(|(h, l)| Fr::from(4 * h + l))
+                        .collect(),
+                ),
+                WitnessColumn(high.iter().map(|&h| Fr::from(h)).collect()),
+                WitnessColumn(low.iter().map(|&l| Fr::from(l)).collect()),
+            ]
+        };
+        let witnesses = build_witnesses(&high, &low);
+        let pi = witnesses[0].0[..2].to_vec();
+
+        let (pk, vk) = <Snark as HyperPlonkSNARK<Fr, Kzg>>::preprocess(&index, &pcs_srs)?;
+        let proof = <Snark as HyperPlonkSNARK<Fr, Kzg>>::prove(&pk, &pi, &witnesses)?;
+        assert!(<Snark as HyperPlonkSNARK<Fr, Kzg>>::verify(
+            &vk, &pi, &proof
+        )?);
+
+        // the proof survives serialization
+        let mut bytes = vec![];
+        proof.serialize_compressed(&mut bytes)?;
+        let proof2 = HyperPlonkProof::<Fr, Snark, Kzg>::deserialize_compressed(&bytes[..])?;
+        assert!(<Snark as HyperPlonkSNARK<Fr, Kzg>>::verify(
+            &vk, &pi, &proof2
+        )?);
+
+        // bad path 1: a proof for one range is rejected for a narrower range
+        let mut other_index = index.clone();
+        other_index.params.range_checks[0].num_bits = 9;
+        let (_, other_vk) =
+            <Snark as HyperPlonkSNARK<Fr, Kzg>>::preprocess(&other_index, &pcs_srs)?;
+        assert!(
+            !<Snark as HyperPlonkSNARK<Fr, Kzg>>::verify(&other_vk, &pi, &proof).unwrap_or(false)
+        );
+
+        // bad path 2: a witness that satisfies the gate but not the range
+        let mut bad_low = low;
+        bad_low[2] = 4;
+        let bad_witnesses = build_witnesses(&high, &bad_low);
+        assert!(<Snark as HyperPlonkSNARK<Fr, Kzg>>::prove(&pk, &pi, &bad_witnesses).is_err());
+
+        // bad path 3: a range check gate on a missing witness column
+        let mut bad_index = index.clone();
+        bad_index.params.range_checks[0].witness = 3;
+        assert!(<Snark as HyperPlonkSNARK<Fr, Kzg>>::preprocess(&bad_index, &pcs_srs).is_err());
+
+        // range check gates are not supported by zero-knowledge keys
+        assert!(blind_index(&index).is_err());
+        Ok(())
+    }
+
+    fn test_hyperplonk_helper<F, PCS>(gate_func: CustomizedGates) -> Result<(), HyperPlonkErrors>
+    where
+        F: PrimeField,
+        PCS: PolynomialCommitmentScheme<
+            F,
+            Polynomial = Arc<DenseMultilinearExtension<F>>,
+            Point = Vec<F>,
+            Evaluation = F,
+        >,
+    {
+        let mut rng = test_rng();
+        let pcs_srs = PCS::gen_srs_for_testing(&mut rng, 16)?;
 
         let num_constraints = 4;
         let num_pub_input = 4;
@@ -670,81 +2008,130 @@ mod tests {
             num_constraints,
             num_pub_input,
             gate_func,
+            blinded: false,
+            lookups: vec![],
+            range_checks: vec![],
         };
         let permutation = identity_permutation(nv, num_witnesses);
-        let q1 = SelectorColumn(vec![
-            E::ScalarField::one(),
-            E::ScalarField::one(),
-            E::ScalarField::one(),
-            E::ScalarField::one(),
-        ]);
+        let q1 = SelectorColumn(vec![F::one(), F::one(), F::one(), F::one()]);
         let index = HyperPlonkIndex {
             params,
             permutation,
             selectors: vec![q1],
+            lookup_selectors: vec![],
+            tables: vec![],
         };
 
         // generate pk and vks
-        let (pk, vk) =
-            <PolyIOP<E::ScalarField> as HyperPlonkSNARK<E, MultilinearKzgPCS<E>>>::preprocess(
-                &index, &pcs_srs,
-            )?;
+        let (pk, vk) = <PolyIOP<F> as HyperPlonkSNARK<F, PCS>>::preprocess(&index, &pcs_srs)?;
 
         // w1 := [0, 1, 2, 3]
-        let w1 = WitnessColumn(vec![
-            E::ScalarField::zero(),
-            E::ScalarField::one(),
-            E::ScalarField::from(2u128),
-            E::ScalarField::from(3u128),
-        ]);
+        let w1 = WitnessColumn(vec![F::zero(), F::one(), F::from(2u128), F::from(3u128)]);
         // w2 := [0^5, 1^5, 2^5, 3^5]
-        let w2 = WitnessColumn(vec![
-            E::ScalarField::zero(),
-            E::ScalarField::one(),
-            E::ScalarField::from(32u128),
-            E::ScalarField::from(243u128),
-        ]);
+        let w2 = WitnessColumn(vec![F::zero(), F::one(), F::from(32u128), F::from(243u128)]);
         // public input = w1
         let pi = w1.clone();
 
         // generate a proof and verify
-        let proof = <PolyIOP<E::ScalarField> as HyperPlonkSNARK<E, MultilinearKzgPCS<E>>>::prove(
-            &pk,
-            &pi.0,
-            &[w1.clone(), w2.clone()],
-        )?;
+        let proof =
+            <PolyIOP<F> as HyperPlonkSNARK<F, PCS>>::prove(&pk, &pi.0, &[w1.clone(), w2.clone()])?;
 
-        let _verify =
-            <PolyIOP<E::ScalarField> as HyperPlonkSNARK<E, MultilinearKzgPCS<E>>>::verify(
-                &vk, &pi.0, &proof,
-            )?;
+        let _verify = <PolyIOP<F> as HyperPlonkSNARK<F, PCS>>::verify(&vk, &pi.0, &proof)?;
 
         // bad path 1: wrong permutation
-        let rand_perm: Vec<E::ScalarField> = random_permutation(nv, num_witnesses, &mut rng);
+        let rand_perm: Vec<F> = random_permutation(nv, num_witnesses, &mut rng);
+        let index_permutation = index.permutation.clone();
         let mut bad_index = index;
         bad_index.permutation = rand_perm;
         // generate pk and vks
         let (_, bad_vk) =
-            <PolyIOP<E::ScalarField> as HyperPlonkSNARK<E, MultilinearKzgPCS<E>>>::preprocess(
-                &bad_index, &pcs_srs,
-            )?;
-        assert!(!<PolyIOP<E::ScalarField> as HyperPlonkSNARK<
-            E,
-            MultilinearKzgPCS<E>,
-        >>::verify(&bad_vk, &pi.0, &proof,)?);
+            <PolyIOP<F> as HyperPlonkSNARK<F, PCS>>::preprocess(&bad_index, &pcs_srs)?;
+        assert_eq!(
+            pk.vk_digest::<IOPTranscript<F>>()?,
+            vk.digest::<IOPTranscript<F>>()?
+        );
+        assert_ne!(
+            vk.digest::<IOPTranscript<F>>()?,
+            bad_vk.digest::<IOPTranscript<F>>()?
+        );
+        // the verifier either fails to verify the sumchecks under the new
+        // transcript, or rejects the final evaluations
+        assert!(
+            !<PolyIOP<F> as HyperPlonkSNARK<F, PCS>>::verify(&bad_vk, &pi.0, &proof)
+                .unwrap_or(false)
+        );
+
+        // bad path 2: a proof for one public input is rejected for another
+        let mut bad_pi = pi.clone();
+        bad_pi.0[0] = F::one();
+        assert!(
+            !<PolyIOP<F> as HyperPlonkSNARK<F, PCS>>::verify(&vk, &bad_pi.0, &proof)
+                .unwrap_or(false)
+        );
 
-        // bad path 2: wrong witness
+        // bad path 3: a proof for one circuit is rejected for another circuit
+        // with the same shape
+        let mut other_index = bad_index.clone();
+        other_index.permutation = index_permutation;
+        other_index.selectors[0].0[0] = F::zero();
+        let (_, other_vk) =
+            <PolyIOP<F> as HyperPlonkSNARK<F, PCS>>::preprocess(&other_index, &pcs_srs)?;
+        assert!(
+            !<PolyIOP<F> as HyperPlonkSNARK<F, PCS>>::verify(&other_vk, &pi.0, &proof)
+                .unwrap_or(false)
+        );
+
+        // bad path 4: wrong witness
         let mut w1_bad = w1;
-        w1_bad.0[0] = E::ScalarField::one();
+        w1_bad.0[0] = F::one();
         assert!(
-            <PolyIOP<E::ScalarField> as HyperPlonkSNARK<E, MultilinearKzgPCS<E>>>::prove(
-                &pk,
-                &pi.0,
-                &[w1_bad, w2],
-            )
-            .is_err()
+            <PolyIOP<F> as HyperPlonkSNARK<F, PCS>>::prove(&pk, &pi.0, &[w1_bad, w2],).is_err()
         );
 
         Ok(())
     }
+
+    /// The Pallas curve `y^2 = x^3 + 5`, whose scalar field is the base field
+    /// of Vesta.
+    // `MontConfig` checks the `asm` feature of ark-ff in this crate
+    #[allow(unexpected_cfgs)]
+    mod pallas {
+        use ark_ec::{
+            models::CurveConfig,
+            short_weierstrass::{self, SWCurveConfig},
+        };
+        use ark_ff::{Fp256, MontBackend, MontConfig, MontFp};
+
+        #[derive(MontConfig)]
+        #[modulus = "28948022309329048855892746252171976963363056481941560715954676764349967630337"]
+        #[generator = "5"]
+        pub struct FqConfig;
+        pub type Fq = Fp256<MontBackend<FqConfig, 4>>;
+
+        #[derive(MontConfig)]
+        #[modulus = "28948022309329048855892746252171976963363056481941647379679742748393362948097"]
+        #[generator = "5"]
+        pub struct FrConfig;
+        pub type Fr = Fp256<MontBackend<FrConfig, 4>>;
+
+        #[derive(Copy, Clone, Default, PartialEq, Eq)]
+        pub struct PallasConfig;
+
+        impl CurveConfig for PallasConfig {
+            type BaseField = Fq;
+            type ScalarField = Fr;
+
+            const COFACTOR: &'static [u64] = &[0x1];
+            const COFACTOR_INV: Fr = MontFp!("1");
+        }
+
+        impl SWCurveConfig for PallasConfig {
+            const COEFF_A: Fq = MontFp!("0");
+            const COEFF_B: Fq = MontFp!("5");
+            const GENERATOR: short_weierstrass::Affine<Self> =
+                short_weierstrass::Affine::new_unchecked(MontFp!("-1"), MontFp!("2"));
+        }
+
+        pub type Projective = short_weierstrass::Projective<PallasConfig>;
+    }
 }
@@ -6,28 +6,41 @@
 
 //! Main module for the HyperPlonk PolyIOP.
 
-use crate::{custom_gate::CustomizedGates, prelude::HyperPlonkErrors, selectors::SelectorColumn};
-use ark_ec::pairing::Pairing;
+use crate::{
+    custom_gate::CustomizedGates,
+    lookup::{LookupGate, TableColumn},
+    prelude::HyperPlonkErrors,
+    range_check::RangeCheckGate,
+    selectors::SelectorColumn,
+};
 use ark_ff::PrimeField;
 use ark_poly::DenseMultilinearExtension;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
 use ark_std::log2;
 use std::sync::Arc;
 use subroutines::{
     pcs::PolynomialCommitmentScheme,
-    poly_iop::prelude::{PermutationCheck, ZeroCheck},
+    poly_iop::prelude::{
+        IOPProof, LookupCheck, PermutationCheck, RangeCheck, ZeroCheck, ZkSumCheckProof,
+    },
 };
+use transcript::Transcript;
 
 /// The proof for the HyperPlonk PolyIOP, consists of the following:
 ///   - the commitments to all witness MLEs
 ///   - a batch opening to all the MLEs at certain index
 ///   - the zero-check proof for checking custom gate-satisfiability
 ///   - the permutation-check proof for checking the copy constraints
+///   - the lookup-check proofs for checking the lookup gates
+///   - the range-check proofs for checking the range check gates
+///   - for a zero-knowledge proof, the masks of the zero-check and of the
+///     permutation-check
 #[derive(Clone, Debug, PartialEq)]
-pub struct HyperPlonkProof<E, PC, PCS>
+pub struct HyperPlonkProof<F, PC, PCS>
 where
-    E: Pairing,
-    PC: PermutationCheck<E, PCS>,
-    PCS: PolynomialCommitmentScheme<E>,
+    F: PrimeField,
+    PC: PermutationCheck<F, PCS> + LookupCheck<F, PCS> + RangeCheck<F, PCS>,
+    PCS: PolynomialCommitmentScheme<F>,
 {
     // PCS commit for witnesses
     pub witness_commits: Vec<PCS::Commitment>,
@@ -36,15 +49,55 @@ where
     // IOP proofs
     // =======================================================================
     // the custom gate zerocheck proof
-    pub zero_check_proof: <PC as ZeroCheck<E::ScalarField>>::ZeroCheckProof,
+    pub zero_check_proof: <PC as ZeroCheck<F>>::ZeroCheckProof,
     // the permutation check proof for copy constraints
     pub perm_check_proof: PC::PermutationProof,
+    // the lookup check proofs, one per lookup gate
+    pub lookup_check_proofs: Vec<PC::LookupCheckProof>,
+    // the range check proofs, one per range check gate
+    pub range_check_proofs: Vec<PC::RangeCheckProof>,
+    // =======================================================================
+    // masks of a zero-knowledge proof
+    // =======================================================================
+    // the mask of the custom gate zerocheck
+    pub zero_check_mask: Option<HyperPlonkMask<F, PCS>>,
+    // the mask of the zerocheck of the permutation check
+    pub perm_check_mask: Option<HyperPlonkMask<F, PCS>>,
+}
+
+/// The mask of a zerocheck in a zero-knowledge HyperPlonk proof, consists of
+/// the following:
+///   - the hiding commitment to the multilinear encoding of the mask
+///   - the sum of the mask over the hypercube
+///   - the evaluations of the univariates of the mask at the zerocheck point
+///   - the openings of the encoding that prove these evaluations
+#[derive(Clone, Debug, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct HyperPlonkMask<F: PrimeField, PCS: PolynomialCommitmentScheme<F>> {
+    pub mask_comm: PCS::Commitment,
+    pub mask_sum: F,
+    pub mask_evaluations: Vec<F>,
+    pub mask_openings: Vec<PCS::Proof>,
+}
+
+impl<F: PrimeField, PCS: PolynomialCommitmentScheme<F>> HyperPlonkMask<F, PCS> {
+    /// The zero-knowledge zerocheck proof made of the mask and of
+    /// `sum_check_proof`, the masked sum check of the HyperPlonk proof.
+    pub(crate) fn zk_sum_check_proof(&self, sum_check_proof: &IOPProof<F>) -> ZkSumCheckProof<F> {
+        ZkSumCheckProof {
+            mask_sum: self.mask_sum,
+            sum_check_proof: sum_check_proof.clone(),
+            mask_evaluations: self.mask_evaluations.clone(),
+        }
+    }
 }
 
 /// The HyperPlonk instance parameters, consists of the following:
 ///   - the number of constraints
 ///   - number of public input columns
 ///   - the customized gate function
+///   - whether the keys are zero-knowledge, with blinding rows
+///   - the lookup gates
+///   - the range check gates
 #[derive(Clone, Debug, Default, PartialEq, Eq)]
 pub struct HyperPlonkParams {
     /// the number of constraints
@@ -55,12 +108,22 @@ pub struct HyperPlonkParams {
     pub num_pub_input: usize,
     /// customized gate function
     pub gate_func: CustomizedGates,
+    /// whether the keys were generated by `preprocess_zk`
+    // zero-knowledge keys have blinding rows: the polynomials have one more
+    // variable, the constraints are the lower half of the hypercube and the
+    // upper half holds random blinding rows.
+    pub blinded: bool,
+    /// lookup gates
+    // each lookup gate has its own lookup selector column in the index.
+    pub lookups: Vec<LookupGate>,
+    /// range check gates
+    pub range_checks: Vec<RangeCheckGate>,
 }
 
 impl HyperPlonkParams {
     /// Number of variables in a multilinear system
     pub fn num_variables(&self) -> usize {
-        log2(self.num_constraints) as usize
+        log2(self.num_constraints) as usize + self.blinded as usize
     }
 
     /// number of selector columns
@@ -73,6 +136,21 @@ impl HyperPlonkParams {
         self.gate_func.num_witness_columns()
     }
 
+    /// number of lookup gates
+    pub fn num_lookups(&self) -> usize {
+        self.lookups.len()
+    }
+
+    /// number of range check gates
+    pub fn num_range_checks(&self) -> usize {
+        self.range_checks.len()
+    }
+
+    /// number of table columns
+    pub fn num_table_columns(&self) -> usize {
+        self.lookups.iter().map(|l| l.table + 1).max().unwrap_or(0)
+    }
+
     /// evaluate the identical polynomial
     pub fn eval_id_oracle<F: PrimeField>(&self, point: &[F]) -> Result<F, HyperPlonkErrors> {
         let len = self.num_variables() + (log2(self.num_witness_columns()) as usize);
@@ -92,17 +170,96 @@ impl HyperPlonkParams {
         }
         Ok(res)
     }
+
+    /// Append the parameters to the transcript: the number of constraints, the
+    /// number of public inputs, the customized gate, the blinding flag,
+    /// the lookup gates and the range check gates, all as field elements.
+    pub(crate) fn append_to_transcript<F: PrimeField, T: Transcript<F>>(
+        &self,
+        transcript: &mut T,
+    ) -> Result<(), HyperPlonkErrors> {
+        transcript
+            .append_field_element(b"num_constraints", &F::from(self.num_constraints as u64))?;
+        transcript.append_field_element(b"num_pub_input", &F::from(self.num_pub_input as u64))?;
+        transcript
+            .append_field_element(b"num_gates", &F::from(self.gate_func.gates.len() as u64))?;
+        for (coeff, selector, witnesses) in self.gate_func.gates.iter() {
+            transcript.append_field_element(b"coeff", &F::from(*coeff))?;
+            // 0 for no selector, and 1 + i for the i-th selector
+            let selector = selector.map_or(0, |q| q as u64 + 1);
+            transcript.append_field_element(b"selector", &F::from(selector))?;
+            transcript.append_field_element(b"num_wires", &F::from(witnesses.len() as u64))?;
+            for &w in witnesses.iter() {
+                transcript.append_field_element(b"wire", &F::from(w as u64))?;
+            }
+        }
+        transcript.append_field_element(b"blinded", &F::from(self.blinded as u64))?;
+        transcript.append_field_element(b"num_lookups", &F::from(self.lookups.len() as u64))?;
+        for lookup in self.lookups.iter() {
+            transcript
+                .append_field_element(b"num_wires", &F::from(lookup.witnesses.len() as u64))?;
+            for &w in lookup.witnesses.iter() {
+                transcript.append_field_element(b"wire", &F::from(w as u64))?;
+            }
+            transcript.append_field_element(b"table", &F::from(lookup.table as u64))?;
+        }
+        transcript.append_field_element(
+            b"num_range_checks",
+            &F::from(self.range_checks.len() as u64),
+        )?;
+        for range_check in self.range_checks.iter() {
+            transcript.append_field_element(b"wire", &F::from(range_check.witness as u64))?;
+            transcript.append_field_element(b"num_bits", &F::from(range_check.num_bits as u64))?;
+        }
+        Ok(())
+    }
+}
+
+/// The digest of a verifying key: the challenge drawn from a fresh transcript
+/// after appending the parameters and the selector, permutation, lookup
+/// selector and table commitments.
+fn vk_digest<F, PCS, T>(
+    params: &HyperPlonkParams,
+    selector_commitments: &[PCS::Commitment],
+    perm_commitments: &[PCS::Commitment],
+    lookup_selector_commitments: &[PCS::Commitment],
+    table_commitments: &[PCS::Commitment],
+) -> Result<F, HyperPlonkErrors>
+where
+    F: PrimeField,
+    PCS: PolynomialCommitmentScheme<F>,
+    T: Transcript<F>,
+{
+    let mut transcript = T::new(b"hyperplonk vk");
+    params.append_to_transcript(&mut transcript)?;
+    for com in selector_commitments.iter() {
+        transcript.append_serializable_element(b"selector_com", com)?;
+    }
+    for com in perm_commitments.iter() {
+        transcript.append_serializable_element(b"perm_com", com)?;
+    }
+    for com in lookup_selector_commitments.iter() {
+        transcript.append_serializable_element(b"lookup_selector_com", com)?;
+    }
+    for com in table_commitments.iter() {
+        transcript.append_serializable_element(b"table_com", com)?;
+    }
+    Ok(transcript.get_and_append_challenge(b"vk_digest")?)
 }
 
 /// The HyperPlonk index, consists of the following:
 ///   - HyperPlonk parameters
 ///   - the wire permutation
 ///   - the selector vectors
+///   - the lookup selector vectors, one per lookup gate
+///   - the table vectors
 #[derive(Clone, Debug, Default, PartialEq, Eq)]
 pub struct HyperPlonkIndex<F: PrimeField> {
     pub params: HyperPlonkParams,
     pub permutation: Vec<F>,
     pub selectors: Vec<SelectorColumn<F>>,
+    pub lookup_selectors: Vec<SelectorColumn<F>>,
+    pub tables: Vec<TableColumn<F>>,
 }
 
 impl<F: PrimeField> HyperPlonkIndex<F> {
@@ -128,27 +285,49 @@ impl<F: PrimeField> HyperPlonkIndex<F> {
 ///   - the commitment to the selectors and permutations
 ///   - the parameters for polynomial commitment
 #[derive(Clone, Debug, Default, PartialEq)]
-pub struct HyperPlonkProvingKey<E: Pairing, PCS: PolynomialCommitmentScheme<E>> {
+pub struct HyperPlonkProvingKey<F: PrimeField, PCS: PolynomialCommitmentScheme<F>> {
     /// Hyperplonk instance parameters
     pub params: HyperPlonkParams,
     /// The preprocessed permutation polynomials
-    pub permutation_oracles: Vec<Arc<DenseMultilinearExtension<E::ScalarField>>>,
+    pub permutation_oracles: Vec<Arc<DenseMultilinearExtension<F>>>,
     /// The preprocessed selector polynomials
-    pub selector_oracles: Vec<Arc<DenseMultilinearExtension<E::ScalarField>>>,
+    pub selector_oracles: Vec<Arc<DenseMultilinearExtension<F>>>,
     /// Commitments to the preprocessed selector polynomials
     pub selector_commitments: Vec<PCS::Commitment>,
     /// Commitments to the preprocessed permutation polynomials
     pub permutation_commitments: Vec<PCS::Commitment>,
+    /// The preprocessed lookup selector polynomials
+    pub lookup_selector_oracles: Vec<Arc<DenseMultilinearExtension<F>>>,
+    /// The preprocessed table polynomials
+    pub table_oracles: Vec<Arc<DenseMultilinearExtension<F>>>,
+    /// Commitments to the preprocessed lookup selector polynomials
+    pub lookup_selector_commitments: Vec<PCS::Commitment>,
+    /// Commitments to the preprocessed table polynomials
+    pub table_commitments: Vec<PCS::Commitment>,
     /// The parameters for PCS commitment
     pub pcs_param: PCS::ProverParam,
 }
 
+impl<F: PrimeField, PCS: PolynomialCommitmentScheme<F>> HyperPlonkProvingKey<F, PCS> {
+    /// The digest of the matching verifying key, see
+    /// `HyperPlonkVerifyingKey::digest`.
+    pub fn vk_digest<T: Transcript<F>>(&self) -> Result<F, HyperPlonkErrors> {
+        vk_digest::<F, PCS, T>(
+            &self.params,
+            &self.selector_commitments,
+            &self.permutation_commitments,
+            &self.lookup_selector_commitments,
+            &self.table_commitments,
+        )
+    }
+}
+
 /// The HyperPlonk verifying key, consists of the following:
 ///   - the hyperplonk instance parameters
 ///   - the commitments to the preprocessed polynomials output by the indexer
 ///   - the parameters for polynomial commitment
 #[derive(Clone, Debug, Default, PartialEq)]
-pub struct HyperPlonkVerifyingKey<E: Pairing, PCS: PolynomialCommitmentScheme<E>> {
+pub struct HyperPlonkVerifyingKey<F: PrimeField, PCS: PolynomialCommitmentScheme<F>> {
     /// Hyperplonk instance parameters
     pub params: HyperPlonkParams,
     /// The parameters for PCS commitment
@@ -157,4 +336,26 @@ pub struct HyperPlonkVerifyingKey<E: Pairing, PCS: PolynomialCommitmentScheme<E>
     pub selector_commitments: Vec<PCS::Commitment>,
     /// Permutation oracles' commitments
     pub perm_commitments: Vec<PCS::Commitment>,
+    /// Lookup selector oracles' commitments
+    pub lookup_selector_commitments: Vec<PCS::Commitment>,
+    /// Table oracles' commitments
+    pub table_commitments: Vec<PCS::Commitment>,
+}
+
+impl<F: PrimeField, PCS: PolynomialCommitmentScheme<F>> HyperPlonkVerifyingKey<F, PCS> {
+    /// A digest of the circuit, computed with the transcript `T` from the
+    /// instance parameters and the commitments to the preprocessed
+    /// polynomials.
+    ///
+    /// The PCS verifier parameters are not part of the digest; the
+    /// commitments are already bound to them.
+    pub fn digest<T: Transcript<F>>(&self) -> Result<F, HyperPlonkErrors> {
+        vk_digest::<F, PCS, T>(
+            &self.params,
+            &self.selector_commitments,
+            &self.perm_commitments,
+            &self.lookup_selector_commitments,
+            &self.table_commitments,
+        )
+    }
 }
@@ -9,17 +9,16 @@ use crate::{
     witness::WitnessColumn,
 };
 use arithmetic::{evaluate_opt, VirtualPolynomial};
-use ark_ec::pairing::Pairing;
 use ark_ff::PrimeField;
 use ark_poly::DenseMultilinearExtension;
 use std::{borrow::Borrow, sync::Arc};
-use subroutines::pcs::{prelude::Commitment, PolynomialCommitmentScheme};
-use transcript::IOPTranscript;
+use subroutines::pcs::{HidingPolynomialCommitmentScheme, PolynomialCommitmentScheme};
+use transcript::Transcript;
 
 /// An accumulator structure that holds a polynomial and
 /// its opening points
 #[derive(Debug)]
-pub(super) struct PcsAccumulator<E: Pairing, PCS: PolynomialCommitmentScheme<E>> {
+pub(super) struct PcsAccumulator<F: PrimeField, PCS: PolynomialCommitmentScheme<F>> {
     // sequence:
     // - prod(x) at 5 points
     // - w_merged at perm check point
@@ -33,15 +32,14 @@ pub(super) struct PcsAccumulator<E: Pairing, PCS: PolynomialCommitmentScheme<E>>
     pub(crate) evals: Vec<PCS::Evaluation>,
 }
 
-impl<E, PCS> PcsAccumulator<E, PCS>
+impl<F, PCS> PcsAccumulator<F, PCS>
 where
-    E: Pairing,
+    F: PrimeField,
     PCS: PolynomialCommitmentScheme<
-        E,
-        Polynomial = Arc<DenseMultilinearExtension<E::ScalarField>>,
-        Point = Vec<E::ScalarField>,
-        Evaluation = E::ScalarField,
-        Commitment = Commitment<E>,
+        F,
+        Polynomial = Arc<DenseMultilinearExtension<F>>,
+        Point = Vec<F>,
+        Evaluation = F,
     >,
 {
     /// Create an empty accumulator.
@@ -70,7 +68,7 @@ where
         self.evals.push(eval);
         self.polynomials.push(poly.clone());
         self.points.push(point.clone());
-        self.commitments.push(*commit);
+        self.commitments.push(commit.clone());
     }
 
     /// Batch open all the points over a merged polynomial.
@@ -78,7 +76,7 @@ where
     pub(super) fn multi_open(
         &self,
         prover_param: impl Borrow<PCS::ProverParam>,
-        transcript: &mut IOPTranscript<E::ScalarField>,
+        transcript: &mut impl Transcript<F>,
     ) -> Result<PCS::BatchProof, HyperPlonkErrors> {
         Ok(PCS::multi_open(
             prover_param.borrow(),
@@ -88,6 +86,28 @@ where
             transcript,
         )?)
     }
+
+    /// Batch open all the points over a merged polynomial with a hiding PCS,
+    /// where `blindings` holds the blinding factor of each inserted
+    /// polynomial. A simple wrapper of PCS::multi_open_with_blindings
+    pub(super) fn multi_open_with_blindings(
+        &self,
+        prover_param: impl Borrow<PCS::ProverParam>,
+        blindings: &[PCS::Blinding],
+        transcript: &mut impl Transcript<F>,
+    ) -> Result<PCS::BatchProof, HyperPlonkErrors>
+    where
+        PCS: HidingPolynomialCommitmentScheme<F>,
+    {
+        Ok(PCS::multi_open_with_blindings(
+            prover_param.borrow(),
+            self.polynomials.as_ref(),
+            blindings,
+            self.points.as_ref(),
+            self.evals.as_ref(),
+            transcript,
+        )?)
+    }
 }
 
 /// Build MLE from matrix of witnesses.
@@ -119,6 +139,21 @@ macro_rules! build_mle {
     }};
 }
 
+/// Bind the transcript to the statement, i.e. the verifying key digest and
+/// the public input. Run by both the prover and the verifier before any
+/// challenge is drawn.
+pub(crate) fn append_statement<F: PrimeField, T: Transcript<F>>(
+    transcript: &mut T,
+    vk_digest: &F,
+    pub_input: &[F],
+) -> Result<(), HyperPlonkErrors> {
+    transcript.append_field_element(b"vk_digest", vk_digest)?;
+    for pi in pub_input.iter() {
+        transcript.append_field_element(b"pi", pi)?;
+    }
+    Ok(())
+}
+
 /// Sanity-check for HyperPlonk SNARK proving
 pub(crate) fn prover_sanity_check<F: PrimeField>(
     params: &HyperPlonkParams,
@@ -297,9 +332,10 @@ mod test {
     use super::*;
     use ark_bls12_381::Fr;
     use ark_ff::PrimeField;
-    use ark_poly::MultilinearExtension;
+    use ark_poly::Polynomial;
     #[test]
     fn test_build_gate() -> Result<(), HyperPlonkErrors> {
+        let _trace = crate::trace_guard();
         test_build_gate_helper::<Fr>()
     }
 
@@ -340,47 +376,52 @@ mod test {
         let gates = CustomizedGates {
             gates: vec![(1, Some(0), vec![0, 0, 0, 0, 0]), (-1, None, vec![1])],
         };
-        let f = build_f(&gates, num_vars, &[ql.clone()], &[w1.clone(), w2.clone()])?;
+        let f = build_f(
+            &gates,
+            num_vars,
+            std::slice::from_ref(&ql),
+            &[w1.clone(), w2.clone()],
+        )?;
 
         // Sanity check on build_f
         // f(0, 0) = 0
-        assert_eq!(f.evaluate(&[F::zero(), F::zero()])?, F::zero());
+        assert_eq!(f.evaluate(&vec![F::zero(), F::zero()])?, F::zero());
         // f(0, 1) = 2 * 0^5 + (-1) * 1 = -1
-        assert_eq!(f.evaluate(&[F::zero(), F::one()])?, -F::one());
+        assert_eq!(f.evaluate(&vec![F::zero(), F::one()])?, -F::one());
         // f(1, 0) = 0 * 1^5 + (-1) * 1 = -1
-        assert_eq!(f.evaluate(&[F::one(), F::zero()])?, -F::one());
+        assert_eq!(f.evaluate(&vec![F::one(), F::zero()])?, -F::one());
         // f(1, 1) = 5 * 2^5 + (-1) * 2 = 158
-        assert_eq!(f.evaluate(&[F::one(), F::one()])?, F::from(158u64));
+        assert_eq!(f.evaluate(&vec![F::one(), F::one()])?, F::from(158u64));
 
         // test eval_f
         {
-            let point = [F::zero(), F::zero()];
-            let selector_evals = ql.evaluate(&point).unwrap();
-            let witness_evals = [w1.evaluate(&point).unwrap(), w2.evaluate(&point).unwrap()];
+            let point = vec![F::zero(), F::zero()];
+            let selector_evals = ql.evaluate(&point);
+            let witness_evals = [w1.evaluate(&point), w2.evaluate(&point)];
             let eval_f = eval_f(&gates, &[selector_evals], &witness_evals)?;
             // f(0, 0) = 0
             assert_eq!(eval_f, F::zero());
         }
         {
-            let point = [F::zero(), F::one()];
-            let selector_evals = ql.evaluate(&point).unwrap();
-            let witness_evals = [w1.evaluate(&point).unwrap(), w2.evaluate(&point).unwrap()];
+            let point = vec![F::zero(), F::one()];
+            let selector_evals = ql.evaluate(&point);
+            let witness_evals = [w1.evaluate(&point), w2.evaluate(&point)];
             let eval_f = eval_f(&gates, &[selector_evals], &witness_evals)?;
             // f(0, 1) = 2 * 0^5 + (-1) * 1 = -1
             assert_eq!(eval_f, -F::one());
         }
         {
-            let point = [F::one(), F::zero()];
-            let selector_evals = ql.evaluate(&point).unwrap();
-            let witness_evals = [w1.evaluate(&point).unwrap(), w2.evaluate(&point).unwrap()];
+            let point = vec![F::one(), F::zero()];
+            let selector_evals = ql.evaluate(&point);
+            let witness_evals = [w1.evaluate(&point), w2.evaluate(&point)];
             let eval_f = eval_f(&gates, &[selector_evals], &witness_evals)?;
             // f(1, 0) = 0 * 1^5 + (-1) * 1 = -1
             assert_eq!(eval_f, -F::one());
         }
         {
-            let point = [F::one(), F::one()];
-            let selector_evals = ql.evaluate(&point).unwrap();
-            let witness_evals = [w1.evaluate(&point).unwrap(), w2.evaluate(&point).unwrap()];
+            let point = vec![F::one(), F::one()];
+            let selector_evals = ql.evaluate(&point);
+            let witness_evals = [w1.evaluate(&point), w2.evaluate(&point)];
             let eval_f = eval_f(&gates, &[selector_evals], &witness_evals)?;
             // f(1, 1) = 5 * 2^5 + (-1) * 2 = 158
             assert_eq!(eval_f, F::from(158u64));
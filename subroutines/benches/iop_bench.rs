@@ -15,6 +15,7 @@ use subroutines::{
         PermutationCheck, PolyIOP, PolyIOPErrors, ProductCheck, SumCheck, ZeroCheck,
     },
 };
+use transcript::Transcript;
 
 type Kzg = MultilinearKzgPCS<Bls12_381>;
 
@@ -155,27 +156,26 @@ fn bench_permutation_check() -> Result<(), PolyIOPErrors> {
         // identity map
         let perms = identity_permutation_mles(nv, 1);
 
-        let proof =
-            {
-                let start = Instant::now();
-                let mut transcript =
-                    <PolyIOP<Fr> as PermutationCheck<Bls12_381, Kzg>>::init_transcript();
-                transcript.append_message(b"testing", b"initializing transcript for testing")?;
+        let proof = {
+            let start = Instant::now();
+            let mut transcript = <PolyIOP<Fr> as PermutationCheck<Fr, Kzg>>::init_transcript();
+            transcript.append_message(b"testing", b"initializing transcript for testing")?;
 
-                let (proof, _q_x, _frac_poly) = <PolyIOP<Fr> as PermutationCheck<
-                    Bls12_381,
-                    Kzg,
-                >>::prove(
-                    &pcs_param, &ws, &ws, &perms, &mut transcript
-                )?;
+            let (proof, _q_x, _frac_poly) = <PolyIOP<Fr> as PermutationCheck<Fr, Kzg>>::prove(
+                &pcs_param,
+                &ws,
+                &ws,
+                &perms,
+                &mut transcript,
+            )?;
 
-                println!(
-                    "permutation check proving time for {} variables: {} ns",
-                    nv,
-                    start.elapsed().as_nanos() / repetition as u128
-                );
-                proof
-            };
+            println!(
+                "permutation check proving time for {} variables: {} ns",
+                nv,
+                start.elapsed().as_nanos() / repetition as u128
+            );
+            proof
+        };
 
         {
             let poly_info = VPAuxInfo {
@@ -185,10 +185,9 @@ fn bench_permutation_check() -> Result<(), PolyIOPErrors> {
             };
 
             let start = Instant::now();
-            let mut transcript =
-                <PolyIOP<Fr> as PermutationCheck<Bls12_381, Kzg>>::init_transcript();
+            let mut transcript = <PolyIOP<Fr> as PermutationCheck<Fr, Kzg>>::init_transcript();
             transcript.append_message(b"testing", b"initializing transcript for testing")?;
-            let _perm_check_sum_claim = <PolyIOP<Fr> as PermutationCheck<Bls12_381, Kzg>>::verify(
+            let _perm_check_sum_claim = <PolyIOP<Fr> as PermutationCheck<Fr, Kzg>>::verify(
                 &proof,
                 &poly_info,
                 &mut transcript,
@@ -229,16 +228,15 @@ fn bench_prod_check() -> Result<(), PolyIOPErrors> {
 
         let proof = {
             let start = Instant::now();
-            let mut transcript = <PolyIOP<Fr> as ProductCheck<Bls12_381, Kzg>>::init_transcript();
+            let mut transcript = <PolyIOP<Fr> as ProductCheck<Fr, Kzg>>::init_transcript();
             transcript.append_message(b"testing", b"initializing transcript for testing")?;
 
-            let (proof, _prod_x, _frac_poly) =
-                <PolyIOP<Fr> as ProductCheck<Bls12_381, Kzg>>::prove(
-                    &pcs_param,
-                    &fs,
-                    &gs,
-                    &mut transcript,
-                )?;
+            let (proof, _prod_x, _frac_poly) = <PolyIOP<Fr> as ProductCheck<Fr, Kzg>>::prove(
+                &pcs_param,
+                &fs,
+                &gs,
+                &mut transcript,
+            )?;
 
             println!(
                 "product check proving time for {} variables: {} ns",
@@ -256,9 +254,9 @@ fn bench_prod_check() -> Result<(), PolyIOPErrors> {
             };
 
             let start = Instant::now();
-            let mut transcript = <PolyIOP<Fr> as ProductCheck<Bls12_381, Kzg>>::init_transcript();
+            let mut transcript = <PolyIOP<Fr> as ProductCheck<Fr, Kzg>>::init_transcript();
             transcript.append_message(b"testing", b"initializing transcript for testing")?;
-            let _perm_check_sum_claim = <PolyIOP<Fr> as ProductCheck<Bls12_381, Kzg>>::verify(
+            let _perm_check_sum_claim = <PolyIOP<Fr> as ProductCheck<Fr, Kzg>>::verify(
                 &proof,
                 &poly_info,
                 &mut transcript,
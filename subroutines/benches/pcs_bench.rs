@@ -4,25 +4,49 @@
 // You should have received a copy of the MIT License
 // along with the HyperPlonk library. If not, see <https://mit-license.org/>.
 
-use ark_bls12_381::{Bls12_381, Fr};
+//! Compare the multilinear KZG with Zeromorph and Gemini, which are built on
+//! univariate KZG, with the transparent Ligero and Basefold, and with Hyrax,
+//! which needs no pairing: running times, and the sizes of the proofs and of
+//! the verifier keys, whose G2 elements are costly for an on-chain verifier.
+
+use ark_bls12_381::{Bls12_381, Fr, G1Projective};
 use ark_ff::UniformRand;
 use ark_poly::{DenseMultilinearExtension, MultilinearExtension};
+use ark_serialize::CanonicalSerialize;
 use ark_std::{sync::Arc, test_rng};
 use std::time::Instant;
-use subroutines::pcs::{
-    prelude::{MultilinearKzgPCS, PCSError, PolynomialCommitmentScheme},
-    StructuredReferenceString,
+use subroutines::pcs::prelude::{
+    BasefoldPCS, GeminiPCS, HyraxPCS, LigeroPCS, MultilinearKzgPCS, PCSError,
+    PolynomialCommitmentScheme, ZeromorphPCS,
 };
 
 fn main() -> Result<(), PCSError> {
-    bench_pcs()
+    bench_pcs::<MultilinearKzgPCS<Bls12_381>>("KZG")?;
+    println!("\n\n");
+    bench_pcs::<ZeromorphPCS<Bls12_381>>("Zeromorph")?;
+    println!("\n\n");
+    bench_pcs::<GeminiPCS<Bls12_381>>("Gemini")?;
+    println!("\n\n");
+    bench_pcs::<LigeroPCS<Fr>>("Ligero")?;
+    println!("\n\n");
+    bench_pcs::<BasefoldPCS<Fr>>("Basefold")?;
+    println!("\n\n");
+    bench_pcs::<HyraxPCS<G1Projective>>("Hyrax")
 }
 
-fn bench_pcs() -> Result<(), PCSError> {
+fn bench_pcs<PCS>(name: &str) -> Result<(), PCSError>
+where
+    PCS: PolynomialCommitmentScheme<
+        Fr,
+        Polynomial = Arc<DenseMultilinearExtension<Fr>>,
+        Point = Vec<Fr>,
+        Evaluation = Fr,
+    >,
+{
     let mut rng = test_rng();
 
     // normal polynomials
-    let uni_params = MultilinearKzgPCS::<Bls12_381>::gen_srs_for_testing(&mut rng, 24)?;
+    let params = PCS::gen_srs_for_testing(&mut rng, 24)?;
 
     for nv in 4..25 {
         let repetition = if nv < 10 {
@@ -34,7 +58,7 @@ fn bench_pcs() -> Result<(), PCSError> {
         };
 
         let poly = Arc::new(DenseMultilinearExtension::rand(nv, &mut rng));
-        let (ck, vk) = uni_params.trim(nv)?;
+        let (ck, vk) = PCS::trim(&params, None, Some(nv))?;
 
         let point: Vec<_> = (0..nv).map(|_| Fr::rand(&mut rng)).collect();
 
@@ -42,48 +66,62 @@ fn bench_pcs() -> Result<(), PCSError> {
         let com = {
             let start = Instant::now();
             for _ in 0..repetition {
-                let _commit = MultilinearKzgPCS::commit(&ck, &poly)?;
+                let _commit = PCS::commit(&ck, &poly)?;
             }
 
             println!(
-                "KZG commit for {} variables: {} ns",
+                "{} commit for {} variables: {} ns",
+                name,
                 nv,
                 start.elapsed().as_nanos() / repetition as u128
             );
 
-            MultilinearKzgPCS::commit(&ck, &poly)?
+            PCS::commit(&ck, &poly)?
         };
 
         // open
         let (proof, value) = {
             let start = Instant::now();
             for _ in 0..repetition {
-                let _open = MultilinearKzgPCS::open(&ck, &poly, &point)?;
+                let _open = PCS::open(&ck, &poly, &point)?;
             }
 
             println!(
-                "KZG open for {} variables: {} ns",
+                "{} open for {} variables: {} ns",
+                name,
                 nv,
                 start.elapsed().as_nanos() / repetition as u128
             );
-            MultilinearKzgPCS::open(&ck, &poly, &point)?
+            PCS::open(&ck, &poly, &point)?
         };
 
         // verify
         {
             let start = Instant::now();
             for _ in 0..repetition {
-                assert!(MultilinearKzgPCS::verify(
-                    &vk, &com, &point, &value, &proof
-                )?);
+                assert!(PCS::verify(&vk, &com, &point, &value, &proof)?);
             }
             println!(
-                "KZG verify for {} variables: {} ns",
+                "{} verify for {} variables: {} ns",
+                name,
                 nv,
                 start.elapsed().as_nanos() / repetition as u128
             );
         }
 
+        println!(
+            "{} proof size for {} variables: {} bytes",
+            name,
+            nv,
+            proof.compressed_size()
+        );
+        println!(
+            "{} verifier key size for {} variables: {} bytes",
+            name,
+            nv,
+            vk.compressed_size()
+        );
+
         println!("====================================");
     }
 
@@ -0,0 +1,129 @@
+// Copyright (c) 2023 Espresso Systems (espressosys.com)
+// This file is part of the HyperPlonk library.
+
+// You should have received a copy of the MIT License
+// along with the HyperPlonk library. If not, see <https://mit-license.org/>.
+
+//! Compare the product check committing to `frac(x)` and `prod(x)` with the
+//! GKR one, which commits to nothing.
+
+use arithmetic::VPAuxInfo;
+use ark_bls12_381::{Bls12_381, Fr};
+use ark_poly::{DenseMultilinearExtension, MultilinearExtension};
+use ark_std::test_rng;
+use std::{marker::PhantomData, sync::Arc, time::Instant};
+use subroutines::{
+    pcs::{prelude::MultilinearKzgPCS, PolynomialCommitmentScheme},
+    poly_iop::prelude::{GkrProduct, PolyIOP, PolyIOPErrors, ProductCheck},
+};
+use transcript::{IOPTranscript, Transcript};
+
+type Kzg = MultilinearKzgPCS<Bls12_381>;
+
+fn main() -> Result<(), PolyIOPErrors> {
+    bench_prod_check::<PolyIOP<Fr>>("committed")?;
+    println!("\n\n");
+    bench_prod_check::<PolyIOP<Fr, IOPTranscript<Fr>, GkrProduct>>("gkr")?;
+    println!("\n\n");
+    bench_commit()
+}
+
+fn bench_prod_check<PC>(name: &str) -> Result<(), PolyIOPErrors>
+where
+    PC: ProductCheck<
+        Fr,
+        Kzg,
+        MultilinearExtension = Arc<DenseMultilinearExtension<Fr>>,
+        Transcript = IOPTranscript<Fr>,
+    >,
+{
+    let mut rng = test_rng();
+
+    for nv in 10..19 {
+        let srs = Kzg::gen_srs_for_testing(&mut rng, nv)?;
+        let (pcs_param, _) = Kzg::trim(&srs, None, Some(nv))?;
+
+        let repetition = if nv < 15 { 10 } else { 2 };
+
+        let f: DenseMultilinearExtension<Fr> = DenseMultilinearExtension::rand(nv, &mut rng);
+        let mut g = f.clone();
+        g.evaluations.reverse();
+        let fs = vec![Arc::new(f)];
+        let gs = vec![Arc::new(g)];
+
+        let proof = {
+            let start = Instant::now();
+            for _ in 0..repetition {
+                let mut transcript = <PC as ProductCheck<Fr, Kzg>>::init_transcript();
+                transcript.append_message(b"testing", b"initializing transcript for testing")?;
+                let _proof =
+                    <PC as ProductCheck<Fr, Kzg>>::prove(&pcs_param, &fs, &gs, &mut transcript)?;
+            }
+            println!(
+                "{} product check proving time for {} variables: {} ns",
+                name,
+                nv,
+                start.elapsed().as_nanos() / repetition as u128
+            );
+
+            let mut transcript = <PC as ProductCheck<Fr, Kzg>>::init_transcript();
+            transcript.append_message(b"testing", b"initializing transcript for testing")?;
+            <PC as ProductCheck<Fr, Kzg>>::prove(&pcs_param, &fs, &gs, &mut transcript)?.0
+        };
+
+        {
+            let poly_info = VPAuxInfo {
+                max_degree: 2,
+                num_variables: nv,
+                phantom: PhantomData,
+            };
+
+            let start = Instant::now();
+            for _ in 0..repetition {
+                let mut transcript = <PC as ProductCheck<Fr, Kzg>>::init_transcript();
+                transcript.append_message(b"testing", b"initializing transcript for testing")?;
+                let _sub_claim =
+                    <PC as ProductCheck<Fr, Kzg>>::verify(&proof, &poly_info, &mut transcript)?;
+            }
+            println!(
+                "{} product check verification time for {} variables: {} ns",
+                name,
+                nv,
+                start.elapsed().as_nanos() / repetition as u128
+            );
+        }
+
+        println!("====================================");
+    }
+
+    Ok(())
+}
+
+/// The MSMs saved by the GKR product check: the commitments to `frac(x)` and
+/// `prod(x)`.
+fn bench_commit() -> Result<(), PolyIOPErrors> {
+    let mut rng = test_rng();
+
+    for nv in 10..19 {
+        let srs = Kzg::gen_srs_for_testing(&mut rng, nv)?;
+        let (pcs_param, _) = Kzg::trim(&srs, None, Some(nv))?;
+
+        let repetition = if nv < 15 { 10 } else { 2 };
+
+        let frac = Arc::new(DenseMultilinearExtension::rand(nv, &mut rng));
+        let prod = Arc::new(DenseMultilinearExtension::rand(nv, &mut rng));
+
+        let start = Instant::now();
+        for _ in 0..repetition {
+            let _frac_comm = Kzg::commit(&pcs_param, &frac)?;
+            let _prod_comm = Kzg::commit(&pcs_param, &prod)?;
+        }
+        println!(
+            "saved commitments time for {} variables: {} ns",
+            nv,
+            start.elapsed().as_nanos() / repetition as u128
+        );
+    }
+
+    Ok(())
+}
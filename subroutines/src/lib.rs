@@ -11,3 +11,23 @@ pub mod poly_iop;
 
 pub use pcs::prelude::*;
 pub use poly_iop::prelude::*;
+
+/// Run the tests that print traces one at a time.
+///
+/// The indentation of the ark-std timers is global: it overflows when the
+/// timers of parallel tests interleave, or when the timers of the proofs that
+/// fail on purpose are never ended. With `print-trace`, the guard holds a
+/// global lock and resets the indentation; otherwise it does nothing.
+#[cfg(test)]
+pub(crate) fn trace_guard() -> Option<std::sync::MutexGuard<'static, ()>> {
+    #[cfg(feature = "print-trace")]
+    {
+        static LOCK: std::sync::Mutex<()> = std::sync::Mutex::new(());
+        // a failed test poisons the lock, which is still usable
+        let guard = LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        ark_std::perf_trace::inner::NUM_INDENT.store(0, std::sync::atomic::Ordering::Relaxed);
+        Some(guard)
+    }
+    #[cfg(not(feature = "print-trace"))]
+    None
+}
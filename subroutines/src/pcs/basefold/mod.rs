@@ -0,0 +1,743 @@
+// Copyright (c) 2023 Espresso Systems (espressosys.com)
+// This file is part of the HyperPlonk library.
+
+// You should have received a copy of the MIT License
+// along with the HyperPlonk library. If not, see <https://mit-license.org/>.
+
+//! Main module for the Basefold commitment scheme, a transparent multilinear
+//! commitment from a foldable Reed-Solomon code, Merkle trees and the sum
+//! check protocol.
+//!
+//! The evaluations of a polynomial `f` of `n` variables are the coefficients
+//! of a univariate polynomial, whose Reed-Solomon encoding of rate
+//! `2^{-log_inv_rate}` is committed with a Merkle tree. The code is foldable:
+//! folding a codeword `c` by `r`,
+//!   c'(y^2) = (1 - r) (c(y) + c(-y)) / 2 + r (c(y) - c(-y)) / (2y),
+//! gives the encoding of `f(r, x_2, ..., x_n)`. For an opening at `u`, the
+//! prover runs a sum check on `f(x) eq(u, x)`, and folds the codeword by the
+//! challenge of each round, as in FRI. The last codeword is the constant
+//! `f(r)`, which the verifier uses for the last check of the sum check, and
+//! random queries check that the folds are consistent.
+
+use crate::{
+    pcs::{
+        merkle::{verify_path, Hash, MerkleTree},
+        PCSError, PolynomialCommitmentScheme,
+    },
+    poly_iop::{IOPProverState, IOPVerifierState, SumCheckProver, SumCheckVerifier},
+    IOPProof,
+};
+use arithmetic::{build_eq_x_r, eq_eval, VPAuxInfo, VirtualPolynomial};
+use ark_ff::PrimeField;
+use ark_poly::{DenseMultilinearExtension, EvaluationDomain, Radix2EvaluationDomain};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_std::{
+    borrow::Borrow, end_timer, format, marker::PhantomData, rand::Rng, start_timer,
+    string::ToString, sync::Arc, vec::Vec,
+};
+use transcript::{Encode, Encoder, IOPTranscript, Transcript};
+
+/// The log of the inverse rate of the Reed-Solomon code.
+const LOG_INV_RATE: usize = 2;
+/// The number of queries: within the unique decoding radius `3/8`, a folding
+/// far from the code passes each query with probability at most `5/8`, and
+/// `(5/8)^148 < 2^{-100}`.
+const NUM_QUERIES: usize = 148;
+
+#[derive(Clone)]
+/// Basefold Polynomial Commitment Scheme on multilinear polynomials.
+///
+/// The scheme is transparent and needs no group, so it is generic over the
+/// field `F` only.
+pub struct BasefoldPCS<F: PrimeField> {
+    #[doc(hidden)]
+    phantom: PhantomData<F>,
+}
+
+/// The public parameters of Basefold, which need no trusted setup.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct BasefoldParams {
+    /// maximum number of variables
+    pub num_vars: usize,
+    /// log of the inverse rate of the Reed-Solomon code
+    pub log_inv_rate: usize,
+    /// number of queries
+    pub num_queries: usize,
+}
+
+impl BasefoldParams {
+    /// The parameters for polynomials of up to `num_vars` variables, with
+    /// about 100 bits of security.
+    pub fn new(num_vars: usize) -> Self {
+        Self {
+            num_vars,
+            log_inv_rate: LOG_INV_RATE,
+            num_queries: NUM_QUERIES,
+        }
+    }
+}
+
+#[derive(
+    Clone, Copy, Debug, Default, Hash, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize,
+)]
+/// A commitment is the Merkle root of the codeword, whose leaves are the
+/// pairs of values at `y` and `-y`.
+pub struct BasefoldCommitment {
+    /// the Merkle root
+    pub root: Hash,
+}
+
+impl Encode for BasefoldCommitment {
+    fn encode<E: Encoder>(&self, encoder: &mut E) {
+        self.root.encode(encoder);
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+/// The openings of a query in each codeword
+pub struct BasefoldQuery<F: PrimeField> {
+    /// The values at `y` and `-y` in each codeword
+    pub leaves: Vec<[F; 2]>,
+    /// The Merkle paths of the leaves
+    pub paths: Vec<Vec<Hash>>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+/// proof of opening
+pub struct BasefoldProof<F: PrimeField> {
+    /// The sum check proof of `f(x) eq(u, x)`
+    pub sum_check_proof: IOPProof<F>,
+    /// The Merkle roots of the folded codewords, but the last one
+    pub roots: Vec<Hash>,
+    /// The last folded codeword, which is the constant `f(r)`
+    pub final_value: F,
+    /// The openings of the queries
+    pub queries: Vec<BasefoldQuery<F>>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+/// batch proof
+pub struct BasefoldBatchProof<F: PrimeField> {
+    /// f_i(point_i)
+    pub evals: Vec<F>,
+    /// the proof of each opening
+    pub proofs: Vec<BasefoldProof<F>>,
+}
+
+impl<F: PrimeField> PolynomialCommitmentScheme<F> for BasefoldPCS<F> {
+    // Parameters
+    type ProverParam = BasefoldParams;
+    type VerifierParam = BasefoldParams;
+    type SRS = BasefoldParams;
+    // Polynomial and its associated types
+    type Polynomial = Arc<DenseMultilinearExtension<F>>;
+    type Point = Vec<F>;
+    type Evaluation = F;
+    // Commitments and proofs
+    type Commitment = BasefoldCommitment;
+    type Proof = BasefoldProof<F>;
+    type BatchProof = BasefoldBatchProof<F>;
+
+    /// Build the public parameters for `supported_size` variables.
+    ///
+    /// Basefold needs no trusted setup: the parameters are public and `rng` is
+    /// unused, so they are also fine outside of tests.
+    fn gen_srs_for_testing<R: Rng>(
+        _rng: &mut R,
+        supported_size: usize,
+    ) -> Result<Self::SRS, PCSError> {
+        Ok(BasefoldParams::new(supported_size))
+    }
+
+    /// Trim the public parameters to `supported_num_vars` variables.
+    /// `supported_degree` must be None or an error is returned.
+    fn trim(
+        srs: impl Borrow<Self::SRS>,
+        supported_degree: Option<usize>,
+        supported_num_vars: Option<usize>,
+    ) -> Result<(Self::ProverParam, Self::VerifierParam), PCSError> {
+        if supported_degree.is_some() {
+            return Err(PCSError::InvalidParameters(
+                "multilinear should not receive a degree param".to_string(),
+            ));
+        }
+        let supported_num_vars = match supported_num_vars {
+            Some(p) => p,
+            None => {
+                return Err(PCSError::InvalidParameters(
+                    "multilinear should receive a num_var param".to_string(),
+                ))
+            },
+        };
+        let srs = srs.borrow();
+        if supported_num_vars > srs.num_vars {
+            return Err(PCSError::InvalidParameters(format!(
+                "parameters of {} variables do not support {} variables",
+                srs.num_vars, supported_num_vars
+            )));
+        }
+        let params = BasefoldParams {
+            num_vars: supported_num_vars,
+            ..*srs
+        };
+        Ok((params, params))
+    }
+
+    /// Generate a commitment for a polynomial.
+    ///
+    /// This function encodes the evaluations of the polynomial, and hashes the
+    /// pairs of values at `y` and `-y` of the codeword.
+    fn commit(
+        prover_param: impl Borrow<Self::ProverParam>,
+        poly: &Self::Polynomial,
+    ) -> Result<Self::Commitment, PCSError> {
+        let commit_timer = start_timer!(|| "commit");
+        let (_, tree) = encode(prover_param.borrow(), poly)?;
+        end_timer!(commit_timer);
+        Ok(BasefoldCommitment { root: tree.root() })
+    }
+
+    /// On input a polynomial `p` and a point `point`, outputs a proof for the
+    /// same.
+    ///
+    /// The codeword is not kept by the commitment, so this function encodes
+    /// and hashes it again.
+    fn open(
+        prover_param: impl Borrow<Self::ProverParam>,
+        polynomial: &Self::Polynomial,
+        point: &Self::Point,
+    ) -> Result<(Self::Proof, Self::Evaluation), PCSError> {
+        open_internal(prover_param.borrow(), polynomial, point)
+    }
+
+    /// Input a list of multilinear extensions, and a same number of points, and
+    /// a transcript, compute a multi-opening for all the polynomials.
+    ///
+    /// Merkle roots cannot be combined, so the polynomials are opened one by
+    /// one.
+    fn multi_open(
+        prover_param: impl Borrow<Self::ProverParam>,
+        polynomials: &[Self::Polynomial],
+        points: &[Self::Point],
+        evals: &[Self::Evaluation],
+        _transcript: &mut impl Transcript<F>,
+    ) -> Result<Self::BatchProof, PCSError> {
+        if polynomials.len() != points.len() || polynomials.len() != evals.len() {
+            return Err(PCSError::InvalidParameters(format!(
+                "{} polynomials, {} points and {} evaluations",
+                polynomials.len(),
+                points.len(),
+                evals.len()
+            )));
+        }
+        let proofs = polynomials
+            .iter()
+            .zip(points.iter())
+            .map(|(poly, point)| Ok(open_internal(prover_param.borrow(), poly, point)?.0))
+            .collect::<Result<Vec<_>, PCSError>>()?;
+        Ok(BasefoldBatchProof {
+            evals: evals.to_vec(),
+            proofs,
+        })
+    }
+
+    /// Verifies that `value` is the evaluation at `x` of the polynomial
+    /// committed inside `comm`.
+    fn verify(
+        verifier_param: &Self::VerifierParam,
+        commitment: &Self::Commitment,
+        point: &Self::Point,
+        value: &F,
+        proof: &Self::Proof,
+    ) -> Result<bool, PCSError> {
+        verify_internal(verifier_param, commitment, point, value, proof)
+    }
+
+    /// Verifies that `value_i` is the evaluation at `x_i` of the polynomial
+    /// `poly_i` committed inside `comm`.
+    fn batch_verify(
+        verifier_param: &Self::VerifierParam,
+        commitments: &[Self::Commitment],
+        points: &[Self::Point],
+        batch_proof: &Self::BatchProof,
+        _transcript: &mut impl Transcript<F>,
+    ) -> Result<bool, PCSError> {
+        if commitments.len() != points.len()
+            || commitments.len() != batch_proof.evals.len()
+            || commitments.len() != batch_proof.proofs.len()
+        {
+            return Err(PCSError::InvalidProof(format!(
+                "the batch proof does not match {} commitments",
+                commitments.len()
+            )));
+        }
+        for (((commitment, point), value), proof) in commitments
+            .iter()
+            .zip(points.iter())
+            .zip(batch_proof.evals.iter())
+            .zip(batch_proof.proofs.iter())
+        {
+            if !verify_internal(verifier_param, commitment, point, value, proof)? {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+
+    fn batch_evaluations(batch_proof: &Self::BatchProof) -> &[Self::Evaluation] {
+        &batch_proof.evals
+    }
+}
+
+/// Open `polynomial` at `point`:
+/// 1. run the sum check on `f(x) eq(u, x)`; after each round, fold the
+///    codeword by the challenge and commit to the folding
+/// 2. send the last folded codeword, which is the constant `f(r)`
+/// 3. get the queries, and open them in each codeword
+fn open_internal<F: PrimeField>(
+    params: &BasefoldParams,
+    polynomial: &Arc<DenseMultilinearExtension<F>>,
+    point: &[F],
+) -> Result<(BasefoldProof<F>, F), PCSError> {
+    let open_timer = start_timer!(|| format!("open mle with {} variable", polynomial.num_vars));
+
+    if polynomial.num_vars != point.len() {
+        return Err(PCSError::InvalidParameters(format!(
+            "Polynomial num_vars {} does not match point len {}",
+            polynomial.num_vars,
+            point.len()
+        )));
+    }
+    let num_vars = point.len();
+
+    let (codeword, tree) = encode(params, polynomial)?;
+    let eq = build_eq_x_r(point)?;
+    let eval = polynomial
+        .evaluations
+        .iter()
+        .zip(eq.evaluations.iter())
+        .fold(F::zero(), |acc, (x, y)| acc + *x * y);
+    let mut poly = VirtualPolynomial::new(num_vars);
+    poly.add_mle_list([polynomial.clone(), eq], F::one())?;
+
+    let mut transcript = init_transcript(&tree.root(), point, &eval)?;
+
+    let step = start_timer!(|| "sum check and folding");
+    // cannot wrap IOPError with PCSError due to cyclic dependency
+    let sum_check_err = |_| PCSError::InvalidProver("Sumcheck in Basefold failed".to_string());
+    let mut prover_state = IOPProverState::prover_init(&poly).map_err(sum_check_err)?;
+    let mut generator = domain::<F>(codeword.len())?.group_gen();
+    let mut layers = vec![(codeword, tree)];
+    let mut final_value = F::zero();
+    let mut roots = Vec::with_capacity(num_vars - 1);
+    let mut challenge = None;
+    let mut prover_msgs = Vec::with_capacity(num_vars);
+    let mut challenges = Vec::with_capacity(num_vars);
+    for round in 0..num_vars {
+        let prover_msg = prover_state
+            .prove_round_and_update_state(&challenge)
+            .map_err(sum_check_err)?;
+        transcript.append_serializable_element(b"prover msg", &prover_msg)?;
+        prover_msgs.push(prover_msg);
+        let r = transcript.get_and_append_challenge(b"Internal round")?;
+        challenges.push(r);
+        challenge = Some(r);
+
+        let folded = fold(&layers[round].0, generator, r)?;
+        generator.square_in_place();
+        if round + 1 < num_vars {
+            let tree = MerkleTree::new(&leaves(&folded))?;
+            transcript.append_serializable_element(b"root", &tree.root())?;
+            roots.push(tree.root());
+            layers.push((folded, tree));
+        } else {
+            final_value = folded[0];
+        }
+    }
+    transcript.append_field_element(b"final value", &final_value)?;
+    end_timer!(step);
+
+    let step = start_timer!(|| "queries");
+    let queries = query_indices(&mut transcript, params, layers[0].0.len() / 2)?
+        .into_iter()
+        .map(|index| {
+            let (leaves, paths) = layers
+                .iter()
+                .map(|(codeword, tree)| {
+                    let half = codeword.len() / 2;
+                    let j = index % half;
+                    ([codeword[j], codeword[j + half]], tree.path(j))
+                })
+                .unzip();
+            BasefoldQuery { leaves, paths }
+        })
+        .collect();
+    end_timer!(step);
+
+    let proof = BasefoldProof {
+        sum_check_proof: IOPProof {
+            point: challenges,
+            proofs: prover_msgs,
+        },
+        roots,
+        final_value,
+        queries,
+    };
+
+    end_timer!(open_timer);
+    Ok((proof, eval))
+}
+
+/// Verifies that `value` is the evaluation at `point` of the polynomial
+/// committed inside `commitment`:
+/// 1. replay the sum check on `f(x) eq(u, x)` with the roots of the folded
+///    codewords, and check its last claim with `f(r)`, the last codeword
+/// 2. check that the queries are in the commitments, and that the folds are
+///    consistent on them
+fn verify_internal<F: PrimeField>(
+    params: &BasefoldParams,
+    commitment: &BasefoldCommitment,
+    point: &[F],
+    value: &F,
+    proof: &BasefoldProof<F>,
+) -> Result<bool, PCSError> {
+    let verify_timer = start_timer!(|| "verify");
+
+    let num_vars = point.len();
+    if num_vars > params.num_vars {
+        return Err(PCSError::InvalidParameters(format!(
+            "point length ({}) exceeds param limit ({})",
+            num_vars, params.num_vars
+        )));
+    }
+    if num_vars == 0 {
+        return Err(PCSError::InvalidParameters(
+            "Basefold needs at least one variable".to_string(),
+        ));
+    }
+    let log_half = num_vars + params.log_inv_rate - 1;
+    if proof.sum_check_proof.proofs.len() != num_vars
+        || proof.roots.len() != num_vars - 1
+        || proof.queries.len() != params.num_queries
+        || proof.queries.iter().any(|query| {
+            query.leaves.len() != num_vars
+                || query.paths.len() != num_vars
+                || query
+                    .paths
+                    .iter()
+                    .enumerate()
+                    .any(|(i, path)| path.len() != log_half - i)
+        })
+    {
+        return Err(PCSError::InvalidProof(format!(
+            "the proof does not match a point of length {}",
+            num_vars
+        )));
+    }
+
+    let mut transcript = init_transcript(&commitment.root, point, value)?;
+    // cannot wrap IOPError with PCSError due to cyclic dependency
+    let sum_check_err = |_| PCSError::InvalidProof("Sumcheck in Basefold failed".to_string());
+    let mut verifier_state = IOPVerifierState::verifier_init(&VPAuxInfo {
+        max_degree: 2,
+        num_variables: num_vars,
+        phantom: PhantomData,
+    });
+    let mut challenges = Vec::with_capacity(num_vars);
+    for (round, prover_msg) in proof.sum_check_proof.proofs.iter().enumerate() {
+        transcript.append_serializable_element(b"prover msg", prover_msg)?;
+        challenges.push(
+            verifier_state
+                .verify_round_and_update_state(prover_msg, &mut transcript)
+                .map_err(sum_check_err)?,
+        );
+        if round + 1 < num_vars {
+            transcript.append_serializable_element(b"root", &proof.roots[round])?;
+        }
+    }
+    transcript.append_field_element(b"final value", &proof.final_value)?;
+
+    let subclaim = match verifier_state.check_and_generate_subclaim(value) {
+        Ok(subclaim) => subclaim,
+        Err(_) => {
+            end_timer!(verify_timer, || "Result: false");
+            return Ok(false);
+        },
+    };
+    if subclaim.expected_evaluation != proof.final_value * eq_eval(point, &challenges)? {
+        end_timer!(verify_timer, || "Result: false");
+        return Ok(false);
+    }
+
+    let mut generators = vec![domain::<F>(2 << log_half)?.group_gen()];
+    for i in 1..num_vars {
+        generators.push(generators[i - 1].square());
+    }
+    let two_inv = F::from(2u64)
+        .inverse()
+        .ok_or_else(|| PCSError::InvalidParameters("2 is not invertible".to_string()))?;
+    let indices = query_indices(&mut transcript, params, 1 << log_half)?;
+    for (index, query) in indices.iter().zip(proof.queries.iter()) {
+        let mut expected = None;
+        for (i, (leaf, path)) in query.leaves.iter().zip(query.paths.iter()).enumerate() {
+            let half = 1 << (log_half - i);
+            let j = index % half;
+            let root = if i == 0 {
+                &commitment.root
+            } else {
+                &proof.roots[i - 1]
+            };
+            let consistent = match expected {
+                None => true,
+                Some(e) => leaf[(index / half) % 2] == e,
+            };
+            if !consistent || !verify_path(root, log_half - i, j, leaf, path)? {
+                end_timer!(verify_timer, || "Result: false");
+                return Ok(false);
+            }
+            let y_inv = generators[i]
+                .pow([j as u64])
+                .inverse()
+                .ok_or_else(|| PCSError::InvalidProof("zero domain element".to_string()))?;
+            expected = Some(fold_pair(leaf, challenges[i], y_inv, two_inv));
+        }
+        if expected != Some(proof.final_value) {
+            end_timer!(verify_timer, || "Result: false");
+            return Ok(false);
+        }
+    }
+
+    end_timer!(verify_timer, || "Result: true");
+    Ok(true)
+}
+
+/// The Reed-Solomon encoding of the evaluations of `poly`, i.e. the
+/// evaluations of the polynomial of coefficients `poly.evaluations` on a
+/// domain `2^log_inv_rate` times larger, and its Merkle tree.
+fn encode<F: PrimeField>(
+    params: &BasefoldParams,
+    poly: &DenseMultilinearExtension<F>,
+) -> Result<(Vec<F>, MerkleTree), PCSError> {
+    if poly.num_vars > params.num_vars {
+        return Err(PCSError::InvalidParameters(format!(
+            "MlE length ({}) exceeds param limit ({})",
+            poly.num_vars, params.num_vars
+        )));
+    }
+    if poly.num_vars == 0 {
+        return Err(PCSError::InvalidParameters(
+            "Basefold needs at least one variable".to_string(),
+        ));
+    }
+
+    let step = start_timer!(|| "encode");
+    let codeword =
+        domain::<F>(poly.evaluations.len() << params.log_inv_rate)?.fft(&poly.evaluations);
+    end_timer!(step);
+
+    let step = start_timer!(|| "hash codeword");
+    let tree = MerkleTree::new(&leaves(&codeword))?;
+    end_timer!(step);
+
+    Ok((codeword, tree))
+}
+
+fn domain<F: PrimeField>(size: usize) -> Result<Radix2EvaluationDomain<F>, PCSError> {
+    Radix2EvaluationDomain::<F>::new(size).ok_or_else(|| {
+        PCSError::InvalidParameters(format!("no evaluation domain of size {}", size))
+    })
+}
+
+/// The leaves of the Merkle tree of `codeword`: the leaf `j` pairs the values
+/// at `y = g^j` and `-y = g^{j + half}`.
+fn leaves<F: PrimeField>(codeword: &[F]) -> Vec<Vec<F>> {
+    let half = codeword.len() / 2;
+    (0..half)
+        .map(|j| vec![codeword[j], codeword[j + half]])
+        .collect()
+}
+
+/// Fold `codeword`, on the domain generated by `generator`, by `r`, which
+/// fixes the first variable of the encoded polynomial to `r`.
+fn fold<F: PrimeField>(codeword: &[F], generator: F, r: F) -> Result<Vec<F>, PCSError> {
+    let generator_inv = generator
+        .inverse()
+        .ok_or_else(|| PCSError::InvalidParameters("zero generator".to_string()))?;
+    let two_inv = F::from(2u64)
+        .inverse()
+        .ok_or_else(|| PCSError::InvalidParameters("2 is not invertible".to_string()))?;
+    let half = codeword.len() / 2;
+    let mut y_inv = F::one();
+    let mut res = Vec::with_capacity(half);
+    for j in 0..half {
+        res.push(fold_pair(
+            &[codeword[j], codeword[j + half]],
+            r,
+            y_inv,
+            two_inv,
+        ));
+        y_inv *= generator_inv;
+    }
+    Ok(res)
+}
+
+/// (1 - r) (c(y) + c(-y)) / 2 + r (c(y) - c(-y)) / (2y)
+fn fold_pair<F: PrimeField>(pair: &[F; 2], r: F, y_inv: F, two_inv: F) -> F {
+    let even = (pair[0] + pair[1]) * two_inv;
+    let odd = (pair[0] - pair[1]) * two_inv * y_inv;
+    even + r * (odd - even)
+}
+
+/// The transcript of an opening, bound to the statement.
+fn init_transcript<F: PrimeField>(
+    root: &Hash,
+    point: &[F],
+    value: &F,
+) -> Result<IOPTranscript<F>, PCSError> {
+    let mut transcript = IOPTranscript::new(b"basefold");
+    transcript.append_serializable_element(b"root", root)?;
+    transcript.append_serializable_element(b"point", &point.to_vec())?;
+    transcript.append_field_element(b"value", value)?;
+    Ok(transcript)
+}
+
+/// The indices of the queried leaves among `num_leaves`.
+fn query_indices<F: PrimeField>(
+    transcript: &mut IOPTranscript<F>,
+    params: &BasefoldParams,
+    num_leaves: usize,
+) -> Result<Vec<usize>, PCSError> {
+    (0..params.num_queries)
+        .map(|_| {
+            let challenge = transcript.get_and_append_challenge(b"query")?;
+            Ok(challenge.into_bigint().as_ref()[0] as usize % num_leaves)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_bls12_381::Fr;
+    use ark_poly::{MultilinearExtension, Polynomial};
+    use ark_std::{test_rng, One, UniformRand};
+
+    fn test_single_helper<R: Rng>(
+        params: &BasefoldParams,
+        poly: &Arc<DenseMultilinearExtension<Fr>>,
+        supported_num_vars: usize,
+        rng: &mut R,
+    ) -> Result<(), PCSError> {
+        let nv = poly.num_vars();
+        let (ck, vk) = BasefoldPCS::<Fr>::trim(params, None, Some(supported_num_vars))?;
+        let point: Vec<_> = (0..nv).map(|_| Fr::rand(rng)).collect();
+        let com = BasefoldPCS::<Fr>::commit(ck, poly)?;
+        let (proof, value) = BasefoldPCS::<Fr>::open(ck, poly, &point)?;
+        assert_eq!(value, poly.evaluate(&point));
+
+        assert!(BasefoldPCS::<Fr>::verify(
+            &vk, &com, &point, &value, &proof
+        )?);
+
+        let bad_value = Fr::rand(rng);
+        assert!(!BasefoldPCS::<Fr>::verify(
+            &vk, &com, &point, &bad_value, &proof
+        )?);
+
+        let mut bad_point = point.clone();
+        bad_point[0] = Fr::rand(rng);
+        assert!(!BasefoldPCS::<Fr>::verify(
+            &vk, &com, &bad_point, &value, &proof
+        )?);
+
+        let mut bad_proof = proof.clone();
+        bad_proof.final_value += Fr::one();
+        assert!(!BasefoldPCS::<Fr>::verify(
+            &vk, &com, &point, &value, &bad_proof
+        )?);
+
+        let mut bad_proof = proof;
+        bad_proof.queries[0].leaves[0][0] += Fr::one();
+        assert!(!BasefoldPCS::<Fr>::verify(
+            &vk, &com, &point, &value, &bad_proof
+        )?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_single_commit() -> Result<(), PCSError> {
+        let _trace = crate::trace_guard();
+        let mut rng = test_rng();
+
+        let params = BasefoldPCS::<Fr>::gen_srs_for_testing(&mut rng, 10)?;
+
+        // normal polynomials
+        let poly1 = Arc::new(DenseMultilinearExtension::rand(8, &mut rng));
+        test_single_helper(&params, &poly1, 8, &mut rng)?;
+
+        // odd number of variables
+        let poly2 = Arc::new(DenseMultilinearExtension::rand(9, &mut rng));
+        test_single_helper(&params, &poly2, 10, &mut rng)?;
+
+        // single-variate polynomials
+        let poly3 = Arc::new(DenseMultilinearExtension::rand(1, &mut rng));
+        test_single_helper(&params, &poly3, 4, &mut rng)?;
+
+        // too many variables for the parameters
+        assert!(BasefoldPCS::<Fr>::trim(params, None, Some(11)).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_multi_open() -> Result<(), PCSError> {
+        let _trace = crate::trace_guard();
+        let mut rng = test_rng();
+
+        let params = BasefoldPCS::<Fr>::gen_srs_for_testing(&mut rng, 10)?;
+        let (ck, vk) = BasefoldPCS::<Fr>::trim(params, None, Some(10))?;
+
+        let polys: Vec<_> = (0..5)
+            .map(|_| Arc::new(DenseMultilinearExtension::rand(8, &mut rng)))
+            .collect();
+        let points: Vec<Vec<Fr>> = (0..5)
+            .map(|_| (0..8).map(|_| Fr::rand(&mut rng)).collect())
+            .collect();
+        let evals: Vec<_> = polys
+            .iter()
+            .zip(points.iter())
+            .map(|(f, p)| f.evaluate(p))
+            .collect();
+        let commitments = polys
+            .iter()
+            .map(|poly| BasefoldPCS::<Fr>::commit(ck, poly))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut transcript = IOPTranscript::new(b"test transcript");
+        let batch_proof =
+            BasefoldPCS::<Fr>::multi_open(ck, &polys, &points, &evals, &mut transcript)?;
+
+        let mut transcript = IOPTranscript::new(b"test transcript");
+        assert!(BasefoldPCS::<Fr>::batch_verify(
+            &vk,
+            &commitments,
+            &points,
+            &batch_proof,
+            &mut transcript
+        )?);
+
+        let mut bad_proof = batch_proof;
+        bad_proof.evals[1] += Fr::one();
+        let mut transcript = IOPTranscript::new(b"test transcript");
+        assert!(!BasefoldPCS::<Fr>::batch_verify(
+            &vk,
+            &commitments,
+            &points,
+            &bad_proof,
+            &mut transcript
+        )?);
+
+        Ok(())
+    }
+}
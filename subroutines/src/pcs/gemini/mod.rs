@@ -0,0 +1,503 @@
+// Copyright (c) 2023 Espresso Systems (espressosys.com)
+// This file is part of the HyperPlonk library.
+
+// You should have received a copy of the MIT License
+// along with the HyperPlonk library. If not, see <https://mit-license.org/>.
+
+//! Main module for the Gemini commitment scheme, which reduces a multilinear
+//! evaluation to univariate KZG openings by folding.
+//!
+//! A multilinear polynomial `f` of `n` variables is committed to as the
+//! univariate polynomial `A_0(X) = \sum_i f(<i>) X^i`. The evaluation `f(u)`
+//! is folded one variable at a time:
+//!   A_{k+1}(X^2) = (1 - u_k) * (A_k(X) + A_k(-X)) / 2
+//!                  + u_k * (A_k(X) - A_k(-X)) / (2X)
+//! so that `A_n = f(u)`. The prover commits to `A_1, ..., A_{n-1}`, and opens
+//! all the `A_k` at `r`, `-r` and `r^2` with three KZG proofs of a random
+//! combination of them. The verifier checks the folds on those evaluations
+//! and the three proofs with 2 pairings, and only needs the G2 elements `h`
+//! and `beta * h` of the univariate SRS.
+
+use crate::{
+    pcs::{
+        multilinear_kzg::batching::{batch_verify_internal, multi_open_internal},
+        prelude::{
+            Commitment, UnivariateKzgPCS, UnivariateKzgProof, UnivariateProverParam,
+            UnivariateUniversalParams, UnivariateVerifierParam,
+        },
+        PCSError, PolynomialCommitmentScheme, StructuredReferenceString,
+    },
+    BatchProof,
+};
+use ark_ec::{
+    pairing::Pairing, scalar_mul::variable_base::VariableBaseMSM, AffineRepr, CurveGroup,
+};
+use ark_ff::Field;
+use ark_poly::{
+    univariate::DensePolynomial, DenseMultilinearExtension, DenseUVPolynomial, Polynomial,
+};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_std::{
+    borrow::Borrow, end_timer, format, marker::PhantomData, rand::Rng, start_timer,
+    string::ToString, sync::Arc, vec, vec::Vec, One, Zero,
+};
+use transcript::{IOPTranscript, Transcript};
+
+#[derive(Clone)]
+/// Gemini Polynomial Commitment Scheme on multilinear polynomials.
+pub struct GeminiPCS<E: Pairing> {
+    #[doc(hidden)]
+    phantom: PhantomData<E>,
+}
+
+#[derive(CanonicalSerialize, CanonicalDeserialize, Clone, Debug, PartialEq, Eq)]
+/// proof of opening
+pub struct GeminiProof<E: Pairing> {
+    /// Commitments to the folded polynomials `A_1, ..., A_{n-1}`
+    pub folds: Vec<Commitment<E>>,
+    /// Evaluations of `A_0, ..., A_{n-1}` at `r`, `-r` and `r^2`
+    pub evals: Vec<[E::ScalarField; 3]>,
+    /// KZG proofs of the combined polynomial at `r`, `-r` and `r^2`
+    pub openings: Vec<UnivariateKzgProof<E>>,
+}
+
+impl<E: Pairing> PolynomialCommitmentScheme<E::ScalarField> for GeminiPCS<E> {
+    // Parameters
+    type ProverParam = UnivariateProverParam<E::G1Affine>;
+    type VerifierParam = UnivariateVerifierParam<E>;
+    type SRS = UnivariateUniversalParams<E>;
+    // Polynomial and its associated types
+    type Polynomial = Arc<DenseMultilinearExtension<E::ScalarField>>;
+    type Point = Vec<E::ScalarField>;
+    type Evaluation = E::ScalarField;
+    // Commitments and proofs
+    type Commitment = Commitment<E>;
+    type Proof = GeminiProof<E>;
+    type BatchProof = BatchProof<E, Self>;
+
+    /// Build SRS for testing.
+    ///
+    /// - For multilinear polynomials, `supported_size` is the number of
+    ///   variables, and the SRS holds `2^supported_size` powers.
+    ///
+    /// WARNING: THIS FUNCTION IS FOR TESTING PURPOSE ONLY.
+    /// THE OUTPUT SRS SHOULD NOT BE USED IN PRODUCTION.
+    fn gen_srs_for_testing<R: Rng>(
+        rng: &mut R,
+        supported_size: usize,
+    ) -> Result<Self::SRS, PCSError> {
+        UnivariateUniversalParams::<E>::gen_srs_for_testing(rng, (1 << supported_size) - 1)
+    }
+
+    /// Trim the universal parameters to specialize the public parameters.
+    /// Input `supported_num_vars` for multilinear.
+    /// `supported_degree` must be None or an error is returned.
+    fn trim(
+        srs: impl Borrow<Self::SRS>,
+        supported_degree: Option<usize>,
+        supported_num_vars: Option<usize>,
+    ) -> Result<(Self::ProverParam, Self::VerifierParam), PCSError> {
+        if supported_degree.is_some() {
+            return Err(PCSError::InvalidParameters(
+                "multilinear should not receive a degree param".to_string(),
+            ));
+        }
+        let supported_num_vars = match supported_num_vars {
+            Some(p) => p,
+            None => {
+                return Err(PCSError::InvalidParameters(
+                    "multilinear should receive a num_var param".to_string(),
+                ))
+            },
+        };
+        let srs = srs.borrow();
+        if srs.max_degree() < 1 << supported_num_vars {
+            return Err(PCSError::InvalidParameters(format!(
+                "SRS of {} powers does not support {} variables",
+                srs.max_degree(),
+                supported_num_vars
+            )));
+        }
+        srs.trim((1 << supported_num_vars) - 1)
+    }
+
+    /// Generate a commitment for a polynomial.
+    ///
+    /// This function takes `2^num_vars` number of scalar multiplications over
+    /// G1.
+    fn commit(
+        prover_param: impl Borrow<Self::ProverParam>,
+        poly: &Self::Polynomial,
+    ) -> Result<Self::Commitment, PCSError> {
+        UnivariateKzgPCS::commit(prover_param, &to_univariate(&poly.evaluations))
+    }
+
+    /// On input a polynomial `p` and a point `point`, outputs a proof for the
+    /// same.
+    ///
+    /// This function takes about `2^{num_var + 3}` number of scalar
+    /// multiplications over G1: the commitment of `p` that the challenges
+    /// are bound to, those of the folds, and the three KZG proofs.
+    fn open(
+        prover_param: impl Borrow<Self::ProverParam>,
+        polynomial: &Self::Polynomial,
+        point: &Self::Point,
+    ) -> Result<(Self::Proof, Self::Evaluation), PCSError> {
+        open_internal(prover_param.borrow(), polynomial, point)
+    }
+
+    /// Input a list of multilinear extensions, and a same number of points, and
+    /// a transcript, compute a multi-opening for all the polynomials.
+    fn multi_open(
+        prover_param: impl Borrow<Self::ProverParam>,
+        polynomials: &[Self::Polynomial],
+        points: &[Self::Point],
+        evals: &[Self::Evaluation],
+        transcript: &mut impl Transcript<E::ScalarField>,
+    ) -> Result<BatchProof<E, Self>, PCSError> {
+        multi_open_internal(
+            prover_param.borrow(),
+            polynomials,
+            points,
+            evals,
+            transcript,
+        )
+    }
+
+    /// Verifies that `value` is the evaluation at `x` of the polynomial
+    /// committed inside `comm`.
+    ///
+    /// This function takes
+    /// - 2 pairings
+    /// - an MSM of `num_var + 4` elements
+    fn verify(
+        verifier_param: &Self::VerifierParam,
+        commitment: &Self::Commitment,
+        point: &Self::Point,
+        value: &E::ScalarField,
+        proof: &Self::Proof,
+    ) -> Result<bool, PCSError> {
+        verify_internal(verifier_param, commitment, point, value, proof)
+    }
+
+    /// Verifies that `value_i` is the evaluation at `x_i` of the polynomial
+    /// `poly_i` committed inside `comm`.
+    fn batch_verify(
+        verifier_param: &Self::VerifierParam,
+        commitments: &[Self::Commitment],
+        points: &[Self::Point],
+        batch_proof: &Self::BatchProof,
+        transcript: &mut impl Transcript<E::ScalarField>,
+    ) -> Result<bool, PCSError> {
+        batch_verify_internal(verifier_param, commitments, points, batch_proof, transcript)
+    }
+
+    fn batch_evaluations(batch_proof: &Self::BatchProof) -> &[Self::Evaluation] {
+        &batch_proof.f_i_eval_at_point_i
+    }
+}
+
+/// Open `polynomial` at `point`:
+/// 1. fold `A_0` into `A_1, ..., A_n = f(u)` and commit to `A_1, ..., A_{n-1}`
+/// 2. get challenge r, and evaluate each `A_k` at `r`, `-r` and `r^2`
+/// 3. get challenge q, and open `B = \sum_k q^k A_k` at `r`, `-r` and `r^2`
+///
+/// The verifier draws the challenge `d` that batches the three openings
+/// after them, which the prover does not need.
+fn open_internal<E: Pairing>(
+    prover_param: &UnivariateProverParam<E::G1Affine>,
+    polynomial: &DenseMultilinearExtension<E::ScalarField>,
+    point: &[E::ScalarField],
+) -> Result<(GeminiProof<E>, E::ScalarField), PCSError> {
+    let open_timer = start_timer!(|| format!("open mle with {} variable", polynomial.num_vars));
+
+    if polynomial.num_vars != point.len() {
+        return Err(PCSError::InvalidParameters(format!(
+            "Polynomial num_vars {} does not match point len {}",
+            polynomial.num_vars,
+            point.len()
+        )));
+    }
+    if point.is_empty() {
+        return Err(PCSError::InvalidParameters(
+            "Gemini needs at least one variable".to_string(),
+        ));
+    }
+
+    let step = start_timer!(|| "fold");
+    let mut folds = vec![to_univariate(&polynomial.evaluations)];
+    for u in point.iter() {
+        let a = folds[folds.len() - 1].coeffs();
+        // the coefficients are trimmed of their trailing zeros
+        let coeffs: Vec<_> = (0..1 << (point.len() - folds.len()))
+            .map(|i| {
+                let even = a.get(2 * i).copied().unwrap_or_default();
+                let odd = a.get(2 * i + 1).copied().unwrap_or_default();
+                even + *u * (odd - even)
+            })
+            .collect();
+        folds.push(DensePolynomial::from_coefficients_vec(coeffs));
+    }
+    let eval = folds
+        .pop()
+        .and_then(|a| a.coeffs().first().copied())
+        .unwrap_or_default();
+    end_timer!(step);
+
+    let step = start_timer!(|| "commit folds");
+    let commitment = UnivariateKzgPCS::<E>::commit(prover_param, &folds[0])?;
+    let fold_comms = folds[1..]
+        .iter()
+        .map(|a| UnivariateKzgPCS::<E>::commit(prover_param, a))
+        .collect::<Result<Vec<_>, PCSError>>()?;
+    end_timer!(step);
+
+    let mut transcript = init_transcript::<E>(&commitment, point, &eval)?;
+    transcript.append_serializable_element(b"folds", &fold_comms)?;
+    let r = transcript.get_and_append_challenge(b"r")?;
+    let eval_points = [r, -r, r.square()];
+
+    let evals: Vec<[E::ScalarField; 3]> = folds
+        .iter()
+        .map(|a| eval_points.map(|x| a.evaluate(&x)))
+        .collect();
+    transcript.append_serializable_element(b"evals", &evals)?;
+    let q = transcript.get_and_append_challenge(b"q")?;
+
+    let step = start_timer!(|| "kzg openings");
+    let mut combined = DensePolynomial::zero();
+    let mut q_pow = E::ScalarField::one();
+    for a in folds.iter() {
+        combined += (q_pow, a);
+        q_pow *= q;
+    }
+    let openings = eval_points
+        .iter()
+        .map(|x| Ok(UnivariateKzgPCS::<E>::open(prover_param, &combined, x)?.0))
+        .collect::<Result<Vec<_>, PCSError>>()?;
+    end_timer!(step);
+
+    end_timer!(open_timer);
+    Ok((
+        GeminiProof {
+            folds: fold_comms,
+            evals,
+            openings,
+        },
+        eval,
+    ))
+}
+
+/// Verifies that `value` is the evaluation at `point` of the polynomial
+/// committed inside `commitment`:
+/// 1. check the folds on the evaluations at `r` and `-r`
+/// 2. check the three KZG proofs `W_j` of `B` at `x_j` at once, with a
+///    challenge `d`:
+///    e(\sum_j d^j (C_B - B(x_j) g + x_j W_j), h) = e(\sum_j d^j W_j, beta h)
+fn verify_internal<E: Pairing>(
+    verifier_param: &UnivariateVerifierParam<E>,
+    commitment: &Commitment<E>,
+    point: &[E::ScalarField],
+    value: &E::ScalarField,
+    proof: &GeminiProof<E>,
+) -> Result<bool, PCSError> {
+    let verify_timer = start_timer!(|| "verify");
+
+    let nv = point.len();
+    if nv == 0
+        || proof.folds.len() != nv - 1
+        || proof.evals.len() != nv
+        || proof.openings.len() != 3
+    {
+        return Err(PCSError::InvalidProof(format!(
+            "the proof does not match a point of length {}",
+            nv
+        )));
+    }
+
+    let mut transcript = init_transcript::<E>(commitment, point, value)?;
+    transcript.append_serializable_element(b"folds", &proof.folds)?;
+    let r = transcript.get_and_append_challenge(b"r")?;
+    let eval_points = [r, -r, r.square()];
+    transcript.append_serializable_element(b"evals", &proof.evals)?;
+    let q = transcript.get_and_append_challenge(b"q")?;
+    transcript.append_serializable_element(b"openings", &proof.openings)?;
+    let d = transcript.get_and_append_challenge(b"d")?;
+
+    // 2r * A_{k+1}(r^2) = r (1 - u_k) (A_k(r) + A_k(-r)) + u_k (A_k(r) - A_k(-r))
+    let two_r = r + r;
+    for (k, (u, [pos, neg, _])) in point.iter().zip(proof.evals.iter()).enumerate() {
+        let next = if k + 1 < nv {
+            proof.evals[k + 1][2]
+        } else {
+            *value
+        };
+        if two_r * next != r * (E::ScalarField::one() - *u) * (*pos + neg) + *u * (*pos - neg) {
+            end_timer!(verify_timer, || "Result: false");
+            return Ok(false);
+        }
+    }
+
+    // B(x_j) = \sum_k q^k A_k(x_j)
+    let mut combined_evals = [E::ScalarField::zero(); 3];
+    let mut q_pows = Vec::with_capacity(nv);
+    let mut q_pow = E::ScalarField::one();
+    for evals in proof.evals.iter() {
+        for (b, a) in combined_evals.iter_mut().zip(evals.iter()) {
+            *b += q_pow * a;
+        }
+        q_pows.push(q_pow);
+        q_pow *= q;
+    }
+
+    let d_pows = [E::ScalarField::one(), d, d.square()];
+    let d_sum: E::ScalarField = d_pows.iter().sum();
+    let mut bases = vec![commitment.0];
+    bases.extend(proof.folds.iter().map(|f| f.0));
+    bases.push(verifier_param.g);
+    bases.extend(proof.openings.iter().map(|w| w.proof));
+    let mut scalars: Vec<_> = q_pows.iter().map(|q| d_sum * q).collect();
+    scalars.push(
+        -d_pows
+            .iter()
+            .zip(combined_evals.iter())
+            .map(|(d, b)| *d * b)
+            .sum::<E::ScalarField>(),
+    );
+    scalars.extend(d_pows.iter().zip(eval_points.iter()).map(|(d, x)| *d * x));
+    let lhs = E::G1::msm_unchecked(&bases, &scalars).into_affine();
+
+    let openings: Vec<_> = proof.openings.iter().map(|w| w.proof).collect();
+    let rhs = E::G1::msm_unchecked(&openings, &d_pows).into_affine();
+
+    let res = E::multi_pairing(
+        [lhs, (-rhs.into_group()).into_affine()],
+        [verifier_param.h, verifier_param.beta_h],
+    )
+    .0
+    .is_one();
+
+    end_timer!(verify_timer, || format!("Result: {}", res));
+    Ok(res)
+}
+
+/// The transcript of an opening, bound to the statement.
+fn init_transcript<E: Pairing>(
+    commitment: &Commitment<E>,
+    point: &[E::ScalarField],
+    value: &E::ScalarField,
+) -> Result<IOPTranscript<E::ScalarField>, PCSError> {
+    let mut transcript = IOPTranscript::new(b"gemini");
+    transcript.append_serializable_element(b"commitment", commitment)?;
+    transcript.append_serializable_element(b"point", &point.to_vec())?;
+    transcript.append_field_element(b"value", value)?;
+    Ok(transcript)
+}
+
+/// The univariate polynomial `\sum_i evals[i] X^i`.
+fn to_univariate<F: Field>(evals: &[F]) -> DensePolynomial<F> {
+    DensePolynomial::from_coefficients_slice(evals)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_bls12_381::Bls12_381;
+    use ark_poly::MultilinearExtension;
+    use ark_std::{test_rng, UniformRand};
+
+    type E = Bls12_381;
+    type Fr = <E as Pairing>::ScalarField;
+
+    fn test_single_helper<R: Rng>(
+        params: &UnivariateUniversalParams<E>,
+        poly: &Arc<DenseMultilinearExtension<Fr>>,
+        supported_num_vars: usize,
+        rng: &mut R,
+    ) -> Result<(), PCSError> {
+        let nv = poly.num_vars();
+        let (ck, vk) = GeminiPCS::trim(params, None, Some(supported_num_vars))?;
+        let point: Vec<_> = (0..nv).map(|_| Fr::rand(rng)).collect();
+        let com = GeminiPCS::commit(&ck, poly)?;
+        let (proof, value) = GeminiPCS::open(&ck, poly, &point)?;
+        assert_eq!(value, poly.evaluate(&point));
+
+        assert!(GeminiPCS::verify(&vk, &com, &point, &value, &proof)?);
+
+        let bad_value = Fr::rand(rng);
+        assert!(!GeminiPCS::verify(&vk, &com, &point, &bad_value, &proof)?);
+
+        let mut bad_point = point.clone();
+        bad_point[0] = Fr::rand(rng);
+        assert!(!GeminiPCS::verify(&vk, &com, &bad_point, &value, &proof)?);
+
+        let mut bad_proof = proof.clone();
+        bad_proof.evals[0][2] += Fr::one();
+        assert!(!GeminiPCS::verify(&vk, &com, &point, &value, &bad_proof)?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_single_commit() -> Result<(), PCSError> {
+        let _trace = crate::trace_guard();
+        let mut rng = test_rng();
+
+        let params = GeminiPCS::<E>::gen_srs_for_testing(&mut rng, 10)?;
+
+        // normal polynomials
+        let poly1 = Arc::new(DenseMultilinearExtension::rand(8, &mut rng));
+        test_single_helper(&params, &poly1, 8, &mut rng)?;
+
+        // polynomials with less variables than the parameters
+        test_single_helper(&params, &poly1, 10, &mut rng)?;
+
+        // single-variate polynomials
+        let poly2 = Arc::new(DenseMultilinearExtension::rand(1, &mut rng));
+        test_single_helper(&params, &poly2, 4, &mut rng)?;
+
+        // too many variables for the SRS
+        assert!(GeminiPCS::trim(&params, None, Some(11)).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_multi_open() -> Result<(), PCSError> {
+        let _trace = crate::trace_guard();
+        let mut rng = test_rng();
+
+        let params = GeminiPCS::<E>::gen_srs_for_testing(&mut rng, 10)?;
+        let (ck, vk) = GeminiPCS::trim(&params, None, Some(10))?;
+
+        let polys: Vec<_> = (0..5)
+            .map(|_| Arc::new(DenseMultilinearExtension::rand(8, &mut rng)))
+            .collect();
+        let points: Vec<Vec<Fr>> = (0..5)
+            .map(|_| (0..8).map(|_| Fr::rand(&mut rng)).collect())
+            .collect();
+        let evals: Vec<_> = polys
+            .iter()
+            .zip(points.iter())
+            .map(|(f, p)| f.evaluate(p))
+            .collect();
+        let commitments = polys
+            .iter()
+            .map(|poly| GeminiPCS::commit(&ck, poly))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut transcript = IOPTranscript::new(b"test transcript");
+        let batch_proof = GeminiPCS::multi_open(&ck, &polys, &points, &evals, &mut transcript)?;
+
+        let mut transcript = IOPTranscript::new(b"test transcript");
+        assert!(GeminiPCS::batch_verify(
+            &vk,
+            &commitments,
+            &points,
+            &batch_proof,
+            &mut transcript
+        )?);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,716 @@
+// Copyright (c) 2023 Espresso Systems (espressosys.com)
+// This file is part of the HyperPlonk library.
+
+// You should have received a copy of the MIT License
+// along with the HyperPlonk library. If not, see <https://mit-license.org/>.
+
+//! Main module for the Hyrax commitment scheme, a multilinear commitment from
+//! Pedersen vector commitments, over any curve group, including curves
+//! without pairings.
+//!
+//! The evaluations of a polynomial of `n` variables are laid out as a matrix
+//! of `2^{n/2}` rows, and the commitment is the list of the Pedersen
+//! commitments to the rows. For an opening at `u = (u_low, u_high)`
+//!   f(u) = \sum_c eq(u_low, c) \sum_r eq(u_high, r) M[r][c]
+//! the verifier combines the row commitments by `eq(u_high, r)` into a
+//! commitment to the combination `t` of the rows, and the prover shows that
+//! `<t, eq(u_low, .)> = f(u)` with an inner product argument of
+//! logarithmic size.
+//!
+//! Since the commitments are homomorphic row by row, a multi-opening is
+//! reduced to a single opening with the sum check of the multilinear KZG
+//! batching.
+
+use crate::{
+    pcs::{
+        multilinear_kzg::batching::{batch_sum_check_claim, BatchSumCheck},
+        utils::{combine_rows, eq_vec, inner_product, matrix_shape},
+        PCSError, PolynomialCommitmentScheme,
+    },
+    poly_iop::{prelude::SumCheck, PolyIOP},
+    IOPProof,
+};
+use arithmetic::{eq_eval, VPAuxInfo};
+use ark_ec::CurveGroup;
+use ark_ff::{Field, PrimeField};
+use ark_poly::DenseMultilinearExtension;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_std::{
+    borrow::Borrow, end_timer, format, marker::PhantomData, rand::Rng, start_timer,
+    string::ToString, sync::Arc, vec::Vec, One,
+};
+use derivative::Derivative;
+use transcript::{encode_point, to_bytes, Encode, Encoder, IOPTranscript, Transcript};
+
+#[derive(Clone)]
+/// Hyrax Polynomial Commitment Scheme on multilinear polynomials, over any
+/// curve group `G`.
+pub struct HyraxPCS<G: CurveGroup> {
+    #[doc(hidden)]
+    phantom: PhantomData<G>,
+}
+
+/// The public parameters of Hyrax: the generators of the Pedersen vector
+/// commitments, which need no trusted setup.
+#[derive(Derivative, CanonicalSerialize, CanonicalDeserialize)]
+#[derivative(
+    Default(bound = ""),
+    Clone(bound = ""),
+    Debug(bound = ""),
+    PartialEq(bound = ""),
+    Eq(bound = "")
+)]
+pub struct HyraxParams<G: CurveGroup> {
+    /// maximum number of variables
+    pub num_vars: usize,
+    /// the generators of the commitments to the rows
+    pub generators: Vec<G::Affine>,
+    /// the generator of the inner product
+    pub h: G::Affine,
+}
+
+/// A commitment is the list of the Pedersen commitments to the rows.
+#[derive(Derivative, CanonicalSerialize, CanonicalDeserialize)]
+#[derivative(
+    Default(bound = ""),
+    Clone(bound = ""),
+    Debug(bound = ""),
+    PartialEq(bound = ""),
+    Eq(bound = "")
+)]
+pub struct HyraxCommitment<G: CurveGroup> {
+    /// the commitments to the rows
+    pub row_commitments: Vec<G::Affine>,
+}
+
+impl<G: CurveGroup> Encode for HyraxCommitment<G> {
+    fn encode<E: Encoder>(&self, encoder: &mut E) {
+        encoder.encode_u64(self.row_commitments.len() as u64);
+        for row_commitment in self.row_commitments.iter() {
+            encode_point(row_commitment, encoder);
+        }
+    }
+}
+
+/// proof of opening, i.e. an inner product argument
+#[derive(Derivative, CanonicalSerialize, CanonicalDeserialize)]
+#[derivative(
+    Default(bound = ""),
+    Clone(bound = ""),
+    Debug(bound = ""),
+    PartialEq(bound = ""),
+    Eq(bound = "")
+)]
+pub struct HyraxProof<G: CurveGroup> {
+    /// The left cross terms of each round
+    pub l_vec: Vec<G::Affine>,
+    /// The right cross terms of each round
+    pub r_vec: Vec<G::Affine>,
+    /// The combination of the rows, folded to a single scalar
+    pub final_scalar: G::ScalarField,
+}
+
+/// batch proof
+#[derive(Derivative, CanonicalSerialize, CanonicalDeserialize)]
+#[derivative(
+    Default(bound = ""),
+    Clone(bound = ""),
+    Debug(bound = ""),
+    PartialEq(bound = ""),
+    Eq(bound = "")
+)]
+pub struct HyraxBatchProof<G: CurveGroup> {
+    /// f_i(point_i)
+    pub evals: Vec<G::ScalarField>,
+    /// A sum check proof proving tilde g's sum
+    pub sum_check_proof: IOPProof<G::ScalarField>,
+    /// proof for g'(a_2)
+    pub g_prime_proof: HyraxProof<G>,
+}
+
+impl<G: CurveGroup> PolynomialCommitmentScheme<G::ScalarField> for HyraxPCS<G> {
+    // Parameters
+    type ProverParam = HyraxParams<G>;
+    type VerifierParam = HyraxParams<G>;
+    type SRS = HyraxParams<G>;
+    // Polynomial and its associated types
+    type Polynomial = Arc<DenseMultilinearExtension<G::ScalarField>>;
+    type Point = Vec<G::ScalarField>;
+    type Evaluation = G::ScalarField;
+    // Commitments and proofs
+    type Commitment = HyraxCommitment<G>;
+    type Proof = HyraxProof<G>;
+    type BatchProof = HyraxBatchProof<G>;
+
+    /// Build the public parameters for `supported_size` variables.
+    ///
+    /// The generators are sampled from `rng`; outside of tests, they should be
+    /// hashed to the curve so that nobody knows their discrete logarithms.
+    fn gen_srs_for_testing<R: Rng>(
+        rng: &mut R,
+        supported_size: usize,
+    ) -> Result<Self::SRS, PCSError> {
+        let (_, num_col_vars) = matrix_shape(supported_size);
+        let generators: Vec<G> = (0..1 << num_col_vars).map(|_| G::rand(rng)).collect();
+        Ok(HyraxParams {
+            num_vars: supported_size,
+            generators: G::normalize_batch(&generators),
+            h: G::rand(rng).into_affine(),
+        })
+    }
+
+    /// Trim the public parameters to `supported_num_vars` variables.
+    /// `supported_degree` must be None or an error is returned.
+    fn trim(
+        srs: impl Borrow<Self::SRS>,
+        supported_degree: Option<usize>,
+        supported_num_vars: Option<usize>,
+    ) -> Result<(Self::ProverParam, Self::VerifierParam), PCSError> {
+        if supported_degree.is_some() {
+            return Err(PCSError::InvalidParameters(
+                "multilinear should not receive a degree param".to_string(),
+            ));
+        }
+        let supported_num_vars = match supported_num_vars {
+            Some(p) => p,
+            None => {
+                return Err(PCSError::InvalidParameters(
+                    "multilinear should receive a num_var param".to_string(),
+                ))
+            },
+        };
+        let srs = srs.borrow();
+        if supported_num_vars > srs.num_vars {
+            return Err(PCSError::InvalidParameters(format!(
+                "parameters of {} variables do not support {} variables",
+                srs.num_vars, supported_num_vars
+            )));
+        }
+        let (_, num_col_vars) = matrix_shape(supported_num_vars);
+        let params = HyraxParams {
+            num_vars: supported_num_vars,
+            generators: srs.generators[..1 << num_col_vars].to_vec(),
+            h: srs.h,
+        };
+        Ok((params.clone(), params))
+    }
+
+    /// Generate a commitment for a polynomial.
+    ///
+    /// This function commits to each of the `2^{n/2}` rows of the matrix.
+    fn commit(
+        prover_param: impl Borrow<Self::ProverParam>,
+        poly: &Self::Polynomial,
+    ) -> Result<Self::Commitment, PCSError> {
+        let prover_param = prover_param.borrow();
+        let commit_timer = start_timer!(|| "commit");
+        if poly.num_vars > prover_param.num_vars {
+            return Err(PCSError::InvalidParameters(format!(
+                "MlE length ({}) exceeds param limit ({})",
+                poly.num_vars, prover_param.num_vars
+            )));
+        }
+
+        let (_, num_col_vars) = matrix_shape(poly.num_vars);
+        let generators = &prover_param.generators[..1 << num_col_vars];
+        let row_commitments: Vec<G> = poly
+            .evaluations
+            .chunks(1 << num_col_vars)
+            .map(|row| G::msm_unchecked(generators, row))
+            .collect();
+
+        end_timer!(commit_timer);
+        Ok(HyraxCommitment {
+            row_commitments: G::normalize_batch(&row_commitments),
+        })
+    }
+
+    /// On input a polynomial `p` and a point `point`, outputs a proof for the
+    /// same.
+    fn open(
+        prover_param: impl Borrow<Self::ProverParam>,
+        polynomial: &Self::Polynomial,
+        point: &Self::Point,
+    ) -> Result<(Self::Proof, Self::Evaluation), PCSError> {
+        open_internal(prover_param.borrow(), polynomial, point)
+    }
+
+    /// Input a list of multilinear extensions, and a same number of points, and
+    /// a transcript, compute a multi-opening for all the polynomials.
+    ///
+    /// The polynomials must have the same number of variables. As in the
+    /// multilinear KZG batching, a sum check driven by `transcript` reduces
+    /// the openings to one of g'(X) = \sum_i eq(a2, point_i) * eq(t, <i>) *
+    /// f_i(X) at a2, so the proof is one sum check and one inner product
+    /// argument whatever the number of polynomials.
+    fn multi_open(
+        prover_param: impl Borrow<Self::ProverParam>,
+        polynomials: &[Self::Polynomial],
+        points: &[Self::Point],
+        evals: &[Self::Evaluation],
+        transcript: &mut impl Transcript<G::ScalarField>,
+    ) -> Result<Self::BatchProof, PCSError> {
+        if polynomials.is_empty()
+            || polynomials.len() != points.len()
+            || polynomials.len() != evals.len()
+        {
+            return Err(PCSError::InvalidParameters(format!(
+                "{} polynomials, {} points and {} evaluations",
+                polynomials.len(),
+                points.len(),
+                evals.len()
+            )));
+        }
+        let num_var = polynomials[0].num_vars;
+        if polynomials.iter().any(|poly| poly.num_vars != num_var) {
+            return Err(PCSError::InvalidParameters(
+                "the polynomials have different numbers of variables".to_string(),
+            ));
+        }
+        let open_timer = start_timer!(|| format!("multi open {} points", points.len()));
+        let batch = BatchSumCheck::new(polynomials, points, evals, transcript)?;
+
+        let sum_check_proof = prove_sum_check(&batch, transcript)?;
+
+        let (g_prime, _) = batch.g_prime(points, &sum_check_proof.point)?;
+        let (g_prime_proof, _) =
+            open_internal(prover_param.borrow(), &g_prime, &sum_check_proof.point)?;
+
+        end_timer!(open_timer);
+        Ok(HyraxBatchProof {
+            evals: evals.to_vec(),
+            sum_check_proof,
+            g_prime_proof,
+        })
+    }
+
+    /// Verifies that `value` is the evaluation at `x` of the polynomial
+    /// committed inside `comm`.
+    fn verify(
+        verifier_param: &Self::VerifierParam,
+        commitment: &Self::Commitment,
+        point: &Self::Point,
+        value: &G::ScalarField,
+        proof: &Self::Proof,
+    ) -> Result<bool, PCSError> {
+        verify_internal(verifier_param, commitment, point, value, proof)
+    }
+
+    /// Verifies that `value_i` is the evaluation at `x_i` of the polynomial
+    /// `poly_i` committed inside `comm`.
+    ///
+    /// The commitment to g'(X) is combined row by row from the commitments,
+    /// and its opening at the point of the sum check is verified.
+    fn batch_verify(
+        verifier_param: &Self::VerifierParam,
+        commitments: &[Self::Commitment],
+        points: &[Self::Point],
+        batch_proof: &Self::BatchProof,
+        transcript: &mut impl Transcript<G::ScalarField>,
+    ) -> Result<bool, PCSError> {
+        if commitments.is_empty()
+            || commitments.len() != points.len()
+            || commitments.len() != batch_proof.evals.len()
+        {
+            return Err(PCSError::InvalidProof(format!(
+                "the batch proof does not match {} commitments",
+                commitments.len()
+            )));
+        }
+        let verify_timer = start_timer!(|| "batch verification");
+        let (eq_t_list, sum) =
+            batch_sum_check_claim(commitments.len(), points, &batch_proof.evals, transcript)?;
+        let num_var = batch_proof.sum_check_proof.point.len();
+        let aux_info = VPAuxInfo {
+            max_degree: 2,
+            num_variables: num_var,
+            phantom: PhantomData,
+        };
+        let subclaim = match <PolyIOP<G::ScalarField, _> as SumCheck<G::ScalarField>>::verify(
+            sum,
+            &batch_proof.sum_check_proof,
+            &aux_info,
+            transcript,
+        ) {
+            Ok(p) => p,
+            // a wrong evaluation is caught by the sum check
+            Err(_e) => return Ok(false),
+        };
+        let a2 = &batch_proof.sum_check_proof.point;
+
+        let g_prime_commitment = g_prime_commitment(commitments, points, &eq_t_list, a2)?;
+        let res = verify_internal(
+            verifier_param,
+            &g_prime_commitment,
+            a2,
+            &subclaim.expected_evaluation,
+            &batch_proof.g_prime_proof,
+        )?;
+
+        end_timer!(verify_timer);
+        Ok(res)
+    }
+
+    fn batch_evaluations(batch_proof: &Self::BatchProof) -> &[Self::Evaluation] {
+        &batch_proof.evals
+    }
+}
+
+/// The sum check of a batch opening.
+fn prove_sum_check<F: PrimeField, T: Transcript<F>>(
+    batch: &BatchSumCheck<F>,
+    transcript: &mut T,
+) -> Result<IOPProof<F>, PCSError> {
+    let timer = start_timer!(|| format!("sum check prove of {} variables", batch.num_var));
+    let proof = <PolyIOP<F, T> as SumCheck<F>>::prove(&batch.poly, transcript).map_err(|_e| {
+        // cannot wrap IOPError with PCSError due to cyclic dependency
+        PCSError::InvalidProver("Sumcheck in batch proving Failed".to_string())
+    })?;
+    end_timer!(timer);
+    Ok(proof)
+}
+
+/// The commitment to g'(X) = \sum_i eq(a2, point_i) * eq(t, <i>) * f_i(X),
+/// combined row by row.
+fn g_prime_commitment<G: CurveGroup>(
+    commitments: &[HyraxCommitment<G>],
+    points: &[Vec<G::ScalarField>],
+    eq_t_list: &[G::ScalarField],
+    a2: &[G::ScalarField],
+) -> Result<HyraxCommitment<G>, PCSError> {
+    let num_rows = commitments[0].row_commitments.len();
+    if commitments
+        .iter()
+        .any(|commitment| commitment.row_commitments.len() != num_rows)
+    {
+        return Err(PCSError::InvalidProof(
+            "the commitments have different numbers of rows".to_string(),
+        ));
+    }
+    let scalars = points
+        .iter()
+        .zip(eq_t_list.iter())
+        .map(|(point, eq_t_i)| Ok(eq_eval(a2, point)? * eq_t_i))
+        .collect::<Result<Vec<_>, PCSError>>()?;
+    let row_commitments: Vec<G> = (0..num_rows)
+        .map(|r| {
+            let bases: Vec<_> = commitments
+                .iter()
+                .map(|commitment| commitment.row_commitments[r])
+                .collect();
+            G::msm_unchecked(&bases, &scalars)
+        })
+        .collect();
+    Ok(HyraxCommitment {
+        row_commitments: G::normalize_batch(&row_commitments),
+    })
+}
+
+/// Open `polynomial` at `point`:
+/// 1. combine the rows with `eq(u_high, r)` into `t`
+/// 2. prove `<t, eq(u_low, .)> = f(u)` with an inner product argument: each
+///    round halves `t`, `eq(u_low, .)` and the generators, with cross terms
+///    `L` and `R` and a challenge `x`
+fn open_internal<G: CurveGroup>(
+    params: &HyraxParams<G>,
+    polynomial: &DenseMultilinearExtension<G::ScalarField>,
+    point: &[G::ScalarField],
+) -> Result<(HyraxProof<G>, G::ScalarField), PCSError> {
+    let open_timer = start_timer!(|| format!("open mle with {} variable", polynomial.num_vars));
+
+    if polynomial.num_vars != point.len() {
+        return Err(PCSError::InvalidParameters(format!(
+            "Polynomial num_vars {} does not match point len {}",
+            polynomial.num_vars,
+            point.len()
+        )));
+    }
+    if polynomial.num_vars > params.num_vars {
+        return Err(PCSError::InvalidParameters(format!(
+            "MlE length ({}) exceeds param limit ({})",
+            polynomial.num_vars, params.num_vars
+        )));
+    }
+
+    let (_, num_col_vars) = matrix_shape(point.len());
+    let rows: Vec<_> = polynomial.evaluations.chunks(1 << num_col_vars).collect();
+    let mut a = combine_rows(&rows, &eq_vec(&point[num_col_vars..])?);
+    let mut b = eq_vec(&point[..num_col_vars])?;
+    let eval = inner_product(&a, &b);
+    let mut generators = params.generators[..1 << num_col_vars].to_vec();
+
+    let combined_commitment = G::msm_unchecked(&generators, &a).into_affine();
+    let mut transcript = init_transcript::<G>(&combined_commitment, point, &eval)?;
+
+    let mut l_vec = Vec::with_capacity(num_col_vars);
+    let mut r_vec = Vec::with_capacity(num_col_vars);
+    while a.len() > 1 {
+        let half = a.len() / 2;
+        let (a_l, a_r) = a.split_at(half);
+        let (b_l, b_r) = b.split_at(half);
+        let (g_l, g_r) = generators.split_at(half);
+
+        let l = (G::msm_unchecked(g_r, a_l) + params.h * inner_product(a_l, b_r)).into_affine();
+        let r = (G::msm_unchecked(g_l, a_r) + params.h * inner_product(a_r, b_l)).into_affine();
+        transcript.append_message(b"L", &to_bytes!(&l)?)?;
+        transcript.append_message(b"R", &to_bytes!(&r)?)?;
+        l_vec.push(l);
+        r_vec.push(r);
+        let (x, x_inv) = ipa_challenge(&mut transcript)?;
+
+        let new_generators: Vec<G> = g_l
+            .iter()
+            .zip(g_r.iter())
+            .map(|(gl, gr)| *gl * x_inv + *gr * x)
+            .collect();
+        a = a_l
+            .iter()
+            .zip(a_r.iter())
+            .map(|(al, ar)| *al * x + *ar * x_inv)
+            .collect();
+        b = b_l
+            .iter()
+            .zip(b_r.iter())
+            .map(|(bl, br)| *bl * x_inv + *br * x)
+            .collect();
+        generators = G::normalize_batch(&new_generators);
+    }
+
+    end_timer!(open_timer);
+    Ok((
+        HyraxProof {
+            l_vec,
+            r_vec,
+            final_scalar: a[0],
+        },
+        eval,
+    ))
+}
+
+/// Verifies that `value` is the evaluation at `point` of the polynomial
+/// committed inside `commitment`:
+/// 1. combine the row commitments with `eq(u_high, r)` into a commitment `P`
+///    to `t`
+/// 2. fold `P + value * h` with the cross terms, and check it against the
+///    folded generators and `eq(u_low, .)`, which are computed at once from
+///    the challenges
+fn verify_internal<G: CurveGroup>(
+    params: &HyraxParams<G>,
+    commitment: &HyraxCommitment<G>,
+    point: &[G::ScalarField],
+    value: &G::ScalarField,
+    proof: &HyraxProof<G>,
+) -> Result<bool, PCSError> {
+    let verify_timer = start_timer!(|| "verify");
+
+    if point.len() > params.num_vars {
+        return Err(PCSError::InvalidParameters(format!(
+            "point length ({}) exceeds param limit ({})",
+            point.len(),
+            params.num_vars
+        )));
+    }
+    let (num_row_vars, num_col_vars) = matrix_shape(point.len());
+    if commitment.row_commitments.len() != 1 << num_row_vars
+        || proof.l_vec.len() != num_col_vars
+        || proof.r_vec.len() != num_col_vars
+    {
+        return Err(PCSError::InvalidProof(format!(
+            "the commitment or the proof does not match a point of length {}",
+            point.len()
+        )));
+    }
+
+    let combined_commitment = G::msm_unchecked(
+        &commitment.row_commitments,
+        &eq_vec(&point[num_col_vars..])?,
+    )
+    .into_affine();
+    let mut transcript = init_transcript::<G>(&combined_commitment, point, value)?;
+
+    // P + value * h + \sum_j x_j^2 L_j + x_j^{-2} R_j
+    let mut bases = vec![combined_commitment, params.h];
+    let mut scalars = vec![G::ScalarField::one(), *value];
+    // s_i = \prod_j x_j^{+-1}, by the bit of `i` halved at round `j`
+    let mut s = vec![G::ScalarField::one()];
+    for (l, r) in proof.l_vec.iter().zip(proof.r_vec.iter()) {
+        transcript.append_message(b"L", &to_bytes!(l)?)?;
+        transcript.append_message(b"R", &to_bytes!(r)?)?;
+        let (x, x_inv) = ipa_challenge(&mut transcript)?;
+        bases.push(*l);
+        scalars.push(x.square());
+        bases.push(*r);
+        scalars.push(x_inv.square());
+        s = s.iter().flat_map(|s_i| [*s_i * x_inv, *s_i * x]).collect();
+    }
+    let folded_commitment = G::msm_unchecked(&bases, &scalars);
+
+    let b = inner_product(&s, &eq_vec(&point[..num_col_vars])?);
+    let folded_generator = G::msm_unchecked(&params.generators[..1 << num_col_vars], &s);
+    let res = folded_commitment
+        == folded_generator * proof.final_scalar + params.h * (proof.final_scalar * b);
+
+    end_timer!(verify_timer, || format!("Result: {}", res));
+    Ok(res)
+}
+
+/// The transcript of an opening, bound to the statement.
+fn init_transcript<G: CurveGroup>(
+    combined_commitment: &G::Affine,
+    point: &[G::ScalarField],
+    value: &G::ScalarField,
+) -> Result<IOPTranscript<G::ScalarField>, PCSError> {
+    let mut transcript = IOPTranscript::new(b"hyrax");
+    transcript.append_message(b"commitment", &to_bytes!(combined_commitment)?)?;
+    transcript.append_serializable_element(b"point", &point.to_vec())?;
+    transcript.append_field_element(b"value", value)?;
+    Ok(transcript)
+}
+
+/// The challenge `x` of a round of the inner product argument, and its
+/// inverse.
+fn ipa_challenge<F: PrimeField>(transcript: &mut IOPTranscript<F>) -> Result<(F, F), PCSError> {
+    let x = transcript.get_and_append_challenge(b"x")?;
+    let x_inv = x
+        .inverse()
+        .ok_or_else(|| PCSError::InvalidProof("zero challenge".to_string()))?;
+    Ok((x, x_inv))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_ed_on_bls12_381_bandersnatch::EdwardsProjective;
+    use ark_poly::{MultilinearExtension, Polynomial};
+    use ark_std::{test_rng, UniformRand};
+
+    fn test_single_helper<G: CurveGroup, R: Rng>(
+        params: &HyraxParams<G>,
+        poly: &Arc<DenseMultilinearExtension<G::ScalarField>>,
+        supported_num_vars: usize,
+        rng: &mut R,
+    ) -> Result<(), PCSError> {
+        let nv = poly.num_vars();
+        let (ck, vk) = HyraxPCS::<G>::trim(params, None, Some(supported_num_vars))?;
+        let point: Vec<_> = (0..nv).map(|_| G::ScalarField::rand(rng)).collect();
+        let com = HyraxPCS::<G>::commit(&ck, poly)?;
+        let (proof, value) = HyraxPCS::<G>::open(&ck, poly, &point)?;
+        assert_eq!(value, poly.evaluate(&point));
+
+        assert!(HyraxPCS::<G>::verify(&vk, &com, &point, &value, &proof)?);
+
+        let bad_value = G::ScalarField::rand(rng);
+        assert!(!HyraxPCS::<G>::verify(
+            &vk, &com, &point, &bad_value, &proof
+        )?);
+
+        let mut bad_point = point.clone();
+        bad_point[0] = G::ScalarField::rand(rng);
+        assert!(!HyraxPCS::<G>::verify(
+            &vk, &com, &bad_point, &value, &proof
+        )?);
+
+        let mut bad_proof = proof;
+        bad_proof.final_scalar += G::ScalarField::one();
+        assert!(!HyraxPCS::<G>::verify(
+            &vk, &com, &point, &value, &bad_proof
+        )?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_single_commit() -> Result<(), PCSError> {
+        let _trace = crate::trace_guard();
+        let mut rng = test_rng();
+
+        // a curve without pairing
+        let params = HyraxPCS::<EdwardsProjective>::gen_srs_for_testing(&mut rng, 10)?;
+
+        // normal polynomials
+        let poly1 = Arc::new(DenseMultilinearExtension::rand(8, &mut rng));
+        test_single_helper(&params, &poly1, 8, &mut rng)?;
+
+        // odd number of variables
+        let poly2 = Arc::new(DenseMultilinearExtension::rand(9, &mut rng));
+        test_single_helper(&params, &poly2, 10, &mut rng)?;
+
+        // single-variate polynomials
+        let poly3 = Arc::new(DenseMultilinearExtension::rand(1, &mut rng));
+        test_single_helper(&params, &poly3, 4, &mut rng)?;
+
+        // too many variables for the parameters
+        assert!(HyraxPCS::<EdwardsProjective>::trim(params, None, Some(11)).is_err());
+
+        // a pairing-friendly curve works as well
+        let params = HyraxPCS::<ark_bls12_381::G1Projective>::gen_srs_for_testing(&mut rng, 8)?;
+        let poly4 = Arc::new(DenseMultilinearExtension::rand(7, &mut rng));
+        test_single_helper(&params, &poly4, 8, &mut rng)?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_multi_open() -> Result<(), PCSError> {
+        let _trace = crate::trace_guard();
+        type G = EdwardsProjective;
+        type Fr = <G as ark_ec::PrimeGroup>::ScalarField;
+
+        let mut rng = test_rng();
+
+        let params = HyraxPCS::<G>::gen_srs_for_testing(&mut rng, 10)?;
+        let (ck, vk) = HyraxPCS::<G>::trim(params, None, Some(10))?;
+
+        let polys: Vec<_> = (0..5)
+            .map(|_| Arc::new(DenseMultilinearExtension::rand(8, &mut rng)))
+            .collect();
+        let points: Vec<Vec<Fr>> = (0..5)
+            .map(|_| (0..8).map(|_| Fr::rand(&mut rng)).collect())
+            .collect();
+        let evals: Vec<_> = polys
+            .iter()
+            .zip(points.iter())
+            .map(|(f, p)| f.evaluate(p))
+            .collect();
+        let commitments = polys
+            .iter()
+            .map(|poly| HyraxPCS::<G>::commit(&ck, poly))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut transcript = IOPTranscript::new(b"test transcript");
+        let batch_proof = HyraxPCS::<G>::multi_open(&ck, &polys, &points, &evals, &mut transcript)?;
+
+        let mut transcript = IOPTranscript::new(b"test transcript");
+        assert!(HyraxPCS::<G>::batch_verify(
+            &vk,
+            &commitments,
+            &points,
+            &batch_proof,
+            &mut transcript
+        )?);
+
+        let mut bad_proof = batch_proof.clone();
+        bad_proof.g_prime_proof.final_scalar += Fr::one();
+        let mut transcript = IOPTranscript::new(b"test transcript");
+        assert!(!HyraxPCS::<G>::batch_verify(
+            &vk,
+            &commitments,
+            &points,
+            &bad_proof,
+            &mut transcript
+        )?);
+
+        let mut bad_proof = batch_proof;
+        bad_proof.evals[1] += Fr::one();
+        let mut transcript = IOPTranscript::new(b"test transcript");
+        assert!(!HyraxPCS::<G>::batch_verify(
+            &vk,
+            &commitments,
+            &points,
+            &bad_proof,
+            &mut transcript
+        )?);
+
+        Ok(())
+    }
+}
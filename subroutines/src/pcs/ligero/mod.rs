@@ -0,0 +1,569 @@
+// Copyright (c) 2023 Espresso Systems (espressosys.com)
+// This file is part of the HyperPlonk library.
+
+// You should have received a copy of the MIT License
+// along with the HyperPlonk library. If not, see <https://mit-license.org/>.
+
+//! Main module for the Ligero commitment scheme, a transparent multilinear
+//! commitment from a Reed-Solomon code and Merkle trees.
+//!
+//! The evaluations of a polynomial of `n` variables are laid out as a matrix
+//! of `2^{n/2}` rows, whose rows are encoded with a Reed-Solomon code of rate
+//! `2^{-log_inv_rate}`. The commitment is the Merkle root of the columns of
+//! the encoded matrix. For an opening at `u = (u_low, u_high)`
+//!   f(u) = \sum_r eq(u_high, r) \sum_c eq(u_low, c) M[r][c]
+//! the prover sends the combinations of the rows by random challenges and by
+//! `eq(u_high, r)`, and opens random columns, on which the verifier checks
+//! the encodings of both combinations.
+
+use crate::pcs::{
+    merkle::{verify_path, Hash, MerkleTree},
+    utils::{combine_rows, eq_vec, inner_product, matrix_shape},
+    PCSError, PolynomialCommitmentScheme,
+};
+use ark_ff::PrimeField;
+use ark_poly::{DenseMultilinearExtension, EvaluationDomain, Radix2EvaluationDomain};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_std::{
+    borrow::Borrow, end_timer, format, marker::PhantomData, rand::Rng, start_timer,
+    string::ToString, sync::Arc, vec::Vec,
+};
+use transcript::{Encode, Encoder, IOPTranscript, Transcript};
+
+/// The log of the inverse rate of the Reed-Solomon code.
+const LOG_INV_RATE: usize = 2;
+/// The number of opened columns: a matrix whose encoding is far from the code
+/// passes each query with probability at most `3/4`, and `(3/4)^241 <
+/// 2^{-100}`.
+const NUM_QUERIES: usize = 241;
+
+#[derive(Clone)]
+/// Ligero Polynomial Commitment Scheme on multilinear polynomials.
+///
+/// The scheme is transparent and needs no group, so it is generic over the
+/// field `F` only.
+pub struct LigeroPCS<F: PrimeField> {
+    #[doc(hidden)]
+    phantom: PhantomData<F>,
+}
+
+/// The public parameters of Ligero, which need no trusted setup.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct LigeroParams {
+    /// maximum number of variables
+    pub num_vars: usize,
+    /// log of the inverse rate of the Reed-Solomon code
+    pub log_inv_rate: usize,
+    /// number of opened columns
+    pub num_queries: usize,
+}
+
+impl LigeroParams {
+    /// The parameters for polynomials of up to `num_vars` variables, with
+    /// about 100 bits of security.
+    pub fn new(num_vars: usize) -> Self {
+        Self {
+            num_vars,
+            log_inv_rate: LOG_INV_RATE,
+            num_queries: NUM_QUERIES,
+        }
+    }
+}
+
+#[derive(
+    Clone, Copy, Debug, Default, Hash, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize,
+)]
+/// A commitment is the Merkle root of the columns of the encoded matrix.
+pub struct LigeroCommitment {
+    /// the Merkle root
+    pub root: Hash,
+}
+
+impl Encode for LigeroCommitment {
+    fn encode<E: Encoder>(&self, encoder: &mut E) {
+        self.root.encode(encoder);
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+/// proof of opening
+pub struct LigeroProof<F: PrimeField> {
+    /// The combination of the rows by the proximity challenges
+    pub proximity_row: Vec<F>,
+    /// The combination of the rows by `eq(u_high, r)`
+    pub eval_row: Vec<F>,
+    /// The opened columns of the encoded matrix
+    pub columns: Vec<Vec<F>>,
+    /// The Merkle paths of the opened columns
+    pub paths: Vec<Vec<Hash>>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+/// batch proof
+pub struct LigeroBatchProof<F: PrimeField> {
+    /// f_i(point_i)
+    pub evals: Vec<F>,
+    /// the proof of each opening
+    pub proofs: Vec<LigeroProof<F>>,
+}
+
+impl<F: PrimeField> PolynomialCommitmentScheme<F> for LigeroPCS<F> {
+    // Parameters
+    type ProverParam = LigeroParams;
+    type VerifierParam = LigeroParams;
+    type SRS = LigeroParams;
+    // Polynomial and its associated types
+    type Polynomial = Arc<DenseMultilinearExtension<F>>;
+    type Point = Vec<F>;
+    type Evaluation = F;
+    // Commitments and proofs
+    type Commitment = LigeroCommitment;
+    type Proof = LigeroProof<F>;
+    type BatchProof = LigeroBatchProof<F>;
+
+    /// Build the public parameters for `supported_size` variables.
+    ///
+    /// Ligero needs no trusted setup: the parameters are public and `rng` is
+    /// unused, so they are also fine outside of tests.
+    fn gen_srs_for_testing<R: Rng>(
+        _rng: &mut R,
+        supported_size: usize,
+    ) -> Result<Self::SRS, PCSError> {
+        Ok(LigeroParams::new(supported_size))
+    }
+
+    /// Trim the public parameters to `supported_num_vars` variables.
+    /// `supported_degree` must be None or an error is returned.
+    fn trim(
+        srs: impl Borrow<Self::SRS>,
+        supported_degree: Option<usize>,
+        supported_num_vars: Option<usize>,
+    ) -> Result<(Self::ProverParam, Self::VerifierParam), PCSError> {
+        if supported_degree.is_some() {
+            return Err(PCSError::InvalidParameters(
+                "multilinear should not receive a degree param".to_string(),
+            ));
+        }
+        let supported_num_vars = match supported_num_vars {
+            Some(p) => p,
+            None => {
+                return Err(PCSError::InvalidParameters(
+                    "multilinear should receive a num_var param".to_string(),
+                ))
+            },
+        };
+        let srs = srs.borrow();
+        if supported_num_vars > srs.num_vars {
+            return Err(PCSError::InvalidParameters(format!(
+                "parameters of {} variables do not support {} variables",
+                srs.num_vars, supported_num_vars
+            )));
+        }
+        let params = LigeroParams {
+            num_vars: supported_num_vars,
+            ..*srs
+        };
+        Ok((params, params))
+    }
+
+    /// Generate a commitment for a polynomial.
+    ///
+    /// This function encodes the `2^{n/2}` rows of the matrix, and hashes the
+    /// columns of the encoding.
+    fn commit(
+        prover_param: impl Borrow<Self::ProverParam>,
+        poly: &Self::Polynomial,
+    ) -> Result<Self::Commitment, PCSError> {
+        let commit_timer = start_timer!(|| "commit");
+        let (_, tree) = encode_matrix(prover_param.borrow(), poly)?;
+        end_timer!(commit_timer);
+        Ok(LigeroCommitment { root: tree.root() })
+    }
+
+    /// On input a polynomial `p` and a point `point`, outputs a proof for the
+    /// same.
+    ///
+    /// The encoded matrix is not kept by the commitment, so this function
+    /// encodes and hashes it again.
+    fn open(
+        prover_param: impl Borrow<Self::ProverParam>,
+        polynomial: &Self::Polynomial,
+        point: &Self::Point,
+    ) -> Result<(Self::Proof, Self::Evaluation), PCSError> {
+        open_internal(prover_param.borrow(), polynomial, point)
+    }
+
+    /// Input a list of multilinear extensions, and a same number of points, and
+    /// a transcript, compute a multi-opening for all the polynomials.
+    ///
+    /// Merkle roots cannot be combined, so the polynomials are opened one by
+    /// one.
+    fn multi_open(
+        prover_param: impl Borrow<Self::ProverParam>,
+        polynomials: &[Self::Polynomial],
+        points: &[Self::Point],
+        evals: &[Self::Evaluation],
+        _transcript: &mut impl Transcript<F>,
+    ) -> Result<Self::BatchProof, PCSError> {
+        if polynomials.len() != points.len() || polynomials.len() != evals.len() {
+            return Err(PCSError::InvalidParameters(format!(
+                "{} polynomials, {} points and {} evaluations",
+                polynomials.len(),
+                points.len(),
+                evals.len()
+            )));
+        }
+        let proofs = polynomials
+            .iter()
+            .zip(points.iter())
+            .map(|(poly, point)| Ok(open_internal(prover_param.borrow(), poly, point)?.0))
+            .collect::<Result<Vec<_>, PCSError>>()?;
+        Ok(LigeroBatchProof {
+            evals: evals.to_vec(),
+            proofs,
+        })
+    }
+
+    /// Verifies that `value` is the evaluation at `x` of the polynomial
+    /// committed inside `comm`.
+    fn verify(
+        verifier_param: &Self::VerifierParam,
+        commitment: &Self::Commitment,
+        point: &Self::Point,
+        value: &F,
+        proof: &Self::Proof,
+    ) -> Result<bool, PCSError> {
+        verify_internal(verifier_param, commitment, point, value, proof)
+    }
+
+    /// Verifies that `value_i` is the evaluation at `x_i` of the polynomial
+    /// `poly_i` committed inside `comm`.
+    fn batch_verify(
+        verifier_param: &Self::VerifierParam,
+        commitments: &[Self::Commitment],
+        points: &[Self::Point],
+        batch_proof: &Self::BatchProof,
+        _transcript: &mut impl Transcript<F>,
+    ) -> Result<bool, PCSError> {
+        if commitments.len() != points.len()
+            || commitments.len() != batch_proof.evals.len()
+            || commitments.len() != batch_proof.proofs.len()
+        {
+            return Err(PCSError::InvalidProof(format!(
+                "the batch proof does not match {} commitments",
+                commitments.len()
+            )));
+        }
+        for (((commitment, point), value), proof) in commitments
+            .iter()
+            .zip(points.iter())
+            .zip(batch_proof.evals.iter())
+            .zip(batch_proof.proofs.iter())
+        {
+            if !verify_internal(verifier_param, commitment, point, value, proof)? {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+
+    fn batch_evaluations(batch_proof: &Self::BatchProof) -> &[Self::Evaluation] {
+        &batch_proof.evals
+    }
+}
+
+/// Open `polynomial` at `point`:
+/// 1. get the proximity challenges, and combine the rows with them
+/// 2. combine the rows with `eq(u_high, r)`
+/// 3. get the indices of the opened columns, and open them
+fn open_internal<F: PrimeField>(
+    params: &LigeroParams,
+    polynomial: &DenseMultilinearExtension<F>,
+    point: &[F],
+) -> Result<(LigeroProof<F>, F), PCSError> {
+    let open_timer = start_timer!(|| format!("open mle with {} variable", polynomial.num_vars));
+
+    if polynomial.num_vars != point.len() {
+        return Err(PCSError::InvalidParameters(format!(
+            "Polynomial num_vars {} does not match point len {}",
+            polynomial.num_vars,
+            point.len()
+        )));
+    }
+
+    let (columns, tree) = encode_matrix(params, polynomial)?;
+    let (num_row_vars, num_col_vars) = matrix_shape(point.len());
+    let rows: Vec<_> = polynomial.evaluations.chunks(1 << num_col_vars).collect();
+
+    let mut transcript = init_transcript(&tree.root(), point)?;
+    let gammas = transcript.get_and_append_challenge_vectors(b"gamma", 1 << num_row_vars)?;
+    let proximity_row = combine_rows(&rows, &gammas);
+    let eval_row = combine_rows(&rows, &eq_vec(&point[num_col_vars..])?);
+    let eval = inner_product(&eval_row, &eq_vec(&point[..num_col_vars])?);
+    transcript.append_serializable_element(b"proximity row", &proximity_row)?;
+    transcript.append_serializable_element(b"eval row", &eval_row)?;
+
+    let indices = query_indices(&mut transcript, params, columns.len())?;
+    let proof = LigeroProof {
+        proximity_row,
+        eval_row,
+        columns: indices.iter().map(|i| columns[*i].clone()).collect(),
+        paths: indices.iter().map(|i| tree.path(*i)).collect(),
+    };
+
+    end_timer!(open_timer);
+    Ok((proof, eval))
+}
+
+/// Verifies that `value` is the evaluation at `point` of the polynomial
+/// committed inside `commitment`:
+/// 1. check `value` on the combination of the rows by `eq(u_high, r)`
+/// 2. check that the opened columns are in the commitment, and that they are
+///    consistent with the encodings of both combinations of the rows
+fn verify_internal<F: PrimeField>(
+    params: &LigeroParams,
+    commitment: &LigeroCommitment,
+    point: &[F],
+    value: &F,
+    proof: &LigeroProof<F>,
+) -> Result<bool, PCSError> {
+    let verify_timer = start_timer!(|| "verify");
+
+    if point.len() > params.num_vars {
+        return Err(PCSError::InvalidParameters(format!(
+            "point length ({}) exceeds param limit ({})",
+            point.len(),
+            params.num_vars
+        )));
+    }
+    let (num_row_vars, num_col_vars) = matrix_shape(point.len());
+    if proof.proximity_row.len() != 1 << num_col_vars
+        || proof.eval_row.len() != 1 << num_col_vars
+        || proof.columns.len() != params.num_queries
+        || proof.paths.len() != params.num_queries
+    {
+        return Err(PCSError::InvalidProof(format!(
+            "the proof does not match a point of length {}",
+            point.len()
+        )));
+    }
+
+    let mut transcript = init_transcript(&commitment.root, point)?;
+    let gammas = transcript.get_and_append_challenge_vectors(b"gamma", 1 << num_row_vars)?;
+    transcript.append_serializable_element(b"proximity row", &proof.proximity_row)?;
+    transcript.append_serializable_element(b"eval row", &proof.eval_row)?;
+
+    let eq_high = eq_vec(&point[num_col_vars..])?;
+    if inner_product(&proof.eval_row, &eq_vec(&point[..num_col_vars])?) != *value {
+        end_timer!(verify_timer, || "Result: false");
+        return Ok(false);
+    }
+
+    let encoded_proximity_row = encode(&proof.proximity_row, params.log_inv_rate)?;
+    let encoded_eval_row = encode(&proof.eval_row, params.log_inv_rate)?;
+    let depth = encoded_eval_row.len().trailing_zeros() as usize;
+    let indices = query_indices(&mut transcript, params, encoded_eval_row.len())?;
+    for ((index, column), path) in indices
+        .iter()
+        .zip(proof.columns.iter())
+        .zip(proof.paths.iter())
+    {
+        if column.len() != 1 << num_row_vars
+            || !verify_path(&commitment.root, depth, *index, column, path)?
+            || inner_product(column, &gammas) != encoded_proximity_row[*index]
+            || inner_product(column, &eq_high) != encoded_eval_row[*index]
+        {
+            end_timer!(verify_timer, || "Result: false");
+            return Ok(false);
+        }
+    }
+
+    end_timer!(verify_timer, || "Result: true");
+    Ok(true)
+}
+
+/// Encode the rows of the matrix of `poly`, and build the Merkle tree of the
+/// columns of the encoding. Returns the columns and the tree.
+fn encode_matrix<F: PrimeField>(
+    params: &LigeroParams,
+    poly: &DenseMultilinearExtension<F>,
+) -> Result<(Vec<Vec<F>>, MerkleTree), PCSError> {
+    if poly.num_vars > params.num_vars {
+        return Err(PCSError::InvalidParameters(format!(
+            "MlE length ({}) exceeds param limit ({})",
+            poly.num_vars, params.num_vars
+        )));
+    }
+
+    let step = start_timer!(|| "encode rows");
+    let (_, num_col_vars) = matrix_shape(poly.num_vars);
+    let encoded_rows = poly
+        .evaluations
+        .chunks(1 << num_col_vars)
+        .map(|row| encode(row, params.log_inv_rate))
+        .collect::<Result<Vec<_>, PCSError>>()?;
+    end_timer!(step);
+
+    let step = start_timer!(|| "hash columns");
+    let columns: Vec<Vec<F>> = (0..encoded_rows[0].len())
+        .map(|j| encoded_rows.iter().map(|row| row[j]).collect())
+        .collect();
+    let tree = MerkleTree::new(&columns)?;
+    end_timer!(step);
+
+    Ok((columns, tree))
+}
+
+/// The Reed-Solomon encoding of `row`, i.e. the evaluations of the
+/// polynomial of coefficients `row` on a domain `2^log_inv_rate` times
+/// larger.
+fn encode<F: PrimeField>(row: &[F], log_inv_rate: usize) -> Result<Vec<F>, PCSError> {
+    let domain = Radix2EvaluationDomain::<F>::new(row.len() << log_inv_rate).ok_or_else(|| {
+        PCSError::InvalidParameters(format!(
+            "no evaluation domain of size {}",
+            row.len() << log_inv_rate
+        ))
+    })?;
+    Ok(domain.fft(row))
+}
+
+/// The transcript of an opening, bound to the statement.
+fn init_transcript<F: PrimeField>(root: &Hash, point: &[F]) -> Result<IOPTranscript<F>, PCSError> {
+    let mut transcript = IOPTranscript::new(b"ligero");
+    transcript.append_serializable_element(b"root", root)?;
+    transcript.append_serializable_element(b"point", &point.to_vec())?;
+    Ok(transcript)
+}
+
+/// The indices of the opened columns among `num_columns`.
+fn query_indices<F: PrimeField>(
+    transcript: &mut IOPTranscript<F>,
+    params: &LigeroParams,
+    num_columns: usize,
+) -> Result<Vec<usize>, PCSError> {
+    (0..params.num_queries)
+        .map(|_| {
+            let challenge = transcript.get_and_append_challenge(b"query")?;
+            Ok(challenge.into_bigint().as_ref()[0] as usize % num_columns)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_bls12_381::Fr;
+    use ark_poly::{MultilinearExtension, Polynomial};
+    use ark_std::{test_rng, One, UniformRand};
+
+    fn test_single_helper<R: Rng>(
+        params: &LigeroParams,
+        poly: &Arc<DenseMultilinearExtension<Fr>>,
+        supported_num_vars: usize,
+        rng: &mut R,
+    ) -> Result<(), PCSError> {
+        let nv = poly.num_vars();
+        let (ck, vk) = LigeroPCS::<Fr>::trim(params, None, Some(supported_num_vars))?;
+        let point: Vec<_> = (0..nv).map(|_| Fr::rand(rng)).collect();
+        let com = LigeroPCS::<Fr>::commit(ck, poly)?;
+        let (proof, value) = LigeroPCS::<Fr>::open(ck, poly, &point)?;
+        assert_eq!(value, poly.evaluate(&point));
+
+        assert!(LigeroPCS::<Fr>::verify(&vk, &com, &point, &value, &proof)?);
+
+        let bad_value = Fr::rand(rng);
+        assert!(!LigeroPCS::<Fr>::verify(
+            &vk, &com, &point, &bad_value, &proof
+        )?);
+
+        let mut bad_point = point.clone();
+        bad_point[0] = Fr::rand(rng);
+        assert!(!LigeroPCS::<Fr>::verify(
+            &vk, &com, &bad_point, &value, &proof
+        )?);
+
+        let mut bad_proof = proof.clone();
+        bad_proof.columns[0][0] += Fr::one();
+        assert!(!LigeroPCS::<Fr>::verify(
+            &vk, &com, &point, &value, &bad_proof
+        )?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_single_commit() -> Result<(), PCSError> {
+        let _trace = crate::trace_guard();
+        let mut rng = test_rng();
+
+        let params = LigeroPCS::<Fr>::gen_srs_for_testing(&mut rng, 10)?;
+
+        // normal polynomials
+        let poly1 = Arc::new(DenseMultilinearExtension::rand(8, &mut rng));
+        test_single_helper(&params, &poly1, 8, &mut rng)?;
+
+        // odd number of variables
+        let poly2 = Arc::new(DenseMultilinearExtension::rand(9, &mut rng));
+        test_single_helper(&params, &poly2, 10, &mut rng)?;
+
+        // single-variate polynomials
+        let poly3 = Arc::new(DenseMultilinearExtension::rand(1, &mut rng));
+        test_single_helper(&params, &poly3, 4, &mut rng)?;
+
+        // too many variables for the parameters
+        assert!(LigeroPCS::<Fr>::trim(params, None, Some(11)).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_multi_open() -> Result<(), PCSError> {
+        let _trace = crate::trace_guard();
+        let mut rng = test_rng();
+
+        let params = LigeroPCS::<Fr>::gen_srs_for_testing(&mut rng, 10)?;
+        let (ck, vk) = LigeroPCS::<Fr>::trim(params, None, Some(10))?;
+
+        let polys: Vec<_> = (0..5)
+            .map(|_| Arc::new(DenseMultilinearExtension::rand(8, &mut rng)))
+            .collect();
+        let points: Vec<Vec<Fr>> = (0..5)
+            .map(|_| (0..8).map(|_| Fr::rand(&mut rng)).collect())
+            .collect();
+        let evals: Vec<_> = polys
+            .iter()
+            .zip(points.iter())
+            .map(|(f, p)| f.evaluate(p))
+            .collect();
+        let commitments = polys
+            .iter()
+            .map(|poly| LigeroPCS::<Fr>::commit(ck, poly))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut transcript = IOPTranscript::new(b"test transcript");
+        let batch_proof =
+            LigeroPCS::<Fr>::multi_open(ck, &polys, &points, &evals, &mut transcript)?;
+
+        let mut transcript = IOPTranscript::new(b"test transcript");
+        assert!(LigeroPCS::<Fr>::batch_verify(
+            &vk,
+            &commitments,
+            &points,
+            &batch_proof,
+            &mut transcript
+        )?);
+
+        let mut bad_proof = batch_proof;
+        bad_proof.evals[1] += Fr::one();
+        let mut transcript = IOPTranscript::new(b"test transcript");
+        assert!(!LigeroPCS::<Fr>::batch_verify(
+            &vk,
+            &commitments,
+            &points,
+            &bad_proof,
+            &mut transcript
+        )?);
+
+        Ok(())
+    }
+}
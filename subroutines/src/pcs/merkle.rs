@@ -0,0 +1,142 @@
+// Copyright (c) 2023 Espresso Systems (espressosys.com)
+// This file is part of the HyperPlonk library.
+
+// You should have received a copy of the MIT License
+// along with the HyperPlonk library. If not, see <https://mit-license.org/>.
+
+//! A SHA-256 Merkle tree over vectors of field elements, for the commitments
+//! of the transparent schemes.
+
+use crate::pcs::PCSError;
+use ark_ff::PrimeField;
+use ark_serialize::CanonicalSerialize;
+use ark_std::vec::Vec;
+use sha2::{Digest, Sha256};
+
+/// A SHA-256 digest.
+pub type Hash = [u8; 32];
+
+/// A Merkle tree over a power of two number of leaves, whose layers go from
+/// the hashes of the leaves to the root.
+pub(crate) struct MerkleTree {
+    layers: Vec<Vec<Hash>>,
+}
+
+impl MerkleTree {
+    /// Build the tree over `leaves`.
+    pub(crate) fn new<F: PrimeField>(leaves: &[Vec<F>]) -> Result<Self, PCSError> {
+        let mut layers = vec![leaves
+            .iter()
+            .map(|leaf| hash_leaf(leaf))
+            .collect::<Result<Vec<_>, PCSError>>()?];
+        while layers[layers.len() - 1].len() > 1 {
+            let layer = layers[layers.len() - 1]
+                .chunks(2)
+                .map(|pair| hash_pair(&pair[0], &pair[1]))
+                .collect();
+            layers.push(layer);
+        }
+        Ok(Self { layers })
+    }
+
+    /// The root of the tree.
+    pub(crate) fn root(&self) -> Hash {
+        self.layers[self.layers.len() - 1][0]
+    }
+
+    /// The siblings on the path from the leaf `index` to the root.
+    pub(crate) fn path(&self, index: usize) -> Vec<Hash> {
+        self.layers[..self.layers.len() - 1]
+            .iter()
+            .enumerate()
+            .map(|(i, layer)| layer[(index >> i) ^ 1])
+            .collect()
+    }
+}
+
+/// Check that `leaf` is the leaf `index` of the tree of root `root` and
+/// `depth` layers above the leaves.
+///
+/// A path of another length is rejected: otherwise an internal node could be
+/// passed off as a leaf, or a leaf of another tree as one of this tree.
+pub(crate) fn verify_path<F: PrimeField>(
+    root: &Hash,
+    depth: usize,
+    index: usize,
+    leaf: &[F],
+    path: &[Hash],
+) -> Result<bool, PCSError> {
+    if path.len() != depth || index >> depth != 0 {
+        return Ok(false);
+    }
+    let mut hash = hash_leaf(leaf)?;
+    for (i, sibling) in path.iter().enumerate() {
+        hash = if (index >> i) & 1 == 0 {
+            hash_pair(&hash, sibling)
+        } else {
+            hash_pair(sibling, &hash)
+        };
+    }
+    Ok(hash == *root)
+}
+
+/// The domain separation tag of the leaf hashes.
+const LEAF_TAG: u8 = 0;
+/// The domain separation tag of the internal node hashes.
+const NODE_TAG: u8 = 1;
+
+fn hash_leaf<F: PrimeField>(leaf: &[F]) -> Result<Hash, PCSError> {
+    let mut bytes = vec![LEAF_TAG];
+    leaf.serialize_compressed(&mut bytes)?;
+    Ok(Sha256::digest(&bytes).into())
+}
+
+fn hash_pair(left: &Hash, right: &Hash) -> Hash {
+    let mut hasher = Sha256::new();
+    hasher.update([NODE_TAG]);
+    hasher.update(left);
+    hasher.update(right);
+    hasher.finalize().into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_bls12_381::Fr;
+    use ark_std::{test_rng, UniformRand};
+
+    #[test]
+    fn test_verify_path() -> Result<(), PCSError> {
+        let mut rng = test_rng();
+        let leaves: Vec<Vec<Fr>> = (0..8)
+            .map(|_| (0..3).map(|_| Fr::rand(&mut rng)).collect())
+            .collect();
+        let tree = MerkleTree::new(&leaves)?;
+        let root = tree.root();
+        for (index, leaf) in leaves.iter().enumerate() {
+            assert!(verify_path(&root, 3, index, leaf, &tree.path(index))?);
+        }
+
+        let path = tree.path(5);
+        // wrong leaf or index
+        assert!(!verify_path(&root, 3, 5, &leaves[4], &path)?);
+        assert!(!verify_path(&root, 3, 4, &leaves[5], &path)?);
+        assert!(!verify_path(&root, 3, 5 + 8, &leaves[5], &path)?);
+        // paths of the wrong length
+        assert!(!verify_path(&root, 3, 5, &leaves[5], &path[..2])?);
+        assert!(!verify_path(&root, 2, 5, &leaves[5], &path[..2])?);
+        let mut long_path = path.clone();
+        long_path.push(root);
+        assert!(!verify_path(&root, 3, 5, &leaves[5], &long_path)?);
+
+        // leaves and internal nodes are hashed in different domains
+        assert_ne!(hash_pair(&path[0], &path[1])[..], hash_leaf::<Fr>(&[])?[..]);
+        let mut bytes = vec![];
+        leaves[0].serialize_compressed(&mut bytes)?;
+        assert_ne!(
+            hash_leaf(&leaves[0])?,
+            <[u8; 32]>::from(Sha256::digest(&bytes))
+        );
+        Ok(())
+    }
+}
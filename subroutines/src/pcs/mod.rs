@@ -4,26 +4,38 @@
 // You should have received a copy of the MIT License
 // along with the HyperPlonk library. If not, see <https://mit-license.org/>.
 
+mod basefold;
 mod errors;
+mod gemini;
+mod hyrax;
+mod ligero;
+mod merkle;
 mod multilinear_kzg;
 mod structs;
 mod univariate_kzg;
+mod utils;
+mod zeromorph;
 
 pub mod prelude;
 
 use ark_ec::pairing::Pairing;
-use ark_ff::Field;
+use ark_ff::{Field, PrimeField};
 use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
 use ark_std::rand::Rng;
 use errors::PCSError;
 use std::{borrow::Borrow, fmt::Debug, hash::Hash};
-use transcript::IOPTranscript;
+use transcript::{Encode, Transcript};
 
 /// This trait defines APIs for polynomial commitment schemes.
-/// Note that for our usage of PCS, we do not require the hiding property.
-pub trait PolynomialCommitmentScheme<E: Pairing> {
+/// Note that for our usage of PCS, we do not require the hiding property;
+/// `HidingPolynomialCommitmentScheme` extends it for when it is needed.
+/// `LigeroPCS` and `BasefoldPCS` are transparent: they are generic over the
+/// field only, and their parameters need no trusted setup.
+/// The trait is generic over the scalar field `F` only, so that `HyraxPCS`
+/// implements it over any curve group, including curves without pairings.
+pub trait PolynomialCommitmentScheme<F: PrimeField> {
     /// Prover parameters
-    type ProverParam: Clone + Sync;
+    type ProverParam: Clone + Sync + CanonicalSerialize + CanonicalDeserialize;
     /// Verifier parameters
     type VerifierParam: Clone + CanonicalSerialize + CanonicalDeserialize;
     /// Structured reference string
@@ -35,11 +47,19 @@ pub trait PolynomialCommitmentScheme<E: Pairing> {
     /// Polynomial Evaluation
     type Evaluation: Field;
     /// Commitments
-    type Commitment: Clone + CanonicalSerialize + CanonicalDeserialize + Debug + PartialEq + Eq;
+    type Commitment: Clone
+        + Send
+        + Sync
+        + CanonicalSerialize
+        + CanonicalDeserialize
+        + Debug
+        + PartialEq
+        + Eq
+        + Encode;
     /// Proofs
     type Proof: Clone + CanonicalSerialize + CanonicalDeserialize + Debug + PartialEq + Eq;
     /// Batch proofs
-    type BatchProof;
+    type BatchProof: CanonicalSerialize + CanonicalDeserialize;
 
     /// Build SRS for testing.
     ///
@@ -99,7 +119,7 @@ pub trait PolynomialCommitmentScheme<E: Pairing> {
         _polynomials: &[Self::Polynomial],
         _points: &[Self::Point],
         _evals: &[Self::Evaluation],
-        _transcript: &mut IOPTranscript<E::ScalarField>,
+        _transcript: &mut impl Transcript<F>,
     ) -> Result<Self::BatchProof, PCSError> {
         // the reason we use unimplemented!() is to enable developers to implement the
         // trait without always implementing the batching APIs.
@@ -112,7 +132,7 @@ pub trait PolynomialCommitmentScheme<E: Pairing> {
         verifier_param: &Self::VerifierParam,
         commitment: &Self::Commitment,
         point: &Self::Point,
-        value: &E::ScalarField,
+        value: &F,
         proof: &Self::Proof,
     ) -> Result<bool, PCSError>;
 
@@ -123,12 +143,63 @@ pub trait PolynomialCommitmentScheme<E: Pairing> {
         _commitments: &[Self::Commitment],
         _points: &[Self::Point],
         _batch_proof: &Self::BatchProof,
-        _transcript: &mut IOPTranscript<E::ScalarField>,
+        _transcript: &mut impl Transcript<F>,
     ) -> Result<bool, PCSError> {
         // the reason we use unimplemented!() is to enable developers to implement the
         // trait without always implementing the batching APIs.
         unimplemented!()
     }
+
+    /// The evaluations claimed by a batch proof, in the order of the
+    /// polynomials and points given to `multi_open`.
+    fn batch_evaluations(_batch_proof: &Self::BatchProof) -> &[Self::Evaluation] {
+        // the reason we use unimplemented!() is to enable developers to implement the
+        // trait without always implementing the batching APIs.
+        unimplemented!()
+    }
+}
+
+/// A polynomial commitment scheme whose commitments and openings hide the
+/// committed polynomial.
+///
+/// The polynomials are those of the underlying scheme; the blinding of a
+/// commitment is returned by `commit_with_rng` and passed back to the
+/// openings. The methods of `PolynomialCommitmentScheme` commit to and open
+/// polynomials with the default blinding, which hides nothing: they are meant
+/// for public polynomials, such as the selectors of a circuit, which are opened
+/// in the same batch as hidden ones.
+pub trait HidingPolynomialCommitmentScheme<F: PrimeField>: PolynomialCommitmentScheme<F> {
+    /// Blinding of a commitment
+    type Blinding: Clone + Debug + Default + PartialEq + Send + Sync;
+
+    /// Generate a hiding commitment for a polynomial, with a fresh blinding
+    /// sampled from `rng`, and return the blinding with it.
+    fn commit_with_rng<R: Rng>(
+        prover_param: impl Borrow<Self::ProverParam>,
+        poly: &Self::Polynomial,
+        rng: &mut R,
+    ) -> Result<(Self::Commitment, Self::Blinding), PCSError>;
+
+    /// On input a polynomial `p` committed to with `blinding`, and a point
+    /// `point`, outputs a hiding proof for the same.
+    fn open_with_blinding(
+        prover_param: impl Borrow<Self::ProverParam>,
+        polynomial: &Self::Polynomial,
+        blinding: &Self::Blinding,
+        point: &Self::Point,
+    ) -> Result<(Self::Proof, Self::Evaluation), PCSError>;
+
+    /// Input a list of multilinear extensions, the blindings of their
+    /// commitments, a same number of points, and a transcript, compute a
+    /// hiding multi-opening for all the polynomials.
+    fn multi_open_with_blindings(
+        prover_param: impl Borrow<Self::ProverParam>,
+        polynomials: &[Self::Polynomial],
+        blindings: &[Self::Blinding],
+        points: &[Self::Point],
+        evals: &[Self::Evaluation],
+        transcript: &mut impl Transcript<F>,
+    ) -> Result<Self::BatchProof, PCSError>;
 }
 
 /// API definitions for structured reference string
@@ -21,17 +21,18 @@ use crate::{
 };
 use arithmetic::{build_eq_x_r_vec, DenseMultilinearExtension, VPAuxInfo, VirtualPolynomial};
 use ark_ec::{pairing::Pairing, scalar_mul::variable_base::VariableBaseMSM, CurveGroup};
+use ark_ff::PrimeField;
 
 use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
-use ark_std::{end_timer, log2, start_timer, One, Zero};
+use ark_std::{end_timer, log2, start_timer, Zero};
 use std::{collections::BTreeMap, iter, marker::PhantomData, ops::Deref, sync::Arc};
-use transcript::IOPTranscript;
+use transcript::Transcript;
 
 #[derive(Clone, Debug, Default, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
 pub struct BatchProof<E, PCS>
 where
     E: Pairing,
-    PCS: PolynomialCommitmentScheme<E>,
+    PCS: PolynomialCommitmentScheme<E::ScalarField>,
 {
     /// A sum check proof proving tilde g's sum
     pub(crate) sum_check_proof: IOPProof<E::ScalarField>,
@@ -49,97 +50,29 @@ where
 /// 5. run sumcheck on \sum_i=1..k \tilde eq_i * \tilde g_i
 /// 6. build g'(X) = \sum_i=1..k \tilde eq_i(a2) * \tilde g_i(X) where (a2) is
 ///    the sumcheck's point 7. open g'(X) at point (a2)
-pub(crate) fn multi_open_internal<E, PCS>(
+pub(crate) fn multi_open_internal<E, PCS, T>(
     prover_param: &PCS::ProverParam,
     polynomials: &[PCS::Polynomial],
     points: &[PCS::Point],
     evals: &[PCS::Evaluation],
-    transcript: &mut IOPTranscript<E::ScalarField>,
+    transcript: &mut T,
 ) -> Result<BatchProof<E, PCS>, PCSError>
 where
     E: Pairing,
     PCS: PolynomialCommitmentScheme<
-        E,
+        E::ScalarField,
         Polynomial = Arc<DenseMultilinearExtension<E::ScalarField>>,
         Point = Vec<E::ScalarField>,
         Evaluation = E::ScalarField,
     >,
+    T: Transcript<E::ScalarField>,
 {
     let open_timer = start_timer!(|| format!("multi open {} points", points.len()));
-    for eval_point in points.iter() {
-        transcript.append_serializable_element(b"eval_point", eval_point)?;
-    }
-    for eval in evals.iter() {
-        transcript.append_field_element(b"eval", eval)?;
-    }
-
-    // TODO: sanity checks
-    let num_var = polynomials[0].num_vars;
-    let k = polynomials.len();
-    let ell = log2(k) as usize;
-
-    // challenge point t
-    let t = transcript.get_and_append_challenge_vectors("t".as_ref(), ell)?;
-
-    // eq(t, i) for i in [0..k]
-    let eq_t_i_list = build_eq_x_r_vec(t.as_ref())?;
-
-    // \tilde g_i(b) = eq(t, i) * f_i(b)
-    let timer = start_timer!(|| format!("compute tilde g for {} points", points.len()));
-    // combine the polynomials that have same opening point first to reduce the
-    // cost of sum check later.
-    let point_indices = points
-        .iter()
-        .fold(BTreeMap::<_, _>::new(), |mut indices, point| {
-            let idx = indices.len();
-            indices.entry(point).or_insert(idx);
-            indices
-        });
-    let deduped_points =
-        BTreeMap::from_iter(point_indices.iter().map(|(point, idx)| (*idx, *point)))
-            .into_values()
-            .collect::<Vec<_>>();
-    let merged_tilde_gs = polynomials
-        .iter()
-        .zip(points.iter())
-        .zip(eq_t_i_list.iter())
-        .fold(
-            iter::repeat_with(DenseMultilinearExtension::zero)
-                .map(Arc::new)
-                .take(point_indices.len())
-                .collect::<Vec<_>>(),
-            |mut merged_tilde_gs, ((poly, point), coeff)| {
-                *Arc::make_mut(&mut merged_tilde_gs[point_indices[point]]) +=
-                    (*coeff, poly.deref());
-                merged_tilde_gs
-            },
-        );
-    end_timer!(timer);
+    let batch = BatchSumCheck::new(polynomials, points, evals, transcript)?;
 
-    let timer = start_timer!(|| format!("compute tilde eq for {} points", points.len()));
-    let tilde_eqs: Vec<_> = deduped_points
-        .iter()
-        .map(|point| {
-            let eq_b_zi = build_eq_x_r_vec(point).unwrap();
-            Arc::new(DenseMultilinearExtension::from_evaluations_vec(
-                num_var, eq_b_zi,
-            ))
-        })
-        .collect();
-    end_timer!(timer);
-
-    // built the virtual polynomial for SumCheck
-    let timer = start_timer!(|| format!("sum check prove of {} variables", num_var));
-
-    let step = start_timer!(|| "add mle");
-    let mut sum_check_vp = VirtualPolynomial::new(num_var);
-    for (merged_tilde_g, tilde_eq) in merged_tilde_gs.iter().zip(tilde_eqs.into_iter()) {
-        sum_check_vp.add_mle_list([merged_tilde_g.clone(), tilde_eq], E::ScalarField::one())?;
-    }
-    end_timer!(step);
-
-    let proof = match <PolyIOP<E::ScalarField> as SumCheck<E::ScalarField>>::prove(
-        &sum_check_vp,
+    let timer = start_timer!(|| format!("sum check prove of {} variables", batch.num_var));
+    let sum_check_proof = match <PolyIOP<E::ScalarField, T> as SumCheck<E::ScalarField>>::prove(
+        &batch.poly,
         transcript,
     ) {
         Ok(p) => p,
@@ -150,106 +83,199 @@ where
             ));
         },
     };
-
     end_timer!(timer);
 
-    // a2 := sumcheck's point
-    let a2 = &proof.point[..num_var];
-
-    // build g'(X) = \sum_i=1..k \tilde eq_i(a2) * \tilde g_i(X) where (a2) is the
-    // sumcheck's point \tilde eq_i(a2) = eq(a2, point_i)
-    let step = start_timer!(|| "evaluate at a2");
-    let mut g_prime = Arc::new(DenseMultilinearExtension::zero());
-    for (merged_tilde_g, point) in merged_tilde_gs.iter().zip(deduped_points.iter()) {
-        let eq_i_a2 = eq_eval(a2, point)?;
-        *Arc::make_mut(&mut g_prime) += (eq_i_a2, merged_tilde_g.deref());
-    }
-    end_timer!(step);
+    let (g_prime, _) = batch.g_prime(points, &sum_check_proof.point)?;
 
     let step = start_timer!(|| "pcs open");
-    let (g_prime_proof, _g_prime_eval) = PCS::open(prover_param, &g_prime, a2.to_vec().as_ref())?;
-    // assert_eq!(g_prime_eval, tilde_g_eval);
-    end_timer!(step);
-
-    let step = start_timer!(|| "evaluate fi(pi)");
+    let (g_prime_proof, _g_prime_eval) = PCS::open(prover_param, &g_prime, &sum_check_proof.point)?;
     end_timer!(step);
     end_timer!(open_timer);
 
     Ok(BatchProof {
-        sum_check_proof: proof,
+        sum_check_proof,
         f_i_eval_at_point_i: evals.to_vec(),
         g_prime_proof,
     })
 }
 
+/// The sum check of a batch opening, built by steps 1 to 4 of
+/// `multi_open_internal`.
+pub(crate) struct BatchSumCheck<F: PrimeField> {
+    /// the number of variables of the polynomials
+    pub(crate) num_var: usize,
+    /// \sum_i=1..k \tilde eq_i * \tilde g_i
+    pub(crate) poly: VirtualPolynomial<F>,
+    /// eq(t, i) for i in [0..k]
+    eq_t_i_list: Vec<F>,
+    /// the \tilde g_i merged by opening point
+    merged_tilde_gs: Vec<Arc<DenseMultilinearExtension<F>>>,
+    /// the distinct opening points
+    deduped_points: Vec<Vec<F>>,
+}
+
+impl<F: PrimeField> BatchSumCheck<F> {
+    /// Steps 1 to 4 of `multi_open_internal`.
+    pub(crate) fn new<T: Transcript<F>>(
+        polynomials: &[Arc<DenseMultilinearExtension<F>>],
+        points: &[Vec<F>],
+        evals: &[F],
+        transcript: &mut T,
+    ) -> Result<Self, PCSError> {
+        for eval_point in points.iter() {
+            transcript.append_serializable_element(b"eval_point", eval_point)?;
+        }
+        for eval in evals.iter() {
+            transcript.append_field_element(b"eval", eval)?;
+        }
+
+        // TODO: sanity checks
+        let num_var = polynomials[0].num_vars;
+        let k = polynomials.len();
+        let ell = log2(k) as usize;
+
+        // challenge point t
+        let t = transcript.get_and_append_challenge_vectors("t".as_ref(), ell)?;
+
+        // eq(t, i) for i in [0..k]
+        let eq_t_i_list = build_eq_x_r_vec(t.as_ref())?;
+
+        // \tilde g_i(b) = eq(t, i) * f_i(b)
+        let timer = start_timer!(|| format!("compute tilde g for {} points", points.len()));
+        // combine the polynomials that have same opening point first to reduce the
+        // cost of sum check later.
+        let point_indices = points
+            .iter()
+            .fold(BTreeMap::<_, _>::new(), |mut indices, point| {
+                let idx = indices.len();
+                indices.entry(point).or_insert(idx);
+                indices
+            });
+        let deduped_points = BTreeMap::from_iter(
+            point_indices
+                .iter()
+                .map(|(point, idx)| (*idx, (*point).clone())),
+        )
+        .into_values()
+        .collect::<Vec<_>>();
+        let merged_tilde_gs = polynomials
+            .iter()
+            .zip(points.iter())
+            .zip(eq_t_i_list.iter())
+            .fold(
+                iter::repeat_with(DenseMultilinearExtension::zero)
+                    .map(Arc::new)
+                    .take(point_indices.len())
+                    .collect::<Vec<_>>(),
+                |mut merged_tilde_gs, ((poly, point), coeff)| {
+                    *Arc::make_mut(&mut merged_tilde_gs[point_indices[point]]) +=
+                        (*coeff, poly.deref());
+                    merged_tilde_gs
+                },
+            );
+        end_timer!(timer);
+
+        let timer = start_timer!(|| format!("compute tilde eq for {} points", points.len()));
+        let tilde_eqs: Vec<_> = deduped_points
+            .iter()
+            .map(|point| {
+                let eq_b_zi = build_eq_x_r_vec(point).unwrap();
+                Arc::new(DenseMultilinearExtension::from_evaluations_vec(
+                    num_var, eq_b_zi,
+                ))
+            })
+            .collect();
+        end_timer!(timer);
+
+        // built the virtual polynomial for SumCheck
+        let step = start_timer!(|| "add mle");
+        let mut poly = VirtualPolynomial::new(num_var);
+        for (merged_tilde_g, tilde_eq) in merged_tilde_gs.iter().zip(tilde_eqs) {
+            poly.add_mle_list([merged_tilde_g.clone(), tilde_eq], F::one())?;
+        }
+        end_timer!(step);
+
+        Ok(Self {
+            num_var,
+            poly,
+            eq_t_i_list,
+            merged_tilde_gs,
+            deduped_points,
+        })
+    }
+
+    /// Step 6 of `multi_open_internal`: returns g'(X) and the coefficient
+    /// eq(t, i) * eq(a2, point_i) of each f_i(X) in g'(X).
+    pub(crate) fn g_prime(
+        &self,
+        points: &[Vec<F>],
+        a2: &[F],
+    ) -> Result<(Arc<DenseMultilinearExtension<F>>, Vec<F>), PCSError> {
+        let a2 = &a2[..self.num_var];
+
+        // build g'(X) = \sum_i=1..k \tilde eq_i(a2) * \tilde g_i(X) where (a2) is the
+        // sumcheck's point \tilde eq_i(a2) = eq(a2, point_i)
+        let step = start_timer!(|| "evaluate at a2");
+        let mut g_prime = Arc::new(DenseMultilinearExtension::zero());
+        for (merged_tilde_g, point) in self.merged_tilde_gs.iter().zip(self.deduped_points.iter()) {
+            let eq_i_a2 = eq_eval(a2, point)?;
+            *Arc::make_mut(&mut g_prime) += (eq_i_a2, merged_tilde_g.deref());
+        }
+        let coeffs = points
+            .iter()
+            .zip(self.eq_t_i_list.iter())
+            .map(|(point, eq_t_i)| Ok(eq_eval(a2, point)? * eq_t_i))
+            .collect::<Result<Vec<_>, PCSError>>()?;
+        end_timer!(step);
+
+        Ok((g_prime, coeffs))
+    }
+}
+
 /// Steps:
 /// 1. get challenge point t from transcript
 /// 2. build g' commitment
 /// 3. ensure \sum_i eq(a2, point_i) * eq(t, <i>) * f_i_evals matches the sum
 ///    via SumCheck verification 4. verify commitment
-pub(crate) fn batch_verify_internal<E, PCS>(
+pub(crate) fn batch_verify_internal<E, PCS, T>(
     verifier_param: &PCS::VerifierParam,
     f_i_commitments: &[Commitment<E>],
     points: &[PCS::Point],
     proof: &BatchProof<E, PCS>,
-    transcript: &mut IOPTranscript<E::ScalarField>,
+    transcript: &mut T,
 ) -> Result<bool, PCSError>
 where
     E: Pairing,
     PCS: PolynomialCommitmentScheme<
-        E,
-        Polynomial = Arc<DenseMultilinearExtension<E::ScalarField>>,
+        E::ScalarField,
         Point = Vec<E::ScalarField>,
         Evaluation = E::ScalarField,
         Commitment = Commitment<E>,
     >,
+    T: Transcript<E::ScalarField>,
 {
     let open_timer = start_timer!(|| "batch verification");
-    for eval_point in points.iter() {
-        transcript.append_serializable_element(b"eval_point", eval_point)?;
-    }
-    for eval in proof.f_i_eval_at_point_i.iter() {
-        transcript.append_field_element(b"eval", eval)?;
-    }
-
-    // TODO: sanity checks
-
-    let k = f_i_commitments.len();
-    let ell = log2(k) as usize;
+    let (eq_t_list, sum) = batch_sum_check_claim(
+        f_i_commitments.len(),
+        points,
+        &proof.f_i_eval_at_point_i,
+        transcript,
+    )?;
     let num_var = proof.sum_check_proof.point.len();
 
-    // challenge point t
-    let t = transcript.get_and_append_challenge_vectors("t".as_ref(), ell)?;
-
     // sum check point (a2)
     let a2 = &proof.sum_check_proof.point[..num_var];
 
     // build g' commitment
-    let step = start_timer!(|| "build homomorphic commitment");
-    let eq_t_list = build_eq_x_r_vec(t.as_ref())?;
-
-    let mut scalars = vec![];
-    let mut bases = vec![];
-
-    for (i, point) in points.iter().enumerate() {
-        let eq_i_a2 = eq_eval(a2, point)?;
-        scalars.push(eq_i_a2 * eq_t_list[i]);
-        bases.push(f_i_commitments[i].0);
-    }
-    let g_prime_commit = E::G1::msm_unchecked(&bases, &scalars);
-    end_timer!(step);
+    let g_prime_commit = g_prime_commitment(f_i_commitments, points, &eq_t_list, a2)?;
 
     // ensure \sum_i eq(t, <i>) * f_i_evals matches the sum via SumCheck
-    let mut sum = E::ScalarField::zero();
-    for (i, &e) in eq_t_list.iter().enumerate().take(k) {
-        sum += e * proof.f_i_eval_at_point_i[i];
-    }
     let aux_info = VPAuxInfo {
         max_degree: 2,
         num_variables: num_var,
         phantom: PhantomData,
     };
-    let subclaim = match <PolyIOP<E::ScalarField> as SumCheck<E::ScalarField>>::verify(
+    let subclaim = match <PolyIOP<E::ScalarField, T> as SumCheck<E::ScalarField>>::verify(
         sum,
         &proof.sum_check_proof,
         &aux_info,
@@ -268,7 +294,7 @@ where
     // verify commitment
     let res = PCS::verify(
         verifier_param,
-        &Commitment(g_prime_commit.into_affine()),
+        &g_prime_commit,
         a2.to_vec().as_ref(),
         &tilde_g_eval,
         &proof.g_prime_proof,
@@ -278,6 +304,61 @@ where
     Ok(res)
 }
 
+/// Step 1 of `batch_verify_internal`: returns eq(t, <i>) and the sum
+/// \sum_i eq(t, <i>) * f_i_evals claimed by the sum check.
+pub(crate) fn batch_sum_check_claim<F, T>(
+    k: usize,
+    points: &[Vec<F>],
+    evals: &[F],
+    transcript: &mut T,
+) -> Result<(Vec<F>, F), PCSError>
+where
+    F: PrimeField,
+    T: Transcript<F>,
+{
+    for eval_point in points.iter() {
+        transcript.append_serializable_element(b"eval_point", eval_point)?;
+    }
+    for eval in evals.iter() {
+        transcript.append_field_element(b"eval", eval)?;
+    }
+
+    // TODO: sanity checks
+    let ell = log2(k) as usize;
+
+    // challenge point t
+    let t = transcript.get_and_append_challenge_vectors("t".as_ref(), ell)?;
+    let eq_t_list = build_eq_x_r_vec(t.as_ref())?;
+
+    let mut sum = F::zero();
+    for (e, eval) in eq_t_list.iter().zip(evals.iter()).take(k) {
+        sum += *e * eval;
+    }
+    Ok((eq_t_list, sum))
+}
+
+/// Step 2 of `batch_verify_internal`: the commitment to
+/// g'(X) = \sum_i eq(a2, point_i) * eq(t, <i>) * f_i(X).
+pub(crate) fn g_prime_commitment<E: Pairing>(
+    f_i_commitments: &[Commitment<E>],
+    points: &[Vec<E::ScalarField>],
+    eq_t_list: &[E::ScalarField],
+    a2: &[E::ScalarField],
+) -> Result<Commitment<E>, PCSError> {
+    let step = start_timer!(|| "build homomorphic commitment");
+    let mut scalars = vec![];
+    let mut bases = vec![];
+
+    for (i, point) in points.iter().enumerate() {
+        let eq_i_a2 = eq_eval(a2, point)?;
+        scalars.push(eq_i_a2 * eq_t_list[i]);
+        bases.push(f_i_commitments[i].0);
+    }
+    let g_prime_commit = E::G1::msm_unchecked(&bases, &scalars);
+    end_timer!(step);
+    Ok(Commitment(g_prime_commit.into_affine()))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -290,6 +371,7 @@ mod tests {
     use ark_ec::pairing::Pairing;
     use ark_poly::{DenseMultilinearExtension, MultilinearExtension, Polynomial};
     use ark_std::{rand::Rng, test_rng, vec::Vec, UniformRand};
+    use transcript::IOPTranscript;
 
     type Fr = <E as Pairing>::ScalarField;
 
@@ -323,7 +405,7 @@ mod tests {
         let mut transcript = IOPTranscript::new("test transcript".as_ref());
         transcript.append_field_element("init".as_ref(), &Fr::zero())?;
 
-        let batch_proof = multi_open_internal::<E, MultilinearKzgPCS<E>>(
+        let batch_proof = multi_open_internal::<E, MultilinearKzgPCS<E>, _>(
             &ml_ck,
             polys,
             &points,
@@ -334,7 +416,7 @@ mod tests {
         // good path
         let mut transcript = IOPTranscript::new("test transcript".as_ref());
         transcript.append_field_element("init".as_ref(), &Fr::zero())?;
-        assert!(batch_verify_internal::<E, MultilinearKzgPCS<E>>(
+        assert!(batch_verify_internal::<E, MultilinearKzgPCS<E>, _>(
             &ml_vk,
             &commitments,
             &points,
@@ -347,6 +429,7 @@ mod tests {
 
     #[test]
     fn test_multi_open_internal() -> Result<(), PCSError> {
+        let _trace = crate::trace_guard();
         let mut rng = test_rng();
 
         let ml_params = MultilinearUniversalParams::<E>::gen_srs_for_testing(&mut rng, 20)?;
@@ -0,0 +1,791 @@
+// Copyright (c) 2023 Espresso Systems (espressosys.com)
+// This file is part of the HyperPlonk library.
+
+// You should have received a copy of the MIT License
+// along with the HyperPlonk library. If not, see <https://mit-license.org/>.
+
+//! Hiding variant of the multilinear KZG commitment scheme.
+//!
+//! A polynomial `f` is committed to with a blinding factor `r` as
+//! `C = g^{f(t)} * (g^gamma)^r`. An opening of `f` at `z` to `v` consists of
+//! - the blinded quotients `pi_i = g^{q_i(t)} * (g^gamma)^{s_i}`, where
+//!   `f(X) - v = \sum_i (X_i - z_i) q_i(X)`;
+//! - the blinding proof `pi_gamma = g^{r - \sum_i s_i (t_i - z_i)}`;
+//!
+//! and is checked with
+//! `e(C / g^v, h) = \prod_i e(pi_i, h^{t_i - z_i}) * e(pi_gamma, h^gamma)`.
+//!
+//! The blinding factor `r` is sampled by
+//! `HidingPolynomialCommitmentScheme::commit_with_rng` and passed back to the
+//! openings, which derive the opening blinders `s_i` from `r` and `z` with a
+//! transcript. `PolynomialCommitmentScheme::commit` and `open` use `r = 0`, for
+//! public polynomials.
+//!
+//! A batch opening runs the sum check of `MultilinearKzgPCS::multi_open` in
+//! zero-knowledge mode: its mask is committed to with the same scheme and
+//! opened next to the batched polynomial. The multilinear encoding of the mask
+//! has `2 + log(num_vars)` variables, which the parameters must support.
+
+use crate::pcs::{
+    multilinear_kzg::{
+        batching::{batch_sum_check_claim, g_prime_commitment, BatchSumCheck},
+        open_internal,
+        srs::{MultilinearProverParam, MultilinearUniversalParams, MultilinearVerifierParam},
+        verify_internal, MultilinearKzgPCS,
+    },
+    prelude::Commitment,
+    HidingPolynomialCommitmentScheme, PCSError, PolynomialCommitmentScheme,
+    StructuredReferenceString,
+};
+use crate::poly_iop::{
+    prelude::{SumCheck, SumCheckMask, SumCheckMaskClaims, ZkSumCheckProof},
+    PolyIOP,
+};
+use arithmetic::VPAuxInfo;
+use ark_ec::{
+    pairing::Pairing, scalar_mul::variable_base::VariableBaseMSM, AffineRepr, CurveGroup,
+};
+use ark_ff::PrimeField;
+use ark_poly::DenseMultilinearExtension;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_std::{
+    borrow::Borrow, end_timer, format, marker::PhantomData, rand::Rng, start_timer, sync::Arc,
+    vec::Vec, UniformRand, Zero,
+};
+use transcript::{IOPTranscript, Transcript};
+
+#[derive(Clone)]
+/// Hiding KZG Polynomial Commitment Scheme on multilinear polynomials.
+pub struct HidingMultilinearKzgPCS<E: Pairing> {
+    #[doc(hidden)]
+    phantom: PhantomData<E>,
+}
+
+/// Universal Parameter
+///
+/// The hiding generators are kept next to, rather than inside, the parameters
+/// of the non-hiding scheme, so that the encoding of those is unchanged.
+#[derive(CanonicalSerialize, CanonicalDeserialize, Clone, Debug)]
+pub struct HidingMultilinearUniversalParams<E: Pairing> {
+    /// the parameters of the non-hiding scheme
+    pub params: MultilinearUniversalParams<E>,
+    /// g^gamma, the blinding base of hiding commitments
+    pub g_gamma: E::G1Affine,
+    /// h^gamma, to check the blinding of hiding openings
+    pub h_gamma: E::G2Affine,
+}
+
+/// Prover Parameters
+#[derive(CanonicalSerialize, CanonicalDeserialize, Clone, Debug)]
+pub struct HidingMultilinearProverParam<E: Pairing> {
+    /// the parameters of the non-hiding scheme
+    pub param: MultilinearProverParam<E>,
+    /// g^gamma
+    pub g_gamma: E::G1Affine,
+}
+
+/// Verifier Parameters
+#[derive(CanonicalSerialize, CanonicalDeserialize, Clone, Debug)]
+pub struct HidingMultilinearVerifierParam<E: Pairing> {
+    /// the parameters of the non-hiding scheme
+    pub param: MultilinearVerifierParam<E>,
+    /// h^gamma
+    pub h_gamma: E::G2Affine,
+}
+
+impl<E: Pairing> StructuredReferenceString<E> for HidingMultilinearUniversalParams<E> {
+    type ProverParam = HidingMultilinearProverParam<E>;
+    type VerifierParam = HidingMultilinearVerifierParam<E>;
+
+    /// Extract the prover parameters from the public parameters.
+    fn extract_prover_param(&self, supported_num_vars: usize) -> Self::ProverParam {
+        HidingMultilinearProverParam {
+            param: self.params.extract_prover_param(supported_num_vars),
+            g_gamma: self.g_gamma,
+        }
+    }
+
+    /// Extract the verifier parameters from the public parameters.
+    fn extract_verifier_param(&self, supported_num_vars: usize) -> Self::VerifierParam {
+        HidingMultilinearVerifierParam {
+            param: self.params.extract_verifier_param(supported_num_vars),
+            h_gamma: self.h_gamma,
+        }
+    }
+
+    /// Trim the universal parameters to specialize the public parameters
+    /// for multilinear polynomials to the given `supported_num_vars`.
+    fn trim(
+        &self,
+        supported_num_vars: usize,
+    ) -> Result<(Self::ProverParam, Self::VerifierParam), PCSError> {
+        let (ck, vk) = self.params.trim(supported_num_vars)?;
+        Ok((
+            HidingMultilinearProverParam {
+                param: ck,
+                g_gamma: self.g_gamma,
+            },
+            HidingMultilinearVerifierParam {
+                param: vk,
+                h_gamma: self.h_gamma,
+            },
+        ))
+    }
+
+    /// Build SRS for testing.
+    ///
+    /// WARNING: THIS FUNCTION IS FOR TESTING PURPOSE ONLY.
+    /// THE OUTPUT SRS SHOULD NOT BE USED IN PRODUCTION.
+    fn gen_srs_for_testing<R: Rng>(rng: &mut R, num_vars: usize) -> Result<Self, PCSError> {
+        let params = MultilinearUniversalParams::<E>::gen_srs_for_testing(rng, num_vars)?;
+        let gamma = E::ScalarField::rand(rng);
+        let g_gamma = (params.prover_param.g * gamma).into_affine();
+        let h_gamma = (params.prover_param.h * gamma).into_affine();
+        Ok(Self {
+            params,
+            g_gamma,
+            h_gamma,
+        })
+    }
+}
+
+#[derive(CanonicalSerialize, CanonicalDeserialize, Clone, Debug, PartialEq, Eq)]
+/// hiding proof of opening
+pub struct HidingMultilinearKzgProof<E: Pairing> {
+    /// Blinded evaluation of quotients
+    pub proofs: Vec<E::G1Affine>,
+    /// Blinding proof, paired with h^gamma
+    pub blinding_proof: E::G1Affine,
+}
+
+/// Batch proof of the hiding scheme.
+#[derive(CanonicalSerialize, CanonicalDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct HidingBatchProof<E: Pairing> {
+    /// commitment to the mask of the sum check
+    pub mask_commitment: Commitment<E>,
+    /// A zero-knowledge sum check proof proving tilde g's sum
+    pub sum_check_proof: ZkSumCheckProof<E::ScalarField>,
+    /// f_i(point_i)
+    pub f_i_eval_at_point_i: Vec<E::ScalarField>,
+    /// proof for g'(a_2)
+    pub g_prime_proof: HidingMultilinearKzgProof<E>,
+    /// proofs for the evaluations of the mask
+    pub mask_proofs: Vec<HidingMultilinearKzgProof<E>>,
+}
+
+impl<E: Pairing> PolynomialCommitmentScheme<E::ScalarField> for HidingMultilinearKzgPCS<E> {
+    // Parameters
+    type ProverParam = HidingMultilinearProverParam<E>;
+    type VerifierParam = HidingMultilinearVerifierParam<E>;
+    type SRS = HidingMultilinearUniversalParams<E>;
+    // Polynomial and its associated types
+    type Polynomial = Arc<DenseMultilinearExtension<E::ScalarField>>;
+    type Point = Vec<E::ScalarField>;
+    type Evaluation = E::ScalarField;
+    // Commitments and proofs
+    type Commitment = Commitment<E>;
+    type Proof = HidingMultilinearKzgProof<E>;
+    type BatchProof = HidingBatchProof<E>;
+
+    /// Build SRS for testing.
+    ///
+    /// WARNING: THIS FUNCTION IS FOR TESTING PURPOSE ONLY.
+    /// THE OUTPUT SRS SHOULD NOT BE USED IN PRODUCTION.
+    fn gen_srs_for_testing<R: Rng>(rng: &mut R, log_size: usize) -> Result<Self::SRS, PCSError> {
+        HidingMultilinearUniversalParams::<E>::gen_srs_for_testing(rng, log_size)
+    }
+
+    /// Trim the universal parameters to specialize the public parameters for
+    /// `supported_num_vars`.
+    fn trim(
+        srs: impl Borrow<Self::SRS>,
+        supported_degree: Option<usize>,
+        supported_num_vars: Option<usize>,
+    ) -> Result<(Self::ProverParam, Self::VerifierParam), PCSError> {
+        let srs = srs.borrow();
+        let (ck, vk) = MultilinearKzgPCS::trim(&srs.params, supported_degree, supported_num_vars)?;
+        Ok((
+            HidingMultilinearProverParam {
+                param: ck,
+                g_gamma: srs.g_gamma,
+            },
+            HidingMultilinearVerifierParam {
+                param: vk,
+                h_gamma: srs.h_gamma,
+            },
+        ))
+    }
+
+    /// Generate a commitment for a polynomial with a zero blinding factor.
+    ///
+    /// The commitment does not hide the polynomial, see
+    /// `HidingPolynomialCommitmentScheme::commit_with_rng`.
+    fn commit(
+        prover_param: impl Borrow<Self::ProverParam>,
+        poly: &Self::Polynomial,
+    ) -> Result<Self::Commitment, PCSError> {
+        hiding_commit_internal(prover_param.borrow(), poly, &E::ScalarField::zero())
+    }
+
+    /// On input a polynomial `p` committed to with a zero blinding factor and
+    /// a point `point`, outputs a proof for the same.
+    fn open(
+        prover_param: impl Borrow<Self::ProverParam>,
+        polynomial: &Self::Polynomial,
+        point: &Self::Point,
+    ) -> Result<(Self::Proof, Self::Evaluation), PCSError> {
+        hiding_open_internal(
+            prover_param.borrow(),
+            polynomial,
+            &E::ScalarField::zero(),
+            point,
+        )
+    }
+
+    /// Input a list of multilinear extensions committed to with zero blinding
+    /// factors, and a same number of points, and a transcript, compute a
+    /// multi-opening for all the polynomials.
+    fn multi_open(
+        prover_param: impl Borrow<Self::ProverParam>,
+        polynomials: &[Self::Polynomial],
+        points: &[Self::Point],
+        evals: &[Self::Evaluation],
+        transcript: &mut impl Transcript<E::ScalarField>,
+    ) -> Result<Self::BatchProof, PCSError> {
+        hiding_multi_open_internal(
+            prover_param.borrow(),
+            polynomials,
+            &vec![E::ScalarField::zero(); polynomials.len()],
+            points,
+            evals,
+            transcript,
+        )
+    }
+
+    /// Verifies that `value` is the evaluation at `x` of the polynomial
+    /// committed inside `comm`.
+    ///
+    /// This function takes
+    /// - num_var + 2 number of pairing product.
+    /// - num_var number of MSM
+    fn verify(
+        verifier_param: &Self::VerifierParam,
+        commitment: &Self::Commitment,
+        point: &Self::Point,
+        value: &E::ScalarField,
+        proof: &Self::Proof,
+    ) -> Result<bool, PCSError> {
+        if proof.proofs.len() != point.len() {
+            return Ok(false);
+        }
+        verify_internal(
+            &verifier_param.param,
+            commitment,
+            point,
+            value,
+            &proof.proofs,
+            Some((proof.blinding_proof, verifier_param.h_gamma)),
+        )
+    }
+
+    /// Verifies that `value_i` is the evaluation at `x_i` of the polynomial
+    /// `poly_i` committed inside `comm`.
+    fn batch_verify(
+        verifier_param: &Self::VerifierParam,
+        commitments: &[Self::Commitment],
+        points: &[Self::Point],
+        batch_proof: &Self::BatchProof,
+        transcript: &mut impl Transcript<E::ScalarField>,
+    ) -> Result<bool, PCSError> {
+        hiding_batch_verify_internal(verifier_param, commitments, points, batch_proof, transcript)
+    }
+
+    fn batch_evaluations(batch_proof: &Self::BatchProof) -> &[Self::Evaluation] {
+        &batch_proof.f_i_eval_at_point_i
+    }
+}
+
+impl<E: Pairing> HidingPolynomialCommitmentScheme<E::ScalarField> for HidingMultilinearKzgPCS<E> {
+    type Blinding = E::ScalarField;
+
+    /// Generate a hiding commitment for a polynomial.
+    ///
+    /// This function takes `2^num_vars + 1` number of scalar multiplications
+    /// over G1.
+    fn commit_with_rng<R: Rng>(
+        prover_param: impl Borrow<Self::ProverParam>,
+        poly: &Self::Polynomial,
+        rng: &mut R,
+    ) -> Result<(Self::Commitment, Self::Blinding), PCSError> {
+        let blinding = E::ScalarField::rand(rng);
+        let commitment = hiding_commit_internal(prover_param.borrow(), poly, &blinding)?;
+        Ok((commitment, blinding))
+    }
+
+    /// On input a polynomial `p` committed to with `blinding` and a point
+    /// `point`, outputs a hiding proof for the same.
+    ///
+    /// On top of the non-hiding opening, this function takes `2 num_var + 1`
+    /// scalar multiplications and about `2^num_var` additions over G1.
+    fn open_with_blinding(
+        prover_param: impl Borrow<Self::ProverParam>,
+        polynomial: &Self::Polynomial,
+        blinding: &Self::Blinding,
+        point: &Self::Point,
+    ) -> Result<(Self::Proof, Self::Evaluation), PCSError> {
+        hiding_open_internal(prover_param.borrow(), polynomial, blinding, point)
+    }
+
+    /// Input a list of multilinear extensions, the blinding factors of their
+    /// commitments, a same number of points, and a transcript, compute a
+    /// hiding multi-opening for all the polynomials.
+    ///
+    /// The polynomials are batched as in `MultilinearKzgPCS::multi_open` with
+    /// a zero-knowledge sum check, and the batched polynomial is opened with
+    /// the same combination of the blinding factors. The mask of the sum check
+    /// and its blinding factor are derived from the blinding factors of the
+    /// polynomials.
+    fn multi_open_with_blindings(
+        prover_param: impl Borrow<Self::ProverParam>,
+        polynomials: &[Self::Polynomial],
+        blindings: &[Self::Blinding],
+        points: &[Self::Point],
+        evals: &[Self::Evaluation],
+        transcript: &mut impl Transcript<E::ScalarField>,
+    ) -> Result<Self::BatchProof, PCSError> {
+        hiding_multi_open_internal(
+            prover_param.borrow(),
+            polynomials,
+            blindings,
+            points,
+            evals,
+            transcript,
+        )
+    }
+}
+
+/// Commit to `poly` with the blinding factor `blinding`.
+fn hiding_commit_internal<E: Pairing>(
+    prover_param: &HidingMultilinearProverParam<E>,
+    poly: &Arc<DenseMultilinearExtension<E::ScalarField>>,
+    blinding: &E::ScalarField,
+) -> Result<Commitment<E>, PCSError> {
+    let commit_timer = start_timer!(|| "hiding commit");
+    let commitment = MultilinearKzgPCS::commit(&prover_param.param, poly)?;
+    let res = Commitment((commitment.0 + prover_param.g_gamma * blinding).into_affine());
+    end_timer!(commit_timer);
+    Ok(res)
+}
+
+/// Steps of `MultilinearKzgPCS::multi_open`, with a zero-knowledge sum check
+/// and hiding openings.
+fn hiding_multi_open_internal<E: Pairing, T: Transcript<E::ScalarField>>(
+    prover_param: &HidingMultilinearProverParam<E>,
+    polynomials: &[Arc<DenseMultilinearExtension<E::ScalarField>>],
+    blindings: &[E::ScalarField],
+    points: &[Vec<E::ScalarField>],
+    evals: &[E::ScalarField],
+    transcript: &mut T,
+) -> Result<HidingBatchProof<E>, PCSError> {
+    let open_timer = start_timer!(|| format!("hiding multi open {} points", points.len()));
+    if blindings.len() != polynomials.len() {
+        return Err(PCSError::InvalidParameters(format!(
+            "{} blinding factors for {} polynomials",
+            blindings.len(),
+            polynomials.len()
+        )));
+    }
+    let batch = BatchSumCheck::new(polynomials, points, evals, transcript)?;
+
+    let (mask, mask_blinding) = batch_mask(blindings, points, batch.num_var)?;
+    let mask_poly = mask.to_mle();
+    let mask_commitment = hiding_commit_internal(prover_param, &mask_poly, &mask_blinding)?;
+
+    let sum_check_proof = match <PolyIOP<E::ScalarField, T> as SumCheck<E::ScalarField>>::prove_zk(
+        &batch.poly,
+        &mask,
+        &mask_commitment,
+        transcript,
+    ) {
+        Ok(p) => p,
+        Err(_e) => {
+            // cannot wrap IOPError with PCSError due to cyclic dependency
+            return Err(PCSError::InvalidProver(
+                "Sumcheck in batch proving Failed".to_string(),
+            ));
+        },
+    };
+    let a2 = &sum_check_proof.sum_check_proof.point;
+
+    let (g_prime, coeffs) = batch.g_prime(points, a2)?;
+    let g_prime_blinding = blindings
+        .iter()
+        .zip(coeffs.iter())
+        .map(|(b, c)| *b * c)
+        .sum();
+    let (g_prime_proof, _) = hiding_open_internal(prover_param, &g_prime, &g_prime_blinding, a2)?;
+
+    // open the mask at the points of the claims of the sum check
+    let mask_claims = SumCheckMaskClaims::new(2, a2, sum_check_proof.mask_evaluations.clone());
+    let mask_proofs = mask_claims
+        .points
+        .iter()
+        .map(|point| Ok(hiding_open_internal(prover_param, &mask_poly, &mask_blinding, point)?.0))
+        .collect::<Result<Vec<_>, PCSError>>()?;
+    end_timer!(open_timer);
+
+    Ok(HidingBatchProof {
+        mask_commitment,
+        sum_check_proof,
+        f_i_eval_at_point_i: evals.to_vec(),
+        g_prime_proof,
+        mask_proofs,
+    })
+}
+
+/// Steps of `MultilinearKzgPCS::batch_verify`, with a zero-knowledge sum check:
+/// the claims on the mask are checked against its commitment.
+fn hiding_batch_verify_internal<E: Pairing, T: Transcript<E::ScalarField>>(
+    verifier_param: &HidingMultilinearVerifierParam<E>,
+    commitments: &[Commitment<E>],
+    points: &[Vec<E::ScalarField>],
+    batch_proof: &HidingBatchProof<E>,
+    transcript: &mut T,
+) -> Result<bool, PCSError> {
+    let open_timer = start_timer!(|| "hiding batch verification");
+    let (eq_t_list, sum) = batch_sum_check_claim(
+        commitments.len(),
+        points,
+        &batch_proof.f_i_eval_at_point_i,
+        transcript,
+    )?;
+    let num_var = batch_proof.sum_check_proof.mask_evaluations.len();
+    if points.iter().any(|p| p.len() != num_var) || batch_proof.mask_proofs.len() != num_var {
+        return Ok(false);
+    }
+
+    let aux_info = VPAuxInfo {
+        max_degree: 2,
+        num_variables: num_var,
+        phantom: PhantomData,
+    };
+    let (subclaim, mask_claims) =
+        match <PolyIOP<E::ScalarField, T> as SumCheck<E::ScalarField>>::verify_zk(
+            sum,
+            &batch_proof.sum_check_proof,
+            &aux_info,
+            &batch_proof.mask_commitment,
+            transcript,
+        ) {
+            Ok(p) => p,
+            Err(_e) => {
+                // cannot wrap IOPError with PCSError due to cyclic dependency
+                return Err(PCSError::InvalidProver(
+                    "Sumcheck in batch verification failed".to_string(),
+                ));
+            },
+        };
+
+    let g_prime_commit = g_prime_commitment(commitments, points, &eq_t_list, &subclaim.point)?;
+    if !HidingMultilinearKzgPCS::verify(
+        verifier_param,
+        &g_prime_commit,
+        &subclaim.point,
+        &subclaim.expected_evaluation,
+        &batch_proof.g_prime_proof,
+    )? {
+        return Ok(false);
+    }
+    for ((point, eval), proof) in mask_claims
+        .points
+        .iter()
+        .zip(mask_claims.evaluations.iter())
+        .zip(batch_proof.mask_proofs.iter())
+    {
+        if !HidingMultilinearKzgPCS::verify(
+            verifier_param,
+            &batch_proof.mask_commitment,
+            point,
+            eval,
+            proof,
+        )? {
+            return Ok(false);
+        }
+    }
+    end_timer!(open_timer);
+    Ok(true)
+}
+
+/// The mask of the sum check of a batch opening of polynomials with blinding
+/// factors `blindings` at `points`, and the blinding factor of its multilinear
+/// encoding.
+///
+/// The mask must stay hidden from the verifier, so it is derived from the
+/// blinding factors of the polynomials, and it must differ between batch
+/// openings, so it is derived from the points as well.
+fn batch_mask<F: PrimeField>(
+    blindings: &[F],
+    points: &[Vec<F>],
+    num_var: usize,
+) -> Result<(SumCheckMask<F>, F), PCSError> {
+    let mut transcript = IOPTranscript::<F>::new(b"hiding multilinear KZG batch mask");
+    for blinding in blindings.iter() {
+        transcript.append_field_element(b"blinding", blinding)?;
+    }
+    for point in points.iter() {
+        transcript.append_serializable_element(b"point", point)?;
+    }
+    // the batch sum check has degree 2
+    let mask = SumCheckMask {
+        univariates: (0..num_var)
+            .map(|_| transcript.get_and_append_challenge_vectors(b"mask", 3))
+            .collect::<Result<Vec<_>, _>>()?,
+    };
+    let blinding = transcript.get_and_append_challenge(b"mask blinding")?;
+    Ok((mask, blinding))
+}
+
+/// Open `polynomial`, committed to with the blinding factor `blinding`, at
+/// `point` with blinded quotients, see the module documentation.
+fn hiding_open_internal<E: Pairing>(
+    prover_param: &HidingMultilinearProverParam<E>,
+    polynomial: &DenseMultilinearExtension<E::ScalarField>,
+    blinding: &E::ScalarField,
+    point: &[E::ScalarField],
+) -> Result<(HidingMultilinearKzgProof<E>, E::ScalarField), PCSError> {
+    let open_timer = start_timer!(|| "hiding open");
+    let (proof, eval) = open_internal(&prover_param.param, polynomial, point)?;
+
+    let blinders = opening_blinders(blinding, point)?;
+    let g_gamma = prover_param.g_gamma.into_group();
+    let proofs = E::G1::normalize_batch(
+        &proof
+            .proofs
+            .iter()
+            .zip(blinders.iter())
+            .map(|(&pi, s)| g_gamma * s + pi)
+            .collect::<Vec<_>>(),
+    );
+
+    // g^{r - \sum_i s_i (t_i - z_i)}
+    let mut bases = g_tau(&prover_param.param, point.len());
+    bases.push(prover_param.param.g);
+    let mut scalars: Vec<_> = blinders.iter().map(|s| -*s).collect();
+    scalars.push(
+        blinders
+            .iter()
+            .zip(point.iter())
+            .fold(*blinding, |acc, (s, z)| acc + *s * z),
+    );
+    let blinding_proof = E::G1::msm_unchecked(&bases, &scalars).into_affine();
+    end_timer!(open_timer);
+
+    Ok((
+        HidingMultilinearKzgProof {
+            proofs,
+            blinding_proof,
+        },
+        eval,
+    ))
+}
+
+/// The blinders `s_i` of an opening of a polynomial with blinding factor
+/// `blinding` at `point`.
+fn opening_blinders<F: PrimeField>(blinding: &F, point: &[F]) -> Result<Vec<F>, PCSError> {
+    let mut transcript = IOPTranscript::<F>::new(b"hiding multilinear KZG opening");
+    transcript.append_field_element(b"blinding", blinding)?;
+    transcript.append_serializable_element(b"point", &point.to_vec())?;
+    Ok(transcript.get_and_append_challenge_vectors(b"blinders", point.len())?)
+}
+
+/// `g^{t_i}` for the `num_vars` variables of a polynomial.
+///
+/// The SRS level that starts at `t_i` holds `g^{eq((t_i, ...), b)}`, whose
+/// entries with `b_i = 1` sum to `g^{t_i}`.
+fn g_tau<E: Pairing>(
+    prover_param: &MultilinearProverParam<E>,
+    num_vars: usize,
+) -> Vec<E::G1Affine> {
+    let ignored = prover_param.num_vars - num_vars;
+    E::G1::normalize_batch(
+        &prover_param.powers_of_g[ignored..ignored + num_vars]
+            .iter()
+            .map(|level| {
+                level
+                    .evals
+                    .iter()
+                    .skip(1)
+                    .step_by(2)
+                    .fold(E::G1::zero(), |acc, x| acc + x)
+            })
+            .collect::<Vec<_>>(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_bls12_381::Bls12_381;
+    use ark_poly::{MultilinearExtension, Polynomial};
+    use ark_std::{test_rng, One, UniformRand};
+
+    type E = Bls12_381;
+    type Fr = <E as Pairing>::ScalarField;
+    type Pcs = HidingMultilinearKzgPCS<E>;
+
+    #[test]
+    fn test_hiding_commit() -> Result<(), PCSError> {
+        let _trace = crate::trace_guard();
+        let mut rng = test_rng();
+        let params = Pcs::gen_srs_for_testing(&mut rng, 10)?;
+
+        for nv in [1, 8] {
+            let (ck, vk) = Pcs::trim(&params, None, Some(10))?;
+            let poly = Arc::new(DenseMultilinearExtension::rand(nv, &mut rng));
+            let (com1, blinding1) = Pcs::commit_with_rng(&ck, &poly, &mut rng)?;
+            let (com2, blinding2) = Pcs::commit_with_rng(&ck, &poly, &mut rng)?;
+            // the same polynomial is committed to differently
+            assert_ne!(com1, com2);
+            assert_ne!(com1, Pcs::commit(&ck, &poly)?);
+            // a zero blinding factor does not hide the polynomial
+            assert_eq!(
+                Pcs::commit(&ck, &poly)?,
+                MultilinearKzgPCS::commit(&ck.param, &poly)?
+            );
+
+            let point: Vec<_> = (0..nv).map(|_| Fr::rand(&mut rng)).collect();
+            let (proof1, value1) = Pcs::open_with_blinding(&ck, &poly, &blinding1, &point)?;
+            let (proof2, value2) = Pcs::open_with_blinding(&ck, &poly, &blinding2, &point)?;
+            assert_eq!(value1, poly.evaluate(&point));
+            assert_eq!(value1, value2);
+            assert_ne!(proof1, proof2);
+            assert!(Pcs::verify(&vk, &com1, &point, &value1, &proof1)?);
+            assert!(Pcs::verify(&vk, &com2, &point, &value2, &proof2)?);
+
+            // wrong value, and an opening of another commitment
+            assert!(!Pcs::verify(
+                &vk,
+                &com1,
+                &point,
+                &(value1 + Fr::one()),
+                &proof1
+            )?);
+            assert!(!Pcs::verify(&vk, &com1, &point, &value2, &proof2)?);
+
+            // public polynomials are opened without blinding
+            let com = Pcs::commit(&ck, &poly)?;
+            let (proof, value) = Pcs::open(&ck, &poly, &point)?;
+            assert!(Pcs::verify(&vk, &com, &point, &value, &proof)?);
+            assert!(!Pcs::verify(&vk, &com1, &point, &value, &proof)?);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_hiding_srs_encoding() -> Result<(), PCSError> {
+        let _trace = crate::trace_guard();
+        let mut rng = test_rng();
+        let srs = Pcs::gen_srs_for_testing(&mut rng, 4)?;
+        let mut bytes = Vec::new();
+        srs.serialize_compressed(&mut bytes)?;
+        let mut params_bytes = Vec::new();
+        srs.params.serialize_compressed(&mut params_bytes)?;
+
+        // the non-hiding parameters are encoded as before, so that they can
+        // be read back on their own
+        assert!(bytes.starts_with(&params_bytes));
+        let params = MultilinearUniversalParams::<E>::deserialize_compressed(&params_bytes[..])?;
+        let (ck, _) = MultilinearKzgPCS::trim(&params, None, Some(4))?;
+        assert_eq!(
+            ck.powers_of_g[0].evals,
+            srs.extract_prover_param(4).param.powers_of_g[0].evals
+        );
+
+        let srs2 = HidingMultilinearUniversalParams::<E>::deserialize_compressed(&bytes[..])?;
+        assert_eq!(srs2.g_gamma, srs.g_gamma);
+        assert_eq!(srs2.h_gamma, srs.h_gamma);
+        Ok(())
+    }
+
+    #[test]
+    fn test_hiding_multi_open() -> Result<(), PCSError> {
+        let _trace = crate::trace_guard();
+        let mut rng = test_rng();
+        let params = Pcs::gen_srs_for_testing(&mut rng, 8)?;
+        let (ck, vk) = Pcs::trim(&params, None, Some(8))?;
+
+        let nv = 5;
+        let poly = Arc::new(DenseMultilinearExtension::rand(nv, &mut rng));
+        let mut polys = vec![poly.clone(), poly];
+        polys.extend((0..3).map(|_| Arc::new(DenseMultilinearExtension::rand(nv, &mut rng))));
+        let mut points: Vec<Vec<Fr>> = (0..4)
+            .map(|_| (0..nv).map(|_| Fr::rand(&mut rng)).collect())
+            .collect();
+        // two of the polynomials share a point
+        points.push(points[0].clone());
+        let evals: Vec<_> = polys
+            .iter()
+            .zip(points.iter())
+            .map(|(p, z)| p.evaluate(z))
+            .collect();
+        // the last polynomial is public
+        let (mut commitments, mut blindings): (Vec<_>, Vec<_>) = polys[..4]
+            .iter()
+            .map(|p| Pcs::commit_with_rng(&ck, p, &mut rng))
+            .collect::<Result<Vec<_>, _>>()?
+            .into_iter()
+            .unzip();
+        commitments.push(Pcs::commit(&ck, &polys[4])?);
+        blindings.push(Fr::zero());
+        assert_ne!(commitments[0], commitments[1]);
+
+        let mut transcript = IOPTranscript::new(b"test transcript");
+        transcript.append_field_element(b"init", &Fr::zero())?;
+        let batch_proof = Pcs::multi_open_with_blindings(
+            &ck,
+            &polys,
+            &blindings,
+            &points,
+            &evals,
+            &mut transcript,
+        )?;
+
+        let mut transcript = IOPTranscript::new(b"test transcript");
+        transcript.append_field_element(b"init", &Fr::zero())?;
+        assert!(Pcs::batch_verify(
+            &vk,
+            &commitments,
+            &points,
+            &batch_proof,
+            &mut transcript
+        )?);
+
+        // the sum check is masked
+        assert_eq!(batch_proof.mask_proofs.len(), nv);
+        let mut bad_proof = batch_proof.clone();
+        bad_proof.sum_check_proof.mask_evaluations[0] += Fr::one();
+        let mut transcript = IOPTranscript::new(b"test transcript");
+        transcript.append_field_element(b"init", &Fr::zero())?;
+        assert!(!Pcs::batch_verify(
+            &vk,
+            &commitments,
+            &points,
+            &bad_proof,
+            &mut transcript
+        )?);
+
+        // swap the commitments to the same polynomial
+        let mut other_commitments = commitments.clone();
+        other_commitments.swap(0, 1);
+        let mut transcript = IOPTranscript::new(b"test transcript");
+        transcript.append_field_element(b"init", &Fr::zero())?;
+        assert!(!Pcs::batch_verify(
+            &vk,
+            &other_commitments,
+            &points,
+            &batch_proof,
+            &mut transcript
+        )?);
+        Ok(())
+    }
+}
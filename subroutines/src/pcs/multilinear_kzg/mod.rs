@@ -7,6 +7,7 @@
 //! Main module for multilinear KZG commitment scheme
 
 pub(crate) mod batching;
+pub(crate) mod hiding;
 pub(crate) mod srs;
 pub(crate) mod util;
 
@@ -29,7 +30,7 @@ use ark_std::{
 use std::ops::Mul;
 // use batching::{batch_verify_internal, multi_open_internal};
 use srs::{MultilinearProverParam, MultilinearUniversalParams, MultilinearVerifierParam};
-use transcript::IOPTranscript;
+use transcript::Transcript;
 
 use self::batching::{batch_verify_internal, multi_open_internal};
 
@@ -47,7 +48,7 @@ pub struct MultilinearKzgProof<E: Pairing> {
     pub proofs: Vec<E::G1Affine>,
 }
 
-impl<E: Pairing> PolynomialCommitmentScheme<E> for MultilinearKzgPCS<E> {
+impl<E: Pairing> PolynomialCommitmentScheme<E::ScalarField> for MultilinearKzgPCS<E> {
     // Parameters
     type ProverParam = MultilinearProverParam<E>;
     type VerifierParam = MultilinearVerifierParam<E>;
@@ -150,7 +151,7 @@ impl<E: Pairing> PolynomialCommitmentScheme<E> for MultilinearKzgPCS<E> {
         polynomials: &[Self::Polynomial],
         points: &[Self::Point],
         evals: &[Self::Evaluation],
-        transcript: &mut IOPTranscript<E::ScalarField>,
+        transcript: &mut impl Transcript<E::ScalarField>,
     ) -> Result<BatchProof<E, Self>, PCSError> {
         multi_open_internal(
             prover_param.borrow(),
@@ -174,7 +175,14 @@ impl<E: Pairing> PolynomialCommitmentScheme<E> for MultilinearKzgPCS<E> {
         value: &E::ScalarField,
         proof: &Self::Proof,
     ) -> Result<bool, PCSError> {
-        verify_internal(verifier_param, commitment, point, value, proof)
+        verify_internal(
+            verifier_param,
+            commitment,
+            point,
+            value,
+            &proof.proofs,
+            None,
+        )
     }
 
     /// Verifies that `value_i` is the evaluation at `x_i` of the polynomial
@@ -184,10 +192,14 @@ impl<E: Pairing> PolynomialCommitmentScheme<E> for MultilinearKzgPCS<E> {
         commitments: &[Self::Commitment],
         points: &[Self::Point],
         batch_proof: &Self::BatchProof,
-        transcript: &mut IOPTranscript<E::ScalarField>,
+        transcript: &mut impl Transcript<E::ScalarField>,
     ) -> Result<bool, PCSError> {
         batch_verify_internal(verifier_param, commitments, points, batch_proof, transcript)
     }
+
+    fn batch_evaluations(batch_proof: &Self::BatchProof) -> &[Self::Evaluation] {
+        &batch_proof.f_i_eval_at_point_i
+    }
 }
 
 /// On input a polynomial `p` and a point `point`, outputs a proof for the
@@ -198,7 +210,7 @@ impl<E: Pairing> PolynomialCommitmentScheme<E> for MultilinearKzgPCS<E> {
 /// G1:
 /// - it proceeds with `num_var` number of rounds,
 /// - at round i, we compute an MSM for `2^{num_var - i}` number of G1 elements.
-fn open_internal<E: Pairing>(
+pub(crate) fn open_internal<E: Pairing>(
     prover_param: &MultilinearProverParam<E>,
     polynomial: &DenseMultilinearExtension<E::ScalarField>,
     point: &[E::ScalarField],
@@ -266,15 +278,20 @@ fn open_internal<E: Pairing>(
 /// Verifies that `value` is the evaluation at `x` of the polynomial
 /// committed inside `comm`.
 ///
+/// `quotients` are the commitments to the quotients of the opening. For a
+/// hiding opening, `blinding` holds the blinding proof and `h^gamma`, whose
+/// pairing is added to the product.
+///
 /// This function takes
 /// - num_var number of pairing product.
 /// - num_var number of MSM
-fn verify_internal<E: Pairing>(
+pub(crate) fn verify_internal<E: Pairing>(
     verifier_param: &MultilinearVerifierParam<E>,
     commitment: &Commitment<E>,
     point: &[E::ScalarField],
     value: &E::ScalarField,
-    proof: &MultilinearKzgProof<E>,
+    quotients: &[E::G1Affine],
+    blinding: Option<(E::G1Affine, E::G2Affine)>,
 ) -> Result<bool, PCSError> {
     let verify_timer = start_timer!(|| "verify");
     let num_var = point.len();
@@ -300,8 +317,7 @@ fn verify_internal<E: Pairing>(
 
     let pairing_product_timer = start_timer!(|| "pairing product");
 
-    let mut pairings: Vec<_> = proof
-        .proofs
+    let mut pairings: Vec<_> = quotients
         .iter()
         .map(|&x| E::G1Prepared::from(x))
         .zip(h_vec.into_iter().take(num_var).map(E::G2Prepared::from))
@@ -313,6 +329,12 @@ fn verify_internal<E: Pairing>(
         ),
         E::G2Prepared::from(verifier_param.h),
     ));
+    if let Some((blinding_proof, h_gamma)) = blinding {
+        pairings.push((
+            E::G1Prepared::from(blinding_proof),
+            E::G2Prepared::from(h_gamma),
+        ));
+    }
 
     let ps = pairings.iter().map(|(p, _)| p.clone());
     let hs = pairings.iter().map(|(_, h)| h.clone());
@@ -361,6 +383,7 @@ mod tests {
 
     #[test]
     fn test_single_commit() -> Result<(), PCSError> {
+        let _trace = crate::trace_guard();
         let mut rng = test_rng();
 
         let params = MultilinearKzgPCS::<E>::gen_srs_for_testing(&mut rng, 10)?;
@@ -152,11 +152,7 @@ impl<E: Pairing> StructuredReferenceString<E> for MultilinearUniversalParams<E>
             eq_arr.push_front(remove_dummy_variable(&base, i)?);
             if i != 0 {
                 let mul = eq.pop_back().unwrap().evaluations;
-                base = base
-                    .into_iter()
-                    .zip(mul.into_iter())
-                    .map(|(a, b)| a * b)
-                    .collect();
+                base = base.into_iter().zip(mul).map(|(a, b)| a * b).collect();
             }
         }
 
@@ -201,6 +197,7 @@ impl<E: Pairing> StructuredReferenceString<E> for MultilinearUniversalParams<E>
         let h_batch_mul_preprocessing = BatchMulPreprocessing::<E::G2>::new(h, num_vars);
         let h_mask = h_batch_mul_preprocessing.batch_mul(&t);
         end_timer!(vp_generation_timer);
+
         end_timer!(total_timer);
         Ok(Self {
             prover_param: pp,
@@ -232,6 +229,7 @@ mod tests {
 
     #[test]
     fn test_srs_gen() -> Result<(), PCSError> {
+        let _trace = crate::trace_guard();
         let mut rng = test_rng();
         for nv in 4..10 {
             let _ = MultilinearUniversalParams::<E>::gen_srs_for_testing(&mut rng, nv)?;
@@ -6,9 +6,21 @@
 
 //! Prelude
 pub use crate::pcs::{
+    basefold::{
+        BasefoldBatchProof, BasefoldCommitment, BasefoldPCS, BasefoldParams, BasefoldProof,
+        BasefoldQuery,
+    },
     errors::PCSError,
+    gemini::{GeminiPCS, GeminiProof},
+    hyrax::{HyraxBatchProof, HyraxCommitment, HyraxPCS, HyraxParams, HyraxProof},
+    ligero::{LigeroBatchProof, LigeroCommitment, LigeroPCS, LigeroParams, LigeroProof},
     multilinear_kzg::{
         batching::BatchProof,
+        hiding::{
+            HidingBatchProof, HidingMultilinearKzgPCS, HidingMultilinearKzgProof,
+            HidingMultilinearProverParam, HidingMultilinearUniversalParams,
+            HidingMultilinearVerifierParam,
+        },
         srs::{MultilinearProverParam, MultilinearUniversalParams, MultilinearVerifierParam},
         MultilinearKzgPCS, MultilinearKzgProof,
     },
@@ -17,5 +29,9 @@ pub use crate::pcs::{
         srs::{UnivariateProverParam, UnivariateUniversalParams, UnivariateVerifierParam},
         UnivariateKzgBatchProof, UnivariateKzgPCS, UnivariateKzgProof,
     },
-    PolynomialCommitmentScheme, StructuredReferenceString,
+    zeromorph::{
+        srs::{ZeromorphProverParam, ZeromorphUniversalParams, ZeromorphVerifierParam},
+        ZeromorphPCS, ZeromorphProof,
+    },
+    HidingPolynomialCommitmentScheme, PolynomialCommitmentScheme, StructuredReferenceString,
 };
@@ -7,6 +7,7 @@
 use ark_ec::pairing::Pairing;
 use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
 use derivative::Derivative;
+use transcript::{encode_point, Encode, Encoder};
 
 #[derive(Derivative, CanonicalSerialize, CanonicalDeserialize)]
 #[derivative(
@@ -23,3 +24,9 @@ pub struct Commitment<E: Pairing>(
     /// the actual commitment is an affine point.
     pub E::G1Affine,
 );
+
+impl<E: Pairing> Encode for Commitment<E> {
+    fn encode<En: Encoder>(&self, encoder: &mut En) {
+        encode_point(&self.0, encoder);
+    }
+}
@@ -21,6 +21,7 @@ use ark_std::{
 };
 use srs::{UnivariateProverParam, UnivariateUniversalParams, UnivariateVerifierParam};
 use std::ops::Mul;
+use transcript::{encode_point, Encode, Encoder};
 
 pub(crate) mod srs;
 
@@ -36,10 +37,17 @@ pub struct UnivariateKzgProof<E: Pairing> {
     /// Evaluation of quotients
     pub proof: E::G1Affine,
 }
+
+impl<E: Pairing> Encode for UnivariateKzgProof<E> {
+    fn encode<En: Encoder>(&self, encoder: &mut En) {
+        encode_point(&self.proof, encoder);
+    }
+}
+
 /// batch proof
 pub type UnivariateKzgBatchProof<E> = Vec<UnivariateKzgProof<E>>;
 
-impl<E: Pairing> PolynomialCommitmentScheme<E> for UnivariateKzgPCS<E> {
+impl<E: Pairing> PolynomialCommitmentScheme<E::ScalarField> for UnivariateKzgPCS<E> {
     // Parameters
     type ProverParam = UnivariateProverParam<E::G1Affine>;
     type VerifierParam = UnivariateVerifierParam<E>;
@@ -247,11 +255,13 @@ mod tests {
 
     #[test]
     fn end_to_end_test() {
+        let _trace = crate::trace_guard();
         end_to_end_test_template::<Bls12_381>().expect("test failed for bls12-381");
     }
 
     #[test]
     fn linear_polynomial_test() {
+        let _trace = crate::trace_guard();
         linear_polynomial_test_template::<Bls12_381>().expect("test failed for bls12-381");
     }
 }
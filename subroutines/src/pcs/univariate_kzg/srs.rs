@@ -8,7 +8,6 @@
 
 use crate::pcs::{PCSError, StructuredReferenceString};
 use ark_ec::{pairing::Pairing, scalar_mul::BatchMulPreprocessing, AffineRepr, CurveGroup};
-use ark_ff::PrimeField;
 use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
 use ark_std::{end_timer, rand::Rng, start_timer, vec, vec::Vec, One, UniformRand};
 use derivative::Derivative;
@@ -118,7 +117,7 @@ impl<E: Pairing> StructuredReferenceString<E> for UnivariateUniversalParams<E> {
             cur *= &beta;
         }
 
-        let g_batch_mul_preprocessing = BatchMulPreprocessing::<E::G1>::new(g, max_degree+1);
+        let g_batch_mul_preprocessing = BatchMulPreprocessing::<E::G1>::new(g, max_degree + 1);
         let powers_of_g = g_batch_mul_preprocessing.batch_mul(&powers_of_beta);
 
         let h = h.into_affine();
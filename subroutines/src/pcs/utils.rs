@@ -0,0 +1,44 @@
+// Copyright (c) 2023 Espresso Systems (espressosys.com)
+// This file is part of the HyperPlonk library.
+
+// You should have received a copy of the MIT License
+// along with the HyperPlonk library. If not, see <https://mit-license.org/>.
+
+//! Helpers shared by the schemes that lay the evaluations of a polynomial out
+//! as a matrix, i.e. Ligero and Hyrax.
+
+use crate::pcs::PCSError;
+use arithmetic::build_eq_x_r_vec;
+use ark_ff::PrimeField;
+use ark_std::vec::Vec;
+
+/// The numbers of row and column variables of the matrix of a polynomial of
+/// `num_vars` variables; the column variables are the low ones.
+pub(crate) fn matrix_shape(num_vars: usize) -> (usize, usize) {
+    (num_vars / 2, num_vars - num_vars / 2)
+}
+
+/// `eq(r, x)` for all `x`, which is `[1]` for an empty `r`.
+pub(crate) fn eq_vec<F: PrimeField>(r: &[F]) -> Result<Vec<F>, PCSError> {
+    if r.is_empty() {
+        return Ok(vec![F::one()]);
+    }
+    Ok(build_eq_x_r_vec(r)?)
+}
+
+/// \sum_r coeffs[r] * rows[r]
+pub(crate) fn combine_rows<F: PrimeField>(rows: &[&[F]], coeffs: &[F]) -> Vec<F> {
+    let mut res = vec![F::zero(); rows[0].len()];
+    for (row, coeff) in rows.iter().zip(coeffs.iter()) {
+        for (r, x) in res.iter_mut().zip(row.iter()) {
+            *r += *coeff * x;
+        }
+    }
+    res
+}
+
+pub(crate) fn inner_product<F: PrimeField>(a: &[F], b: &[F]) -> F {
+    a.iter()
+        .zip(b.iter())
+        .fold(F::zero(), |acc, (x, y)| acc + *x * y)
+}
@@ -0,0 +1,714 @@
+// Copyright (c) 2023 Espresso Systems (espressosys.com)
+// This file is part of the HyperPlonk library.
+
+// You should have received a copy of the MIT License
+// along with the HyperPlonk library. If not, see <https://mit-license.org/>.
+
+//! Main module for the Zeromorph commitment scheme, which commits to
+//! multilinear polynomials with univariate KZG.
+//!
+//! A multilinear polynomial `f` of `n` variables is committed to as the
+//! univariate polynomial `U_n(f)(X) = \sum_i f(<i>) X^i`. An evaluation
+//! `f(u) = v` holds if and only if there are quotients `q_k` of `k` variables
+//! with `f - v = \sum_k (X_k - u_k) q_k`, which maps to the univariate
+//! identity
+//!   U_n(f)(X) - v Phi_n(X) = \sum_k (X^{2^k} Phi_{n-k-1}(X^{2^{k+1}})
+//!                            - u_k Phi_{n-k}(X^{2^k})) U_k(q_k)(X)
+//! where `Phi_m(X) = \sum_{i < 2^m} X^i`. The prover commits to the
+//! quotients, proves that `U_k(q_k)` has degree less than `2^k` with a batched
+//! degree check, and opens the identity at a random `x` with a single KZG
+//! proof.
+//!
+//! The degree check commits to `q(X) = \sum_k y^k X^{N - 2^k} U_k(q_k)(X)`,
+//! where `N = 2^n` for the `n` variables of the parameters, and to `X^{D + 1 -
+//! N} q(X)`, where `D` is the degree of the whole SRS: a quotient of degree at
+//! least `2^k` would need a power beyond `[\tau^D]_1`, which no one knows. The
+//! verifier checks the shift against `[\tau^{D + 1 - N}]_2`, so the prover
+//! only keeps the first and the last `N` powers of the SRS.
+
+pub(crate) mod srs;
+
+use crate::{
+    pcs::{
+        multilinear_kzg::batching::{batch_verify_internal, multi_open_internal},
+        prelude::Commitment,
+        PCSError, PolynomialCommitmentScheme,
+    },
+    BatchProof,
+};
+use ark_ec::{
+    pairing::Pairing, scalar_mul::variable_base::VariableBaseMSM, AffineRepr, CurveGroup,
+};
+use ark_ff::PrimeField;
+use ark_poly::DenseMultilinearExtension;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_std::{
+    borrow::Borrow, end_timer, format, marker::PhantomData, rand::Rng, start_timer,
+    string::ToString, sync::Arc, vec, vec::Vec, One, Zero,
+};
+use srs::{ZeromorphProverParam, ZeromorphUniversalParams, ZeromorphVerifierParam};
+use transcript::{IOPTranscript, Transcript};
+
+#[derive(Clone)]
+/// Zeromorph Polynomial Commitment Scheme on multilinear polynomials.
+pub struct ZeromorphPCS<E: Pairing> {
+    #[doc(hidden)]
+    phantom: PhantomData<E>,
+}
+
+#[derive(CanonicalSerialize, CanonicalDeserialize, Clone, Debug, PartialEq, Eq)]
+/// proof of opening
+pub struct ZeromorphProof<E: Pairing> {
+    /// Commitments to the quotients `U_k(q_k)`
+    pub quotients: Vec<Commitment<E>>,
+    /// Commitment to the shifted quotients `q` of the degree check
+    pub degree_quotient: Commitment<E>,
+    /// Commitment to `X^{D + 1 - N} q`
+    pub shifted_degree_quotient: Commitment<E>,
+    /// KZG proof that the combined identity vanishes at `x`
+    pub opening: E::G1Affine,
+}
+
+impl<E: Pairing> PolynomialCommitmentScheme<E::ScalarField> for ZeromorphPCS<E> {
+    // Parameters
+    type ProverParam = ZeromorphProverParam<E>;
+    type VerifierParam = ZeromorphVerifierParam<E>;
+    type SRS = ZeromorphUniversalParams<E>;
+    // Polynomial and its associated types
+    type Polynomial = Arc<DenseMultilinearExtension<E::ScalarField>>;
+    type Point = Vec<E::ScalarField>;
+    type Evaluation = E::ScalarField;
+    // Commitments and proofs
+    type Commitment = Commitment<E>;
+    type Proof = ZeromorphProof<E>;
+    type BatchProof = BatchProof<E, Self>;
+
+    /// Build SRS for testing.
+    ///
+    /// - For multilinear polynomials, `supported_size` is the number of
+    ///   variables, and the SRS holds `2^supported_size` powers.
+    ///
+    /// WARNING: THIS FUNCTION IS FOR TESTING PURPOSE ONLY.
+    /// THE OUTPUT SRS SHOULD NOT BE USED IN PRODUCTION.
+    fn gen_srs_for_testing<R: Rng>(
+        rng: &mut R,
+        supported_size: usize,
+    ) -> Result<Self::SRS, PCSError> {
+        ZeromorphUniversalParams::<E>::gen_srs_for_testing(rng, (1 << supported_size) - 1)
+    }
+
+    /// Trim the universal parameters to specialize the public parameters.
+    /// Input `supported_num_vars` for multilinear.
+    /// `supported_degree` must be None or an error is returned.
+    fn trim(
+        srs: impl Borrow<Self::SRS>,
+        supported_degree: Option<usize>,
+        supported_num_vars: Option<usize>,
+    ) -> Result<(Self::ProverParam, Self::VerifierParam), PCSError> {
+        if supported_degree.is_some() {
+            return Err(PCSError::InvalidParameters(
+                "multilinear should not receive a degree param".to_string(),
+            ));
+        }
+        let supported_num_vars = match supported_num_vars {
+            Some(p) => p,
+            None => {
+                return Err(PCSError::InvalidParameters(
+                    "multilinear should receive a num_var param".to_string(),
+                ))
+            },
+        };
+        srs::trim(srs.borrow(), supported_num_vars)
+    }
+
+    /// Generate a commitment for a polynomial.
+    ///
+    /// This function takes `2^num_vars` number of scalar multiplications over
+    /// G1.
+    fn commit(
+        prover_param: impl Borrow<Self::ProverParam>,
+        poly: &Self::Polynomial,
+    ) -> Result<Self::Commitment, PCSError> {
+        let prover_param = prover_param.borrow();
+        let commit_timer = start_timer!(|| "commit");
+        if prover_param.num_vars < poly.num_vars {
+            return Err(PCSError::InvalidParameters(format!(
+                "MlE length ({}) exceeds param limit ({})",
+                poly.num_vars, prover_param.num_vars
+            )));
+        }
+        let commitment = commit_coeffs::<E>(&prover_param.powers_of_g, &poly.evaluations);
+
+        end_timer!(commit_timer);
+        Ok(Commitment(commitment))
+    }
+
+    /// On input a polynomial `p` and a point `point`, outputs a proof for the
+    /// same.
+    ///
+    /// This function takes about `2^{num_var + 1} + 3N` number of scalar
+    /// multiplications over G1, where `N = 2^num_vars` for the parameters:
+    /// the commitment of `p` that the challenges are bound to and those of
+    /// the quotients, then those of the degree check and of the KZG proof.
+    fn open(
+        prover_param: impl Borrow<Self::ProverParam>,
+        polynomial: &Self::Polynomial,
+        point: &Self::Point,
+    ) -> Result<(Self::Proof, Self::Evaluation), PCSError> {
+        open_internal(prover_param.borrow(), polynomial, point)
+    }
+
+    /// Input a list of multilinear extensions, and a same number of points, and
+    /// a transcript, compute a multi-opening for all the polynomials.
+    fn multi_open(
+        prover_param: impl Borrow<Self::ProverParam>,
+        polynomials: &[Self::Polynomial],
+        points: &[Self::Point],
+        evals: &[Self::Evaluation],
+        transcript: &mut impl Transcript<E::ScalarField>,
+    ) -> Result<BatchProof<E, Self>, PCSError> {
+        multi_open_internal(
+            prover_param.borrow(),
+            polynomials,
+            points,
+            evals,
+            transcript,
+        )
+    }
+
+    /// Verifies that `value` is the evaluation at `x` of the polynomial
+    /// committed inside `comm`.
+    ///
+    /// This function takes
+    /// - 3 pairings
+    /// - an MSM of `num_var + 5` elements
+    fn verify(
+        verifier_param: &Self::VerifierParam,
+        commitment: &Self::Commitment,
+        point: &Self::Point,
+        value: &E::ScalarField,
+        proof: &Self::Proof,
+    ) -> Result<bool, PCSError> {
+        verify_internal(verifier_param, commitment, point, value, proof)
+    }
+
+    /// Verifies that `value_i` is the evaluation at `x_i` of the polynomial
+    /// `poly_i` committed inside `comm`.
+    fn batch_verify(
+        verifier_param: &Self::VerifierParam,
+        commitments: &[Self::Commitment],
+        points: &[Self::Point],
+        batch_proof: &Self::BatchProof,
+        transcript: &mut impl Transcript<E::ScalarField>,
+    ) -> Result<bool, PCSError> {
+        batch_verify_internal(verifier_param, commitments, points, batch_proof, transcript)
+    }
+
+    fn batch_evaluations(batch_proof: &Self::BatchProof) -> &[Self::Evaluation] {
+        &batch_proof.f_i_eval_at_point_i
+    }
+}
+
+/// Open `polynomial` at `point`:
+/// 1. compute the quotients `q_k` and commit to them
+/// 2. get challenge y, and commit to the degree check
+///    q(X) = \sum_k y^k X^{N - 2^k} U_k(q_k)(X)
+///    and to `X^{D + 1 - N} q(X)`, where `D` is the degree of the SRS
+/// 3. get challenges x and z, and commit to the KZG proof that
+///    q(X) - \sum_k y^k x^{N - 2^k} U_k(q_k)(X) + z * Z_x(X)
+///    vanishes at x, where `Z_x` is the Zeromorph identity with the shifts
+///    evaluated at x
+fn open_internal<E: Pairing>(
+    prover_param: &ZeromorphProverParam<E>,
+    polynomial: &DenseMultilinearExtension<E::ScalarField>,
+    point: &[E::ScalarField],
+) -> Result<(ZeromorphProof<E>, E::ScalarField), PCSError> {
+    let open_timer = start_timer!(|| format!("open mle with {} variable", polynomial.num_vars));
+
+    if polynomial.num_vars > prover_param.num_vars {
+        return Err(PCSError::InvalidParameters(format!(
+            "Polynomial num_vars {} exceed the limit {}",
+            polynomial.num_vars, prover_param.num_vars
+        )));
+    }
+    if polynomial.num_vars != point.len() {
+        return Err(PCSError::InvalidParameters(format!(
+            "Polynomial num_vars {} does not match point len {}",
+            polynomial.num_vars,
+            point.len()
+        )));
+    }
+
+    let powers_of_g = &prover_param.powers_of_g;
+    let nv = polynomial.num_vars;
+    let n = powers_of_g.len();
+
+    // q_k = f_{k+1}(X_0, ..., X_{k-1}, 1) - f_{k+1}(X_0, ..., X_{k-1}, 0)
+    // f_k = f_{k+1}(X_0, ..., X_{k-1}, u_k)
+    let step = start_timer!(|| "compute quotients");
+    let mut f = polynomial.evaluations.clone();
+    let mut quotients = vec![vec![]; nv];
+    for k in (0..nv).rev() {
+        let (low, high) = f.split_at(1 << k);
+        let q: Vec<_> = low.iter().zip(high.iter()).map(|(l, h)| *h - l).collect();
+        f = low
+            .iter()
+            .zip(q.iter())
+            .map(|(l, q)| *l + point[k] * q)
+            .collect();
+        quotients[k] = q;
+    }
+    let eval = f[0];
+    end_timer!(step);
+
+    let step = start_timer!(|| "commit quotients");
+    let commitment = Commitment(commit_coeffs::<E>(powers_of_g, &polynomial.evaluations));
+    let quotient_comms: Vec<_> = quotients
+        .iter()
+        .map(|q| Commitment(commit_coeffs::<E>(powers_of_g, q)))
+        .collect();
+    end_timer!(step);
+
+    let mut transcript = init_transcript::<E>(&commitment, point, &eval)?;
+    transcript.append_serializable_element(b"quotients", &quotient_comms)?;
+    let y = transcript.get_and_append_challenge(b"y")?;
+
+    let step = start_timer!(|| "commit degree check");
+    let mut degree_quotient = vec![E::ScalarField::zero(); n];
+    let mut y_pow = E::ScalarField::one();
+    for q in quotients.iter() {
+        let offset = n - q.len();
+        for (d, c) in degree_quotient[offset..].iter_mut().zip(q.iter()) {
+            *d += y_pow * c;
+        }
+        y_pow *= y;
+    }
+    let degree_quotient_comm = Commitment(commit_coeffs::<E>(powers_of_g, &degree_quotient));
+    let shifted_degree_quotient_comm = Commitment(commit_coeffs::<E>(
+        &prover_param.shifted_powers_of_g,
+        &degree_quotient,
+    ));
+    end_timer!(step);
+
+    transcript.append_serializable_element(b"degree quotient", &degree_quotient_comm)?;
+    transcript
+        .append_serializable_element(b"shifted degree quotient", &shifted_degree_quotient_comm)?;
+    let x = transcript.get_and_append_challenge(b"x")?;
+    let z = transcript.get_and_append_challenge(b"z")?;
+
+    let step = start_timer!(|| "compute kzg proof");
+    let (quotient_scalars, eval_scalar) = identity_scalars(point, n, x, y, z);
+    let mut h = degree_quotient;
+    for (q, s) in quotients.iter().zip(quotient_scalars.iter()) {
+        for (h, c) in h.iter_mut().zip(q.iter()) {
+            *h -= *s * c;
+        }
+    }
+    for (h, c) in h.iter_mut().zip(polynomial.evaluations.iter()) {
+        *h += z * c;
+    }
+    h[0] -= eval_scalar * eval;
+    // h(X) / (X - x), where h(x) = 0
+    let mut opening = vec![E::ScalarField::zero(); n - 1];
+    let mut carry = E::ScalarField::zero();
+    for i in (1..n).rev() {
+        carry = h[i] + carry * x;
+        opening[i - 1] = carry;
+    }
+    let opening_comm = commit_coeffs::<E>(powers_of_g, &opening);
+    end_timer!(step);
+
+    end_timer!(open_timer);
+    Ok((
+        ZeromorphProof {
+            quotients: quotient_comms,
+            degree_quotient: degree_quotient_comm,
+            shifted_degree_quotient: shifted_degree_quotient_comm,
+            opening: opening_comm,
+        },
+        eval,
+    ))
+}
+
+/// Verifies that `value` is the evaluation at `point` of the polynomial
+/// committed inside `commitment`, by checking the KZG proof at x of the
+/// commitment
+///   C = [q] - \sum_k (y^k x^{N - 2^k} + z c_k) [q_k] + z [f] - z v Phi_n(x)
+///   [1]
+/// and the degree check e([q], [tau^{D + 1 - N}]_2) = e([X^{D + 1 - N} q],
+/// [1]_2), batched by a challenge r.
+fn verify_internal<E: Pairing>(
+    verifier_param: &ZeromorphVerifierParam<E>,
+    commitment: &Commitment<E>,
+    point: &[E::ScalarField],
+    value: &E::ScalarField,
+    proof: &ZeromorphProof<E>,
+) -> Result<bool, PCSError> {
+    let verify_timer = start_timer!(|| "verify");
+
+    if point.len() > verifier_param.num_vars {
+        return Err(PCSError::InvalidParameters(format!(
+            "point length ({}) exceeds param limit ({})",
+            point.len(),
+            verifier_param.num_vars
+        )));
+    }
+    if proof.quotients.len() != point.len() {
+        return Err(PCSError::InvalidProof(format!(
+            "{} quotients for a point of length {}",
+            proof.quotients.len(),
+            point.len()
+        )));
+    }
+
+    let mut transcript = init_transcript::<E>(commitment, point, value)?;
+    transcript.append_serializable_element(b"quotients", &proof.quotients)?;
+    let y = transcript.get_and_append_challenge(b"y")?;
+    transcript.append_serializable_element(b"degree quotient", &proof.degree_quotient)?;
+    transcript
+        .append_serializable_element(b"shifted degree quotient", &proof.shifted_degree_quotient)?;
+    let x = transcript.get_and_append_challenge(b"x")?;
+    let z = transcript.get_and_append_challenge(b"z")?;
+    transcript.append_serializable_element(b"opening", &Commitment::<E>(proof.opening))?;
+    let r = transcript.get_and_append_challenge(b"r")?;
+
+    let (quotient_scalars, eval_scalar) =
+        identity_scalars(point, 1 << verifier_param.num_vars, x, y, z);
+
+    // C + x * [pi] + r * [X^{D + 1 - N} q], which is tau * [pi] + r * tau^{D
+    // + 1 - N} * [q] for a valid proof
+    let vk = &verifier_param.univariate;
+    let mut bases = vec![proof.degree_quotient.0];
+    bases.extend(proof.quotients.iter().map(|q| q.0));
+    bases.extend_from_slice(&[
+        commitment.0,
+        vk.g,
+        proof.opening,
+        proof.shifted_degree_quotient.0,
+    ]);
+    let mut scalars = vec![E::ScalarField::one()];
+    scalars.extend(quotient_scalars.iter().map(|s| -*s));
+    scalars.extend_from_slice(&[z, -eval_scalar * value, x, r]);
+    let lhs = E::G1::msm_unchecked(&bases, &scalars).into_affine();
+
+    let res = E::multi_pairing(
+        [
+            lhs,
+            (-proof.opening.into_group()).into_affine(),
+            (proof.degree_quotient.0 * -r).into_affine(),
+        ],
+        [vk.h, vk.beta_h, verifier_param.shift_h],
+    )
+    .0
+    .is_one();
+
+    end_timer!(verify_timer, || format!("Result: {}", res));
+    Ok(res)
+}
+
+/// The transcript of an opening, bound to the statement.
+fn init_transcript<E: Pairing>(
+    commitment: &Commitment<E>,
+    point: &[E::ScalarField],
+    value: &E::ScalarField,
+) -> Result<IOPTranscript<E::ScalarField>, PCSError> {
+    let mut transcript = IOPTranscript::new(b"zeromorph");
+    transcript.append_serializable_element(b"commitment", commitment)?;
+    transcript.append_serializable_element(b"point", &point.to_vec())?;
+    transcript.append_field_element(b"value", value)?;
+    Ok(transcript)
+}
+
+/// The scalars of the combined identity opened at `x`:
+/// - the coefficient `y^k x^{N - 2^k} + z c_k` of each `U_k(q_k)`, where
+///   c_k = x^{2^k} Phi_{n-k-1}(x^{2^{k+1}}) - u_k Phi_{n-k}(x^{2^k})
+/// - the coefficient `z Phi_n(x)` of the evaluation
+///
+/// where `N` is the number of powers of the parameters and `n` is the length
+/// of `point`.
+fn identity_scalars<F: PrimeField>(point: &[F], n_powers: usize, x: F, y: F, z: F) -> (Vec<F>, F) {
+    let nv = point.len();
+
+    // x^{2^k} for k in [0..=n]
+    let mut x_pows = vec![x];
+    for k in 0..nv {
+        x_pows.push(x_pows[k].square());
+    }
+    // Phi_{n-k}(x^{2^k}) = \prod_{i=k}^{n-1} (1 + x^{2^i}) for k in [0..=n]
+    let mut phis = vec![F::one(); nv + 1];
+    for k in (0..nv).rev() {
+        phis[k] = phis[k + 1] * (F::one() + x_pows[k]);
+    }
+
+    let mut y_pow = F::one();
+    let mut scalars = Vec::with_capacity(nv);
+    for k in 0..nv {
+        let c = x_pows[k] * phis[k + 1] - point[k] * phis[k];
+        scalars.push(y_pow * x.pow([(n_powers - (1 << k)) as u64]) + z * c);
+        y_pow *= y;
+    }
+    (scalars, z * phis[0])
+}
+
+/// Commit to the univariate polynomial of coefficients `coeffs`.
+fn commit_coeffs<E: Pairing>(
+    powers_of_g: &[E::G1Affine],
+    coeffs: &[E::ScalarField],
+) -> E::G1Affine {
+    E::G1::msm_unchecked(&powers_of_g[..coeffs.len()], coeffs).into_affine()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_bls12_381::Bls12_381;
+    use ark_ff::Field;
+    use ark_poly::{MultilinearExtension, Polynomial};
+    use ark_std::{test_rng, UniformRand};
+
+    type E = Bls12_381;
+    type Fr = <E as Pairing>::ScalarField;
+
+    fn test_single_helper<R: Rng>(
+        params: &ZeromorphUniversalParams<E>,
+        poly: &Arc<DenseMultilinearExtension<Fr>>,
+        supported_num_vars: usize,
+        rng: &mut R,
+    ) -> Result<(), PCSError> {
+        let nv = poly.num_vars();
+        let (ck, vk) = ZeromorphPCS::trim(params, None, Some(supported_num_vars))?;
+        let point: Vec<_> = (0..nv).map(|_| Fr::rand(rng)).collect();
+        let com = ZeromorphPCS::commit(&ck, poly)?;
+        let (proof, value) = ZeromorphPCS::open(&ck, poly, &point)?;
+        assert_eq!(value, poly.evaluate(&point));
+
+        assert!(ZeromorphPCS::verify(&vk, &com, &point, &value, &proof)?);
+
+        let bad_value = Fr::rand(rng);
+        assert!(!ZeromorphPCS::verify(
+            &vk, &com, &point, &bad_value, &proof
+        )?);
+
+        let mut bad_point = point.clone();
+        bad_point[0] = Fr::rand(rng);
+        assert!(!ZeromorphPCS::verify(
+            &vk, &com, &bad_point, &value, &proof
+        )?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_single_commit() -> Result<(), PCSError> {
+        let _trace = crate::trace_guard();
+        let mut rng = test_rng();
+
+        let params = ZeromorphPCS::<E>::gen_srs_for_testing(&mut rng, 10)?;
+
+        // normal polynomials
+        let poly1 = Arc::new(DenseMultilinearExtension::rand(8, &mut rng));
+        test_single_helper(&params, &poly1, 8, &mut rng)?;
+
+        // polynomials with less variables than the parameters
+        test_single_helper(&params, &poly1, 10, &mut rng)?;
+
+        // single-variate polynomials
+        let poly2 = Arc::new(DenseMultilinearExtension::rand(1, &mut rng));
+        test_single_helper(&params, &poly2, 4, &mut rng)?;
+
+        // too many variables for the SRS
+        assert!(ZeromorphPCS::trim(&params, None, Some(11)).is_err());
+
+        Ok(())
+    }
+
+    /// Solve the square system `m * sol = rhs` by Gaussian elimination.
+    fn solve(mut m: Vec<Vec<Fr>>, mut rhs: Vec<Fr>) -> Vec<Fr> {
+        let n = rhs.len();
+        for col in 0..n {
+            let pivot = (col..n).find(|&r| !m[r][col].is_zero()).unwrap();
+            m.swap(col, pivot);
+            rhs.swap(col, pivot);
+            let inv = m[col][col].inverse().unwrap();
+            let pivot_row = m[col].clone();
+            let pivot_rhs = rhs[col];
+            for row in (0..n).filter(|&row| row != col) {
+                let factor = m[row][col] * inv;
+                for (a, p) in m[row].iter_mut().zip(pivot_row.iter()) {
+                    *a -= factor * p;
+                }
+                rhs[row] -= factor * pivot_rhs;
+            }
+        }
+        (0..n).map(|i| rhs[i] / m[i][i]).collect()
+    }
+
+    #[test]
+    fn test_over_degree_quotient() -> Result<(), PCSError> {
+        let _trace = crate::trace_guard();
+        let mut rng = test_rng();
+
+        // a ceremony SRS much larger than the polynomial needs
+        let params = ZeromorphPCS::<E>::gen_srs_for_testing(&mut rng, 6)?;
+        let (ck, vk) = ZeromorphPCS::trim(&params, None, Some(2))?;
+        assert_eq!(ck.powers_of_g.len(), 4);
+        assert_eq!(ck.shifted_powers_of_g, params.powers_of_g[60..]);
+        assert_eq!(vk.shift_h, params.powers_of_h[60]);
+
+        let poly = Arc::new(DenseMultilinearExtension::<Fr>::rand(2, &mut rng));
+        let point = vec![Fr::rand(&mut rng), Fr::rand(&mut rng)];
+        let value = poly.evaluate(&point) + Fr::one();
+        let commitment = ZeromorphPCS::commit(&ck, &poly)?;
+
+        // Without the degree bounds, the identity
+        //   U_2(f) - v Phi_2 = c_0 U_0(q_0) + c_1 U_1(q_1)
+        // holds for a wrong `v` with `q_0` of degree 1 and `q_1` of degree 2.
+        let c_0 = [
+            -point[0],
+            Fr::one() - point[0],
+            -point[0],
+            Fr::one() - point[0],
+        ];
+        let c_1 = [-point[1], Fr::zero(), Fr::one() - point[1]];
+        let mut m = vec![vec![Fr::zero(); 5]; 5];
+        for j in 0..2 {
+            for (i, c) in c_0.iter().enumerate() {
+                m[i + j][j] = *c;
+            }
+        }
+        for j in 0..3 {
+            for (i, c) in c_1.iter().enumerate() {
+                m[i + j][2 + j] = *c;
+            }
+        }
+        let mut rhs: Vec<_> = poly.evaluations.iter().map(|f| *f - value).collect();
+        rhs.push(Fr::zero());
+        let sol = solve(m, rhs);
+        let quotients = [sol[..2].to_vec(), sol[2..].to_vec()];
+
+        // Forge the rest of the proof with the untrimmed SRS, shifting the
+        // degree check by `X^shift` instead of `X^{D + 1 - N}`.
+        let forge = |shift: usize| -> Result<ZeromorphProof<E>, PCSError> {
+            let powers_of_g = &params.powers_of_g;
+            let quotient_comms: Vec<_> = quotients
+                .iter()
+                .map(|q| Commitment(commit_coeffs::<E>(powers_of_g, q)))
+                .collect();
+            let mut transcript = init_transcript::<E>(&commitment, &point, &value)?;
+            transcript.append_serializable_element(b"quotients", &quotient_comms)?;
+            let y = transcript.get_and_append_challenge(b"y")?;
+            // of degree N = 4, one more than the bound
+            let mut degree_quotient = vec![Fr::zero(); 5];
+            let mut y_pow = Fr::one();
+            for (k, q) in quotients.iter().enumerate() {
+                let offset = 4 - (1 << k);
+                for (d, c) in degree_quotient[offset..].iter_mut().zip(q.iter()) {
+                    *d += y_pow * c;
+                }
+                y_pow *= y;
+            }
+            let degree_quotient_comm =
+                Commitment(commit_coeffs::<E>(powers_of_g, &degree_quotient));
+            let shifted_degree_quotient_comm =
+                Commitment(commit_coeffs::<E>(&powers_of_g[shift..], &degree_quotient));
+            transcript.append_serializable_element(b"degree quotient", &degree_quotient_comm)?;
+            transcript.append_serializable_element(
+                b"shifted degree quotient",
+                &shifted_degree_quotient_comm,
+            )?;
+            let x = transcript.get_and_append_challenge(b"x")?;
+            let z = transcript.get_and_append_challenge(b"z")?;
+
+            let (quotient_scalars, eval_scalar) = identity_scalars(&point, 4, x, y, z);
+            let mut h = degree_quotient;
+            for (q, s) in quotients.iter().zip(quotient_scalars.iter()) {
+                for (h, c) in h.iter_mut().zip(q.iter()) {
+                    *h -= *s * c;
+                }
+            }
+            for (h, c) in h.iter_mut().zip(poly.evaluations.iter()) {
+                *h += z * c;
+            }
+            h[0] -= eval_scalar * value;
+            let mut opening = vec![Fr::zero(); h.len() - 1];
+            let mut carry = Fr::zero();
+            for i in (1..h.len()).rev() {
+                carry = h[i] + carry * x;
+                opening[i - 1] = carry;
+            }
+            assert_eq!(h[0] + carry * x, Fr::zero());
+            Ok(ZeromorphProof {
+                quotients: quotient_comms,
+                degree_quotient: degree_quotient_comm,
+                shifted_degree_quotient: shifted_degree_quotient_comm,
+                opening: commit_coeffs::<E>(powers_of_g, &opening),
+            })
+        };
+
+        // shifted by one power less, as if the SRS stopped at `[tau^62]_1`,
+        // the check passes
+        let proof = forge(59)?;
+        let smaller_vk = ZeromorphVerifierParam {
+            shift_h: params.powers_of_h[59],
+            ..vk
+        };
+        assert!(verify_internal(
+            &smaller_vk,
+            &commitment,
+            &point,
+            &value,
+            &proof
+        )?);
+        // but not against the shift of the SRS
+        assert!(!ZeromorphPCS::verify(
+            &vk,
+            &commitment,
+            &point,
+            &value,
+            &proof
+        )?);
+        // and shifting by `X^{D + 1 - N}` runs past the last power of the SRS
+        assert!(std::panic::catch_unwind(|| forge(60)).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_multi_open() -> Result<(), PCSError> {
+        let _trace = crate::trace_guard();
+        let mut rng = test_rng();
+
+        let params = ZeromorphPCS::<E>::gen_srs_for_testing(&mut rng, 10)?;
+        let (ck, vk) = ZeromorphPCS::trim(&params, None, Some(10))?;
+
+        let polys: Vec<_> = (0..5)
+            .map(|_| Arc::new(DenseMultilinearExtension::rand(8, &mut rng)))
+            .collect();
+        let points: Vec<Vec<Fr>> = (0..5)
+            .map(|_| (0..8).map(|_| Fr::rand(&mut rng)).collect())
+            .collect();
+        let evals: Vec<_> = polys
+            .iter()
+            .zip(points.iter())
+            .map(|(f, p)| f.evaluate(p))
+            .collect();
+        let commitments = polys
+            .iter()
+            .map(|poly| ZeromorphPCS::commit(&ck, poly))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut transcript = IOPTranscript::new(b"test transcript");
+        let batch_proof = ZeromorphPCS::multi_open(&ck, &polys, &points, &evals, &mut transcript)?;
+
+        let mut transcript = IOPTranscript::new(b"test transcript");
+        assert!(ZeromorphPCS::batch_verify(
+            &vk,
+            &commitments,
+            &points,
+            &batch_proof,
+            &mut transcript
+        )?);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,138 @@
+// Copyright (c) 2023 Espresso Systems (espressosys.com)
+// This file is part of the HyperPlonk library.
+
+// You should have received a copy of the MIT License
+// along with the HyperPlonk library. If not, see <https://mit-license.org/>.
+
+//! Implementing the Zeromorph parameters on top of a univariate SRS
+
+use crate::pcs::{prelude::UnivariateVerifierParam, PCSError};
+use ark_ec::{pairing::Pairing, scalar_mul::BatchMulPreprocessing};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_std::{end_timer, format, rand::Rng, start_timer, vec, vec::Vec, One, UniformRand};
+use derivative::Derivative;
+
+/// `ZeromorphUniversalParams` are the powers of `tau` in both groups, e.g.
+/// the output of a powers-of-tau ceremony.
+#[derive(Debug, Clone, Eq, PartialEq, CanonicalSerialize, CanonicalDeserialize, Default)]
+pub struct ZeromorphUniversalParams<E: Pairing> {
+    /// Group elements of the form `{ \tau^i G }`, where `i` ranges from 0 to
+    /// the degree `D` of the SRS.
+    pub powers_of_g: Vec<E::G1Affine>,
+    /// Group elements of the form `{ \tau^i H }`, where `i` ranges from 0 to
+    /// `D`.
+    pub powers_of_h: Vec<E::G2Affine>,
+}
+
+impl<E: Pairing> ZeromorphUniversalParams<E> {
+    /// Returns the degree `D` of the SRS.
+    pub fn max_degree(&self) -> usize {
+        self.powers_of_g.len() - 1
+    }
+
+    /// Build SRS for testing.
+    /// WARNING: THIS FUNCTION IS FOR TESTING PURPOSE ONLY.
+    /// THE OUTPUT SRS SHOULD NOT BE USED IN PRODUCTION.
+    pub fn gen_srs_for_testing<R: Rng>(rng: &mut R, max_degree: usize) -> Result<Self, PCSError> {
+        let setup_time = start_timer!(|| format!("Zeromorph::Setup with degree {}", max_degree));
+        let tau = E::ScalarField::rand(rng);
+        let g = E::G1::rand(rng);
+        let h = E::G2::rand(rng);
+
+        let mut powers_of_tau = vec![E::ScalarField::one()];
+        let mut cur = tau;
+        for _ in 0..max_degree {
+            powers_of_tau.push(cur);
+            cur *= &tau;
+        }
+
+        let powers_of_g =
+            BatchMulPreprocessing::<E::G1>::new(g, max_degree + 1).batch_mul(&powers_of_tau);
+        let powers_of_h =
+            BatchMulPreprocessing::<E::G2>::new(h, max_degree + 1).batch_mul(&powers_of_tau);
+
+        end_timer!(setup_time);
+        Ok(Self {
+            powers_of_g,
+            powers_of_h,
+        })
+    }
+}
+
+/// `ZeromorphProverParam` is used to commit to and open multilinear
+/// polynomials of up to `num_vars` variables.
+#[derive(Derivative, CanonicalSerialize, CanonicalDeserialize)]
+#[derivative(
+    Default(bound = ""),
+    Clone(bound = ""),
+    Debug(bound = ""),
+    PartialEq(bound = ""),
+    Eq(bound = "")
+)]
+pub struct ZeromorphProverParam<E: Pairing> {
+    /// number of variables
+    pub num_vars: usize,
+    /// the first `N = 2^num_vars` powers `[\tau^i]_1` of the SRS
+    pub powers_of_g: Vec<E::G1Affine>,
+    /// the last `N` powers `[\tau^{D + 1 - N + i}]_1` of the SRS, which the
+    /// degree check is committed with
+    pub shifted_powers_of_g: Vec<E::G1Affine>,
+}
+
+/// `ZeromorphVerifierParam` is used to check evaluation proofs of multilinear
+/// polynomials of up to `num_vars` variables.
+#[derive(Derivative, CanonicalSerialize, CanonicalDeserialize)]
+#[derivative(
+    Default(bound = ""),
+    Clone(bound = ""),
+    Copy(bound = ""),
+    Debug(bound = ""),
+    PartialEq(bound = ""),
+    Eq(bound = "")
+)]
+pub struct ZeromorphVerifierParam<E: Pairing> {
+    /// number of variables
+    pub num_vars: usize,
+    /// the univariate verifier parameters
+    pub univariate: UnivariateVerifierParam<E>,
+    /// `[\tau^{D + 1 - N}]_2`, the shift of the degree check
+    pub shift_h: E::G2Affine,
+}
+
+/// Specialize the SRS to multilinear polynomials of `num_vars` variables.
+///
+/// A polynomial of degree less than `N = 2^num_vars` is committed to with the
+/// first `N` powers. The degree check commits to such a polynomial shifted by
+/// `X^{D + 1 - N}`, with the last `N` powers: a polynomial of degree `N` or
+/// more would need a power beyond `[\tau^D]_1`, which no one knows. The
+/// verifier checks the shift against `[\tau^{D + 1 - N}]_2`.
+pub(crate) fn trim<E: Pairing>(
+    srs: &ZeromorphUniversalParams<E>,
+    num_vars: usize,
+) -> Result<(ZeromorphProverParam<E>, ZeromorphVerifierParam<E>), PCSError> {
+    let n = 1 << num_vars;
+    if srs.powers_of_g.len() < n.max(2) || srs.powers_of_h.len() != srs.powers_of_g.len() {
+        return Err(PCSError::InvalidParameters(format!(
+            "SRS of {} powers does not support {} variables",
+            srs.powers_of_g.len(),
+            num_vars
+        )));
+    }
+    let shift = srs.max_degree() + 1 - n;
+    Ok((
+        ZeromorphProverParam {
+            num_vars,
+            powers_of_g: srs.powers_of_g[..n].to_vec(),
+            shifted_powers_of_g: srs.powers_of_g[shift..].to_vec(),
+        },
+        ZeromorphVerifierParam {
+            num_vars,
+            univariate: UnivariateVerifierParam {
+                g: srs.powers_of_g[0],
+                h: srs.powers_of_h[0],
+                beta_h: srs.powers_of_h[1],
+            },
+            shift_h: srs.powers_of_h[shift],
+        },
+    ))
+}
@@ -6,7 +6,7 @@
 
 //! Error module.
 
-// use crate::pcs::prelude::PCSError;
+use crate::pcs::prelude::PCSError;
 use arithmetic::ArithErrors;
 use ark_std::string::String;
 use displaydoc::Display;
@@ -33,8 +33,8 @@ pub enum PolyIOPErrors {
     TranscriptErrors(TranscriptError),
     /// Arithmetic Error: {0}
     ArithmeticErrors(ArithErrors),
-    // PCS error {0}
-    // PCSErrors(PCSError),
+    /// PCS error {0}
+    PCSErrors(PCSError),
 }
 
 impl From<ark_serialize::SerializationError> for PolyIOPErrors {
@@ -55,8 +55,8 @@ impl From<ArithErrors> for PolyIOPErrors {
     }
 }
 
-// impl From<PCSError> for PolyIOPErrors {
-//     fn from(e: PCSError) -> Self {
-//         Self::PCSErrors(e)
-//     }
-// }
+impl From<PCSError> for PolyIOPErrors {
+    fn from(e: PCSError) -> Self {
+        Self::PCSErrors(e)
+    }
+}
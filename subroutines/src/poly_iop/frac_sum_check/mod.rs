@@ -0,0 +1,492 @@
+// Copyright (c) 2023 Espresso Systems (espressosys.com)
+// This file is part of the HyperPlonk library.
+
+// You should have received a copy of the MIT License
+// along with the HyperPlonk library. If not, see <https://mit-license.org/>.
+
+//! Main module for the Fractional Sum Check protocol
+
+use crate::poly_iop::{
+    errors::PolyIOPErrors,
+    frac_sum_check::util::{
+        build_fraction_layers, build_layer_poly, build_leaves, eval_index_eqs, num_index_vars,
+    },
+    sum_check::SumCheck,
+    PolyIOP,
+};
+use arithmetic::{eq_eval, evaluate_opt, fix_variables, VPAuxInfo};
+use ark_ff::PrimeField;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_std::{end_timer, start_timer};
+use std::{fmt::Debug, marker::PhantomData};
+use transcript::Transcript;
+
+mod util;
+
+/// Marker for the PermutationCheck that proves a fractional sum instead of a
+/// product, see `PermutationCheck`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct GkrFraction;
+
+/// A fractional sum check proves that two lists of n-variate multilinear
+/// polynomials `(p1, ..., pk)` and `(q1, ..., qk)` satisfy
+///   \sum_{x \in {0,1}^n} p1(x) / q1(x) + ... + pk(x) / qk(x) = c
+/// for the sum `c` output to the verifier. This is the LogUp argument: with
+/// `pi = 1` and `qi = beta + fi` for the witnesses, and `p = -m` and
+/// `q = beta + t` for a table of the same size, the sum is zero iff the `fi`
+/// are looked up in `t` with the multiplicities `m`.
+///
+/// The fractions are the leaves `p(x, i) / q(x, i) = pi(x) / qi(x)` of a
+/// binary tree of additions, whose `d`-th layer has `2^d` fractions with
+///   p_d(x) = p_{d+1}(x, 0) * q_{d+1}(x, 1) + p_{d+1}(x, 1) * q_{d+1}(x, 0)
+///   q_d(x) = q_{d+1}(x, 0) * q_{d+1}(x, 1)
+/// so that the root is the sum. It is proven with a GKR protocol, i.e. one
+/// sum check per layer, which reduces the claims on `p_d(r)` and `q_d(r)` to
+/// claims on `p_{d+1}(r')` and `q_{d+1}(r')`. The prover commits to no
+/// polynomial, and never inverts the denominators.
+///
+/// The verifier ends with claims on the leaves `p(r, s)` and `q(r, s)`, which
+/// the prover reduces to the evaluations of the `pi` and `qi` at `r`.
+pub trait FractionalSumCheck<F: PrimeField>: SumCheck<F> {
+    type FractionalSumCheckSubClaim: Clone + Debug + Default + PartialEq;
+    type FractionalSumCheckProof: Clone
+        + Debug
+        + Default
+        + PartialEq
+        + CanonicalSerialize
+        + CanonicalDeserialize;
+
+    /// Initialize the system with a transcript
+    ///
+    /// This function is optional -- in the case where a FractionalSumCheck is
+    /// an building block for a more complex protocol, the transcript
+    /// may be initialized by this complex protocol, and passed to the
+    /// FractionalSumCheck prover/verifier.
+    fn init_transcript() -> Self::Transcript;
+
+    /// Proves the sum of the fractions `p1(x) / q1(x), ..., pk(x) / qk(x)`
+    /// over the boolean hypercube.
+    ///
+    /// Inputs:
+    /// - ps: the list of numerator multilinear polynomials
+    /// - qs: the list of denominator multilinear polynomials
+    /// - transcript: the IOP transcript
+    ///
+    /// Outputs
+    /// - the fractional sum check proof
+    /// - the numerators of the leaves, i.e. the `pi` concatenated (used for
+    ///   testing)
+    /// - the denominators of the leaves, i.e. the `qi` concatenated (used for
+    ///   testing)
+    ///
+    /// Cost: O(N)
+    #[allow(clippy::type_complexity)]
+    fn prove(
+        ps: &[Self::MultilinearExtension],
+        qs: &[Self::MultilinearExtension],
+        transcript: &mut Self::Transcript,
+    ) -> Result<
+        (
+            Self::FractionalSumCheckProof,
+            Self::MultilinearExtension,
+            Self::MultilinearExtension,
+        ),
+        PolyIOPErrors,
+    >;
+
+    /// Verify the proof of the sum of `num_fractions` fractions of
+    /// `num_vars`-variate polynomials, and output the sum together with the
+    /// claims on the polynomials.
+    fn verify(
+        proof: &Self::FractionalSumCheckProof,
+        num_fractions: usize,
+        num_vars: usize,
+        transcript: &mut Self::Transcript,
+    ) -> Result<Self::FractionalSumCheckSubClaim, PolyIOPErrors>;
+}
+
+/// A fractional sum check subclaim consists of
+/// - the sum of the fractions
+/// - the point the polynomials are queried at
+/// - the expected evaluations of `p1, ..., pk` at the point
+/// - the expected evaluations of `q1, ..., qk` at the point
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct FractionalSumCheckSubClaim<F: PrimeField> {
+    /// the sum of the fractions
+    pub sum: F,
+    /// the point of the queries
+    pub point: Vec<F>,
+    /// the expected evaluations of the numerators
+    pub p_evals: Vec<F>,
+    /// the expected evaluations of the denominators
+    pub q_evals: Vec<F>,
+}
+
+/// A fractional sum check proof consists of
+/// - a sum check proof per layer of the tree, except for the root
+/// - per layer, the evaluations of `p_{d+1}` and `q_{d+1}` at `(r', 0)` and
+///   `(r', 1)`
+/// - the evaluations of `p1, ..., pk, q1, ..., qk` at the final point
+#[derive(Clone, Debug, Default, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct FractionalSumCheckProof<F: PrimeField, SC: SumCheck<F>> {
+    pub layer_sum_check_proofs: Vec<SC::SumCheckProof>,
+    pub layer_evals: Vec<Vec<F>>,
+    pub leaf_evals: Vec<F>,
+}
+
+impl<F: PrimeField, T: Transcript<F>, P> FractionalSumCheck<F> for PolyIOP<F, T, P> {
+    type FractionalSumCheckSubClaim = FractionalSumCheckSubClaim<F>;
+    type FractionalSumCheckProof = FractionalSumCheckProof<F, Self>;
+
+    fn init_transcript() -> Self::Transcript {
+        T::new(b"Initializing FractionalSumCheck transcript")
+    }
+
+    fn prove(
+        ps: &[Self::MultilinearExtension],
+        qs: &[Self::MultilinearExtension],
+        transcript: &mut Self::Transcript,
+    ) -> Result<
+        (
+            Self::FractionalSumCheckProof,
+            Self::MultilinearExtension,
+            Self::MultilinearExtension,
+        ),
+        PolyIOPErrors,
+    > {
+        let start = start_timer!(|| "frac_sum_check prove");
+
+        if ps.is_empty() {
+            return Err(PolyIOPErrors::InvalidParameters("ps is empty".to_string()));
+        }
+        if ps.len() != qs.len() {
+            return Err(PolyIOPErrors::InvalidParameters(
+                "ps and qs have different number of polynomials".to_string(),
+            ));
+        }
+        let num_vars = ps[0].num_vars;
+        for poly in ps.iter().chain(qs.iter()) {
+            if poly.num_vars != num_vars {
+                return Err(PolyIOPErrors::InvalidParameters(
+                    "p and q have different number of variables".to_string(),
+                ));
+            }
+        }
+        let num_leaf_vars = num_vars + num_index_vars(ps.len());
+        if num_leaf_vars == 0 {
+            return Err(PolyIOPErrors::InvalidParameters(
+                "a single fraction of no variables".to_string(),
+            ));
+        }
+
+        let (p_leaves, q_leaves) = build_leaves(ps, qs);
+        // layers[d] is the layer of d + 1 variables, so that the last one holds
+        // the leaves
+        let layers = build_fraction_layers(p_leaves.clone(), q_leaves.clone());
+
+        let mut point = vec![];
+        let mut layer_sum_check_proofs = Vec::with_capacity(num_leaf_vars - 1);
+        let mut layer_evals = Vec::with_capacity(num_leaf_vars);
+        for (d, (p, q)) in layers.iter().enumerate() {
+            let mut next_point = if d == 0 {
+                vec![]
+            } else {
+                let lambda = transcript.get_and_append_challenge(b"lambda")?;
+                let poly = build_layer_poly(&point, p, q, lambda)?;
+                let proof = <Self as SumCheck<F>>::prove(&poly, transcript)?;
+                let next_point = proof.point.clone();
+                layer_sum_check_proofs.push(proof);
+                next_point
+            };
+
+            let mut evals = fix_variables(p, &next_point).evaluations;
+            evals.extend(fix_variables(q, &next_point).evaluations);
+            transcript.append_serializable_element(b"layer evals", &evals)?;
+            next_point.push(transcript.get_and_append_challenge(b"mu")?);
+
+            layer_evals.push(evals);
+            point = next_point;
+        }
+
+        // reduce the claims on the leaves to the polynomials
+        let leaf_evals = ps
+            .iter()
+            .chain(qs.iter())
+            .map(|poly| evaluate_opt(poly, &point[..num_vars]))
+            .collect::<Vec<_>>();
+        transcript.append_serializable_element(b"leaf evals", &leaf_evals)?;
+
+        end_timer!(start);
+
+        Ok((
+            FractionalSumCheckProof {
+                layer_sum_check_proofs,
+                layer_evals,
+                leaf_evals,
+            },
+            p_leaves,
+            q_leaves,
+        ))
+    }
+
+    fn verify(
+        proof: &Self::FractionalSumCheckProof,
+        num_fractions: usize,
+        num_vars: usize,
+        transcript: &mut Self::Transcript,
+    ) -> Result<Self::FractionalSumCheckSubClaim, PolyIOPErrors> {
+        let start = start_timer!(|| "frac_sum_check verify");
+
+        let num_leaf_vars = num_vars + num_index_vars(num_fractions);
+        if num_fractions == 0 || num_leaf_vars == 0 {
+            return Err(PolyIOPErrors::InvalidParameters(format!(
+                "cannot sum {} fractions of {} variables",
+                num_fractions, num_vars
+            )));
+        }
+        if proof.layer_evals.len() != num_leaf_vars
+            || proof.layer_sum_check_proofs.len() + 1 != num_leaf_vars
+            || proof.leaf_evals.len() != 2 * num_fractions
+        {
+            return Err(PolyIOPErrors::InvalidProof(format!(
+                "fractional sum check: {} layer evaluations, {} sum check proofs and {} leaf \
+                 evaluations for {} fractions of {} variables",
+                proof.layer_evals.len(),
+                proof.layer_sum_check_proofs.len(),
+                proof.leaf_evals.len(),
+                num_fractions,
+                num_vars
+            )));
+        }
+
+        let mut sum = F::zero();
+        let mut point = vec![];
+        // the claims on p_d(point) and q_d(point)
+        let mut claims = (F::zero(), F::zero());
+        for (d, evals) in proof.layer_evals.iter().enumerate() {
+            if evals.len() != 4 {
+                return Err(PolyIOPErrors::InvalidProof(format!(
+                    "fractional sum check: {} evaluations for layer {}, expect 4",
+                    evals.len(),
+                    d + 1
+                )));
+            }
+            let p_eval = evals[0] * evals[3] + evals[1] * evals[2];
+            let q_eval = evals[2] * evals[3];
+
+            let mut next_point = if d == 0 {
+                // the root of the tree is the sum
+                let q_inverse = q_eval.inverse().ok_or_else(|| {
+                    PolyIOPErrors::InvalidProof(
+                        "fractional sum check: the denominator of the sum is zero".to_string(),
+                    )
+                })?;
+                sum = p_eval * q_inverse;
+                vec![]
+            } else {
+                let lambda = transcript.get_and_append_challenge(b"lambda")?;
+                let layer_aux_info = VPAuxInfo {
+                    max_degree: 3,
+                    num_variables: d,
+                    phantom: PhantomData,
+                };
+                let sum_check_sub_claim = <Self as SumCheck<F>>::verify(
+                    claims.0 + lambda * claims.1,
+                    &proof.layer_sum_check_proofs[d - 1],
+                    &layer_aux_info,
+                    transcript,
+                )?;
+                let eq_eval = eq_eval(&point, &sum_check_sub_claim.point)?;
+                if eq_eval * (p_eval + lambda * q_eval) != sum_check_sub_claim.expected_evaluation {
+                    return Err(PolyIOPErrors::InvalidProof(format!(
+                        "fractional sum check: wrong evaluations for layer {}",
+                        d + 1
+                    )));
+                }
+                sum_check_sub_claim.point
+            };
+
+            transcript.append_serializable_element(b"layer evals", evals)?;
+            let mu = transcript.get_and_append_challenge(b"mu")?;
+            next_point.push(mu);
+
+            // reduce the claims on (r', 0) and (r', 1) to a claim on (r', mu)
+            claims = (
+                evals[0] + mu * (evals[1] - evals[0]),
+                evals[2] + mu * (evals[3] - evals[2]),
+            );
+            point = next_point;
+        }
+
+        // the leaves are p(x, i) = pi(x), padded with 0 / 1
+        let (p_evals, q_evals) = proof.leaf_evals.split_at(num_fractions);
+        let index_eqs = eval_index_eqs(&point[num_vars..], num_fractions)?;
+        let padding = F::one() - index_eqs.iter().sum::<F>();
+        let p_leaf: F = p_evals
+            .iter()
+            .zip(index_eqs.iter())
+            .map(|(p, e)| *p * e)
+            .sum();
+        let q_leaf: F = q_evals
+            .iter()
+            .zip(index_eqs.iter())
+            .map(|(q, e)| *q * e)
+            .sum::<F>()
+            + padding;
+        if (p_leaf, q_leaf) != claims {
+            return Err(PolyIOPErrors::InvalidProof(
+                "fractional sum check: wrong evaluations for the leaves".to_string(),
+            ));
+        }
+        transcript.append_serializable_element(b"leaf evals", &proof.leaf_evals)?;
+
+        end_timer!(start);
+        Ok(FractionalSumCheckSubClaim {
+            sum,
+            point: point[..num_vars].to_vec(),
+            p_evals: p_evals.to_vec(),
+            q_evals: q_evals.to_vec(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::FractionalSumCheck;
+    use crate::poly_iop::{errors::PolyIOPErrors, PolyIOP};
+    use arithmetic::evaluate_opt;
+    use ark_bls12_381::Fr;
+    use ark_ff::{Field, One, UniformRand};
+    use ark_poly::{DenseMultilinearExtension, MultilinearExtension};
+    use ark_std::test_rng;
+    use std::sync::Arc;
+    use transcript::Transcript;
+
+    fn prove_and_verify(
+        ps: &[Arc<DenseMultilinearExtension<Fr>>],
+        qs: &[Arc<DenseMultilinearExtension<Fr>>],
+    ) -> Result<Fr, PolyIOPErrors> {
+        let mut transcript = <PolyIOP<Fr> as FractionalSumCheck<Fr>>::init_transcript();
+        transcript.append_message(b"testing", b"initializing transcript for testing")?;
+        let (proof, p_leaves, q_leaves) =
+            <PolyIOP<Fr> as FractionalSumCheck<Fr>>::prove(ps, qs, &mut transcript)?;
+        assert_eq!(
+            p_leaves.evaluations[..1 << ps[0].num_vars],
+            ps[0].evaluations
+        );
+        assert_eq!(q_leaves.num_vars, p_leaves.num_vars);
+
+        let mut transcript = <PolyIOP<Fr> as FractionalSumCheck<Fr>>::init_transcript();
+        transcript.append_message(b"testing", b"initializing transcript for testing")?;
+        let sub_claim = <PolyIOP<Fr> as FractionalSumCheck<Fr>>::verify(
+            &proof,
+            ps.len(),
+            ps[0].num_vars,
+            &mut transcript,
+        )?;
+        for (poly, eval) in ps
+            .iter()
+            .chain(qs.iter())
+            .zip(sub_claim.p_evals.iter().chain(sub_claim.q_evals.iter()))
+        {
+            if evaluate_opt(poly, &sub_claim.point) != *eval {
+                return Err(PolyIOPErrors::InvalidVerifier("wrong subclaim".to_string()));
+            }
+        }
+        Ok(sub_claim.sum)
+    }
+
+    fn test_fractional_sum_check(nv: usize, num_fractions: usize) -> Result<(), PolyIOPErrors> {
+        let mut rng = test_rng();
+
+        let ps: Vec<_> = (0..num_fractions)
+            .map(|_| Arc::new(DenseMultilinearExtension::<Fr>::rand(nv, &mut rng)))
+            .collect();
+        let qs: Vec<_> = (0..num_fractions)
+            .map(|_| Arc::new(DenseMultilinearExtension::<Fr>::rand(nv, &mut rng)))
+            .collect();
+        let expected: Fr = ps
+            .iter()
+            .zip(qs.iter())
+            .flat_map(|(p, q)| {
+                p.evaluations
+                    .iter()
+                    .zip(q.evaluations.iter())
+                    .map(|(p, q)| *p * q.inverse().unwrap())
+            })
+            .sum();
+        assert_eq!(prove_and_verify(&ps, &qs)?, expected);
+
+        // bad path: a tampered proof
+        let mut transcript = <PolyIOP<Fr> as FractionalSumCheck<Fr>>::init_transcript();
+        let (mut proof, _, _) =
+            <PolyIOP<Fr> as FractionalSumCheck<Fr>>::prove(&ps, &qs, &mut transcript)?;
+        proof.leaf_evals[0] += Fr::one();
+        let mut transcript = <PolyIOP<Fr> as FractionalSumCheck<Fr>>::init_transcript();
+        assert!(<PolyIOP<Fr> as FractionalSumCheck<Fr>>::verify(
+            &proof,
+            num_fractions,
+            nv,
+            &mut transcript
+        )
+        .is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_trivial_polynomial() -> Result<(), PolyIOPErrors> {
+        let _trace = crate::trace_guard();
+        test_fractional_sum_check(1, 1)?;
+        test_fractional_sum_check(0, 2)
+    }
+
+    #[test]
+    fn test_normal_polynomial() -> Result<(), PolyIOPErrors> {
+        let _trace = crate::trace_guard();
+        test_fractional_sum_check(8, 1)?;
+        test_fractional_sum_check(8, 3)
+    }
+
+    #[test]
+    fn test_logup() -> Result<(), PolyIOPErrors> {
+        let _trace = crate::trace_guard();
+        let mut rng = test_rng();
+        let nv = 5;
+
+        // f looks up each entry of the table t with multiplicities m
+        let t: Vec<Fr> = (0..1 << nv).map(|_| Fr::rand(&mut rng)).collect();
+        let mut m = vec![Fr::from(0u64); 1 << nv];
+        let f: Vec<Fr> = (0..1 << nv)
+            .map(|i| {
+                let j = (i * 7) % 5;
+                m[j] += Fr::one();
+                t[j]
+            })
+            .collect();
+        let beta = Fr::rand(&mut rng);
+        let mle =
+            |evals: Vec<Fr>| Arc::new(DenseMultilinearExtension::from_evaluations_vec(nv, evals));
+        let ps = vec![
+            mle(vec![Fr::one(); 1 << nv]),
+            mle(m.iter().map(|m| -*m).collect()),
+        ];
+        let qs = vec![
+            mle(f.iter().map(|f| beta + f).collect()),
+            mle(t.iter().map(|t| beta + t).collect()),
+        ];
+        assert_eq!(prove_and_verify(&ps, &qs)?, Fr::from(0u64));
+
+        // bad path: a wrong multiplicity
+        let ps = vec![ps[0].clone(), mle(vec![-Fr::one(); 1 << nv])];
+        assert_ne!(prove_and_verify(&ps, &qs)?, Fr::from(0u64));
+
+        Ok(())
+    }
+
+    #[test]
+    fn zero_polynomial_should_error() {
+        let _trace = crate::trace_guard();
+        assert!(test_fractional_sum_check(0, 1).is_err());
+    }
+}
@@ -0,0 +1,120 @@
+// Copyright (c) 2023 Espresso Systems (espressosys.com)
+// This file is part of the HyperPlonk library.
+
+// You should have received a copy of the MIT License
+// along with the HyperPlonk library. If not, see <https://mit-license.org/>.
+
+//! This module implements useful functions for the fractional sum check
+//! protocol.
+
+use crate::poly_iop::{errors::PolyIOPErrors, prod_check::split_halves};
+use arithmetic::{build_eq_x_r, build_eq_x_r_vec, VirtualPolynomial};
+use ark_ff::PrimeField;
+use ark_poly::DenseMultilinearExtension;
+use ark_std::{end_timer, log2, start_timer};
+use std::sync::Arc;
+
+/// The number of variables that index the fractions, for `num_fractions`
+/// fractions.
+pub(super) fn num_index_vars(num_fractions: usize) -> usize {
+    log2(num_fractions) as usize
+}
+
+/// Concatenate `p1, ..., pk` (resp. `q1, ..., qk`) into the leaves `p(x, i) =
+/// pi(x)` of the circuit, where the index `i` is in the top variables. The
+/// padding fractions are `0 / 1`.
+///
+/// The caller needs to sanity-check that the number of polynomials and
+/// variables match in ps and qs.
+pub(super) fn build_leaves<F: PrimeField>(
+    ps: &[Arc<DenseMultilinearExtension<F>>],
+    qs: &[Arc<DenseMultilinearExtension<F>>],
+) -> (
+    Arc<DenseMultilinearExtension<F>>,
+    Arc<DenseMultilinearExtension<F>>,
+) {
+    let num_vars = ps[0].num_vars + num_index_vars(ps.len());
+    let concat = |polys: &[Arc<DenseMultilinearExtension<F>>], padding: F| {
+        let mut evals = Vec::with_capacity(1 << num_vars);
+        for poly in polys.iter() {
+            evals.extend_from_slice(&poly.evaluations);
+        }
+        evals.resize(1 << num_vars, padding);
+        Arc::new(DenseMultilinearExtension::from_evaluations_vec(
+            num_vars, evals,
+        ))
+    };
+    (concat(ps, F::zero()), concat(qs, F::one()))
+}
+
+/// Build the layers of the binary tree of fraction additions whose leaves are
+/// `p(x) / q(x)`, from the one of 1 variable to the leaves, where
+///   p_d(x) = p_{d+1}(x, 0) * q_{d+1}(x, 1) + p_{d+1}(x, 1) * q_{d+1}(x, 0)
+///   q_d(x) = q_{d+1}(x, 0) * q_{d+1}(x, 1)
+#[allow(clippy::type_complexity)]
+pub(super) fn build_fraction_layers<F: PrimeField>(
+    p: Arc<DenseMultilinearExtension<F>>,
+    q: Arc<DenseMultilinearExtension<F>>,
+) -> Vec<(
+    Arc<DenseMultilinearExtension<F>>,
+    Arc<DenseMultilinearExtension<F>>,
+)> {
+    let start = start_timer!(|| "build fraction layers");
+
+    let num_vars = p.num_vars;
+    let mut layers = Vec::with_capacity(num_vars);
+    layers.push((p, q));
+    for nv in (1..num_vars).rev() {
+        let (p, q) = &layers[layers.len() - 1];
+        let (p_low, p_high) = p.evaluations.split_at(1 << nv);
+        let (q_low, q_high) = q.evaluations.split_at(1 << nv);
+        let mut p_evals = Vec::with_capacity(1 << nv);
+        let mut q_evals = Vec::with_capacity(1 << nv);
+        for i in 0..1 << nv {
+            p_evals.push(p_low[i] * q_high[i] + p_high[i] * q_low[i]);
+            q_evals.push(q_low[i] * q_high[i]);
+        }
+        layers.push((
+            Arc::new(DenseMultilinearExtension::from_evaluations_vec(nv, p_evals)),
+            Arc::new(DenseMultilinearExtension::from_evaluations_vec(nv, q_evals)),
+        ));
+    }
+    layers.reverse();
+
+    end_timer!(start);
+    layers
+}
+
+/// Build the virtual polynomial of the sum check from layer `d` to layer
+/// `d + 1`:
+///   eq(r, x) * (p(x, 0) * q(x, 1) + p(x, 1) * q(x, 0))
+///     + lambda * eq(r, x) * q(x, 0) * q(x, 1)
+/// where `p` and `q` have `d + 1` variables and `r` has `d` entries.
+pub(super) fn build_layer_poly<F: PrimeField>(
+    r: &[F],
+    p: &Arc<DenseMultilinearExtension<F>>,
+    q: &Arc<DenseMultilinearExtension<F>>,
+    lambda: F,
+) -> Result<VirtualPolynomial<F>, PolyIOPErrors> {
+    let eq_x_r = build_eq_x_r(r)?;
+    let (p_low, p_high) = split_halves(p);
+    let (q_low, q_high) = split_halves(q);
+    let mut poly = VirtualPolynomial::new(r.len());
+    poly.add_mle_list([eq_x_r.clone(), p_low, q_high.clone()], F::one())?;
+    poly.add_mle_list([eq_x_r.clone(), p_high, q_low.clone()], F::one())?;
+    poly.add_mle_list([eq_x_r, q_low, q_high], lambda)?;
+    Ok(poly)
+}
+
+/// Evaluate `eq(r, i)` for the indices `i < num_fractions` of the fractions.
+pub(super) fn eval_index_eqs<F: PrimeField>(
+    r: &[F],
+    num_fractions: usize,
+) -> Result<Vec<F>, PolyIOPErrors> {
+    if r.is_empty() {
+        return Ok(vec![F::one()]);
+    }
+    let mut res = build_eq_x_r_vec(r)?;
+    res.truncate(num_fractions);
+    Ok(res)
+}
@@ -0,0 +1,216 @@
+// Copyright (c) 2023 Espresso Systems (espressosys.com)
+// This file is part of the HyperPlonk library.
+
+// You should have received a copy of the MIT License
+// along with the HyperPlonk library. If not, see <https://mit-license.org/>.
+
+//! This module defines the layered circuits of the GKR protocol.
+
+use crate::poly_iop::errors::PolyIOPErrors;
+use arithmetic::build_eq_x_r_vec;
+use ark_ff::PrimeField;
+use ark_poly::DenseMultilinearExtension;
+use ark_std::{end_timer, log2, start_timer};
+use std::sync::Arc;
+
+/// A gate of a layered circuit, whose inputs are two outputs of the previous
+/// layer, given by their indices.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Gate {
+    /// the sum of the two inputs
+    Add(usize, usize),
+    /// the product of the two inputs
+    Mul(usize, usize),
+}
+
+impl Gate {
+    /// The indices of the inputs of the gate.
+    pub fn inputs(&self) -> (usize, usize) {
+        match *self {
+            Gate::Add(left, right) | Gate::Mul(left, right) => (left, right),
+        }
+    }
+
+    fn evaluate<F: PrimeField>(&self, values: &[F]) -> F {
+        match *self {
+            Gate::Add(left, right) => values[left] + values[right],
+            Gate::Mul(left, right) => values[left] * values[right],
+        }
+    }
+}
+
+/// A layered circuit of add and mul gates, where the gates of a layer read
+/// the outputs of the previous layer, and those of the first layer read the
+/// inputs.
+///
+/// Each layer of `m` values is padded with zeros to `2^s` values, for `s =
+/// max(1, log2(m))` variables. The circuit runs on `2^b` instances at once,
+/// whose values of a layer are the MLE of `s + b` variables with
+///   V(g, j) = the value of the gate `g` in the instance `j`
+/// i.e. the instances are in the top `b` variables.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct LayeredCircuit {
+    num_inputs: usize,
+    layers: Vec<Vec<Gate>>,
+}
+
+impl LayeredCircuit {
+    /// Build a circuit of `num_inputs` inputs and the `layers` of gates, from
+    /// the one reading the inputs to the outputs.
+    pub fn new(num_inputs: usize, layers: Vec<Vec<Gate>>) -> Result<Self, PolyIOPErrors> {
+        if num_inputs == 0 || layers.is_empty() {
+            return Err(PolyIOPErrors::InvalidParameters(
+                "a circuit needs inputs and layers".to_string(),
+            ));
+        }
+        let mut num_values = num_inputs;
+        for (i, layer) in layers.iter().enumerate() {
+            if layer.is_empty() {
+                return Err(PolyIOPErrors::InvalidParameters(format!(
+                    "layer {} has no gate",
+                    i
+                )));
+            }
+            for gate in layer.iter() {
+                let (left, right) = gate.inputs();
+                if left >= num_values || right >= num_values {
+                    return Err(PolyIOPErrors::InvalidParameters(format!(
+                        "gate {:?} of layer {} reads out of the {} values of the previous layer",
+                        gate, i, num_values
+                    )));
+                }
+            }
+            num_values = layer.len();
+        }
+        Ok(Self { num_inputs, layers })
+    }
+
+    /// The number of inputs of an instance of the circuit.
+    pub fn num_inputs(&self) -> usize {
+        self.num_inputs
+    }
+
+    /// The layers of gates, from the one reading the inputs to the outputs.
+    pub fn layers(&self) -> &[Vec<Gate>] {
+        &self.layers
+    }
+
+    /// The number of variables of the inputs of an instance.
+    pub fn num_input_vars(&self) -> usize {
+        num_vars(self.num_inputs)
+    }
+
+    /// The number of variables of the outputs of an instance.
+    pub fn num_output_vars(&self) -> usize {
+        num_vars(self.layers[self.layers.len() - 1].len())
+    }
+
+    /// The number of variables of the values read by the `i`-th layer.
+    pub(super) fn num_layer_input_vars(&self, i: usize) -> usize {
+        if i == 0 {
+            self.num_input_vars()
+        } else {
+            num_vars(self.layers[i - 1].len())
+        }
+    }
+
+    /// Evaluate the circuit on the MLE of the inputs of `2^b` instances, and
+    /// return the MLEs of the values of each layer.
+    pub fn evaluate<F: PrimeField>(
+        &self,
+        inputs: &Arc<DenseMultilinearExtension<F>>,
+    ) -> Result<Vec<Arc<DenseMultilinearExtension<F>>>, PolyIOPErrors> {
+        let start = start_timer!(|| "evaluate layered circuit");
+
+        let num_input_vars = self.num_input_vars();
+        if inputs.num_vars < num_input_vars {
+            return Err(PolyIOPErrors::InvalidParameters(format!(
+                "inputs of {} variables for a circuit of {} input variables",
+                inputs.num_vars, num_input_vars
+            )));
+        }
+        let num_instance_vars = inputs.num_vars - num_input_vars;
+
+        let mut res: Vec<Arc<DenseMultilinearExtension<F>>> = Vec::with_capacity(self.layers.len());
+        for (i, layer) in self.layers.iter().enumerate() {
+            let previous = if i == 0 { inputs } else { &res[i - 1] };
+            let num_previous_vars = self.num_layer_input_vars(i);
+            let num_layer_vars = num_vars(layer.len());
+            let mut evals = vec![F::zero(); 1 << (num_layer_vars + num_instance_vars)];
+            for j in 0..1 << num_instance_vars {
+                let values =
+                    &previous.evaluations[j << num_previous_vars..(j + 1) << num_previous_vars];
+                for (g, gate) in layer.iter().enumerate() {
+                    evals[(j << num_layer_vars) + g] = gate.evaluate(values);
+                }
+            }
+            res.push(Arc::new(DenseMultilinearExtension::from_evaluations_vec(
+                num_layer_vars + num_instance_vars,
+                evals,
+            )));
+        }
+
+        end_timer!(start);
+        Ok(res)
+    }
+
+    /// The evaluations of the MLEs `add(g)` and `mul(g)` of the `i`-th layer,
+    /// which are one on the add (resp. mul) gates and zero elsewhere, at
+    /// `point`.
+    pub(super) fn eval_gate_types<F: PrimeField>(
+        &self,
+        i: usize,
+        point: &[F],
+    ) -> Result<(F, F), PolyIOPErrors> {
+        let eq = build_eq_x_r_vec(point)?;
+        let mut res = (F::zero(), F::zero());
+        for (gate, e) in self.layers[i].iter().zip(eq.iter()) {
+            match gate {
+                Gate::Add(..) => res.0 += e,
+                Gate::Mul(..) => res.1 += e,
+            }
+        }
+        Ok(res)
+    }
+
+    /// The evaluations of the wiring MLE of the `i`-th layer
+    ///   w(g, x) = [left(g) = x] + alpha * [right(g) = x]
+    /// at `g = gate_point` for each `x` of its inputs.
+    pub(super) fn build_wiring<F: PrimeField>(
+        &self,
+        i: usize,
+        gate_point: &[F],
+        alpha: F,
+    ) -> Result<Vec<F>, PolyIOPErrors> {
+        let eq = build_eq_x_r_vec(gate_point)?;
+        let mut res = vec![F::zero(); 1 << self.num_layer_input_vars(i)];
+        for (gate, e) in self.layers[i].iter().zip(eq.iter()) {
+            let (left, right) = gate.inputs();
+            res[left] += e;
+            res[right] += alpha * e;
+        }
+        Ok(res)
+    }
+
+    /// Evaluate the wiring MLE of `build_wiring` at `x = input_point`.
+    pub(super) fn eval_wiring<F: PrimeField>(
+        &self,
+        i: usize,
+        gate_point: &[F],
+        input_point: &[F],
+        alpha: F,
+    ) -> Result<F, PolyIOPErrors> {
+        let eq = build_eq_x_r_vec(input_point)?;
+        Ok(self
+            .build_wiring(i, gate_point, alpha)?
+            .iter()
+            .zip(eq.iter())
+            .map(|(w, e)| *w * e)
+            .sum())
+    }
+}
+
+/// The number of variables of a layer of `num_values` values.
+fn num_vars(num_values: usize) -> usize {
+    (log2(num_values) as usize).max(1)
+}
@@ -0,0 +1,438 @@
+// Copyright (c) 2023 Espresso Systems (espressosys.com)
+// This file is part of the HyperPlonk library.
+
+// You should have received a copy of the MIT License
+// along with the HyperPlonk library. If not, see <https://mit-license.org/>.
+
+//! Main module for the GKR protocol on layered circuits
+
+use crate::poly_iop::{errors::PolyIOPErrors, sum_check::SumCheck, PolyIOP};
+use arithmetic::{
+    build_eq_x_r, eq_eval, evaluate_opt, fix_last_variables, VPAuxInfo, VirtualPolynomial,
+};
+use ark_ff::PrimeField;
+use ark_poly::DenseMultilinearExtension;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_std::{end_timer, start_timer};
+use std::{fmt::Debug, marker::PhantomData, sync::Arc};
+use transcript::Transcript;
+
+mod circuit;
+
+pub use circuit::{Gate, LayeredCircuit};
+
+/// A GKR subclaim for the inputs `V(x)` of a circuit consists of
+/// - the point `x` the inputs are queried at
+/// - the expected evaluation `V(x)`
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct GkrSubClaim<F: PrimeField> {
+    /// the point of the query
+    pub point: Vec<F>,
+    /// the expected evaluation
+    pub expected_evaluation: F,
+}
+
+/// A GKR proof consists of, for each layer from the outputs to the inputs
+/// - the sum check proof on the gates of the layer
+/// - the evaluations of the left and right inputs of the gates
+/// - the sum check proof on the wiring of the layer
+/// - the evaluation of the values read by the layer
+#[derive(Clone, Debug, Default, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct GkrProof<F: PrimeField, SC: SumCheck<F>> {
+    pub gate_sum_check_proofs: Vec<SC::SumCheckProof>,
+    pub wire_evals: Vec<(F, F)>,
+    pub wiring_sum_check_proofs: Vec<SC::SumCheckProof>,
+    pub layer_evals: Vec<F>,
+}
+
+/// A GKR protocol proves that the outputs of a `LayeredCircuit` run on `2^b`
+/// instances are those of the committed inputs, without committing to any
+/// intermediate value.
+///
+/// A claim on the values `V_i(r, s)` of the layer `i` at the gates `r` and
+/// the instances `s` is reduced to a claim on the values `V_{i-1}` it reads
+/// with two sum checks:
+/// 1. on the gates and the instances
+///    V_i(r, s) = \sum_{g, j} eq((r, s), (g, j)) * (add(g) * (L(g, j) + R(g,
+///    j)) + mul(g) * L(g, j) * R(g, j))
+///    where `L(g, j)` and `R(g, j)` are the left and right inputs of the gate
+///    `g` in the instance `j`, which reduces to claims on `L(r', s')` and
+///    `R(r', s')`;
+/// 2. on the wiring of the layer, after a random `alpha`
+///    L(r', s') + alpha * R(r', s') = \sum_x w(r', x) * V_{i-1}(x, s')
+///    where `w(g, x) = [left(g) = x] + alpha * [right(g) = x]`, which reduces
+///    to a claim on `V_{i-1}(r'', s')`.
+///
+/// The verifier evaluates `add`, `mul` and `w` from the circuit, so the cost
+/// of the prover is linear in the size of the circuit, and that of the
+/// verifier is linear in the size of an instance. The claim on the inputs is
+/// left to the caller, e.g. as an opening of their commitment.
+pub trait Gkr<F: PrimeField>: SumCheck<F> {
+    type GkrSubClaim: Clone + Debug + Default + PartialEq;
+    type GkrProof: Clone + Debug + Default + PartialEq + CanonicalSerialize + CanonicalDeserialize;
+
+    /// Initialize the system with a transcript
+    ///
+    /// This function is optional -- in the case where a Gkr is
+    /// an building block for a more complex protocol, the transcript
+    /// may be initialized by this complex protocol, and passed to the
+    /// Gkr prover/verifier.
+    fn init_transcript() -> Self::Transcript;
+
+    /// Run the circuit on the inputs of `2^b` instances, i.e. the MLE of
+    /// `s + b` variables of `LayeredCircuit`, and prove its outputs.
+    ///
+    /// Outputs
+    /// - the GKR proof
+    /// - the outputs of the circuit
+    ///
+    /// Cost: O(N)
+    fn prove(
+        circuit: &LayeredCircuit,
+        inputs: &Self::MultilinearExtension,
+        transcript: &mut Self::Transcript,
+    ) -> Result<(Self::GkrProof, Self::MultilinearExtension), PolyIOPErrors>;
+
+    /// Verify that the circuit outputs `outputs`, and generate the subclaim
+    /// on its inputs.
+    fn verify(
+        circuit: &LayeredCircuit,
+        outputs: &Self::MultilinearExtension,
+        proof: &Self::GkrProof,
+        transcript: &mut Self::Transcript,
+    ) -> Result<Self::GkrSubClaim, PolyIOPErrors>;
+}
+
+impl<F: PrimeField, T: Transcript<F>, P> Gkr<F> for PolyIOP<F, T, P> {
+    type GkrSubClaim = GkrSubClaim<F>;
+    type GkrProof = GkrProof<F, Self>;
+
+    fn init_transcript() -> Self::Transcript {
+        T::new(b"Initializing Gkr transcript")
+    }
+
+    fn prove(
+        circuit: &LayeredCircuit,
+        inputs: &Self::MultilinearExtension,
+        transcript: &mut Self::Transcript,
+    ) -> Result<(Self::GkrProof, Self::MultilinearExtension), PolyIOPErrors> {
+        let start = start_timer!(|| "gkr prove");
+
+        let values = circuit.evaluate(inputs)?;
+        let num_instance_vars = inputs.num_vars - circuit.num_input_vars();
+        let outputs = values[values.len() - 1].clone();
+
+        transcript.append_serializable_element(b"outputs", &outputs.evaluations)?;
+        let mut point = transcript.get_and_append_challenge_vectors(b"gkr r", outputs.num_vars)?;
+
+        let num_layers = circuit.layers().len();
+        let mut proof = GkrProof::default();
+        for i in (0..num_layers).rev() {
+            let previous = if i == 0 { inputs } else { &values[i - 1] };
+            let num_gate_vars = values[i].num_vars - num_instance_vars;
+            let num_previous_vars = circuit.num_layer_input_vars(i);
+
+            // 1. sum check on the gates
+            let (add, mul, left, right) = build_gate_mles(
+                &circuit.layers()[i],
+                previous,
+                num_gate_vars,
+                num_previous_vars,
+                num_instance_vars,
+            );
+            let eq_x_r = build_eq_x_r(&point)?;
+            let mut poly = VirtualPolynomial::new(point.len());
+            poly.add_mle_list([eq_x_r.clone(), add.clone(), left.clone()], F::one())?;
+            poly.add_mle_list([eq_x_r.clone(), add, right.clone()], F::one())?;
+            poly.add_mle_list([eq_x_r, mul, left.clone(), right.clone()], F::one())?;
+            let gate_proof = <Self as SumCheck<F>>::prove(&poly, transcript)?;
+            let gate_point = gate_proof.point.clone();
+            let wire_evals = (
+                evaluate_opt(&left, &gate_point),
+                evaluate_opt(&right, &gate_point),
+            );
+            transcript.append_serializable_element(b"wire evals", &wire_evals)?;
+            let alpha = transcript.get_and_append_challenge(b"alpha")?;
+
+            // 2. sum check on the wiring
+            let (gate_point, instance_point) = gate_point.split_at(num_gate_vars);
+            let wiring = Arc::new(DenseMultilinearExtension::from_evaluations_vec(
+                num_previous_vars,
+                circuit.build_wiring(i, gate_point, alpha)?,
+            ));
+            let previous = Arc::new(fix_last_variables(previous, instance_point));
+            let mut poly = VirtualPolynomial::new(num_previous_vars);
+            poly.add_mle_list([wiring, previous.clone()], F::one())?;
+            let wiring_proof = <Self as SumCheck<F>>::prove(&poly, transcript)?;
+            let layer_eval = evaluate_opt(&previous, &wiring_proof.point);
+            transcript.append_field_element(b"layer eval", &layer_eval)?;
+
+            point = [wiring_proof.point.as_slice(), instance_point].concat();
+            proof.gate_sum_check_proofs.push(gate_proof);
+            proof.wire_evals.push(wire_evals);
+            proof.wiring_sum_check_proofs.push(wiring_proof);
+            proof.layer_evals.push(layer_eval);
+        }
+
+        end_timer!(start);
+        Ok((proof, outputs))
+    }
+
+    fn verify(
+        circuit: &LayeredCircuit,
+        outputs: &Self::MultilinearExtension,
+        proof: &Self::GkrProof,
+        transcript: &mut Self::Transcript,
+    ) -> Result<Self::GkrSubClaim, PolyIOPErrors> {
+        let start = start_timer!(|| "gkr verify");
+
+        let num_layers = circuit.layers().len();
+        if outputs.num_vars < circuit.num_output_vars() {
+            return Err(PolyIOPErrors::InvalidParameters(format!(
+                "outputs of {} variables for a circuit of {} output variables",
+                outputs.num_vars,
+                circuit.num_output_vars()
+            )));
+        }
+        if proof.gate_sum_check_proofs.len() != num_layers
+            || proof.wire_evals.len() != num_layers
+            || proof.wiring_sum_check_proofs.len() != num_layers
+            || proof.layer_evals.len() != num_layers
+        {
+            return Err(PolyIOPErrors::InvalidProof(format!(
+                "gkr: the proof does not have {} layers",
+                num_layers
+            )));
+        }
+        let num_instance_vars = outputs.num_vars - circuit.num_output_vars();
+
+        transcript.append_serializable_element(b"outputs", &outputs.evaluations)?;
+        let mut point = transcript.get_and_append_challenge_vectors(b"gkr r", outputs.num_vars)?;
+        let mut claim = evaluate_opt(outputs, &point);
+
+        // the proof goes from the outputs to the inputs
+        for (k, i) in (0..num_layers).rev().enumerate() {
+            let num_gate_vars = point.len() - num_instance_vars;
+            let num_previous_vars = circuit.num_layer_input_vars(i);
+
+            // 1. sum check on the gates
+            let gate_aux_info = VPAuxInfo {
+                max_degree: 4,
+                num_variables: point.len(),
+                phantom: PhantomData,
+            };
+            let gate_sub_claim = <Self as SumCheck<F>>::verify(
+                claim,
+                &proof.gate_sum_check_proofs[k],
+                &gate_aux_info,
+                transcript,
+            )?;
+            let (left, right) = proof.wire_evals[k];
+            let (gate_point, instance_point) = gate_sub_claim.point.split_at(num_gate_vars);
+            let (add, mul) = circuit.eval_gate_types(i, gate_point)?;
+            if eq_eval(&point, &gate_sub_claim.point)? * (add * (left + right) + mul * left * right)
+                != gate_sub_claim.expected_evaluation
+            {
+                return Err(PolyIOPErrors::InvalidProof(format!(
+                    "gkr: wrong wire evaluations for layer {}",
+                    i
+                )));
+            }
+            transcript.append_serializable_element(b"wire evals", &proof.wire_evals[k])?;
+            let alpha = transcript.get_and_append_challenge(b"alpha")?;
+
+            // 2. sum check on the wiring
+            let wiring_aux_info = VPAuxInfo {
+                max_degree: 2,
+                num_variables: num_previous_vars,
+                phantom: PhantomData,
+            };
+            let wiring_sub_claim = <Self as SumCheck<F>>::verify(
+                left + alpha * right,
+                &proof.wiring_sum_check_proofs[k],
+                &wiring_aux_info,
+                transcript,
+            )?;
+            let layer_eval = proof.layer_evals[k];
+            if circuit.eval_wiring(i, gate_point, &wiring_sub_claim.point, alpha)? * layer_eval
+                != wiring_sub_claim.expected_evaluation
+            {
+                return Err(PolyIOPErrors::InvalidProof(format!(
+                    "gkr: wrong evaluation of the values read by layer {}",
+                    i
+                )));
+            }
+            transcript.append_field_element(b"layer eval", &layer_eval)?;
+
+            point = [wiring_sub_claim.point.as_slice(), instance_point].concat();
+            claim = layer_eval;
+        }
+
+        end_timer!(start);
+        Ok(GkrSubClaim {
+            point,
+            expected_evaluation: claim,
+        })
+    }
+}
+
+/// Build the MLEs of `s + b` variables of a layer of gates on `2^b` instances
+/// - add(g, j) and mul(g, j), which are one on the add (resp. mul) gates
+/// - L(g, j) and R(g, j), the left and right inputs of the gate `g` in the
+///   instance `j`
+#[allow(clippy::type_complexity)]
+fn build_gate_mles<F: PrimeField>(
+    gates: &[Gate],
+    previous: &Arc<DenseMultilinearExtension<F>>,
+    num_gate_vars: usize,
+    num_previous_vars: usize,
+    num_instance_vars: usize,
+) -> (
+    Arc<DenseMultilinearExtension<F>>,
+    Arc<DenseMultilinearExtension<F>>,
+    Arc<DenseMultilinearExtension<F>>,
+    Arc<DenseMultilinearExtension<F>>,
+) {
+    let start = start_timer!(|| "build gate mles");
+
+    let num_vars = num_gate_vars + num_instance_vars;
+    let mut add = vec![F::zero(); 1 << num_vars];
+    let mut mul = vec![F::zero(); 1 << num_vars];
+    let mut left = vec![F::zero(); 1 << num_vars];
+    let mut right = vec![F::zero(); 1 << num_vars];
+    for j in 0..1 << num_instance_vars {
+        let values = &previous.evaluations[j << num_previous_vars..(j + 1) << num_previous_vars];
+        for (g, gate) in gates.iter().enumerate() {
+            let index = (j << num_gate_vars) + g;
+            let (l, r) = gate.inputs();
+            match gate {
+                Gate::Add(..) => add[index] = F::one(),
+                Gate::Mul(..) => mul[index] = F::one(),
+            }
+            left[index] = values[l];
+            right[index] = values[r];
+        }
+    }
+
+    let mle = |evals| {
+        Arc::new(DenseMultilinearExtension::from_evaluations_vec(
+            num_vars, evals,
+        ))
+    };
+    let res = (mle(add), mle(mul), mle(left), mle(right));
+
+    end_timer!(start);
+    res
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Gate, Gkr, LayeredCircuit};
+    use crate::{
+        pcs::{prelude::MultilinearKzgPCS, PolynomialCommitmentScheme},
+        poly_iop::{errors::PolyIOPErrors, PolyIOP},
+    };
+    use ark_bls12_381::{Bls12_381, Fr};
+    use ark_ff::{Field, One, UniformRand, Zero};
+    use ark_poly::{DenseMultilinearExtension, Polynomial};
+    use ark_std::test_rng;
+    use std::sync::Arc;
+
+    type Kzg = MultilinearKzgPCS<Bls12_381>;
+
+    /// The S-box `x^5` of Poseidon, on the inputs `(x, 0)`.
+    fn sbox_circuit() -> Result<LayeredCircuit, PolyIOPErrors> {
+        LayeredCircuit::new(
+            2,
+            vec![
+                // x^2, x, 0
+                vec![Gate::Mul(0, 0), Gate::Add(0, 1), Gate::Add(1, 1)],
+                // x^4, x
+                vec![Gate::Mul(0, 0), Gate::Add(1, 2)],
+                // x^5
+                vec![Gate::Mul(0, 1)],
+            ],
+        )
+    }
+
+    fn test_gkr(num_instance_vars: usize) -> Result<(), PolyIOPErrors> {
+        let mut rng = test_rng();
+        let circuit = sbox_circuit()?;
+
+        let xs: Vec<Fr> = (0..1 << num_instance_vars)
+            .map(|_| Fr::rand(&mut rng))
+            .collect();
+        let inputs = Arc::new(DenseMultilinearExtension::from_evaluations_vec(
+            1 + num_instance_vars,
+            xs.iter().flat_map(|x| [*x, Fr::zero()]).collect(),
+        ));
+
+        let srs = Kzg::gen_srs_for_testing(&mut rng, inputs.num_vars)?;
+        let (ck, vk) = Kzg::trim(&srs, None, Some(inputs.num_vars))?;
+        let inputs_comm = Kzg::commit(&ck, &inputs)?;
+
+        let mut transcript = <PolyIOP<Fr> as Gkr<Fr>>::init_transcript();
+        let (proof, outputs) = <PolyIOP<Fr> as Gkr<Fr>>::prove(&circuit, &inputs, &mut transcript)?;
+        for (j, x) in xs.iter().enumerate() {
+            assert_eq!(outputs.evaluations[2 * j], x.pow([5]));
+        }
+
+        let mut transcript = <PolyIOP<Fr> as Gkr<Fr>>::init_transcript();
+        let sub_claim =
+            <PolyIOP<Fr> as Gkr<Fr>>::verify(&circuit, &outputs, &proof, &mut transcript)?;
+        // discharge the claim on the inputs with an opening
+        let (opening, eval) = Kzg::open(&ck, &inputs, &sub_claim.point)?;
+        assert_eq!(eval, sub_claim.expected_evaluation);
+        assert!(Kzg::verify(
+            &vk,
+            &inputs_comm,
+            &sub_claim.point,
+            &eval,
+            &opening
+        )?);
+
+        // bad path: wrong outputs
+        let mut bad_outputs = (*outputs).clone();
+        bad_outputs.evaluations[0] += Fr::one();
+        let mut transcript = <PolyIOP<Fr> as Gkr<Fr>>::init_transcript();
+        assert!(<PolyIOP<Fr> as Gkr<Fr>>::verify(
+            &circuit,
+            &Arc::new(bad_outputs),
+            &proof,
+            &mut transcript
+        )
+        .is_err());
+
+        // bad path: wrong inputs
+        let mut bad_inputs = (*inputs).clone();
+        bad_inputs.evaluations[0] += Fr::one();
+        let mut transcript = <PolyIOP<Fr> as Gkr<Fr>>::init_transcript();
+        let sub_claim =
+            <PolyIOP<Fr> as Gkr<Fr>>::verify(&circuit, &outputs, &proof, &mut transcript)?;
+        assert_ne!(
+            bad_inputs.evaluate(&sub_claim.point),
+            sub_claim.expected_evaluation
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_single_instance() -> Result<(), PolyIOPErrors> {
+        let _trace = crate::trace_guard();
+        test_gkr(0)
+    }
+
+    #[test]
+    fn test_many_instances() -> Result<(), PolyIOPErrors> {
+        let _trace = crate::trace_guard();
+        test_gkr(6)
+    }
+
+    #[test]
+    fn test_invalid_circuit() {
+        assert!(LayeredCircuit::new(2, vec![vec![Gate::Add(0, 2)]]).is_err());
+        assert!(LayeredCircuit::new(2, vec![vec![Gate::Add(0, 1)], vec![]]).is_err());
+        assert!(LayeredCircuit::new(2, vec![]).is_err());
+    }
+}
@@ -0,0 +1,546 @@
+// Copyright (c) 2023 Espresso Systems (espressosys.com)
+// This file is part of the HyperPlonk library.
+
+// You should have received a copy of the MIT License
+// along with the HyperPlonk library. If not, see <https://mit-license.org/>.
+
+//! Main module for the Lookup Check protocol
+
+use self::util::{
+    build_table_poly, build_witness_poly, compute_multiplicity_poly, compute_table_frac_poly,
+    compute_witness_frac_poly,
+};
+use crate::{
+    pcs::PolynomialCommitmentScheme,
+    poly_iop::{
+        errors::PolyIOPErrors,
+        sum_check::{SumCheck, SumCheckSubClaim},
+        PolyIOP,
+    },
+};
+use arithmetic::{eq_eval, VPAuxInfo};
+use ark_ff::PrimeField;
+use ark_poly::DenseMultilinearExtension;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_std::{end_timer, start_timer};
+use std::sync::Arc;
+use transcript::Transcript;
+
+mod util;
+
+/// A LookupCheck w.r.t. `(fs, q, t)` proves that every evaluation of
+/// `(f1, ..., fk)` on the boolean hypercube where the selector `q` is one is an
+/// evaluation of the table `t` on the boolean hypercube, which may have a
+/// different number of variables. Without a selector, every evaluation is
+/// looked up.
+///
+/// It implements the logarithmic derivative (LogUp) lookup of
+/// [Haböck22](https://eprint.iacr.org/2022/1530.pdf): with the multiplicity
+/// `m(y)` of each table entry in the witnesses, the lookup holds if and only if
+///   \sum_{x \in {0,1}^n} q(x) * \sum_i 1 / (beta + fi(x))
+/// = \sum_{y \in {0,1}^m} m(y) / (beta + t(y))
+/// for a random `beta`.
+///
+/// Prover steps:
+/// 1. build and commit to the multiplicity polynomial `m(y)`, and generate
+///    `beta` from the transcript
+/// 2. build and commit to the fractional polynomials `h_f(x) = q(x) * \sum_i
+///    1 / (beta + fi(x))` and `h_t(y) = m(y) / (beta + t(y))`, and generate
+///    `alpha`, `r_f` and `r_t` from the transcript
+/// 3. generate sumcheck proofs that the following virtual polynomials have the
+///    same sum
+///
+///    W(x) = h_f(x) + alpha * eq(x, r_f) * [h_f(x) * \prod_i (beta + fi(x))
+///     - q(x) * \sum_i \prod_{j != i} (beta + fj(x))]
+///
+///    T(y) = h_t(y) + alpha * eq(y, r_t) * [h_t(y) * (beta + t(y)) - m(y)]
+///
+///    where the terms in alpha check that the fractional polynomials are well
+///    formed on the boolean hypercube, as in a zero check.
+///
+/// Verifier steps:
+/// 1. Extract the commitments from the proof, push them to the transcript, and
+///    generate the same challenges
+/// 2. `verify` the two sumcheck proofs against the sum of the first one, and
+///    generate the subclaims for polynomial evaluations
+pub trait LookupCheck<F, PCS>: SumCheck<F>
+where
+    F: PrimeField,
+    PCS: PolynomialCommitmentScheme<F>,
+{
+    type LookupCheckSubClaim;
+    type LookupCheckProof: CanonicalSerialize + CanonicalDeserialize;
+
+    /// Initialize the system with a transcript
+    ///
+    /// This function is optional -- in the case where a LookupCheck is
+    /// an building block for a more complex protocol, the transcript
+    /// may be initialized by this complex protocol, and passed to the
+    /// LookupCheck prover/verifier.
+    fn init_transcript() -> Self::Transcript;
+
+    /// Inputs:
+    /// - pcs_param: PCS committing key
+    /// - fxs = (f1, ..., fk), the witnesses looked up
+    /// - selector: the rows of the witnesses that are looked up, all of them if
+    ///   `None`
+    /// - table: the table
+    /// - transcript: the IOP transcript
+    ///
+    /// Outputs:
+    /// - a lookup check proof proving that the evaluations of fxs are table
+    ///   entries
+    /// - the multiplicity polynomial `m(y)`
+    /// - the witness fractional polynomial `h_f(x)`
+    /// - the table fractional polynomial `h_t(y)`
+    ///
+    /// Cost: O(kN + M)
+    #[allow(clippy::type_complexity)]
+    fn prove(
+        pcs_param: &PCS::ProverParam,
+        fxs: &[Self::MultilinearExtension],
+        selector: Option<&Self::MultilinearExtension>,
+        table: &Self::MultilinearExtension,
+        transcript: &mut Self::Transcript,
+    ) -> Result<
+        (
+            Self::LookupCheckProof,
+            Self::MultilinearExtension,
+            Self::MultilinearExtension,
+            Self::MultilinearExtension,
+        ),
+        PolyIOPErrors,
+    >;
+
+    /// Verify that the evaluations of (f1, ..., fk) are table entries.
+    ///
+    /// `aux_info` describes the witnesses, whose `max_degree` is `k + 1`, and
+    /// `table_aux_info` describes the table, whose `max_degree` is `2`.
+    fn verify(
+        proof: &Self::LookupCheckProof,
+        aux_info: &VPAuxInfo<F>,
+        table_aux_info: &VPAuxInfo<F>,
+        transcript: &mut Self::Transcript,
+    ) -> Result<Self::LookupCheckSubClaim, PolyIOPErrors>;
+}
+
+/// A lookup check subclaim consists of
+/// - the SubClaims from the sumchecks on the witness and the table sides
+/// - the random challenges `beta`, `alpha`, `r_f` and `r_t`
+///
+/// The caller checks the expected evaluations of the subclaims against
+/// `witness_evaluation` and `table_evaluation`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct LookupCheckSubClaim<F: PrimeField> {
+    /// the SubClaim from the SumCheck of `W(x)`
+    pub witness_sub_claim: SumCheckSubClaim<F>,
+    /// the SubClaim from the SumCheck of `T(y)`
+    pub table_sub_claim: SumCheckSubClaim<F>,
+    /// the challenge of the fractions
+    pub beta: F,
+    /// the challenge batching the well formedness of the fractions
+    pub alpha: F,
+    /// the challenge `r_f` of the witness side
+    pub witness_challenge: Vec<F>,
+    /// the challenge `r_t` of the table side
+    pub table_challenge: Vec<F>,
+}
+
+impl<F: PrimeField> LookupCheckSubClaim<F> {
+    /// The evaluation of `W(x)` at the point of the witness subclaim, given
+    /// the evaluations of `f1, ..., fk`, `q` and `h_f` at that point. The
+    /// evaluation of `q` is one if there is no selector.
+    pub fn witness_evaluation(
+        &self,
+        fx_evals: &[F],
+        selector_eval: F,
+        frac_eval: F,
+    ) -> Result<F, PolyIOPErrors> {
+        let eq_x_r = eq_eval(&self.witness_sub_claim.point, &self.witness_challenge)?;
+        let shifted: Vec<F> = fx_evals.iter().map(|f| self.beta + f).collect();
+        let prod: F = shifted.iter().product();
+        let partial_prods: F = (0..shifted.len())
+            .map(|i| {
+                shifted
+                    .iter()
+                    .enumerate()
+                    .filter(|(j, _)| *j != i)
+                    .map(|(_, s)| s)
+                    .product::<F>()
+            })
+            .sum();
+        Ok(frac_eval + self.alpha * eq_x_r * (frac_eval * prod - selector_eval * partial_prods))
+    }
+
+    /// The evaluation of `T(y)` at the point of the table subclaim, given
+    /// the evaluations of `t`, `m` and `h_t` at that point.
+    pub fn table_evaluation(
+        &self,
+        table_eval: F,
+        multiplicity_eval: F,
+        frac_eval: F,
+    ) -> Result<F, PolyIOPErrors> {
+        let eq_y_r = eq_eval(&self.table_sub_claim.point, &self.table_challenge)?;
+        Ok(frac_eval
+            + self.alpha * eq_y_r * (frac_eval * (self.beta + table_eval) - multiplicity_eval))
+    }
+}
+
+/// A lookup check proof consists of
+/// - the sumcheck proofs on the witness and the table sides
+/// - a commitment to the multiplicity polynomial
+/// - commitments to the witness and table fractional polynomials
+#[derive(Clone, Debug, Default, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct LookupCheckProof<F: PrimeField, PCS: PolynomialCommitmentScheme<F>, SC: SumCheck<F>> {
+    pub witness_sum_check_proof: SC::SumCheckProof,
+    pub table_sum_check_proof: SC::SumCheckProof,
+    pub multiplicity_comm: PCS::Commitment,
+    pub witness_frac_comm: PCS::Commitment,
+    pub table_frac_comm: PCS::Commitment,
+}
+
+impl<F, PCS, T> LookupCheck<F, PCS> for PolyIOP<F, T>
+where
+    F: PrimeField,
+    PCS: PolynomialCommitmentScheme<F, Polynomial = Arc<DenseMultilinearExtension<F>>>,
+    T: Transcript<F>,
+{
+    type LookupCheckSubClaim = LookupCheckSubClaim<F>;
+    type LookupCheckProof = LookupCheckProof<F, PCS, Self>;
+
+    fn init_transcript() -> Self::Transcript {
+        T::new(b"Initializing LookupCheck transcript")
+    }
+
+    fn prove(
+        pcs_param: &PCS::ProverParam,
+        fxs: &[Self::MultilinearExtension],
+        selector: Option<&Self::MultilinearExtension>,
+        table: &Self::MultilinearExtension,
+        transcript: &mut Self::Transcript,
+    ) -> Result<
+        (
+            Self::LookupCheckProof,
+            Self::MultilinearExtension,
+            Self::MultilinearExtension,
+            Self::MultilinearExtension,
+        ),
+        PolyIOPErrors,
+    > {
+        let start = start_timer!(|| "lookup_check prove");
+
+        if fxs.is_empty() {
+            return Err(PolyIOPErrors::InvalidParameters("fxs is empty".to_string()));
+        }
+        for fx in fxs.iter().chain(selector) {
+            if fx.num_vars != fxs[0].num_vars {
+                return Err(PolyIOPErrors::InvalidParameters(
+                    "fxs and selector have different number of variables".to_string(),
+                ));
+            }
+        }
+
+        // the multiplicity polynomial
+        let multiplicity = compute_multiplicity_poly(fxs, selector, table)?;
+        let multiplicity_comm = PCS::commit(pcs_param, &multiplicity)?;
+        transcript.append_serializable_element(b"m(x)", &multiplicity_comm)?;
+        let beta = transcript.get_and_append_challenge(b"beta")?;
+
+        // the fractional polynomials
+        let witness_frac = compute_witness_frac_poly(&beta, fxs, selector)?;
+        let table_frac = compute_table_frac_poly(&beta, table, &multiplicity)?;
+        let witness_frac_comm = PCS::commit(pcs_param, &witness_frac)?;
+        let table_frac_comm = PCS::commit(pcs_param, &table_frac)?;
+        transcript.append_serializable_element(b"h_f(x)", &witness_frac_comm)?;
+        transcript.append_serializable_element(b"h_t(x)", &table_frac_comm)?;
+        let alpha = transcript.get_and_append_challenge(b"alpha")?;
+        let r_f = transcript.get_and_append_challenge_vectors(b"r_f", fxs[0].num_vars)?;
+        let r_t = transcript.get_and_append_challenge_vectors(b"r_t", table.num_vars)?;
+
+        // the sumchecks of both sides
+        let witness_poly = build_witness_poly(fxs, selector, &witness_frac, &beta, &alpha, &r_f)?;
+        let witness_sum_check_proof = <Self as SumCheck<F>>::prove(&witness_poly, transcript)?;
+        let table_poly = build_table_poly(table, &multiplicity, &table_frac, &beta, &alpha, &r_t)?;
+        let table_sum_check_proof = <Self as SumCheck<F>>::prove(&table_poly, transcript)?;
+
+        end_timer!(start);
+
+        Ok((
+            LookupCheckProof {
+                witness_sum_check_proof,
+                table_sum_check_proof,
+                multiplicity_comm,
+                witness_frac_comm,
+                table_frac_comm,
+            },
+            multiplicity,
+            witness_frac,
+            table_frac,
+        ))
+    }
+
+    fn verify(
+        proof: &Self::LookupCheckProof,
+        aux_info: &VPAuxInfo<F>,
+        table_aux_info: &VPAuxInfo<F>,
+        transcript: &mut Self::Transcript,
+    ) -> Result<Self::LookupCheckSubClaim, PolyIOPErrors> {
+        let start = start_timer!(|| "lookup_check verify");
+
+        if proof.witness_sum_check_proof.proofs.is_empty() {
+            return Err(PolyIOPErrors::InvalidProof(
+                "witness sum check proof is empty".to_string(),
+            ));
+        }
+
+        // update transcript and generate challenges
+        transcript.append_serializable_element(b"m(x)", &proof.multiplicity_comm)?;
+        let beta = transcript.get_and_append_challenge(b"beta")?;
+        transcript.append_serializable_element(b"h_f(x)", &proof.witness_frac_comm)?;
+        transcript.append_serializable_element(b"h_t(x)", &proof.table_frac_comm)?;
+        let alpha = transcript.get_and_append_challenge(b"alpha")?;
+        let witness_challenge =
+            transcript.get_and_append_challenge_vectors(b"r_f", aux_info.num_variables)?;
+        let table_challenge =
+            transcript.get_and_append_challenge_vectors(b"r_t", table_aux_info.num_variables)?;
+
+        // both sides have the same sum, and their degrees are increased by
+        // eq(x, r) which is 1
+        let sum = <Self as SumCheck<F>>::extract_sum(&proof.witness_sum_check_proof);
+        let mut witness_aux_info = aux_info.clone();
+        witness_aux_info.max_degree += 1;
+        let witness_sub_claim = <Self as SumCheck<F>>::verify(
+            sum,
+            &proof.witness_sum_check_proof,
+            &witness_aux_info,
+            transcript,
+        )?;
+        let mut table_aux_info = table_aux_info.clone();
+        table_aux_info.max_degree += 1;
+        let table_sub_claim = <Self as SumCheck<F>>::verify(
+            sum,
+            &proof.table_sum_check_proof,
+            &table_aux_info,
+            transcript,
+        )?;
+
+        end_timer!(start);
+
+        Ok(LookupCheckSubClaim {
+            witness_sub_claim,
+            table_sub_claim,
+            beta,
+            alpha,
+            witness_challenge,
+            table_challenge,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::LookupCheck;
+    use crate::{
+        pcs::{prelude::MultilinearKzgPCS, PolynomialCommitmentScheme},
+        poly_iop::{errors::PolyIOPErrors, PolyIOP},
+    };
+    use arithmetic::{evaluate_opt, VPAuxInfo};
+    use ark_bls12_381::{Bls12_381, Fr};
+    use ark_ff::PrimeField;
+    use ark_poly::{DenseMultilinearExtension, MultilinearExtension};
+    use ark_std::{rand::Rng, test_rng, UniformRand};
+    use std::{marker::PhantomData, sync::Arc};
+    use transcript::Transcript;
+
+    type Kzg = MultilinearKzgPCS<Bls12_381>;
+
+    /// Prove the lookup of `fxs` and check the subclaims against `claimed_fxs`.
+    fn test_lookup_check_helper<F, PCS>(
+        pcs_param: &PCS::ProverParam,
+        fxs: &[Arc<DenseMultilinearExtension<F>>],
+        claimed_fxs: &[Arc<DenseMultilinearExtension<F>>],
+        selector: Option<&Arc<DenseMultilinearExtension<F>>>,
+        table: &Arc<DenseMultilinearExtension<F>>,
+    ) -> Result<(), PolyIOPErrors>
+    where
+        F: PrimeField,
+        PCS: PolynomialCommitmentScheme<F, Polynomial = Arc<DenseMultilinearExtension<F>>>,
+    {
+        let aux_info = VPAuxInfo {
+            max_degree: fxs.len() + 1,
+            num_variables: fxs[0].num_vars,
+            phantom: PhantomData,
+        };
+        let table_aux_info = VPAuxInfo {
+            max_degree: 2,
+            num_variables: table.num_vars,
+            phantom: PhantomData,
+        };
+
+        // prover
+        let mut transcript = <PolyIOP<F> as LookupCheck<F, PCS>>::init_transcript();
+        transcript.append_message(b"testing", b"initializing transcript for testing")?;
+        let (proof, multiplicity, witness_frac, table_frac) =
+            <PolyIOP<F> as LookupCheck<F, PCS>>::prove(
+                pcs_param,
+                fxs,
+                selector,
+                table,
+                &mut transcript,
+            )?;
+
+        // verifier
+        let mut transcript = <PolyIOP<F> as LookupCheck<F, PCS>>::init_transcript();
+        transcript.append_message(b"testing", b"initializing transcript for testing")?;
+        let sub_claim = <PolyIOP<F> as LookupCheck<F, PCS>>::verify(
+            &proof,
+            &aux_info,
+            &table_aux_info,
+            &mut transcript,
+        )?;
+
+        // check the subclaims
+        let point = &sub_claim.witness_sub_claim.point;
+        let fx_evals: Vec<_> = claimed_fxs
+            .iter()
+            .map(|fx| evaluate_opt(fx, point))
+            .collect();
+        let selector_eval = selector.map_or(F::one(), |q| evaluate_opt(q, point));
+        if sub_claim.witness_evaluation(
+            &fx_evals,
+            selector_eval,
+            evaluate_opt(&witness_frac, point),
+        )? != sub_claim.witness_sub_claim.expected_evaluation
+        {
+            return Err(PolyIOPErrors::InvalidVerifier(
+                "wrong witness subclaim".to_string(),
+            ));
+        }
+        let point = &sub_claim.table_sub_claim.point;
+        if sub_claim.table_evaluation(
+            evaluate_opt(table, point),
+            evaluate_opt(&multiplicity, point),
+            evaluate_opt(&table_frac, point),
+        )? != sub_claim.table_sub_claim.expected_evaluation
+        {
+            return Err(PolyIOPErrors::InvalidVerifier(
+                "wrong table subclaim".to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// `num_witnesses` random lookups of `nv` variables into `table`.
+    fn random_lookups<R: Rng>(
+        nv: usize,
+        table: &DenseMultilinearExtension<Fr>,
+        num_witnesses: usize,
+        rng: &mut R,
+    ) -> Vec<Arc<DenseMultilinearExtension<Fr>>> {
+        (0..num_witnesses)
+            .map(|_| {
+                Arc::new(DenseMultilinearExtension::from_evaluations_vec(
+                    nv,
+                    (0..1 << nv)
+                        .map(|_| table.evaluations[rng.gen_range(0..table.evaluations.len())])
+                        .collect(),
+                ))
+            })
+            .collect()
+    }
+
+    fn test_lookup_check(nv: usize, table_nv: usize) -> Result<(), PolyIOPErrors> {
+        let mut rng = test_rng();
+
+        let max_nv = nv.max(table_nv);
+        let srs = MultilinearKzgPCS::<Bls12_381>::gen_srs_for_testing(&mut rng, max_nv)?;
+        let (pcs_param, _) = MultilinearKzgPCS::<Bls12_381>::trim(&srs, None, Some(max_nv))?;
+
+        {
+            // good path: one and three witnesses
+            for num_witnesses in [1, 3] {
+                let table = Arc::new(DenseMultilinearExtension::rand(table_nv, &mut rng));
+                let fxs = random_lookups(nv, &table, num_witnesses, &mut rng);
+                test_lookup_check_helper::<Fr, Kzg>(&pcs_param, &fxs, &fxs, None, &table)?;
+            }
+        }
+
+        {
+            // good path: a table with repeated entries
+            let mut evals = DenseMultilinearExtension::<Fr>::rand(table_nv, &mut rng).evaluations;
+            evals[0] = evals[evals.len() - 1];
+            let table = Arc::new(DenseMultilinearExtension::from_evaluations_vec(
+                table_nv, evals,
+            ));
+            let fxs = random_lookups(nv, &table, 2, &mut rng);
+            test_lookup_check_helper::<Fr, Kzg>(&pcs_param, &fxs, &fxs, None, &table)?;
+        }
+
+        {
+            // bad path 1: a witness entry is not in the table
+            let table = Arc::new(DenseMultilinearExtension::rand(table_nv, &mut rng));
+            let mut fxs = random_lookups(nv, &table, 2, &mut rng);
+            fxs[1] = Arc::new(DenseMultilinearExtension::rand(nv, &mut rng));
+            assert!(
+                test_lookup_check_helper::<Fr, Kzg>(&pcs_param, &fxs, &fxs, None, &table).is_err()
+            );
+        }
+
+        {
+            // bad path 2: the subclaims are checked against other witnesses
+            let table = Arc::new(DenseMultilinearExtension::rand(table_nv, &mut rng));
+            let fxs = random_lookups(nv, &table, 2, &mut rng);
+            let other_fxs = random_lookups(nv, &table, 2, &mut rng);
+            assert!(test_lookup_check_helper::<Fr, Kzg>(
+                &pcs_param, &fxs, &other_fxs, None, &table
+            )
+            .is_err());
+        }
+
+        {
+            // good path: the unselected rows are not in the table
+            let table = Arc::new(DenseMultilinearExtension::rand(table_nv, &mut rng));
+            let mut fxs = random_lookups(nv, &table, 2, &mut rng);
+            let selector = Arc::new(DenseMultilinearExtension::from_evaluations_vec(
+                nv,
+                (0..1 << nv).map(|x| Fr::from((x % 2) as u64)).collect(),
+            ));
+            Arc::make_mut(&mut fxs[0]).evaluations[0] = Fr::rand(&mut rng);
+            test_lookup_check_helper::<Fr, Kzg>(&pcs_param, &fxs, &fxs, Some(&selector), &table)?;
+
+            // bad path 3: but they are without the selector
+            assert!(
+                test_lookup_check_helper::<Fr, Kzg>(&pcs_param, &fxs, &fxs, None, &table).is_err()
+            );
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_trivial_polynomial() -> Result<(), PolyIOPErrors> {
+        let _trace = crate::trace_guard();
+        test_lookup_check(1, 1)
+    }
+
+    #[test]
+    fn test_normal_polynomial() -> Result<(), PolyIOPErrors> {
+        let _trace = crate::trace_guard();
+        test_lookup_check(5, 5)
+    }
+
+    #[test]
+    fn test_different_table_sizes() -> Result<(), PolyIOPErrors> {
+        let _trace = crate::trace_guard();
+        // smaller and larger tables than the witnesses
+        test_lookup_check(6, 3)?;
+        test_lookup_check(3, 6)
+    }
+
+    #[test]
+    fn zero_polynomial_should_error() -> Result<(), PolyIOPErrors> {
+        assert!(test_lookup_check(0, 0).is_err());
+        Ok(())
+    }
+}
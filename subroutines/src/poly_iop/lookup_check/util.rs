@@ -0,0 +1,201 @@
+// Copyright (c) 2023 Espresso Systems (espressosys.com)
+// This file is part of the HyperPlonk library.
+
+// You should have received a copy of the MIT License
+// along with the HyperPlonk library. If not, see <https://mit-license.org/>.
+
+//! This module implements useful functions for the lookup check protocol.
+
+use crate::poly_iop::errors::PolyIOPErrors;
+use arithmetic::{build_eq_x_r, VirtualPolynomial};
+use ark_ff::{batch_inversion, PrimeField};
+use ark_poly::DenseMultilinearExtension;
+use ark_std::{end_timer, start_timer};
+use std::{collections::HashMap, sync::Arc};
+
+/// Compute the multiplicity polynomial `m(y)`, i.e., the number of times the
+/// table entry `t(y)` appears in `f1, ..., fk`, where each occurrence at `x`
+/// is weighted by the selector `q(x)`. If an entry appears several times in
+/// the table, all its occurrences in the witnesses are counted at its first
+/// index.
+///
+/// The caller needs to sanity-check that fxs is not empty and that the number
+/// of variables match within fxs and the selector.
+pub(super) fn compute_multiplicity_poly<F: PrimeField>(
+    fxs: &[Arc<DenseMultilinearExtension<F>>],
+    selector: Option<&Arc<DenseMultilinearExtension<F>>>,
+    table: &Arc<DenseMultilinearExtension<F>>,
+) -> Result<Arc<DenseMultilinearExtension<F>>, PolyIOPErrors> {
+    let start = start_timer!(|| "compute m(x)");
+
+    let mut indices = HashMap::with_capacity(table.evaluations.len());
+    for (i, t) in table.evaluations.iter().enumerate() {
+        indices.entry(*t).or_insert(i);
+    }
+    let mut counts = vec![F::zero(); table.evaluations.len()];
+    for fx in fxs.iter() {
+        for (x, f) in fx.evaluations.iter().enumerate() {
+            let q = selector.map_or(F::one(), |s| s.evaluations[x]);
+            if q.is_zero() {
+                continue;
+            }
+            match indices.get(f) {
+                Some(&i) => counts[i] += q,
+                None => {
+                    return Err(PolyIOPErrors::InvalidParameters(format!(
+                        "witness entry {} is not in the table",
+                        f
+                    )))
+                },
+            }
+        }
+    }
+
+    end_timer!(start);
+    Ok(Arc::new(DenseMultilinearExtension::from_evaluations_vec(
+        table.num_vars,
+        counts,
+    )))
+}
+
+/// Compute the witness fractional polynomial `h_f(x) = q(x) * \sum_i 1 /
+/// (beta + fi(x))` for all x \in {0,1}^n, where the selector `q(x)` is one
+/// if there is none.
+pub(super) fn compute_witness_frac_poly<F: PrimeField>(
+    beta: &F,
+    fxs: &[Arc<DenseMultilinearExtension<F>>],
+    selector: Option<&Arc<DenseMultilinearExtension<F>>>,
+) -> Result<Arc<DenseMultilinearExtension<F>>, PolyIOPErrors> {
+    let start = start_timer!(|| "compute h_f(x)");
+
+    let mut denoms: Vec<F> = fxs
+        .iter()
+        .flat_map(|fx| fx.evaluations.iter().map(|f| *beta + f))
+        .collect();
+    if denoms.iter().any(|d| d.is_zero()) {
+        return Err(PolyIOPErrors::InvalidParameters(
+            "beta + fxs has zero entries in the boolean hypercube".to_string(),
+        ));
+    }
+    batch_inversion(&mut denoms);
+
+    let len = 1 << fxs[0].num_vars;
+    let mut evals = vec![F::zero(); len];
+    for chunk in denoms.chunks(len) {
+        for (eval, d) in evals.iter_mut().zip(chunk.iter()) {
+            *eval += d;
+        }
+    }
+    if let Some(selector) = selector {
+        for (eval, q) in evals.iter_mut().zip(selector.evaluations.iter()) {
+            *eval *= q;
+        }
+    }
+
+    end_timer!(start);
+    Ok(Arc::new(DenseMultilinearExtension::from_evaluations_vec(
+        fxs[0].num_vars,
+        evals,
+    )))
+}
+
+/// Compute the table fractional polynomial `h_t(y) = m(y) / (beta + t(y))`
+/// for all y \in {0,1}^m
+pub(super) fn compute_table_frac_poly<F: PrimeField>(
+    beta: &F,
+    table: &Arc<DenseMultilinearExtension<F>>,
+    multiplicity: &Arc<DenseMultilinearExtension<F>>,
+) -> Result<Arc<DenseMultilinearExtension<F>>, PolyIOPErrors> {
+    let start = start_timer!(|| "compute h_t(x)");
+
+    let mut evals: Vec<F> = table.evaluations.iter().map(|t| *beta + t).collect();
+    if evals.iter().any(|d| d.is_zero()) {
+        return Err(PolyIOPErrors::InvalidParameters(
+            "beta + table has zero entries in the boolean hypercube".to_string(),
+        ));
+    }
+    batch_inversion(&mut evals);
+    for (eval, m) in evals.iter_mut().zip(multiplicity.evaluations.iter()) {
+        *eval *= m;
+    }
+
+    end_timer!(start);
+    Ok(Arc::new(DenseMultilinearExtension::from_evaluations_vec(
+        table.num_vars,
+        evals,
+    )))
+}
+
+/// Build the virtual polynomial summed on the witness side
+///
+/// W(x) = h_f(x) + alpha * eq(x, r) * [h_f(x) * \prod_i (beta + fi(x))
+///     - q(x) * \sum_i \prod_{j != i} (beta + fj(x))]
+pub(super) fn build_witness_poly<F: PrimeField>(
+    fxs: &[Arc<DenseMultilinearExtension<F>>],
+    selector: Option<&Arc<DenseMultilinearExtension<F>>>,
+    frac_poly: &Arc<DenseMultilinearExtension<F>>,
+    beta: &F,
+    alpha: &F,
+    r: &[F],
+) -> Result<VirtualPolynomial<F>, PolyIOPErrors> {
+    let eq_x_r = build_eq_x_r(r)?;
+    let shifted: Vec<_> = fxs.iter().map(|fx| shift(beta, fx)).collect();
+
+    let mut poly = VirtualPolynomial::new_from_mle(frac_poly, F::one());
+    poly.add_mle_list(
+        [eq_x_r.clone(), frac_poly.clone()]
+            .into_iter()
+            .chain(shifted.iter().cloned()),
+        *alpha,
+    )?;
+    for i in 0..shifted.len() {
+        poly.add_mle_list(
+            iter_except(&shifted, i)
+                .chain([eq_x_r.clone()])
+                .chain(selector.cloned()),
+            -*alpha,
+        )?;
+    }
+    Ok(poly)
+}
+
+/// Build the virtual polynomial summed on the table side
+///
+/// T(y) = h_t(y) + alpha * eq(y, r) * [h_t(y) * (beta + t(y)) - m(y)]
+pub(super) fn build_table_poly<F: PrimeField>(
+    table: &Arc<DenseMultilinearExtension<F>>,
+    multiplicity: &Arc<DenseMultilinearExtension<F>>,
+    frac_poly: &Arc<DenseMultilinearExtension<F>>,
+    beta: &F,
+    alpha: &F,
+    r: &[F],
+) -> Result<VirtualPolynomial<F>, PolyIOPErrors> {
+    let eq_x_r = build_eq_x_r(r)?;
+
+    let mut poly = VirtualPolynomial::new_from_mle(frac_poly, F::one());
+    poly.add_mle_list(
+        [eq_x_r.clone(), frac_poly.clone(), shift(beta, table)],
+        *alpha,
+    )?;
+    poly.add_mle_list([eq_x_r, multiplicity.clone()], -*alpha)?;
+    Ok(poly)
+}
+
+/// `beta + f(x)`
+fn shift<F: PrimeField>(
+    beta: &F,
+    poly: &Arc<DenseMultilinearExtension<F>>,
+) -> Arc<DenseMultilinearExtension<F>> {
+    Arc::new(DenseMultilinearExtension::from_evaluations_vec(
+        poly.num_vars,
+        poly.evaluations.iter().map(|f| *beta + f).collect(),
+    ))
+}
+
+fn iter_except<T: Clone>(items: &[T], i: usize) -> impl Iterator<Item = T> + '_ {
+    items
+        .iter()
+        .enumerate()
+        .filter(move |(j, _)| *j != i)
+        .map(|(_, item)| item.clone())
+}
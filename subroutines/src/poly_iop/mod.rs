@@ -5,32 +5,63 @@
 // along with the HyperPlonk library. If not, see <https://mit-license.org/>.
 
 use ark_ff::PrimeField;
+use derivative::Derivative;
+use prod_check::CommittedProduct;
 use std::marker::PhantomData;
+use transcript::IOPTranscript;
 
 mod errors;
-// mod perm_check;
+mod frac_sum_check;
+mod gkr;
+mod lookup_check;
+mod perm_check;
+mod plookup_check;
 pub mod prelude;
-// mod prod_check;
+mod prod_check;
+mod range_check;
 mod structs;
 mod sum_check;
 mod utils;
-// mod zero_check;
+mod zero_check;
 
-#[derive(Clone, Debug, Default, Copy, PartialEq, Eq)]
+pub(crate) use structs::{IOPProverState, IOPVerifierState};
+pub(crate) use sum_check::{SumCheckProver, SumCheckVerifier};
+
+#[derive(Derivative)]
+#[derivative(
+    Clone(bound = ""),
+    Debug(bound = ""),
+    Default(bound = ""),
+    Copy(bound = ""),
+    PartialEq(bound = ""),
+    Eq(bound = "")
+)]
 /// Struct for PolyIOP protocol.
 /// It has an associated type `F` that defines the prime field the multi-variate
-/// polynomial operates on.
+/// polynomial operates on, and a transcript type `T` that the Fiat-Shamir
+/// challenges are derived from (the Merlin-backed `IOPTranscript` by default).
+/// The marker type `P` selects the argument behind the ProductCheck and
+/// PermutationCheck protocols: `CommittedProduct` (the default) commits to the
+/// product polynomial, `GkrProduct` proves the product with a GKR circuit and
+/// commits to nothing, and `GkrFraction` replaces the product of the
+/// PermutationCheck with a FractionalSumCheck.
 ///
 /// An PolyIOP may be instantiated with one of the following:
 /// - SumCheck protocol.
 /// - ZeroCheck protocol.
+/// - FractionalSumCheck protocol.
 /// - PermutationCheck protocol.
+/// - LookupCheck protocol.
+/// - PlookupCheck protocol.
+/// - RangeCheck protocol.
+/// - Gkr protocol.
 ///
 /// Those individual protocol may have similar or identical APIs.
 /// The systematic way to invoke specific protocol is, for example
 ///     `<PolyIOP<F> as SumCheck<F>>::prove()`
-pub struct PolyIOP<F: PrimeField> {
-    /// Associated field
+pub struct PolyIOP<F: PrimeField, T = IOPTranscript<F>, P = CommittedProduct> {
+    /// Associated field, transcript and product argument
     #[doc(hidden)]
-    phantom: PhantomData<F>,
+    #[allow(clippy::type_complexity)]
+    phantom: PhantomData<(F, fn() -> (T, P))>,
 }
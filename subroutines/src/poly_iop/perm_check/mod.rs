@@ -6,31 +6,53 @@
 
 //! Main module for the Permutation Check protocol
 
-use self::util::computer_nums_and_denoms;
+use self::util::{check_inputs, computer_nums_and_denoms};
 use crate::{
-    pcs::PolynomialCommitmentScheme,
-    poly_iop::{errors::PolyIOPErrors, prelude::ProductCheck, PolyIOP},
+    pcs::{HidingPolynomialCommitmentScheme, PolynomialCommitmentScheme},
+    poly_iop::{
+        errors::PolyIOPErrors,
+        frac_sum_check::{FractionalSumCheck, GkrFraction},
+        prelude::ProductCheck,
+        prod_check::{ProductArgument, ZkProductCheckOracles, ZkProductCheckProof},
+        sum_check::SumCheckMaskClaims,
+        zero_check::ZeroCheck,
+        PolyIOP,
+    },
 };
-use ark_ec::pairing::Pairing;
+use arithmetic::VPAuxInfo;
+use ark_ff::PrimeField;
 use ark_poly::DenseMultilinearExtension;
-use ark_std::{end_timer, start_timer};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_std::{end_timer, rand::Rng, start_timer};
 use std::sync::Arc;
-use transcript::IOPTranscript;
+use transcript::Transcript;
 
 /// A permutation subclaim consists of
 /// - the SubClaim from the ProductCheck
 /// - Challenges beta and gamma
 #[derive(Clone, Debug, Default, PartialEq)]
-pub struct PermutationCheckSubClaim<E, PCS, PC>
+pub struct PermutationCheckSubClaim<F, PCS, PC>
 where
-    E: Pairing,
-    PC: ProductCheck<E, PCS>,
-    PCS: PolynomialCommitmentScheme<E>,
+    F: PrimeField,
+    PC: ProductCheck<F, PCS>,
+    PCS: PolynomialCommitmentScheme<F>,
 {
     /// the SubClaim from the ProductCheck
     pub product_check_sub_claim: PC::ProductCheckSubClaim,
     /// Challenges beta and gamma
-    pub challenges: (E::ScalarField, E::ScalarField),
+    pub challenges: (F, F),
+}
+
+/// A permutation subclaim from a fractional sum consists of
+/// - the SubClaim from the FractionalSumCheck, whose denominators are the
+///   numerators and the denominators of `computer_nums_and_denoms`
+/// - Challenges beta and gamma
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct FractionalPermutationCheckSubClaim<F: PrimeField, FC: FractionalSumCheck<F>> {
+    /// the SubClaim from the FractionalSumCheck
+    pub fractional_sum_check_sub_claim: FC::FractionalSumCheckSubClaim,
+    /// Challenges beta and gamma
+    pub challenges: (F, F),
 }
 
 pub mod util;
@@ -38,7 +60,12 @@ pub mod util;
 /// A PermutationCheck w.r.t. `(fs, gs, perms)`
 /// proves that (g1, ..., gk) is a permutation of (f1, ..., fk) under
 /// permutation `(p1, ..., pk)`
-/// It is derived from ProductCheck.
+/// It is derived from ProductCheck, i.e.
+///   \prod_x \prod_i (fi(x) + beta * s_id_i(x) + gamma)
+///     = \prod_x \prod_i (gi(x) + beta * pi(x) + gamma)
+/// or, for `PolyIOP<F, T, GkrFraction>`, from FractionalSumCheck, i.e.
+///   \sum_x \sum_i 1 / (fi(x) + beta * s_id_i(x) + gamma)
+///     - 1 / (gi(x) + beta * pi(x) + gamma) = 0
 ///
 /// A Permutation Check IOP takes the following steps:
 ///
@@ -46,13 +73,17 @@ pub mod util;
 /// - fs = (f1, ..., fk)
 /// - gs = (g1, ..., gk)
 /// - permutation oracles = (p1, ..., pk)
-pub trait PermutationCheck<E, PCS>: ProductCheck<E, PCS>
+///
+/// `PolyIOP<F, T, P>` runs it on the ProductCheck selected by `P`, and has a
+/// zero-knowledge mode with the committed product check, see
+/// `ProductCheck::prove_zk`.
+pub trait PermutationCheck<F, PCS>: ZeroCheck<F>
 where
-    E: Pairing,
-    PCS: PolynomialCommitmentScheme<E>,
+    F: PrimeField,
+    PCS: PolynomialCommitmentScheme<F>,
 {
     type PermutationCheckSubClaim;
-    type PermutationProof;
+    type PermutationProof: CanonicalSerialize + CanonicalDeserialize;
 
     /// Initialize the system with a transcript
     ///
@@ -80,7 +111,7 @@ where
         fxs: &[Self::MultilinearExtension],
         gxs: &[Self::MultilinearExtension],
         perms: &[Self::MultilinearExtension],
-        transcript: &mut IOPTranscript<E::ScalarField>,
+        transcript: &mut Self::Transcript,
     ) -> Result<
         (
             Self::PermutationProof,
@@ -97,18 +128,55 @@ where
         aux_info: &Self::VPAuxInfo,
         transcript: &mut Self::Transcript,
     ) -> Result<Self::PermutationCheckSubClaim, PolyIOPErrors>;
+
+    /// Zero-knowledge variant of `prove`, drawing the blindings of the
+    /// commitments and the mask of the product check from `rng`.
+    ///
+    /// Outputs:
+    /// - a zero-knowledge product check proof proving that gs is a
+    ///   permutation of fs under permutation
+    /// - the polynomials committed to by the product check, with the
+    ///   blindings of their commitments
+    #[allow(clippy::type_complexity)]
+    fn prove_zk<R: Rng>(
+        pcs_param: &PCS::ProverParam,
+        fxs: &[Self::MultilinearExtension],
+        gxs: &[Self::MultilinearExtension],
+        perms: &[Self::MultilinearExtension],
+        transcript: &mut Self::Transcript,
+        rng: &mut R,
+    ) -> Result<(ZkProductCheckProof<F, PCS>, ZkProductCheckOracles<F, PCS>), PolyIOPErrors>
+    where
+        PCS: HidingPolynomialCommitmentScheme<F>;
+
+    /// Verify a zero-knowledge permutation check proof, and return the
+    /// subclaim together with the claims on the mask of the product check.
+    fn verify_zk(
+        proof: &ZkProductCheckProof<F, PCS>,
+        aux_info: &Self::VPAuxInfo,
+        transcript: &mut Self::Transcript,
+    ) -> Result<(Self::PermutationCheckSubClaim, SumCheckMaskClaims<F>), PolyIOPErrors>;
 }
 
-impl<E, PCS> PermutationCheck<E, PCS> for PolyIOP<E::ScalarField>
+impl<F, PCS, T, P> PermutationCheck<F, PCS> for PolyIOP<F, T, P>
 where
-    E: Pairing,
-    PCS: PolynomialCommitmentScheme<E, Polynomial = Arc<DenseMultilinearExtension<E::ScalarField>>>,
+    F: PrimeField,
+    PCS: PolynomialCommitmentScheme<F, Polynomial = Arc<DenseMultilinearExtension<F>>>,
+    T: Transcript<F>,
+    P: ProductArgument,
+    Self: ProductCheck<
+        F,
+        PCS,
+        MultilinearExtension = Arc<DenseMultilinearExtension<F>>,
+        VPAuxInfo = VPAuxInfo<F>,
+        Transcript = T,
+    >,
 {
-    type PermutationCheckSubClaim = PermutationCheckSubClaim<E, PCS, Self>;
-    type PermutationProof = Self::ProductCheckProof;
+    type PermutationCheckSubClaim = PermutationCheckSubClaim<F, PCS, Self>;
+    type PermutationProof = <Self as ProductCheck<F, PCS>>::ProductCheckProof;
 
     fn init_transcript() -> Self::Transcript {
-        IOPTranscript::<E::ScalarField>::new(b"Initializing PermutationCheck transcript")
+        T::new(b"Initializing PermutationCheck transcript")
     }
 
     fn prove(
@@ -116,7 +184,7 @@ where
         fxs: &[Self::MultilinearExtension],
         gxs: &[Self::MultilinearExtension],
         perms: &[Self::MultilinearExtension],
-        transcript: &mut IOPTranscript<E::ScalarField>,
+        transcript: &mut Self::Transcript,
     ) -> Result<
         (
             Self::PermutationProof,
@@ -126,27 +194,7 @@ where
         PolyIOPErrors,
     > {
         let start = start_timer!(|| "Permutation check prove");
-        if fxs.is_empty() {
-            return Err(PolyIOPErrors::InvalidParameters("fxs is empty".to_string()));
-        }
-        if (fxs.len() != gxs.len()) || (fxs.len() != perms.len()) {
-            return Err(PolyIOPErrors::InvalidProof(format!(
-                "fxs.len() = {}, gxs.len() = {}, perms.len() = {}",
-                fxs.len(),
-                gxs.len(),
-                perms.len(),
-            )));
-        }
-
-        let num_vars = fxs[0].num_vars;
-        for ((fx, gx), perm) in fxs.iter().zip(gxs.iter()).zip(perms.iter()) {
-            if (fx.num_vars != num_vars) || (gx.num_vars != num_vars) || (perm.num_vars != num_vars)
-            {
-                return Err(PolyIOPErrors::InvalidParameters(
-                    "number of variables unmatched".to_string(),
-                ));
-            }
-        }
+        check_inputs(fxs, gxs, perms)?;
 
         // generate challenge `beta` and `gamma` from current transcript
         let beta = transcript.get_and_append_challenge(b"beta")?;
@@ -154,7 +202,7 @@ where
         let (numerators, denominators) = computer_nums_and_denoms(&beta, &gamma, fxs, gxs, perms)?;
 
         // invoke product check on numerator and denominator
-        let (proof, prod_poly, frac_poly) = <Self as ProductCheck<E, PCS>>::prove(
+        let (proof, prod_poly, frac_poly) = <Self as ProductCheck<F, PCS>>::prove(
             pcs_param,
             &numerators,
             &denominators,
@@ -177,7 +225,7 @@ where
 
         // invoke the zero check on the iop_proof
         let product_check_sub_claim =
-            <Self as ProductCheck<E, PCS>>::verify(proof, aux_info, transcript)?;
+            <Self as ProductCheck<F, PCS>>::verify(proof, aux_info, transcript)?;
 
         end_timer!(start);
         Ok(PermutationCheckSubClaim {
@@ -185,36 +233,230 @@ where
             challenges: (beta, gamma),
         })
     }
+
+    fn prove_zk<R: Rng>(
+        pcs_param: &PCS::ProverParam,
+        fxs: &[Self::MultilinearExtension],
+        gxs: &[Self::MultilinearExtension],
+        perms: &[Self::MultilinearExtension],
+        transcript: &mut Self::Transcript,
+        rng: &mut R,
+    ) -> Result<(ZkProductCheckProof<F, PCS>, ZkProductCheckOracles<F, PCS>), PolyIOPErrors>
+    where
+        PCS: HidingPolynomialCommitmentScheme<F>,
+    {
+        let start = start_timer!(|| "zk Permutation check prove");
+        check_inputs(fxs, gxs, perms)?;
+
+        // generate challenge `beta` and `gamma` from current transcript
+        let beta = transcript.get_and_append_challenge(b"beta")?;
+        let gamma = transcript.get_and_append_challenge(b"gamma")?;
+        let (numerators, denominators) = computer_nums_and_denoms(&beta, &gamma, fxs, gxs, perms)?;
+
+        // invoke zero-knowledge product check on numerator and denominator
+        let res = <Self as ProductCheck<F, PCS>>::prove_zk(
+            pcs_param,
+            &numerators,
+            &denominators,
+            transcript,
+            rng,
+        )?;
+
+        end_timer!(start);
+        Ok(res)
+    }
+
+    fn verify_zk(
+        proof: &ZkProductCheckProof<F, PCS>,
+        aux_info: &Self::VPAuxInfo,
+        transcript: &mut Self::Transcript,
+    ) -> Result<(Self::PermutationCheckSubClaim, SumCheckMaskClaims<F>), PolyIOPErrors> {
+        let start = start_timer!(|| "zk Permutation check verify");
+
+        let beta = transcript.get_and_append_challenge(b"beta")?;
+        let gamma = transcript.get_and_append_challenge(b"gamma")?;
+
+        let (product_check_sub_claim, mask_claims) =
+            <Self as ProductCheck<F, PCS>>::verify_zk(proof, aux_info, transcript)?;
+
+        end_timer!(start);
+        Ok((
+            PermutationCheckSubClaim {
+                product_check_sub_claim,
+                challenges: (beta, gamma),
+            },
+            mask_claims,
+        ))
+    }
+}
+
+impl<F, PCS, T> PermutationCheck<F, PCS> for PolyIOP<F, T, GkrFraction>
+where
+    F: PrimeField,
+    PCS: PolynomialCommitmentScheme<F>,
+    T: Transcript<F>,
+{
+    type PermutationCheckSubClaim = FractionalPermutationCheckSubClaim<F, Self>;
+    type PermutationProof = <Self as FractionalSumCheck<F>>::FractionalSumCheckProof;
+
+    fn init_transcript() -> Self::Transcript {
+        T::new(b"Initializing PermutationCheck transcript")
+    }
+
+    /// Outputs the leaves of the fractional sum instead of the product and
+    /// fractional polynomials.
+    fn prove(
+        _pcs_param: &PCS::ProverParam,
+        fxs: &[Self::MultilinearExtension],
+        gxs: &[Self::MultilinearExtension],
+        perms: &[Self::MultilinearExtension],
+        transcript: &mut Self::Transcript,
+    ) -> Result<
+        (
+            Self::PermutationProof,
+            Self::MultilinearExtension,
+            Self::MultilinearExtension,
+        ),
+        PolyIOPErrors,
+    > {
+        let start = start_timer!(|| "Permutation check prove");
+        check_inputs(fxs, gxs, perms)?;
+
+        // generate challenge `beta` and `gamma` from current transcript
+        let beta = transcript.get_and_append_challenge(b"beta")?;
+        let gamma = transcript.get_and_append_challenge(b"gamma")?;
+        let (numerators, denominators) = computer_nums_and_denoms(&beta, &gamma, fxs, gxs, perms)?;
+
+        // invoke fractional sum check on 1 / numerator - 1 / denominator
+        let num_vars = fxs[0].num_vars;
+        let one = F::one();
+        let ones = Arc::new(DenseMultilinearExtension::from_evaluations_vec(
+            num_vars,
+            vec![one; 1 << num_vars],
+        ));
+        let minus_ones = Arc::new(DenseMultilinearExtension::from_evaluations_vec(
+            num_vars,
+            vec![-one; 1 << num_vars],
+        ));
+        let ps = [vec![ones; fxs.len()], vec![minus_ones; fxs.len()]].concat();
+        let qs = [numerators, denominators].concat();
+        let (proof, p_leaves, q_leaves) =
+            <Self as FractionalSumCheck<F>>::prove(&ps, &qs, transcript)?;
+
+        end_timer!(start);
+        Ok((proof, p_leaves, q_leaves))
+    }
+
+    fn verify(
+        proof: &Self::PermutationProof,
+        aux_info: &Self::VPAuxInfo,
+        transcript: &mut Self::Transcript,
+    ) -> Result<Self::PermutationCheckSubClaim, PolyIOPErrors> {
+        let start = start_timer!(|| "Permutation check verify");
+
+        // the aux info is that of the product check, whose degree is k + 1
+        if aux_info.max_degree < 2 {
+            return Err(PolyIOPErrors::InvalidParameters(format!(
+                "permutation check: invalid aux info {:?}",
+                aux_info
+            )));
+        }
+        let num_polys = aux_info.max_degree - 1;
+
+        let beta = transcript.get_and_append_challenge(b"beta")?;
+        let gamma = transcript.get_and_append_challenge(b"gamma")?;
+
+        let fractional_sum_check_sub_claim = <Self as FractionalSumCheck<F>>::verify(
+            proof,
+            2 * num_polys,
+            aux_info.num_variables,
+            transcript,
+        )?;
+        if !fractional_sum_check_sub_claim.sum.is_zero() {
+            return Err(PolyIOPErrors::InvalidProof(
+                "permutation check: the fractional sum is not zero".to_string(),
+            ));
+        }
+        // the numerators are the constants 1 and -1
+        let (ones, minus_ones) = fractional_sum_check_sub_claim.p_evals.split_at(num_polys);
+        let one = F::one();
+        if ones.iter().any(|e| *e != one) || minus_ones.iter().any(|e| *e != -one) {
+            return Err(PolyIOPErrors::InvalidProof(
+                "permutation check: wrong evaluations of the numerators".to_string(),
+            ));
+        }
+
+        end_timer!(start);
+        Ok(FractionalPermutationCheckSubClaim {
+            fractional_sum_check_sub_claim,
+            challenges: (beta, gamma),
+        })
+    }
+
+    fn prove_zk<R: Rng>(
+        _pcs_param: &PCS::ProverParam,
+        _fxs: &[Self::MultilinearExtension],
+        _gxs: &[Self::MultilinearExtension],
+        _perms: &[Self::MultilinearExtension],
+        _transcript: &mut Self::Transcript,
+        _rng: &mut R,
+    ) -> Result<(ZkProductCheckProof<F, PCS>, ZkProductCheckOracles<F, PCS>), PolyIOPErrors>
+    where
+        PCS: HidingPolynomialCommitmentScheme<F>,
+    {
+        Err(PolyIOPErrors::InvalidParameters(
+            "the fractional permutation check has no zero-knowledge mode".to_string(),
+        ))
+    }
+
+    fn verify_zk(
+        _proof: &ZkProductCheckProof<F, PCS>,
+        _aux_info: &Self::VPAuxInfo,
+        _transcript: &mut Self::Transcript,
+    ) -> Result<(Self::PermutationCheckSubClaim, SumCheckMaskClaims<F>), PolyIOPErrors> {
+        Err(PolyIOPErrors::InvalidParameters(
+            "the fractional permutation check has no zero-knowledge mode".to_string(),
+        ))
+    }
 }
 
 #[cfg(test)]
 mod test {
-    use super::PermutationCheck;
+    use super::{
+        util::computer_nums_and_denoms, FractionalPermutationCheckSubClaim, PermutationCheck,
+        PermutationCheckSubClaim,
+    };
     use crate::{
-        pcs::{prelude::MultilinearKzgPCS, PolynomialCommitmentScheme},
-        poly_iop::{errors::PolyIOPErrors, PolyIOP},
+        pcs::{
+            prelude::{HidingMultilinearKzgPCS, MultilinearKzgPCS},
+            HidingPolynomialCommitmentScheme, PolynomialCommitmentScheme,
+        },
+        poly_iop::{
+            errors::PolyIOPErrors, frac_sum_check::GkrFraction, prod_check::GkrProduct, PolyIOP,
+        },
     };
     use arithmetic::{evaluate_opt, identity_permutation_mles, random_permutation_mles, VPAuxInfo};
-    use ark_bls12_381::Bls12_381;
-    use ark_ec::pairing::Pairing;
+    use ark_bls12_381::{Bls12_381, Fr};
+    use ark_ff::PrimeField;
     use ark_poly::{DenseMultilinearExtension, MultilinearExtension};
     use ark_std::test_rng;
     use std::{marker::PhantomData, sync::Arc};
+    use transcript::{IOPTranscript, Transcript};
 
     type Kzg = MultilinearKzgPCS<Bls12_381>;
+    type HidingKzg = HidingMultilinearKzgPCS<Bls12_381>;
+    type Gkr = PolyIOP<Fr, IOPTranscript<Fr>, GkrProduct>;
+    type LogUp = PolyIOP<Fr, IOPTranscript<Fr>, GkrFraction>;
 
-    fn test_permutation_check_helper<E, PCS>(
+    fn test_permutation_check_helper<F, PCS>(
         pcs_param: &PCS::ProverParam,
-        fxs: &[Arc<DenseMultilinearExtension<E::ScalarField>>],
-        gxs: &[Arc<DenseMultilinearExtension<E::ScalarField>>],
-        perms: &[Arc<DenseMultilinearExtension<E::ScalarField>>],
+        fxs: &[Arc<DenseMultilinearExtension<F>>],
+        gxs: &[Arc<DenseMultilinearExtension<F>>],
+        perms: &[Arc<DenseMultilinearExtension<F>>],
     ) -> Result<(), PolyIOPErrors>
     where
-        E: Pairing,
-        PCS: PolynomialCommitmentScheme<
-            E,
-            Polynomial = Arc<DenseMultilinearExtension<E::ScalarField>>,
-        >,
+        F: PrimeField,
+        PCS: PolynomialCommitmentScheme<F, Polynomial = Arc<DenseMultilinearExtension<F>>>,
     {
         let nv = fxs[0].num_vars;
         // what's AuxInfo used for?
@@ -225,27 +467,21 @@ mod test {
         };
 
         // prover
-        let mut transcript =
-            <PolyIOP<E::ScalarField> as PermutationCheck<E, PCS>>::init_transcript();
+        let mut transcript = <PolyIOP<F> as PermutationCheck<F, PCS>>::init_transcript();
         transcript.append_message(b"testing", b"initializing transcript for testing")?;
-        let (proof, prod_x, _frac_poly) =
-            <PolyIOP<E::ScalarField> as PermutationCheck<E, PCS>>::prove(
-                pcs_param,
-                fxs,
-                gxs,
-                perms,
-                &mut transcript,
-            )?;
+        let (proof, prod_x, _frac_poly) = <PolyIOP<F> as PermutationCheck<F, PCS>>::prove(
+            pcs_param,
+            fxs,
+            gxs,
+            perms,
+            &mut transcript,
+        )?;
 
         // verifier
-        let mut transcript =
-            <PolyIOP<E::ScalarField> as PermutationCheck<E, PCS>>::init_transcript();
+        let mut transcript = <PolyIOP<F> as PermutationCheck<F, PCS>>::init_transcript();
         transcript.append_message(b"testing", b"initializing transcript for testing")?;
-        let perm_check_sub_claim = <PolyIOP<E::ScalarField> as PermutationCheck<E, PCS>>::verify(
-            &proof,
-            &poly_info,
-            &mut transcript,
-        )?;
+        let perm_check_sub_claim =
+            <PolyIOP<F> as PermutationCheck<F, PCS>>::verify(&proof, &poly_info, &mut transcript)?;
 
         // check product subclaim
         if evaluate_opt(
@@ -274,7 +510,7 @@ mod test {
                 Arc::new(DenseMultilinearExtension::rand(nv, &mut rng)),
             ];
             // perms is the identity map
-            test_permutation_check_helper::<Bls12_381, Kzg>(&pcs_param, &ws, &ws, &id_perms)?;
+            test_permutation_check_helper::<Fr, Kzg>(&pcs_param, &ws, &ws, &id_perms)?;
         }
 
         {
@@ -288,7 +524,7 @@ mod test {
             // perms is the reverse identity map
             let mut perms = id_perms.clone();
             perms.reverse();
-            test_permutation_check_helper::<Bls12_381, Kzg>(&pcs_param, &fs, &gs, &perms)?;
+            test_permutation_check_helper::<Fr, Kzg>(&pcs_param, &fs, &gs, &perms)?;
         }
 
         {
@@ -301,8 +537,7 @@ mod test {
             let perms = random_permutation_mles(nv, 2, &mut rng);
 
             assert!(
-                test_permutation_check_helper::<Bls12_381, Kzg>(&pcs_param, &ws, &ws, &perms)
-                    .is_err()
+                test_permutation_check_helper::<Fr, Kzg>(&pcs_param, &ws, &ws, &perms).is_err()
             );
         }
 
@@ -318,21 +553,276 @@ mod test {
             ];
             // s_perm is the identity map
 
-            assert!(test_permutation_check_helper::<Bls12_381, Kzg>(
-                &pcs_param, &fs, &gs, &id_perms
+            assert!(
+                test_permutation_check_helper::<Fr, Kzg>(&pcs_param, &fs, &gs, &id_perms).is_err()
+            );
+        }
+
+        Ok(())
+    }
+
+    /// The challenges of a subclaim, and the point and the evaluations of the
+    /// numerators and denominators it queries.
+    type SubClaimQueries = ((Fr, Fr), Vec<Fr>, Vec<Fr>);
+
+    fn test_gkr_permutation_check_helper<PC>(
+        pcs_param: &<Kzg as PolynomialCommitmentScheme<Fr>>::ProverParam,
+        fxs: &[Arc<DenseMultilinearExtension<Fr>>],
+        gxs: &[Arc<DenseMultilinearExtension<Fr>>],
+        perms: &[Arc<DenseMultilinearExtension<Fr>>],
+        queries: fn(PC::PermutationCheckSubClaim) -> SubClaimQueries,
+    ) -> Result<(), PolyIOPErrors>
+    where
+        PC: PermutationCheck<
+            Fr,
+            Kzg,
+            MultilinearExtension = Arc<DenseMultilinearExtension<Fr>>,
+            VPAuxInfo = VPAuxInfo<Fr>,
+            Transcript = IOPTranscript<Fr>,
+        >,
+    {
+        let poly_info = VPAuxInfo {
+            max_degree: fxs.len() + 1,
+            num_variables: fxs[0].num_vars,
+            phantom: PhantomData,
+        };
+
+        // prover
+        let mut transcript = <PC as PermutationCheck<Fr, Kzg>>::init_transcript();
+        transcript.append_message(b"testing", b"initializing transcript for testing")?;
+        let (proof, _, _) =
+            <PC as PermutationCheck<Fr, Kzg>>::prove(pcs_param, fxs, gxs, perms, &mut transcript)?;
+
+        // verifier
+        let mut transcript = <PC as PermutationCheck<Fr, Kzg>>::init_transcript();
+        transcript.append_message(b"testing", b"initializing transcript for testing")?;
+        let perm_check_sub_claim =
+            <PC as PermutationCheck<Fr, Kzg>>::verify(&proof, &poly_info, &mut transcript)?;
+
+        // check the evaluations of the numerators and denominators
+        let ((beta, gamma), point, evals) = queries(perm_check_sub_claim);
+        let (nums, denoms) = computer_nums_and_denoms(&beta, &gamma, fxs, gxs, perms)?;
+        for (poly, eval) in nums.iter().chain(denoms.iter()).zip(evals.iter()) {
+            if evaluate_opt(poly, &point) != *eval {
+                return Err(PolyIOPErrors::InvalidVerifier("wrong subclaim".to_string()));
+            }
+        }
+
+        Ok(())
+    }
+
+    fn test_gkr_permutation_check<PC>(
+        nv: usize,
+        queries: fn(PC::PermutationCheckSubClaim) -> SubClaimQueries,
+    ) -> Result<(), PolyIOPErrors>
+    where
+        PC: PermutationCheck<
+            Fr,
+            Kzg,
+            MultilinearExtension = Arc<DenseMultilinearExtension<Fr>>,
+            VPAuxInfo = VPAuxInfo<Fr>,
+            Transcript = IOPTranscript<Fr>,
+        >,
+    {
+        let mut rng = test_rng();
+
+        let srs = Kzg::gen_srs_for_testing(&mut rng, nv)?;
+        let (pcs_param, _) = Kzg::trim(&srs, None, Some(nv))?;
+        let id_perms = identity_permutation_mles(nv, 2);
+
+        // good path: f = (w1, w2) is a permutation of g = (w2, w1) itself under a map
+        let mut fs = vec![
+            Arc::new(DenseMultilinearExtension::rand(nv, &mut rng)),
+            Arc::new(DenseMultilinearExtension::rand(nv, &mut rng)),
+        ];
+        let gs = fs.clone();
+        fs.reverse();
+        let mut perms = id_perms.clone();
+        perms.reverse();
+        test_gkr_permutation_check_helper::<PC>(&pcs_param, &fs, &gs, &perms, queries)?;
+
+        // bad path: w is a not permutation of w itself under a random map
+        let perms = random_permutation_mles(nv, 2, &mut rng);
+        assert!(
+            test_gkr_permutation_check_helper::<PC>(&pcs_param, &fs, &fs, &perms, queries).is_err()
+        );
+
+        Ok(())
+    }
+
+    fn test_zk_permutation_check(nv: usize) -> Result<(), PolyIOPErrors> {
+        let mut rng = test_rng();
+
+        // the mask of the product check of 2 polynomials has degree 4, and its
+        // multilinear encoding has 3 + log(nv) variables
+        let supported_nv = nv.max(3 + ark_std::log2(nv) as usize);
+        let srs = HidingKzg::gen_srs_for_testing(&mut rng, supported_nv)?;
+        let (ck, vk) = HidingKzg::trim(&srs, None, Some(supported_nv))?;
+        let poly_info = VPAuxInfo {
+            max_degree: 3,
+            num_variables: nv,
+            phantom: PhantomData,
+        };
+
+        let mut fs = vec![
+            Arc::new(DenseMultilinearExtension::rand(nv, &mut rng)),
+            Arc::new(DenseMultilinearExtension::rand(nv, &mut rng)),
+        ];
+        let gs = fs.clone();
+        fs.reverse();
+        let mut perms = identity_permutation_mles(nv, 2);
+        perms.reverse();
+
+        let prove = |perms: &[_], rng: &mut _| {
+            let mut transcript =
+                <PolyIOP<Fr> as PermutationCheck<Fr, HidingKzg>>::init_transcript();
+            transcript.append_message(b"testing", b"initializing transcript for testing")?;
+            <PolyIOP<Fr> as PermutationCheck<Fr, HidingKzg>>::prove_zk(
+                &ck,
+                &fs,
+                &gs,
+                perms,
+                &mut transcript,
+                rng,
             )
-            .is_err());
+        };
+        let (proof, oracles) = prove(&perms, &mut rng)?;
+
+        let mut transcript = <PolyIOP<Fr> as PermutationCheck<Fr, HidingKzg>>::init_transcript();
+        transcript.append_message(b"testing", b"initializing transcript for testing")?;
+        let (sub_claim, mask_claims) = <PolyIOP<Fr> as PermutationCheck<Fr, HidingKzg>>::verify_zk(
+            &proof,
+            &poly_info,
+            &mut transcript,
+        )?;
+        let final_query = &sub_claim.product_check_sub_claim.final_query;
+        assert_eq!(evaluate_opt(&oracles.prod_x, &final_query.0), final_query.1);
+
+        // the committed polynomials are opened with their blindings
+        let point = &sub_claim.product_check_sub_claim.zero_check_sub_claim.point;
+        for (poly, blinding, comm) in [
+            (
+                &oracles.prod_x,
+                &oracles.prod_x_blinding,
+                &proof.prod_x_comm,
+            ),
+            (&oracles.frac_poly, &oracles.frac_blinding, &proof.frac_comm),
+        ] {
+            let (opening, eval) = HidingKzg::open_with_blinding(&ck, poly, blinding, point)?;
+            assert!(HidingKzg::verify(&vk, comm, point, &eval, &opening)?);
+        }
+
+        // the claims on the mask are checked against its commitment
+        assert_eq!(mask_claims.points.len(), nv);
+        for (point, eval) in mask_claims
+            .points
+            .iter()
+            .zip(mask_claims.evaluations.iter())
+        {
+            let (opening, value) =
+                HidingKzg::open_with_blinding(&ck, &oracles.mask, &oracles.mask_blinding, point)?;
+            assert_eq!(value, *eval);
+            assert!(HidingKzg::verify(
+                &vk,
+                &proof.mask_comm,
+                point,
+                eval,
+                &opening
+            )?);
+            assert!(!HidingKzg::verify(
+                &vk,
+                &proof.mask_comm,
+                point,
+                &(*eval + Fr::from(1u64)),
+                &opening
+            )?);
         }
 
+        // a second proof of the same permutation is masked differently
+        let (proof2, _) = prove(&perms, &mut rng)?;
+        assert_ne!(proof2.prod_x_comm, proof.prod_x_comm);
+        assert_ne!(proof2.mask_comm, proof.mask_comm);
+        assert_ne!(
+            proof2.zero_check_proof.sum_check_proof.proofs,
+            proof.zero_check_proof.sum_check_proof.proofs
+        );
+
+        // bad path: fs is not a permutation of gs under a random map
+        let bad_perms = random_permutation_mles(nv, 2, &mut rng);
+        let (bad_proof, bad_oracles) = prove(&bad_perms, &mut rng)?;
+        let mut transcript = <PolyIOP<Fr> as PermutationCheck<Fr, HidingKzg>>::init_transcript();
+        transcript.append_message(b"testing", b"initializing transcript for testing")?;
+        let (bad_sub_claim, _) = <PolyIOP<Fr> as PermutationCheck<Fr, HidingKzg>>::verify_zk(
+            &bad_proof,
+            &poly_info,
+            &mut transcript,
+        )?;
+        let final_query = &bad_sub_claim.product_check_sub_claim.final_query;
+        assert_ne!(
+            evaluate_opt(&bad_oracles.prod_x, &final_query.0),
+            final_query.1
+        );
+
+        // the gkr product check has no zero-knowledge mode
+        let mut transcript = <Gkr as PermutationCheck<Fr, HidingKzg>>::init_transcript();
+        assert!(<Gkr as PermutationCheck<Fr, HidingKzg>>::prove_zk(
+            &ck,
+            &fs,
+            &gs,
+            &perms,
+            &mut transcript,
+            &mut rng
+        )
+        .is_err());
+
         Ok(())
     }
 
+    #[test]
+    fn test_zk_polynomial() -> Result<(), PolyIOPErrors> {
+        let _trace = crate::trace_guard();
+        test_zk_permutation_check(1)?;
+        test_zk_permutation_check(5)
+    }
+
     #[test]
     fn test_trivial_polynomial() -> Result<(), PolyIOPErrors> {
+        let _trace = crate::trace_guard();
         test_permutation_check(1)
     }
+
+    #[test]
+    fn test_gkr_polynomial() -> Result<(), PolyIOPErrors> {
+        let _trace = crate::trace_guard();
+        let queries = |sub_claim: PermutationCheckSubClaim<Fr, Kzg, Gkr>| {
+            let sub_claim_queries = sub_claim.product_check_sub_claim;
+            (
+                sub_claim.challenges,
+                sub_claim_queries.point,
+                [sub_claim_queries.fx_evals, sub_claim_queries.gx_evals].concat(),
+            )
+        };
+        test_gkr_permutation_check::<Gkr>(1, queries)?;
+        test_gkr_permutation_check::<Gkr>(5, queries)
+    }
+
+    #[test]
+    fn test_fractional_polynomial() -> Result<(), PolyIOPErrors> {
+        let _trace = crate::trace_guard();
+        let queries = |sub_claim: FractionalPermutationCheckSubClaim<Fr, LogUp>| {
+            let sub_claim_queries = sub_claim.fractional_sum_check_sub_claim;
+            (
+                sub_claim.challenges,
+                sub_claim_queries.point,
+                sub_claim_queries.q_evals,
+            )
+        };
+        test_gkr_permutation_check::<LogUp>(1, queries)?;
+        test_gkr_permutation_check::<LogUp>(5, queries)
+    }
     #[test]
     fn test_normal_polynomial() -> Result<(), PolyIOPErrors> {
+        let _trace = crate::trace_guard();
         test_permutation_check(5)
     }
 
@@ -13,6 +13,36 @@ use ark_poly::DenseMultilinearExtension;
 use ark_std::{end_timer, start_timer};
 use std::sync::Arc;
 
+/// Check that `fxs`, `gxs` and `perms` are non-empty lists of the same number
+/// of polynomials, with the same number of variables.
+pub(super) fn check_inputs<F: PrimeField>(
+    fxs: &[Arc<DenseMultilinearExtension<F>>],
+    gxs: &[Arc<DenseMultilinearExtension<F>>],
+    perms: &[Arc<DenseMultilinearExtension<F>>],
+) -> Result<(), PolyIOPErrors> {
+    if fxs.is_empty() {
+        return Err(PolyIOPErrors::InvalidParameters("fxs is empty".to_string()));
+    }
+    if (fxs.len() != gxs.len()) || (fxs.len() != perms.len()) {
+        return Err(PolyIOPErrors::InvalidProof(format!(
+            "fxs.len() = {}, gxs.len() = {}, perms.len() = {}",
+            fxs.len(),
+            gxs.len(),
+            perms.len(),
+        )));
+    }
+
+    let num_vars = fxs[0].num_vars;
+    for ((fx, gx), perm) in fxs.iter().zip(gxs.iter()).zip(perms.iter()) {
+        if (fx.num_vars != num_vars) || (gx.num_vars != num_vars) || (perm.num_vars != num_vars) {
+            return Err(PolyIOPErrors::InvalidParameters(
+                "number of variables unmatched".to_string(),
+            ));
+        }
+    }
+    Ok(())
+}
+
 /// Returns the evaluations of two list of MLEs:
 /// - numerators = (a1, ..., ak)
 /// - denominators = (b1, ..., bk)
@@ -0,0 +1,546 @@
+// Copyright (c) 2023 Espresso Systems (espressosys.com)
+// This file is part of the HyperPlonk library.
+
+// You should have received a copy of the MIT License
+// along with the HyperPlonk library. If not, see <https://mit-license.org/>.
+
+//! Main module for the Plookup Check protocol
+
+use self::util::{
+    compute_nums_and_denoms, compute_sorted_polys, eval_ids, eval_nums_and_denoms, shift_polys,
+};
+use crate::{
+    pcs::PolynomialCommitmentScheme,
+    poly_iop::{
+        errors::PolyIOPErrors, prelude::ProductCheck, prod_check::ProductCheckSubClaim,
+        zero_check::ZeroCheck, PolyIOP,
+    },
+};
+use arithmetic::VPAuxInfo;
+use ark_ff::PrimeField;
+use ark_poly::DenseMultilinearExtension;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_std::{end_timer, start_timer};
+use std::sync::Arc;
+use transcript::Transcript;
+
+mod util;
+
+/// A PlookupCheck w.r.t. `(fs, t)` proves that every evaluation of
+/// `(f1, ..., fk)` on the boolean hypercube is an evaluation of the table `t`
+/// on the boolean hypercube, with the same number of variables. A smaller
+/// table is padded by repeating one of its entries.
+/// It is derived from ProductCheck.
+///
+/// It implements the lookup of [GW20](https://eprint.iacr.org/2020/315.pdf):
+/// with the sorted vector `s` of the concatenation of the witnesses and the
+/// table, ordered as the table, the lookup holds if and only if
+///   (1 + beta)^{kN} \prod_x \prod_i (gamma + fi(x))
+///     * \prod_x (gamma * (1 + beta) + t(x) + beta * t'(x))
+/// = \prod_y (gamma * (1 + beta) + s(y) + beta * s'(y))
+/// for random `beta` and `gamma`, where `'` denotes the cyclic shift by one
+/// row. The vector `s` is split into the `k + 1` sorted polynomials `s0, ...,
+/// sk`.
+///
+/// On the boolean hypercube there is no cheap shift, so the prover commits to
+/// the shifts `s0', ..., sk'` and `t'` as well, and proves that they are the
+/// shifts of `s0, ..., sk` and `t` with a permutation argument on the
+/// challenges `delta` and `epsilon`, whose identity and permutation are
+/// evaluated by the verifier. Both arguments are merged in a single product
+/// check.
+///
+/// Prover steps:
+/// 1. build and commit to the sorted polynomials `(s0, ..., sk)` and to the
+///    shifts `(s0', ..., sk')` and `t'`
+/// 2. generate `beta`, `gamma`, `delta` and `epsilon` from the transcript
+/// 3. run a product check on the `2k + 3` numerators and denominators of both
+///    arguments
+///
+/// Verifier steps:
+/// 1. Extract the commitments from the proof, push them to the transcript, and
+///    generate the same challenges
+/// 2. `verify` the product check proof, and generate the subclaim for
+///    polynomial evaluations
+pub trait PlookupCheck<F, PCS>: ProductCheck<F, PCS>
+where
+    F: PrimeField,
+    PCS: PolynomialCommitmentScheme<F>,
+{
+    type PlookupCheckSubClaim;
+    type PlookupCheckProof: CanonicalSerialize + CanonicalDeserialize;
+
+    /// Initialize the system with a transcript
+    ///
+    /// This function is optional -- in the case where a PlookupCheck is
+    /// an building block for a more complex protocol, the transcript
+    /// may be initialized by this complex protocol, and passed to the
+    /// PlookupCheck prover/verifier.
+    fn init_transcript() -> Self::Transcript;
+
+    /// Inputs:
+    /// - pcs_param: PCS committing key
+    /// - fxs = (f1, ..., fk), the witnesses looked up
+    /// - table: the table
+    /// - transcript: the IOP transcript
+    ///
+    /// Outputs:
+    /// - a plookup check proof proving that the evaluations of fxs are table
+    ///   entries
+    /// - the sorted polynomials `(s0, ..., sk)`
+    /// - their shifts `(s0', ..., sk')`
+    /// - the shift `t'` of the table
+    /// - the product polynomial built during product check
+    /// - the fractional polynomial built during product check
+    ///
+    /// Cost: O(kN)
+    #[allow(clippy::type_complexity)]
+    fn prove(
+        pcs_param: &PCS::ProverParam,
+        fxs: &[Self::MultilinearExtension],
+        table: &Self::MultilinearExtension,
+        transcript: &mut Self::Transcript,
+    ) -> Result<
+        (
+            Self::PlookupCheckProof,
+            Vec<Self::MultilinearExtension>,
+            Vec<Self::MultilinearExtension>,
+            Self::MultilinearExtension,
+            Self::MultilinearExtension,
+            Self::MultilinearExtension,
+        ),
+        PolyIOPErrors,
+    >;
+
+    /// Verify that the evaluations of (f1, ..., fk) are table entries.
+    ///
+    /// `aux_info` describes the product check, whose `max_degree` is
+    /// `2k + 4`.
+    fn verify(
+        proof: &Self::PlookupCheckProof,
+        aux_info: &VPAuxInfo<F>,
+        transcript: &mut Self::Transcript,
+    ) -> Result<Self::PlookupCheckSubClaim, PolyIOPErrors>;
+}
+
+/// A plookup check subclaim consists of
+/// - the SubClaim from the ProductCheck
+/// - the random challenges `beta`, `gamma`, `delta` and `epsilon`
+///
+/// The caller checks the expected evaluation of the zero check subclaim
+/// against `evaluation`, and the final query against the product polynomial.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PlookupCheckSubClaim<F: PrimeField, ZC: ZeroCheck<F>> {
+    /// the SubClaim from the ProductCheck
+    pub product_check_sub_claim: ProductCheckSubClaim<F, ZC>,
+    /// the challenges of the lookup argument
+    pub beta: F,
+    pub gamma: F,
+    /// the challenges of the shift argument
+    pub delta: F,
+    pub epsilon: F,
+}
+
+impl<F: PrimeField, ZC: ZeroCheck<F>> PlookupCheckSubClaim<F, ZC> {
+    /// The evaluation at the point `(x_1, ..., x_n)` of the zero check
+    /// subclaim of the virtual polynomial of the product check, given
+    /// - the evaluations of `prod(x)` and `frac(x)` at `(x_1, ..., x_n)`,
+    ///   `(0, x_1, ..., x_{n-1})` and `(1, x_1, ..., x_{n-1})`,
+    /// - and the evaluations of `f1, ..., fk`, `t`, `t'`, `s0, ..., sk` and
+    ///   `s0', ..., sk'` at `(x_1, ..., x_n)`.
+    #[allow(clippy::too_many_arguments)]
+    pub fn evaluation(
+        &self,
+        point: &[F],
+        prod_evals: &[F],
+        frac_evals: &[F],
+        fx_evals: &[F],
+        table_eval: F,
+        table_shift_eval: F,
+        sorted_evals: &[F],
+        sorted_shift_evals: &[F],
+    ) -> Result<F, PolyIOPErrors> {
+        if point.is_empty()
+            || prod_evals.len() != 3
+            || frac_evals.len() != 3
+            || sorted_evals.len() != fx_evals.len() + 1
+            || sorted_shift_evals.len() != sorted_evals.len()
+        {
+            return Err(PolyIOPErrors::InvalidParameters(
+                "wrong number of evaluations".to_string(),
+            ));
+        }
+        let (ids, sigmas) = eval_ids(point, sorted_evals.len());
+        let (nums, denoms) = eval_nums_and_denoms(
+            &[self.beta, self.gamma, self.delta, self.epsilon],
+            &ids,
+            &sigmas,
+            fx_evals,
+            table_eval,
+            table_shift_eval,
+            sorted_evals,
+            sorted_shift_evals,
+        );
+
+        // Q(x) = prod(x) - p1(x) * p2(x)
+        //     + alpha * [frac(x) * g1(x) * ... * gk(x) - f1(x) * ... * fk(x)]
+        let x1 = point[point.len() - 1];
+        let p1_eval = frac_evals[1] + x1 * (prod_evals[1] - frac_evals[1]);
+        let p2_eval = frac_evals[2] + x1 * (prod_evals[2] - frac_evals[2]);
+        let num_prod: F = nums.iter().product();
+        let denom_prod: F = denoms.iter().product();
+        Ok(prod_evals[0] - p1_eval * p2_eval
+            + self.product_check_sub_claim.alpha * (frac_evals[0] * denom_prod - num_prod))
+    }
+}
+
+/// A plookup check proof consists of
+/// - the product check proof
+/// - commitments to the sorted polynomials and their shifts
+/// - a commitment to the shift of the table
+#[derive(Clone, Debug, Default, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct PlookupCheckProof<
+    F: PrimeField,
+    PCS: PolynomialCommitmentScheme<F>,
+    PC: ProductCheck<F, PCS>,
+> {
+    pub product_check_proof: PC::ProductCheckProof,
+    pub sorted_comms: Vec<PCS::Commitment>,
+    pub sorted_shift_comms: Vec<PCS::Commitment>,
+    pub table_shift_comm: PCS::Commitment,
+}
+
+impl<F, PCS, T> PlookupCheck<F, PCS> for PolyIOP<F, T>
+where
+    F: PrimeField,
+    PCS: PolynomialCommitmentScheme<F, Polynomial = Arc<DenseMultilinearExtension<F>>>,
+    T: Transcript<F>,
+{
+    type PlookupCheckSubClaim = PlookupCheckSubClaim<F, Self>;
+    type PlookupCheckProof = PlookupCheckProof<F, PCS, Self>;
+
+    fn init_transcript() -> Self::Transcript {
+        T::new(b"Initializing PlookupCheck transcript")
+    }
+
+    fn prove(
+        pcs_param: &PCS::ProverParam,
+        fxs: &[Self::MultilinearExtension],
+        table: &Self::MultilinearExtension,
+        transcript: &mut Self::Transcript,
+    ) -> Result<
+        (
+            Self::PlookupCheckProof,
+            Vec<Self::MultilinearExtension>,
+            Vec<Self::MultilinearExtension>,
+            Self::MultilinearExtension,
+            Self::MultilinearExtension,
+            Self::MultilinearExtension,
+        ),
+        PolyIOPErrors,
+    > {
+        let start = start_timer!(|| "plookup_check prove");
+
+        if fxs.is_empty() {
+            return Err(PolyIOPErrors::InvalidParameters("fxs is empty".to_string()));
+        }
+        for fx in fxs.iter() {
+            if fx.num_vars != table.num_vars {
+                return Err(PolyIOPErrors::InvalidParameters(
+                    "fxs and table have different number of variables".to_string(),
+                ));
+            }
+        }
+
+        // the sorted polynomials and the shifts
+        let sorted = compute_sorted_polys(fxs, table)?;
+        let sorted_shift = shift_polys(&sorted);
+        let table_shift = shift_polys(std::slice::from_ref(table)).remove(0);
+        let sorted_comms = sorted
+            .iter()
+            .map(|s| PCS::commit(pcs_param, s))
+            .collect::<Result<Vec<_>, _>>()?;
+        let sorted_shift_comms = sorted_shift
+            .iter()
+            .map(|s| PCS::commit(pcs_param, s))
+            .collect::<Result<Vec<_>, _>>()?;
+        let table_shift_comm = PCS::commit(pcs_param, &table_shift)?;
+        for comm in sorted_comms.iter() {
+            transcript.append_serializable_element(b"s(x)", comm)?;
+        }
+        for comm in sorted_shift_comms.iter() {
+            transcript.append_serializable_element(b"s'(x)", comm)?;
+        }
+        transcript.append_serializable_element(b"t'(x)", &table_shift_comm)?;
+
+        // generate the challenges from current transcript
+        let beta = transcript.get_and_append_challenge(b"beta")?;
+        let gamma = transcript.get_and_append_challenge(b"gamma")?;
+        let delta = transcript.get_and_append_challenge(b"delta")?;
+        let epsilon = transcript.get_and_append_challenge(b"epsilon")?;
+        let (numerators, denominators) = compute_nums_and_denoms(
+            &[beta, gamma, delta, epsilon],
+            fxs,
+            table,
+            &table_shift,
+            &sorted,
+            &sorted_shift,
+        )?;
+
+        // invoke product check on numerator and denominator
+        let (product_check_proof, prod_poly, frac_poly) = <Self as ProductCheck<F, PCS>>::prove(
+            pcs_param,
+            &numerators,
+            &denominators,
+            transcript,
+        )?;
+
+        end_timer!(start);
+        Ok((
+            PlookupCheckProof {
+                product_check_proof,
+                sorted_comms,
+                sorted_shift_comms,
+                table_shift_comm,
+            },
+            sorted,
+            sorted_shift,
+            table_shift,
+            prod_poly,
+            frac_poly,
+        ))
+    }
+
+    fn verify(
+        proof: &Self::PlookupCheckProof,
+        aux_info: &VPAuxInfo<F>,
+        transcript: &mut Self::Transcript,
+    ) -> Result<Self::PlookupCheckSubClaim, PolyIOPErrors> {
+        let start = start_timer!(|| "plookup_check verify");
+
+        // k witnesses have k + 1 sorted polynomials, and a product check of
+        // degree 2k + 4
+        let num_sorted = proof.sorted_comms.len();
+        if num_sorted < 2
+            || proof.sorted_shift_comms.len() != num_sorted
+            || aux_info.max_degree != 2 * num_sorted + 2
+        {
+            return Err(PolyIOPErrors::InvalidProof(format!(
+                "got {} sorted and {} shifted commitments for a max degree of {}",
+                num_sorted,
+                proof.sorted_shift_comms.len(),
+                aux_info.max_degree
+            )));
+        }
+
+        // update transcript and generate challenges
+        for comm in proof.sorted_comms.iter() {
+            transcript.append_serializable_element(b"s(x)", comm)?;
+        }
+        for comm in proof.sorted_shift_comms.iter() {
+            transcript.append_serializable_element(b"s'(x)", comm)?;
+        }
+        transcript.append_serializable_element(b"t'(x)", &proof.table_shift_comm)?;
+        let beta = transcript.get_and_append_challenge(b"beta")?;
+        let gamma = transcript.get_and_append_challenge(b"gamma")?;
+        let delta = transcript.get_and_append_challenge(b"delta")?;
+        let epsilon = transcript.get_and_append_challenge(b"epsilon")?;
+
+        // invoke the product check on the proof
+        let product_check_sub_claim = <Self as ProductCheck<F, PCS>>::verify(
+            &proof.product_check_proof,
+            aux_info,
+            transcript,
+        )?;
+
+        end_timer!(start);
+        Ok(PlookupCheckSubClaim {
+            product_check_sub_claim,
+            beta,
+            gamma,
+            delta,
+            epsilon,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::PlookupCheck;
+    use crate::{
+        pcs::{prelude::MultilinearKzgPCS, PolynomialCommitmentScheme},
+        poly_iop::{errors::PolyIOPErrors, PolyIOP},
+    };
+    use arithmetic::{evaluate_opt, VPAuxInfo};
+    use ark_bls12_381::{Bls12_381, Fr};
+    use ark_ff::PrimeField;
+    use ark_poly::{DenseMultilinearExtension, MultilinearExtension};
+    use ark_std::{rand::Rng, test_rng};
+    use std::{marker::PhantomData, sync::Arc};
+    use transcript::Transcript;
+
+    type Kzg = MultilinearKzgPCS<Bls12_381>;
+
+    /// Prove the lookup of `fxs` and check the subclaim against `claimed_fxs`.
+    fn test_plookup_check_helper<F, PCS>(
+        pcs_param: &PCS::ProverParam,
+        fxs: &[Arc<DenseMultilinearExtension<F>>],
+        claimed_fxs: &[Arc<DenseMultilinearExtension<F>>],
+        table: &Arc<DenseMultilinearExtension<F>>,
+    ) -> Result<(), PolyIOPErrors>
+    where
+        F: PrimeField,
+        PCS: PolynomialCommitmentScheme<F, Polynomial = Arc<DenseMultilinearExtension<F>>>,
+    {
+        let nv = table.num_vars;
+        let aux_info = VPAuxInfo {
+            max_degree: 2 * fxs.len() + 4,
+            num_variables: nv,
+            phantom: PhantomData,
+        };
+
+        // prover
+        let mut transcript = <PolyIOP<F> as PlookupCheck<F, PCS>>::init_transcript();
+        transcript.append_message(b"testing", b"initializing transcript for testing")?;
+        let (proof, sorted, sorted_shift, table_shift, prod_x, frac_poly) =
+            <PolyIOP<F> as PlookupCheck<F, PCS>>::prove(pcs_param, fxs, table, &mut transcript)?;
+
+        // verifier
+        let mut transcript = <PolyIOP<F> as PlookupCheck<F, PCS>>::init_transcript();
+        transcript.append_message(b"testing", b"initializing transcript for testing")?;
+        let sub_claim =
+            <PolyIOP<F> as PlookupCheck<F, PCS>>::verify(&proof, &aux_info, &mut transcript)?;
+
+        // check the final query
+        let final_query = &sub_claim.product_check_sub_claim.final_query;
+        if evaluate_opt(&prod_x, &final_query.0) != final_query.1 {
+            return Err(PolyIOPErrors::InvalidVerifier(
+                "wrong final query".to_string(),
+            ));
+        }
+
+        // check the zero check subclaim
+        let zero_check_sub_claim = &sub_claim.product_check_sub_claim.zero_check_sub_claim;
+        let point = &zero_check_sub_claim.point;
+        let point_0 = [&[F::zero()], &point[0..nv - 1]].concat();
+        let point_1 = [&[F::one()], &point[0..nv - 1]].concat();
+        let evals_at = |poly: &Arc<DenseMultilinearExtension<F>>| {
+            vec![
+                evaluate_opt(poly, point),
+                evaluate_opt(poly, &point_0),
+                evaluate_opt(poly, &point_1),
+            ]
+        };
+        let evals = |polys: &[Arc<DenseMultilinearExtension<F>>]| -> Vec<_> {
+            polys.iter().map(|p| evaluate_opt(p, point)).collect()
+        };
+        if sub_claim.evaluation(
+            point,
+            &evals_at(&prod_x),
+            &evals_at(&frac_poly),
+            &evals(claimed_fxs),
+            evaluate_opt(table, point),
+            evaluate_opt(&table_shift, point),
+            &evals(&sorted),
+            &evals(&sorted_shift),
+        )? != zero_check_sub_claim.expected_evaluation
+        {
+            return Err(PolyIOPErrors::InvalidVerifier(
+                "wrong zero check subclaim".to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// `num_witnesses` random lookups into `table`.
+    fn random_lookups<R: Rng>(
+        table: &DenseMultilinearExtension<Fr>,
+        num_witnesses: usize,
+        rng: &mut R,
+    ) -> Vec<Arc<DenseMultilinearExtension<Fr>>> {
+        (0..num_witnesses)
+            .map(|_| {
+                Arc::new(DenseMultilinearExtension::from_evaluations_vec(
+                    table.num_vars,
+                    (0..table.evaluations.len())
+                        .map(|_| table.evaluations[rng.gen_range(0..table.evaluations.len())])
+                        .collect(),
+                ))
+            })
+            .collect()
+    }
+
+    fn test_plookup_check(nv: usize) -> Result<(), PolyIOPErrors> {
+        let mut rng = test_rng();
+
+        let srs = MultilinearKzgPCS::<Bls12_381>::gen_srs_for_testing(&mut rng, nv)?;
+        let (pcs_param, _) = MultilinearKzgPCS::<Bls12_381>::trim(&srs, None, Some(nv))?;
+
+        {
+            // good path: random lookups into a random table
+            let table = DenseMultilinearExtension::rand(nv, &mut rng);
+            for num_witnesses in 1..3 {
+                let fxs = random_lookups(&table, num_witnesses, &mut rng);
+                test_plookup_check_helper::<Fr, Kzg>(
+                    &pcs_param,
+                    &fxs,
+                    &fxs,
+                    &Arc::new(table.clone()),
+                )?;
+            }
+        }
+
+        {
+            // good path: a table with repeated entries, e.g. padded
+            let mut table = DenseMultilinearExtension::rand(nv, &mut rng);
+            let last = table.evaluations[0];
+            let len = table.evaluations.len();
+            table.evaluations[len / 2..].fill(last);
+            let fxs = random_lookups(&table, 2, &mut rng);
+            test_plookup_check_helper::<Fr, Kzg>(&pcs_param, &fxs, &fxs, &Arc::new(table))?;
+        }
+
+        {
+            // bad path 1: a witness that is not in the table
+            let table = DenseMultilinearExtension::rand(nv, &mut rng);
+            let fxs = vec![Arc::new(DenseMultilinearExtension::rand(nv, &mut rng))];
+            assert!(
+                test_plookup_check_helper::<Fr, Kzg>(&pcs_param, &fxs, &fxs, &Arc::new(table))
+                    .is_err()
+            );
+        }
+
+        {
+            // bad path 2: the subclaim is checked against other witnesses
+            let table = DenseMultilinearExtension::rand(nv, &mut rng);
+            let fxs = random_lookups(&table, 1, &mut rng);
+            let claimed_fxs = random_lookups(&table, 1, &mut rng);
+            assert!(test_plookup_check_helper::<Fr, Kzg>(
+                &pcs_param,
+                &fxs,
+                &claimed_fxs,
+                &Arc::new(table)
+            )
+            .is_err());
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_trivial_polynomial() -> Result<(), PolyIOPErrors> {
+        let _trace = crate::trace_guard();
+        test_plookup_check(1)
+    }
+    #[test]
+    fn test_normal_polynomial() -> Result<(), PolyIOPErrors> {
+        let _trace = crate::trace_guard();
+        test_plookup_check(5)
+    }
+
+    #[test]
+    fn zero_polynomial_should_error() -> Result<(), PolyIOPErrors> {
+        assert!(test_plookup_check(0).is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,233 @@
+// Copyright (c) 2023 Espresso Systems (espressosys.com)
+// This file is part of the HyperPlonk library.
+
+// You should have received a copy of the MIT License
+// along with the HyperPlonk library. If not, see <https://mit-license.org/>.
+
+//! This module implements useful functions for the Plookup check protocol.
+
+use crate::poly_iop::errors::PolyIOPErrors;
+use ark_ff::PrimeField;
+use ark_poly::DenseMultilinearExtension;
+use ark_std::{end_timer, start_timer};
+use std::{collections::HashMap, sync::Arc};
+
+/// Compute the sorted polynomials `(s0, ..., sk)`, i.e. the concatenation of
+/// `f1, ..., fk` and `t` sorted by the order of `t`, split into `k + 1`
+/// polynomials of the number of variables of `t`. Each witness entry is placed
+/// right after the first occurrence of the same entry in the table.
+///
+/// The caller needs to sanity-check that fxs is not empty and that the number
+/// of variables match within fxs and the table.
+pub(super) fn compute_sorted_polys<F: PrimeField>(
+    fxs: &[Arc<DenseMultilinearExtension<F>>],
+    table: &Arc<DenseMultilinearExtension<F>>,
+) -> Result<Vec<Arc<DenseMultilinearExtension<F>>>, PolyIOPErrors> {
+    let start = start_timer!(|| "compute s(x)");
+
+    let mut indices = HashMap::with_capacity(table.evaluations.len());
+    for (i, t) in table.evaluations.iter().enumerate() {
+        indices.entry(*t).or_insert(i);
+    }
+    let mut counts = vec![0usize; table.evaluations.len()];
+    for fx in fxs.iter() {
+        for f in fx.evaluations.iter() {
+            match indices.get(f) {
+                Some(&i) => counts[i] += 1,
+                None => {
+                    return Err(PolyIOPErrors::InvalidParameters(format!(
+                        "witness entry {} is not in the table",
+                        f
+                    )))
+                },
+            }
+        }
+    }
+
+    let mut sorted = Vec::with_capacity((fxs.len() + 1) * table.evaluations.len());
+    for (t, &count) in table.evaluations.iter().zip(counts.iter()) {
+        sorted.extend(std::iter::repeat_n(*t, count + 1));
+    }
+
+    end_timer!(start);
+    Ok(split(&sorted, table.num_vars))
+}
+
+/// Shift the concatenation of `polys` by one position, cyclically, i.e. the
+/// `j`-th evaluation of the output is the `j + 1`-th evaluation of the input,
+/// and split it back into polynomials of the same size.
+pub(super) fn shift_polys<F: PrimeField>(
+    polys: &[Arc<DenseMultilinearExtension<F>>],
+) -> Vec<Arc<DenseMultilinearExtension<F>>> {
+    let num_vars = polys[0].num_vars;
+    let mut evals: Vec<F> = polys
+        .iter()
+        .flat_map(|p| p.evaluations.iter().copied())
+        .collect();
+    evals.rotate_left(1);
+    split(&evals, num_vars)
+}
+
+/// Returns the evaluations of two lists of MLEs:
+/// - numerators:
+///   - `(1 + beta) * (gamma + fi(x))` for each witness,
+///   - `gamma * (1 + beta) + t(x) + beta * t'(x)`,
+///   - `sc(x) + delta * id_c(x) + epsilon` for each sorted polynomial,
+///   - `t(x) + delta * id_t(x) + epsilon`;
+/// - denominators:
+///   - `gamma * (1 + beta) + sc(x) + beta * sc'(x)` for each sorted
+///     polynomial,
+///   - `sc'(x) + delta * sigma_c(x) + epsilon` for each sorted polynomial,
+///   - `t'(x) + delta * sigma_t(x) + epsilon`,
+///
+/// where the `id` and `sigma` are given by `eval_ids`.
+///
+/// The caller is responsible for sanity-check
+#[allow(clippy::type_complexity)]
+pub(super) fn compute_nums_and_denoms<F: PrimeField>(
+    challenges: &[F; 4],
+    fxs: &[Arc<DenseMultilinearExtension<F>>],
+    table: &Arc<DenseMultilinearExtension<F>>,
+    table_shift: &Arc<DenseMultilinearExtension<F>>,
+    sorted: &[Arc<DenseMultilinearExtension<F>>],
+    sorted_shift: &[Arc<DenseMultilinearExtension<F>>],
+) -> Result<
+    (
+        Vec<Arc<DenseMultilinearExtension<F>>>,
+        Vec<Arc<DenseMultilinearExtension<F>>>,
+    ),
+    PolyIOPErrors,
+> {
+    let start = start_timer!(|| "compute numerators and denominators");
+
+    let num_vars = table.num_vars;
+    let size = F::from(1u64 << num_vars);
+    let mut numerators = vec![vec![]; fxs.len() + sorted.len() + 2];
+    let mut denominators = vec![vec![]; 2 * sorted.len() + 1];
+    for x in 0..1 << num_vars {
+        let is_last = F::from((x + 1 == 1 << num_vars) as u64);
+        let (ids, sigmas) = ids_and_sigmas(F::from(x as u64), is_last, size, sorted.len());
+        let (nums, denoms) = eval_nums_and_denoms(
+            challenges,
+            &ids,
+            &sigmas,
+            &fxs.iter().map(|f| f.evaluations[x]).collect::<Vec<_>>(),
+            table.evaluations[x],
+            table_shift.evaluations[x],
+            &sorted.iter().map(|s| s.evaluations[x]).collect::<Vec<_>>(),
+            &sorted_shift
+                .iter()
+                .map(|s| s.evaluations[x])
+                .collect::<Vec<_>>(),
+        );
+        for (evals, num) in numerators.iter_mut().zip(nums) {
+            evals.push(num);
+        }
+        for (evals, denom) in denominators.iter_mut().zip(denoms) {
+            evals.push(denom);
+        }
+    }
+
+    let to_mles = |evals: Vec<Vec<F>>| {
+        evals
+            .into_iter()
+            .map(|e| Arc::new(DenseMultilinearExtension::from_evaluations_vec(num_vars, e)))
+            .collect()
+    };
+
+    end_timer!(start);
+    Ok((to_mles(numerators), to_mles(denominators)))
+}
+
+/// The evaluations of the numerators and denominators of
+/// `compute_nums_and_denoms` at a point, given the evaluations of the
+/// identities and shifts of `eval_ids`, of the witnesses, of the table and of
+/// the sorted polynomials at that point.
+#[allow(clippy::too_many_arguments)]
+pub(super) fn eval_nums_and_denoms<F: PrimeField>(
+    challenges: &[F; 4],
+    ids: &[F],
+    sigmas: &[F],
+    fx_evals: &[F],
+    table_eval: F,
+    table_shift_eval: F,
+    sorted_evals: &[F],
+    sorted_shift_evals: &[F],
+) -> (Vec<F>, Vec<F>) {
+    let [beta, gamma, delta, epsilon] = *challenges;
+    let one_plus_beta = F::one() + beta;
+    let gamma_one_plus_beta = gamma * one_plus_beta;
+
+    let mut nums: Vec<F> = fx_evals
+        .iter()
+        .map(|f| one_plus_beta * (gamma + f))
+        .collect();
+    nums.push(gamma_one_plus_beta + table_eval + beta * table_shift_eval);
+    nums.extend(
+        sorted_evals
+            .iter()
+            .zip(ids.iter())
+            .map(|(s, id)| *s + delta * id + epsilon),
+    );
+    nums.push(table_eval + delta * ids[sorted_evals.len()] + epsilon);
+
+    let mut denoms: Vec<F> = sorted_evals
+        .iter()
+        .zip(sorted_shift_evals.iter())
+        .map(|(s, s_shift)| gamma_one_plus_beta + s + beta * s_shift)
+        .collect();
+    denoms.extend(
+        sorted_shift_evals
+            .iter()
+            .zip(sigmas.iter())
+            .map(|(s, sigma)| *s + delta * sigma + epsilon),
+    );
+    denoms.push(table_shift_eval + delta * sigmas[sorted_evals.len()] + epsilon);
+
+    (nums, denoms)
+}
+
+/// Evaluate at `point` the identity `id_c(x) = c * 2^n + x` and the shift
+/// `sigma_c(x)` of the sorted polynomials `c = 0, ..., num_sorted - 1` and of
+/// the table, whose identity is `id_t(x) = num_sorted * 2^n + x`.
+///
+/// The sorted polynomials are shifted cyclically as a whole, i.e. `sigma_c(x)
+/// = id_c(x) + 1` except for the last evaluation of the last polynomial which
+/// is mapped to `0`, and the table is shifted cyclically on its own.
+pub(super) fn eval_ids<F: PrimeField>(point: &[F], num_sorted: usize) -> (Vec<F>, Vec<F>) {
+    // the integer `x` and the indicator of the last index
+    let (x, is_last) = point
+        .iter()
+        .rev()
+        .fold((F::zero(), F::one()), |(x, last), r| {
+            (x.double() + r, last * r)
+        });
+    ids_and_sigmas(x, is_last, F::from(1u64 << point.len()), num_sorted)
+}
+
+/// The identities and shifts of `eval_ids` for the evaluations `x` of the
+/// index and `is_last` of the indicator of the last index, over `size`
+/// evaluations per polynomial.
+fn ids_and_sigmas<F: PrimeField>(x: F, is_last: F, size: F, num_sorted: usize) -> (Vec<F>, Vec<F>) {
+    let mut ids = Vec::with_capacity(num_sorted + 1);
+    let mut sigmas = Vec::with_capacity(num_sorted + 1);
+    for c in 0..=num_sorted {
+        let id = F::from(c as u64) * size + x;
+        ids.push(id);
+        sigmas.push(id + F::one());
+    }
+    sigmas[num_sorted - 1] -= F::from(num_sorted as u64) * size * is_last;
+    sigmas[num_sorted] -= size * is_last;
+    (ids, sigmas)
+}
+
+fn split<F: PrimeField>(evals: &[F], num_vars: usize) -> Vec<Arc<DenseMultilinearExtension<F>>> {
+    evals
+        .chunks(1 << num_vars)
+        .map(|chunk| {
+            Arc::new(DenseMultilinearExtension::from_evaluations_slice(
+                num_vars, chunk,
+            ))
+        })
+        .collect()
+}
@@ -8,10 +8,19 @@
 
 pub use crate::poly_iop::{
     errors::PolyIOPErrors,
-    // perm_check::PermutationCheck, prod_check::ProductCheck,
+    frac_sum_check::{FractionalSumCheck, GkrFraction},
+    gkr::{Gate, Gkr, GkrProof, GkrSubClaim, LayeredCircuit},
+    lookup_check::LookupCheck,
+    perm_check::PermutationCheck,
+    plookup_check::PlookupCheck,
+    prod_check::{
+        CommittedProduct, GkrProduct, ProductCheck, ProductCheckProof, ZkProductCheckOracles,
+        ZkProductCheckProof,
+    },
+    range_check::RangeCheck,
     structs::IOPProof,
-    sum_check::SumCheck,
+    sum_check::{SumCheck, SumCheckMask, SumCheckMaskClaims, ZkSumCheckProof},
     utils::*,
-    // zero_check::ZeroCheck,
+    zero_check::ZeroCheck,
     PolyIOP,
 };
@@ -0,0 +1,478 @@
+// Copyright (c) 2023 Espresso Systems (espressosys.com)
+// This file is part of the HyperPlonk library.
+
+// You should have received a copy of the MIT License
+// along with the HyperPlonk library. If not, see <https://mit-license.org/>.
+
+//! This module implements the product check with layered GKR circuits.
+//!
+//! The products `\prod_x f1(x) * ... * fk(x)` and `\prod_x g1(x) * ... *
+//! gk(x)` are computed by two binary trees of multiplication gates, whose
+//! `d`-th layer `V_d` has `2^d` entries with
+//!   V_d(x) = V_{d+1}(x, 0) * V_{d+1}(x, 1)
+//! and whose leaves are `V_n(x) = f1(x) * ... * fk(x)` (resp. `g`). The
+//! verifier checks that the roots match, and reduces a claim on `V_d(r)` to a
+//! claim on `V_{d+1}(r')` with a sum check on
+//!   V_d(r) = \sum_{x \in {0,1}^d} eq(r, x) * V_{d+1}(x, 0) * V_{d+1}(x, 1)
+//! where the claims on the two trees are batched with a random `lambda`. The
+//! last sum check runs on the `fi` and `gi` directly, so the verifier ends
+//! with a claim on their evaluations at a random point, and no intermediate
+//! polynomial is committed.
+
+use crate::{
+    pcs::{HidingPolynomialCommitmentScheme, PolynomialCommitmentScheme},
+    poly_iop::{
+        errors::PolyIOPErrors,
+        prod_check::{GkrProduct, ProductCheck, ZkProductCheckOracles, ZkProductCheckProof},
+        sum_check::{SumCheck, SumCheckMaskClaims},
+        PolyIOP,
+    },
+};
+use arithmetic::{build_eq_x_r, eq_eval, fix_variables, VPAuxInfo, VirtualPolynomial};
+use ark_ff::PrimeField;
+use ark_poly::DenseMultilinearExtension;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_std::{end_timer, rand::Rng, start_timer};
+use std::{marker::PhantomData, sync::Arc};
+use transcript::Transcript;
+
+/// A GKR product check subclaim consists of
+/// - the point the polynomials are queried at
+/// - the expected evaluations of `f1, ..., fk` at the point
+/// - the expected evaluations of `g1, ..., gk` at the point
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct GkrProductCheckSubClaim<F: PrimeField> {
+    /// the point of the queries
+    pub point: Vec<F>,
+    /// the expected evaluations of the numerators
+    pub fx_evals: Vec<F>,
+    /// the expected evaluations of the denominators
+    pub gx_evals: Vec<F>,
+}
+
+/// A GKR product check proof consists of
+/// - a sum check proof per layer of the trees, except for the root
+/// - per layer, the evaluations of `V_{d+1}(r', 0)` and `V_{d+1}(r', 1)` on
+///   both trees, or those of each `fi` and `gi` for the last layer
+#[derive(Clone, Debug, Default, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct GkrProductCheckProof<F: PrimeField, SC: SumCheck<F>> {
+    pub layer_sum_check_proofs: Vec<SC::SumCheckProof>,
+    pub layer_evals: Vec<Vec<F>>,
+}
+
+impl<F, PCS, T> ProductCheck<F, PCS> for PolyIOP<F, T, GkrProduct>
+where
+    F: PrimeField,
+    PCS: PolynomialCommitmentScheme<F>,
+    T: Transcript<F>,
+{
+    type ProductCheckSubClaim = GkrProductCheckSubClaim<F>;
+    type ProductCheckProof = GkrProductCheckProof<F, Self>;
+
+    fn init_transcript() -> Self::Transcript {
+        T::new(b"Initializing ProductCheck transcript")
+    }
+
+    fn prove(
+        _pcs_param: &PCS::ProverParam,
+        fxs: &[Self::MultilinearExtension],
+        gxs: &[Self::MultilinearExtension],
+        transcript: &mut Self::Transcript,
+    ) -> Result<
+        (
+            Self::ProductCheckProof,
+            Self::MultilinearExtension,
+            Self::MultilinearExtension,
+        ),
+        PolyIOPErrors,
+    > {
+        let start = start_timer!(|| "gkr prod_check prove");
+
+        if fxs.is_empty() {
+            return Err(PolyIOPErrors::InvalidParameters("fxs is empty".to_string()));
+        }
+        if fxs.len() != gxs.len() {
+            return Err(PolyIOPErrors::InvalidParameters(
+                "fxs and gxs have different number of polynomials".to_string(),
+            ));
+        }
+        let num_vars = fxs[0].num_vars;
+        if num_vars == 0 {
+            return Err(PolyIOPErrors::InvalidParameters(
+                "fx and gx have no variables".to_string(),
+            ));
+        }
+        for poly in fxs.iter().chain(gxs.iter()) {
+            if poly.num_vars != num_vars {
+                return Err(PolyIOPErrors::InvalidParameters(
+                    "fx and gx have different number of variables".to_string(),
+                ));
+            }
+        }
+
+        // layers[d] is the layer of d + 1 variables, so that the last one holds
+        // the leaves
+        let num_layers = build_product_layers(fxs);
+        let den_layers = build_product_layers(gxs);
+
+        let mut point = vec![];
+        let mut layer_sum_check_proofs = Vec::with_capacity(num_vars - 1);
+        let mut layer_evals = Vec::with_capacity(num_vars);
+        for d in 0..num_vars {
+            // the polynomials whose products make the layer d + 1
+            let (nums, dens) = if d + 1 == num_vars {
+                (fxs.to_vec(), gxs.to_vec())
+            } else {
+                (vec![num_layers[d].clone()], vec![den_layers[d].clone()])
+            };
+
+            let mut next_point = if d == 0 {
+                vec![]
+            } else {
+                let lambda = transcript.get_and_append_challenge(b"lambda")?;
+                let poly = build_layer_poly(&point, &nums, &dens, lambda)?;
+                let proof = <Self as SumCheck<F>>::prove(&poly, transcript)?;
+                let next_point = proof.point.clone();
+                layer_sum_check_proofs.push(proof);
+                next_point
+            };
+
+            let evals = nums
+                .iter()
+                .chain(dens.iter())
+                .flat_map(|poly| fix_variables(poly, &next_point).evaluations)
+                .collect::<Vec<_>>();
+            transcript.append_serializable_element(b"layer evals", &evals)?;
+            next_point.push(transcript.get_and_append_challenge(b"mu")?);
+
+            layer_evals.push(evals);
+            point = next_point;
+        }
+
+        end_timer!(start);
+
+        Ok((
+            GkrProductCheckProof {
+                layer_sum_check_proofs,
+                layer_evals,
+            },
+            num_layers[num_vars - 1].clone(),
+            den_layers[num_vars - 1].clone(),
+        ))
+    }
+
+    fn verify(
+        proof: &Self::ProductCheckProof,
+        aux_info: &VPAuxInfo<F>,
+        transcript: &mut Self::Transcript,
+    ) -> Result<Self::ProductCheckSubClaim, PolyIOPErrors> {
+        let start = start_timer!(|| "gkr prod_check verify");
+
+        let num_vars = aux_info.num_variables;
+        // the aux info is that of the product check, whose degree is k + 1
+        if num_vars == 0 || aux_info.max_degree < 2 {
+            return Err(PolyIOPErrors::InvalidParameters(format!(
+                "gkr product check: invalid aux info {:?}",
+                aux_info
+            )));
+        }
+        let num_polys = aux_info.max_degree - 1;
+        if proof.layer_evals.len() != num_vars || proof.layer_sum_check_proofs.len() + 1 != num_vars
+        {
+            return Err(PolyIOPErrors::InvalidProof(format!(
+                "gkr product check: {} layer evaluations and {} sum check proofs for {} variables",
+                proof.layer_evals.len(),
+                proof.layer_sum_check_proofs.len(),
+                num_vars
+            )));
+        }
+
+        let mut point = vec![];
+        // the claims on V_d(point) of the numerator and denominator trees
+        let mut claims = (F::zero(), F::zero());
+        let mut sub_claim = GkrProductCheckSubClaim::default();
+        for (d, evals) in proof.layer_evals.iter().enumerate() {
+            let width = if d + 1 == num_vars { num_polys } else { 1 };
+            if evals.len() != 4 * width {
+                return Err(PolyIOPErrors::InvalidProof(format!(
+                    "gkr product check: {} evaluations for layer {}, expect {}",
+                    evals.len(),
+                    d + 1,
+                    4 * width
+                )));
+            }
+            let product =
+                |evals: &[F], b: usize| -> F { evals.iter().skip(b).step_by(2).product() };
+            let (nums, dens) = evals.split_at(2 * width);
+            let num_eval = product(nums, 0) * product(nums, 1);
+            let den_eval = product(dens, 0) * product(dens, 1);
+
+            let mut next_point = if d == 0 {
+                // the roots of the trees are the two products
+                if num_eval != den_eval {
+                    return Err(PolyIOPErrors::InvalidProof(
+                        "gkr product check: the products are different".to_string(),
+                    ));
+                }
+                vec![]
+            } else {
+                let lambda = transcript.get_and_append_challenge(b"lambda")?;
+                let layer_aux_info = VPAuxInfo {
+                    max_degree: 2 * width + 1,
+                    num_variables: d,
+                    phantom: PhantomData,
+                };
+                let sum_check_sub_claim = <Self as SumCheck<F>>::verify(
+                    claims.0 + lambda * claims.1,
+                    &proof.layer_sum_check_proofs[d - 1],
+                    &layer_aux_info,
+                    transcript,
+                )?;
+                let eq_eval = eq_eval(&point, &sum_check_sub_claim.point)?;
+                if eq_eval * (num_eval + lambda * den_eval)
+                    != sum_check_sub_claim.expected_evaluation
+                {
+                    return Err(PolyIOPErrors::InvalidProof(format!(
+                        "gkr product check: wrong evaluations for layer {}",
+                        d + 1
+                    )));
+                }
+                sum_check_sub_claim.point
+            };
+
+            transcript.append_serializable_element(b"layer evals", evals)?;
+            let mu = transcript.get_and_append_challenge(b"mu")?;
+            next_point.push(mu);
+
+            // reduce the claims on (r', 0) and (r', 1) to a claim on (r', mu)
+            let reduced = evals
+                .chunks(2)
+                .map(|e| e[0] + mu * (e[1] - e[0]))
+                .collect::<Vec<_>>();
+            if d + 1 == num_vars {
+                sub_claim = GkrProductCheckSubClaim {
+                    point: next_point.clone(),
+                    fx_evals: reduced[..num_polys].to_vec(),
+                    gx_evals: reduced[num_polys..].to_vec(),
+                };
+            } else {
+                claims = (reduced[0], reduced[1]);
+            }
+            point = next_point;
+        }
+
+        end_timer!(start);
+        Ok(sub_claim)
+    }
+
+    fn prove_zk<R: Rng>(
+        _pcs_param: &PCS::ProverParam,
+        _fxs: &[Self::MultilinearExtension],
+        _gxs: &[Self::MultilinearExtension],
+        _transcript: &mut Self::Transcript,
+        _rng: &mut R,
+    ) -> Result<(ZkProductCheckProof<F, PCS>, ZkProductCheckOracles<F, PCS>), PolyIOPErrors>
+    where
+        PCS: HidingPolynomialCommitmentScheme<F>,
+    {
+        Err(PolyIOPErrors::InvalidParameters(
+            "the gkr product check has no zero-knowledge mode".to_string(),
+        ))
+    }
+
+    fn verify_zk(
+        _proof: &ZkProductCheckProof<F, PCS>,
+        _aux_info: &VPAuxInfo<F>,
+        _transcript: &mut Self::Transcript,
+    ) -> Result<(Self::ProductCheckSubClaim, SumCheckMaskClaims<F>), PolyIOPErrors> {
+        Err(PolyIOPErrors::InvalidParameters(
+            "the gkr product check has no zero-knowledge mode".to_string(),
+        ))
+    }
+}
+
+/// Build the layers of the binary tree of multiplications whose leaves are
+/// `p1(x) * ... * pk(x)`, from the one of 1 variable to the leaves.
+fn build_product_layers<F: PrimeField>(
+    polys: &[Arc<DenseMultilinearExtension<F>>],
+) -> Vec<Arc<DenseMultilinearExtension<F>>> {
+    let start = start_timer!(|| "build product layers");
+
+    let num_vars = polys[0].num_vars;
+    let mut leaves = vec![F::one(); 1 << num_vars];
+    for poly in polys.iter() {
+        for (leaf, p) in leaves.iter_mut().zip(poly.iter()) {
+            *leaf *= p;
+        }
+    }
+
+    let mut layers = Vec::with_capacity(num_vars);
+    layers.push(Arc::new(DenseMultilinearExtension::from_evaluations_vec(
+        num_vars, leaves,
+    )));
+    for nv in (1..num_vars).rev() {
+        let (low, high) = layers[layers.len() - 1].evaluations.split_at(1 << nv);
+        let layer = low.iter().zip(high.iter()).map(|(l, h)| *l * h).collect();
+        layers.push(Arc::new(DenseMultilinearExtension::from_evaluations_vec(
+            nv, layer,
+        )));
+    }
+    layers.reverse();
+
+    end_timer!(start);
+    layers
+}
+
+/// Build the virtual polynomial of the sum check from layer `d` to layer
+/// `d + 1`:
+///   eq(r, x) * \prod_i num_i(x, 0) * num_i(x, 1)
+///     + lambda * eq(r, x) * \prod_i den_i(x, 0) * den_i(x, 1)
+/// where `nums` and `dens` have `d + 1` variables and `r` has `d` entries.
+fn build_layer_poly<F: PrimeField>(
+    r: &[F],
+    nums: &[Arc<DenseMultilinearExtension<F>>],
+    dens: &[Arc<DenseMultilinearExtension<F>>],
+    lambda: F,
+) -> Result<VirtualPolynomial<F>, PolyIOPErrors> {
+    let eq_x_r = build_eq_x_r(r)?;
+    let mut poly = VirtualPolynomial::new(r.len());
+    for (polys, coefficient) in [(nums, F::one()), (dens, lambda)] {
+        let (lows, highs): (Vec<_>, Vec<_>) = polys.iter().map(|p| split_halves(p)).unzip();
+        poly.add_mle_list(
+            std::iter::once(eq_x_r.clone()).chain(lows).chain(highs),
+            coefficient,
+        )?;
+    }
+    Ok(poly)
+}
+
+/// Split `p(x, y)` into `p(x, 0)` and `p(x, 1)`.
+pub(in crate::poly_iop) fn split_halves<F: PrimeField>(
+    poly: &Arc<DenseMultilinearExtension<F>>,
+) -> (
+    Arc<DenseMultilinearExtension<F>>,
+    Arc<DenseMultilinearExtension<F>>,
+) {
+    let nv = poly.num_vars - 1;
+    let (low, high) = poly.evaluations.split_at(1 << nv);
+    (
+        Arc::new(DenseMultilinearExtension::from_evaluations_slice(nv, low)),
+        Arc::new(DenseMultilinearExtension::from_evaluations_slice(nv, high)),
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        pcs::{prelude::MultilinearKzgPCS, PolynomialCommitmentScheme},
+        poly_iop::{
+            errors::PolyIOPErrors,
+            prod_check::{GkrProduct, ProductCheck},
+            PolyIOP,
+        },
+    };
+    use arithmetic::{evaluate_opt, VPAuxInfo};
+    use ark_bls12_381::{Bls12_381, Fr};
+    use ark_poly::{DenseMultilinearExtension, MultilinearExtension};
+    use ark_std::test_rng;
+    use std::{marker::PhantomData, sync::Arc};
+    use transcript::Transcript;
+
+    type Kzg = MultilinearKzgPCS<Bls12_381>;
+    type Gkr = PolyIOP<Fr, transcript::IOPTranscript<Fr>, GkrProduct>;
+
+    fn prove_and_verify(
+        pcs_param: &<Kzg as PolynomialCommitmentScheme<Fr>>::ProverParam,
+        fs: &[Arc<DenseMultilinearExtension<Fr>>],
+        gs: &[Arc<DenseMultilinearExtension<Fr>>],
+    ) -> Result<(), PolyIOPErrors> {
+        let mut transcript = <Gkr as ProductCheck<Fr, Kzg>>::init_transcript();
+        transcript.append_message(b"testing", b"initializing transcript for testing")?;
+        let (proof, num_leaves, den_leaves) =
+            <Gkr as ProductCheck<Fr, Kzg>>::prove(pcs_param, fs, gs, &mut transcript)?;
+        assert_eq!(
+            num_leaves.evaluations,
+            (0..1 << fs[0].num_vars)
+                .map(|i| fs.iter().map(|f| f.evaluations[i]).product::<Fr>())
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(den_leaves.num_vars, gs[0].num_vars);
+
+        let mut transcript = <Gkr as ProductCheck<Fr, Kzg>>::init_transcript();
+        transcript.append_message(b"testing", b"initializing transcript for testing")?;
+        let aux_info = VPAuxInfo {
+            max_degree: fs.len() + 1,
+            num_variables: fs[0].num_vars,
+            phantom: PhantomData,
+        };
+        let sub_claim = <Gkr as ProductCheck<Fr, Kzg>>::verify(&proof, &aux_info, &mut transcript)?;
+        for (poly, eval) in fs
+            .iter()
+            .chain(gs.iter())
+            .zip(sub_claim.fx_evals.iter().chain(sub_claim.gx_evals.iter()))
+        {
+            if evaluate_opt(poly, &sub_claim.point) != *eval {
+                return Err(PolyIOPErrors::InvalidVerifier("wrong subclaim".to_string()));
+            }
+        }
+        Ok(())
+    }
+
+    fn test_gkr_product_check(nv: usize) -> Result<(), PolyIOPErrors> {
+        let mut rng = test_rng();
+        let srs = Kzg::gen_srs_for_testing(&mut rng, nv)?;
+        let (pcs_param, _) = Kzg::trim(&srs, None, Some(nv))?;
+
+        let f1: DenseMultilinearExtension<Fr> = DenseMultilinearExtension::rand(nv, &mut rng);
+        let mut g1 = f1.clone();
+        g1.evaluations.reverse();
+        let f2: DenseMultilinearExtension<Fr> = DenseMultilinearExtension::rand(nv, &mut rng);
+        let mut g2 = f2.clone();
+        g2.evaluations.reverse();
+        let fs = vec![Arc::new(f1), Arc::new(f2)];
+        let gs = vec![Arc::new(g2), Arc::new(g1)];
+        prove_and_verify(&pcs_param, &fs, &gs)?;
+
+        // bad path: different products
+        let hs = vec![
+            Arc::new(DenseMultilinearExtension::rand(nv, &mut rng)),
+            Arc::new(DenseMultilinearExtension::rand(nv, &mut rng)),
+        ];
+        assert!(prove_and_verify(&pcs_param, &fs, &hs).is_err());
+
+        // bad path: a tampered layer evaluation
+        let mut transcript = <Gkr as ProductCheck<Fr, Kzg>>::init_transcript();
+        let (mut proof, _, _) =
+            <Gkr as ProductCheck<Fr, Kzg>>::prove(&pcs_param, &fs, &gs, &mut transcript)?;
+        let evals = proof.layer_evals.last_mut().unwrap();
+        evals[0] += Fr::from(1u64);
+        let mut transcript = <Gkr as ProductCheck<Fr, Kzg>>::init_transcript();
+        let aux_info = VPAuxInfo {
+            max_degree: fs.len() + 1,
+            num_variables: nv,
+            phantom: PhantomData,
+        };
+        assert!(
+            <Gkr as ProductCheck<Fr, Kzg>>::verify(&proof, &aux_info, &mut transcript).is_err()
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_trivial_polynomial() -> Result<(), PolyIOPErrors> {
+        let _trace = crate::trace_guard();
+        test_gkr_product_check(1)
+    }
+
+    #[test]
+    fn test_normal_polynomial() -> Result<(), PolyIOPErrors> {
+        let _trace = crate::trace_guard();
+        test_gkr_product_check(10)
+    }
+
+    #[test]
+    fn zero_polynomial_should_error() {
+        assert!(test_gkr_product_check(0).is_err());
+    }
+}
@@ -7,24 +7,47 @@
 //! Main module for the Product Check protocol
 
 use crate::{
-    pcs::PolynomialCommitmentScheme,
+    pcs::{HidingPolynomialCommitmentScheme, PolynomialCommitmentScheme},
     poly_iop::{
         errors::PolyIOPErrors,
-        prod_check::util::{compute_frac_poly, compute_product_poly, prove_zero_check},
+        prod_check::util::{
+            build_zero_check_poly, compute_frac_poly, compute_product_poly, prove_zero_check,
+        },
+        sum_check::{SumCheckMask, SumCheckMaskClaims, ZkSumCheckProof},
         zero_check::ZeroCheck,
         PolyIOP,
     },
 };
 use arithmetic::VPAuxInfo;
-use ark_ec::pairing::Pairing;
-use ark_ff::{One, PrimeField, Zero};
+use ark_ff::PrimeField;
 use ark_poly::DenseMultilinearExtension;
-use ark_std::{end_timer, start_timer};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_std::{end_timer, rand::Rng, start_timer};
 use std::sync::Arc;
-use transcript::IOPTranscript;
+use transcript::Transcript;
 
+mod gkr;
 mod util;
 
+pub(super) use gkr::split_halves;
+
+/// The markers selecting the ProductCheck of a `PolyIOP`.
+pub trait ProductArgument {}
+
+impl ProductArgument for CommittedProduct {}
+
+impl ProductArgument for GkrProduct {}
+
+/// Marker for the product check that commits to the fractional polynomial
+/// `frac(x)` and the product polynomial `prod(x)`, see `ProductCheck`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct CommittedProduct;
+
+/// Marker for the product check that proves the products with layered GKR
+/// circuits, and needs no commitment, see `GkrProductCheckProof`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct GkrProduct;
+
 /// A product-check proves that two lists of n-variate multilinear polynomials
 /// `(f1, f2, ..., fk)` and `(g1, ..., gk)` satisfy:
 /// \prod_{x \in {0,1}^n} f1(x) * ... * fk(x) = \prod_{x \in {0,1}^n} g1(x) *
@@ -52,13 +75,22 @@ mod util;
 /// 2. `generate_challenge` from current transcript (generate alpha)
 /// 3. `verify` to verify the zerocheck proof and generate the subclaim for
 ///    polynomial evaluations
-pub trait ProductCheck<E, PCS>: ZeroCheck<E::ScalarField>
+///
+/// The steps above are those of `PolyIOP<F, T, CommittedProduct>`;
+/// `PolyIOP<F, T, GkrProduct>` implements the same trait without committing
+/// to any polynomial.
+///
+/// In zero-knowledge mode, `frac(x)` and `prod(x)` are committed to with a
+/// hiding PCS, and the zerocheck is masked with a random polynomial that is
+/// committed to with the same PCS, see `ZeroCheck::prove_zk`. Only the
+/// committed product check has a zero-knowledge mode.
+pub trait ProductCheck<F, PCS>: ZeroCheck<F>
 where
-    E: Pairing,
-    PCS: PolynomialCommitmentScheme<E>,
+    F: PrimeField,
+    PCS: PolynomialCommitmentScheme<F>,
 {
     type ProductCheckSubClaim;
-    type ProductCheckProof;
+    type ProductCheckProof: CanonicalSerialize + CanonicalDeserialize;
 
     /// Initialize the system with a transcript
     ///
@@ -84,13 +116,16 @@ where
     /// - the product polynomial (used for testing)
     /// - the fractional polynomial (used for testing)
     ///
+    /// The GKR product check has neither, and outputs the leaves of its
+    /// circuits instead, i.e. `f1(x) * ... * fk(x)` and `g1(x) * ... * gk(x)`.
+    ///
     /// Cost: O(N)
     #[allow(clippy::type_complexity)]
     fn prove(
         pcs_param: &PCS::ProverParam,
         fxs: &[Self::MultilinearExtension],
         gxs: &[Self::MultilinearExtension],
-        transcript: &mut IOPTranscript<E::ScalarField>,
+        transcript: &mut Self::Transcript,
     ) -> Result<
         (
             Self::ProductCheckProof,
@@ -106,9 +141,37 @@ where
     ///     = \prod_{x \in {0,1}^n} g1(x) * ... * gk(x)`
     fn verify(
         proof: &Self::ProductCheckProof,
-        aux_info: &VPAuxInfo<E::ScalarField>,
+        aux_info: &VPAuxInfo<F>,
         transcript: &mut Self::Transcript,
     ) -> Result<Self::ProductCheckSubClaim, PolyIOPErrors>;
+
+    /// Zero-knowledge variant of `prove`, drawing the blindings of the
+    /// commitments and the mask of the zerocheck from `rng`.
+    ///
+    /// Outputs
+    /// - the zero-knowledge product check proof
+    /// - the product, fractional and mask polynomials with the blindings of
+    ///   their commitments, which the caller opens with the other polynomials
+    ///   of the protocol
+    #[allow(clippy::type_complexity)]
+    fn prove_zk<R: Rng>(
+        pcs_param: &PCS::ProverParam,
+        fxs: &[Self::MultilinearExtension],
+        gxs: &[Self::MultilinearExtension],
+        transcript: &mut Self::Transcript,
+        rng: &mut R,
+    ) -> Result<(ZkProductCheckProof<F, PCS>, ZkProductCheckOracles<F, PCS>), PolyIOPErrors>
+    where
+        PCS: HidingPolynomialCommitmentScheme<F>;
+
+    /// Verify a zero-knowledge product check proof, and return the subclaim
+    /// together with the claims on the mask of the zerocheck, which the
+    /// caller checks against `proof.mask_comm`.
+    fn verify_zk(
+        proof: &ZkProductCheckProof<F, PCS>,
+        aux_info: &VPAuxInfo<F>,
+        transcript: &mut Self::Transcript,
+    ) -> Result<(Self::ProductCheckSubClaim, SumCheckMaskClaims<F>), PolyIOPErrors>;
 }
 
 /// A product check subclaim consists of
@@ -134,34 +197,59 @@ pub struct ProductCheckSubClaim<F: PrimeField, ZC: ZeroCheck<F>> {
 /// - a zerocheck proof
 /// - a product polynomial commitment
 /// - a polynomial commitment for the fractional polynomial
-#[derive(Clone, Debug, Default, PartialEq)]
-pub struct ProductCheckProof<
-    E: Pairing,
-    PCS: PolynomialCommitmentScheme<E>,
-    ZC: ZeroCheck<E::ScalarField>,
-> {
+#[derive(Clone, Debug, Default, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct ProductCheckProof<F: PrimeField, PCS: PolynomialCommitmentScheme<F>, ZC: ZeroCheck<F>> {
     pub zero_check_proof: ZC::ZeroCheckProof,
     pub prod_x_comm: PCS::Commitment,
     pub frac_comm: PCS::Commitment,
 }
 
-impl<E, PCS> ProductCheck<E, PCS> for PolyIOP<E::ScalarField>
+/// A zero-knowledge product check proof consists of
+/// - a zero-knowledge zerocheck proof
+/// - a hiding product polynomial commitment
+/// - a hiding polynomial commitment for the fractional polynomial
+/// - a hiding commitment to the mask of the zerocheck
+#[derive(Clone, Debug, Default, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct ZkProductCheckProof<F: PrimeField, PCS: PolynomialCommitmentScheme<F>> {
+    pub zero_check_proof: ZkSumCheckProof<F>,
+    pub prod_x_comm: PCS::Commitment,
+    pub frac_comm: PCS::Commitment,
+    pub mask_comm: PCS::Commitment,
+}
+
+/// The polynomials committed to by a zero-knowledge product check, with the
+/// blindings of their commitments.
+#[derive(Clone, Debug)]
+pub struct ZkProductCheckOracles<F: PrimeField, PCS: HidingPolynomialCommitmentScheme<F>> {
+    /// the product polynomial
+    pub prod_x: Arc<DenseMultilinearExtension<F>>,
+    pub prod_x_blinding: PCS::Blinding,
+    /// the fractional polynomial
+    pub frac_poly: Arc<DenseMultilinearExtension<F>>,
+    pub frac_blinding: PCS::Blinding,
+    /// the multilinear encoding of the mask of the zerocheck
+    pub mask: Arc<DenseMultilinearExtension<F>>,
+    pub mask_blinding: PCS::Blinding,
+}
+
+impl<F, PCS, T> ProductCheck<F, PCS> for PolyIOP<F, T>
 where
-    E: Pairing,
-    PCS: PolynomialCommitmentScheme<E, Polynomial = Arc<DenseMultilinearExtension<E::ScalarField>>>,
+    F: PrimeField,
+    PCS: PolynomialCommitmentScheme<F, Polynomial = Arc<DenseMultilinearExtension<F>>>,
+    T: Transcript<F>,
 {
-    type ProductCheckSubClaim = ProductCheckSubClaim<E::ScalarField, Self>;
-    type ProductCheckProof = ProductCheckProof<E, PCS, Self>;
+    type ProductCheckSubClaim = ProductCheckSubClaim<F, Self>;
+    type ProductCheckProof = ProductCheckProof<F, PCS, Self>;
 
     fn init_transcript() -> Self::Transcript {
-        IOPTranscript::<E::ScalarField>::new(b"Initializing ProductCheck transcript")
+        T::new(b"Initializing ProductCheck transcript")
     }
 
     fn prove(
         pcs_param: &PCS::ProverParam,
         fxs: &[Self::MultilinearExtension],
         gxs: &[Self::MultilinearExtension],
-        transcript: &mut IOPTranscript<E::ScalarField>,
+        transcript: &mut Self::Transcript,
     ) -> Result<
         (
             Self::ProductCheckProof,
@@ -220,7 +308,7 @@ where
 
     fn verify(
         proof: &Self::ProductCheckProof,
-        aux_info: &VPAuxInfo<E::ScalarField>,
+        aux_info: &VPAuxInfo<F>,
         transcript: &mut Self::Transcript,
     ) -> Result<Self::ProductCheckSubClaim, PolyIOPErrors> {
         let start = start_timer!(|| "prod_check verify");
@@ -232,17 +320,14 @@ where
 
         // invoke the zero check on the iop_proof
         // the virtual poly info for Q(x)
-        let zero_check_sub_claim = <Self as ZeroCheck<E::ScalarField>>::verify(
-            &proof.zero_check_proof,
-            aux_info,
-            transcript,
-        )?;
+        let zero_check_sub_claim =
+            <Self as ZeroCheck<F>>::verify(&proof.zero_check_proof, aux_info, transcript)?;
 
         // the final query is on prod_x
-        let mut final_query = vec![E::ScalarField::one(); aux_info.num_variables];
+        let mut final_query = vec![F::one(); aux_info.num_variables];
         // the point has to be reversed because Arkworks uses big-endian.
-        final_query[0] = E::ScalarField::zero();
-        let final_eval = E::ScalarField::one();
+        final_query[0] = F::zero();
+        let final_eval = F::one();
 
         end_timer!(start);
 
@@ -252,6 +337,110 @@ where
             alpha,
         })
     }
+
+    fn prove_zk<R: Rng>(
+        pcs_param: &PCS::ProverParam,
+        fxs: &[Self::MultilinearExtension],
+        gxs: &[Self::MultilinearExtension],
+        transcript: &mut Self::Transcript,
+        rng: &mut R,
+    ) -> Result<(ZkProductCheckProof<F, PCS>, ZkProductCheckOracles<F, PCS>), PolyIOPErrors>
+    where
+        PCS: HidingPolynomialCommitmentScheme<F>,
+    {
+        let start = start_timer!(|| "zk prod_check prove");
+
+        if fxs.is_empty() {
+            return Err(PolyIOPErrors::InvalidParameters("fxs is empty".to_string()));
+        }
+        if fxs.len() != gxs.len() {
+            return Err(PolyIOPErrors::InvalidParameters(
+                "fxs and gxs have different number of polynomials".to_string(),
+            ));
+        }
+        for poly in fxs.iter().chain(gxs.iter()) {
+            if poly.num_vars != fxs[0].num_vars {
+                return Err(PolyIOPErrors::InvalidParameters(
+                    "fx and gx have different number of variables".to_string(),
+                ));
+            }
+        }
+
+        let frac_poly = compute_frac_poly(fxs, gxs)?;
+        let prod_x = compute_product_poly(&frac_poly)?;
+
+        // generate challenge
+        let (frac_comm, frac_blinding) = PCS::commit_with_rng(pcs_param, &frac_poly, rng)?;
+        let (prod_x_comm, prod_x_blinding) = PCS::commit_with_rng(pcs_param, &prod_x, rng)?;
+        transcript.append_serializable_element(b"frac(x)", &frac_comm)?;
+        transcript.append_serializable_element(b"prod(x)", &prod_x_comm)?;
+        let alpha = transcript.get_and_append_challenge(b"alpha")?;
+
+        // build the zero-check proof, whose sum check runs on Q(x) * eq(x, r)
+        let q_x = build_zero_check_poly(fxs, gxs, &frac_poly, &prod_x, &alpha)?;
+        let mask =
+            SumCheckMask::rand(q_x.aux_info.num_variables, q_x.aux_info.max_degree + 1, rng)?;
+        let mask_poly = mask.to_mle();
+        let (mask_comm, mask_blinding) = PCS::commit_with_rng(pcs_param, &mask_poly, rng)?;
+        let zero_check_proof =
+            <Self as ZeroCheck<F>>::prove_zk(&q_x, &mask, &mask_comm, transcript)?;
+
+        end_timer!(start);
+
+        Ok((
+            ZkProductCheckProof {
+                zero_check_proof,
+                prod_x_comm,
+                frac_comm,
+                mask_comm,
+            },
+            ZkProductCheckOracles {
+                prod_x,
+                prod_x_blinding,
+                frac_poly,
+                frac_blinding,
+                mask: mask_poly,
+                mask_blinding,
+            },
+        ))
+    }
+
+    fn verify_zk(
+        proof: &ZkProductCheckProof<F, PCS>,
+        aux_info: &VPAuxInfo<F>,
+        transcript: &mut Self::Transcript,
+    ) -> Result<(Self::ProductCheckSubClaim, SumCheckMaskClaims<F>), PolyIOPErrors> {
+        let start = start_timer!(|| "zk prod_check verify");
+
+        // update transcript and generate challenge
+        transcript.append_serializable_element(b"frac(x)", &proof.frac_comm)?;
+        transcript.append_serializable_element(b"prod(x)", &proof.prod_x_comm)?;
+        let alpha = transcript.get_and_append_challenge(b"alpha")?;
+
+        let (zero_check_sub_claim, mask_claims) = <Self as ZeroCheck<F>>::verify_zk(
+            &proof.zero_check_proof,
+            aux_info,
+            &proof.mask_comm,
+            transcript,
+        )?;
+
+        // the final query is on prod_x
+        let mut final_query = vec![F::one(); aux_info.num_variables];
+        // the point has to be reversed because Arkworks uses big-endian.
+        final_query[0] = F::zero();
+        let final_eval = F::one();
+
+        end_timer!(start);
+
+        Ok((
+            ProductCheckSubClaim {
+                zero_check_sub_claim,
+                final_query: (final_query, final_eval),
+                alpha,
+            },
+            mask_claims,
+        ))
+    }
 }
 
 #[cfg(test)]
@@ -263,27 +452,28 @@ mod test {
     };
     use arithmetic::VPAuxInfo;
     use ark_bls12_381::{Bls12_381, Fr};
-    use ark_ec::pairing::Pairing;
-    use ark_poly::{DenseMultilinearExtension, MultilinearExtension};
+    use ark_ff::PrimeField;
+    use ark_poly::{DenseMultilinearExtension, MultilinearExtension, Polynomial};
     use ark_std::test_rng;
     use std::{marker::PhantomData, sync::Arc};
+    use transcript::Transcript;
 
-    fn check_frac_poly<E>(
-        frac_poly: &Arc<DenseMultilinearExtension<E::ScalarField>>,
-        fs: &[Arc<DenseMultilinearExtension<E::ScalarField>>],
-        gs: &[Arc<DenseMultilinearExtension<E::ScalarField>>],
+    fn check_frac_poly<F>(
+        frac_poly: &Arc<DenseMultilinearExtension<F>>,
+        fs: &[Arc<DenseMultilinearExtension<F>>],
+        gs: &[Arc<DenseMultilinearExtension<F>>],
     ) where
-        E: Pairing,
+        F: PrimeField,
     {
         let mut flag = true;
         let num_vars = frac_poly.num_vars;
         for i in 0..1 << num_vars {
             let nom = fs
                 .iter()
-                .fold(E::ScalarField::from(1u8), |acc, f| acc * f.evaluations[i]);
+                .fold(F::from(1u8), |acc, f| acc * f.evaluations[i]);
             let denom = gs
                 .iter()
-                .fold(E::ScalarField::from(1u8), |acc, g| acc * g.evaluations[i]);
+                .fold(F::from(1u8), |acc, g| acc * g.evaluations[i]);
             if denom * frac_poly.evaluations[i] != nom {
                 flag = false;
                 break;
@@ -293,30 +483,23 @@ mod test {
     }
     // fs and gs are guaranteed to have the same product
     // fs and hs doesn't have the same product
-    fn test_product_check_helper<E, PCS>(
-        fs: &[Arc<DenseMultilinearExtension<E::ScalarField>>],
-        gs: &[Arc<DenseMultilinearExtension<E::ScalarField>>],
-        hs: &[Arc<DenseMultilinearExtension<E::ScalarField>>],
+    fn test_product_check_helper<F, PCS>(
+        fs: &[Arc<DenseMultilinearExtension<F>>],
+        gs: &[Arc<DenseMultilinearExtension<F>>],
+        hs: &[Arc<DenseMultilinearExtension<F>>],
         pcs_param: &PCS::ProverParam,
     ) -> Result<(), PolyIOPErrors>
     where
-        E: Pairing,
-        PCS: PolynomialCommitmentScheme<
-            E,
-            Polynomial = Arc<DenseMultilinearExtension<E::ScalarField>>,
-        >,
+        F: PrimeField,
+        PCS: PolynomialCommitmentScheme<F, Polynomial = Arc<DenseMultilinearExtension<F>>>,
     {
-        let mut transcript = <PolyIOP<E::ScalarField> as ProductCheck<E, PCS>>::init_transcript();
+        let mut transcript = <PolyIOP<F> as ProductCheck<F, PCS>>::init_transcript();
         transcript.append_message(b"testing", b"initializing transcript for testing")?;
 
-        let (proof, prod_x, frac_poly) = <PolyIOP<E::ScalarField> as ProductCheck<E, PCS>>::prove(
-            pcs_param,
-            fs,
-            gs,
-            &mut transcript,
-        )?;
+        let (proof, prod_x, frac_poly) =
+            <PolyIOP<F> as ProductCheck<F, PCS>>::prove(pcs_param, fs, gs, &mut transcript)?;
 
-        let mut transcript = <PolyIOP<E::ScalarField> as ProductCheck<E, PCS>>::init_transcript();
+        let mut transcript = <PolyIOP<F> as ProductCheck<F, PCS>>::init_transcript();
         transcript.append_message(b"testing", b"initializing transcript for testing")?;
 
         // what's aux_info for?
@@ -325,43 +508,33 @@ mod test {
             num_variables: fs[0].num_vars,
             phantom: PhantomData,
         };
-        let prod_subclaim = <PolyIOP<E::ScalarField> as ProductCheck<E, PCS>>::verify(
-            &proof,
-            &aux_info,
-            &mut transcript,
-        )?;
+        let prod_subclaim =
+            <PolyIOP<F> as ProductCheck<F, PCS>>::verify(&proof, &aux_info, &mut transcript)?;
         assert_eq!(
-            prod_x.evaluate(&prod_subclaim.final_query.0).unwrap(),
+            prod_x.evaluate(&prod_subclaim.final_query.0),
             prod_subclaim.final_query.1,
             "different product"
         );
-        check_frac_poly::<E>(&frac_poly, fs, gs);
+        check_frac_poly::<F>(&frac_poly, fs, gs);
 
         // bad path
-        let mut transcript = <PolyIOP<E::ScalarField> as ProductCheck<E, PCS>>::init_transcript();
+        let mut transcript = <PolyIOP<F> as ProductCheck<F, PCS>>::init_transcript();
         transcript.append_message(b"testing", b"initializing transcript for testing")?;
 
-        let (bad_proof, prod_x_bad, frac_poly) = <PolyIOP<E::ScalarField> as ProductCheck<
-            E,
-            PCS,
-        >>::prove(
-            pcs_param, fs, hs, &mut transcript
-        )?;
+        let (bad_proof, prod_x_bad, frac_poly) =
+            <PolyIOP<F> as ProductCheck<F, PCS>>::prove(pcs_param, fs, hs, &mut transcript)?;
 
-        let mut transcript = <PolyIOP<E::ScalarField> as ProductCheck<E, PCS>>::init_transcript();
+        let mut transcript = <PolyIOP<F> as ProductCheck<F, PCS>>::init_transcript();
         transcript.append_message(b"testing", b"initializing transcript for testing")?;
-        let bad_subclaim = <PolyIOP<E::ScalarField> as ProductCheck<E, PCS>>::verify(
-            &bad_proof,
-            &aux_info,
-            &mut transcript,
-        )?;
+        let bad_subclaim =
+            <PolyIOP<F> as ProductCheck<F, PCS>>::verify(&bad_proof, &aux_info, &mut transcript)?;
         assert_ne!(
-            prod_x_bad.evaluate(&bad_subclaim.final_query.0).unwrap(),
+            prod_x_bad.evaluate(&bad_subclaim.final_query.0),
             bad_subclaim.final_query.1,
             "can't detect wrong proof"
         );
         // the frac_poly should still be computed correctly
-        check_frac_poly::<E>(&frac_poly, fs, hs);
+        check_frac_poly::<F>(&frac_poly, fs, hs);
 
         Ok(())
     }
@@ -388,19 +561,19 @@ mod test {
         let srs = MultilinearKzgPCS::<Bls12_381>::gen_srs_for_testing(&mut rng, nv)?;
         let (pcs_param, _) = MultilinearKzgPCS::<Bls12_381>::trim(&srs, None, Some(nv))?;
 
-        test_product_check_helper::<Bls12_381, MultilinearKzgPCS<Bls12_381>>(
-            &fs, &gs, &hs, &pcs_param,
-        )?;
+        test_product_check_helper::<Fr, MultilinearKzgPCS<Bls12_381>>(&fs, &gs, &hs, &pcs_param)?;
 
         Ok(())
     }
 
     #[test]
     fn test_trivial_polynomial() -> Result<(), PolyIOPErrors> {
+        let _trace = crate::trace_guard();
         test_product_check(1)
     }
     #[test]
     fn test_normal_polynomial() -> Result<(), PolyIOPErrors> {
+        let _trace = crate::trace_guard();
         test_product_check(10)
     }
 }
@@ -12,7 +12,7 @@ use ark_ff::{batch_inversion, PrimeField};
 use ark_poly::DenseMultilinearExtension;
 use ark_std::{end_timer, start_timer};
 use std::sync::Arc;
-use transcript::IOPTranscript;
+use transcript::Transcript;
 
 /// Compute multilinear fractional polynomial s.t. frac(x) = f1(x) * ... * fk(x)
 /// / (g1(x) * ... * gk(x)) for all x \in {0,1}^n
@@ -119,15 +119,32 @@ pub(super) fn compute_product_poly<F: PrimeField>(
 /// Returns proof.
 ///
 /// Cost: O(N)
-pub(super) fn prove_zero_check<F: PrimeField>(
+pub(super) fn prove_zero_check<F: PrimeField, T: Transcript<F>>(
     fxs: &[Arc<DenseMultilinearExtension<F>>],
     gxs: &[Arc<DenseMultilinearExtension<F>>],
     frac_poly: &Arc<DenseMultilinearExtension<F>>,
     prod_x: &Arc<DenseMultilinearExtension<F>>,
     alpha: &F,
-    transcript: &mut IOPTranscript<F>,
+    transcript: &mut T,
 ) -> Result<(IOPProof<F>, VirtualPolynomial<F>), PolyIOPErrors> {
     let start = start_timer!(|| "zerocheck in product check");
+
+    let q_x = build_zero_check_poly(fxs, gxs, frac_poly, prod_x, alpha)?;
+    let iop_proof = <PolyIOP<F, T> as ZeroCheck<F>>::prove(&q_x, transcript)?;
+
+    end_timer!(start);
+    Ok((iop_proof, q_x))
+}
+
+/// build the virtual polynomial Q(x) of the zerocheck in the product check,
+/// see `prove_zero_check`.
+pub(super) fn build_zero_check_poly<F: PrimeField>(
+    fxs: &[Arc<DenseMultilinearExtension<F>>],
+    gxs: &[Arc<DenseMultilinearExtension<F>>],
+    frac_poly: &Arc<DenseMultilinearExtension<F>>,
+    prod_x: &Arc<DenseMultilinearExtension<F>>,
+    alpha: &F,
+) -> Result<VirtualPolynomial<F>, PolyIOPErrors> {
     let num_vars = frac_poly.num_vars;
 
     // compute p1(x) = (1-x1) * frac(x2, ..., xn, 0) + x1 * prod(x2, ..., xn, 0)
@@ -172,8 +189,5 @@ pub(super) fn prove_zero_check<F: PrimeField>(
     // - alpha * f1(x) * ... * fk(x)]
     q_x.add_mle_list(fxs.to_vec(), -*alpha)?;
 
-    let iop_proof = <PolyIOP<F> as ZeroCheck<F>>::prove(&q_x, transcript)?;
-
-    end_timer!(start);
-    Ok((iop_proof, q_x))
+    Ok(q_x)
 }
@@ -0,0 +1,501 @@
+// Copyright (c) 2023 Espresso Systems (espressosys.com)
+// This file is part of the HyperPlonk library.
+
+// You should have received a copy of the MIT License
+// along with the HyperPlonk library. If not, see <https://mit-license.org/>.
+
+//! Main module for the Range Check protocol
+
+use self::util::{
+    build_range_table, decompose, eval_lookups, eval_range_table, limb_layout,
+    num_lookups_per_witness, scale,
+};
+use crate::{
+    pcs::PolynomialCommitmentScheme,
+    poly_iop::{
+        errors::PolyIOPErrors, lookup_check::LookupCheckSubClaim, prelude::LookupCheck, PolyIOP,
+    },
+};
+use arithmetic::VPAuxInfo;
+use ark_ff::PrimeField;
+use ark_poly::DenseMultilinearExtension;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_std::{end_timer, start_timer};
+use std::{marker::PhantomData, sync::Arc};
+use transcript::Transcript;
+
+mod util;
+
+/// A RangeCheck w.r.t. `(fs, b)` proves that every evaluation of
+/// `(f1, ..., fk)` on the boolean hypercube lies in `[0, 2^b)`.
+/// It is derived from LookupCheck.
+///
+/// Each evaluation is decomposed into `m` limbs of `l` bits, the top limb
+/// having the remaining `b - (m - 1) * l` bits, and all the limbs are looked
+/// up in the range table `[0, 2^l)`. A top limb shorter than `l` bits is
+/// looked up a second time, scaled by `2^{l - top bits}`, which bounds it to
+/// its own range. The table is public and evaluated by the verifier, and the
+/// top limb is derived from `fi` and the other limbs, so only `m - 1` limbs
+/// per polynomial are committed, and none if `b <= l`.
+///
+/// The range table has `max(l, n)` variables for polynomials of `n`
+/// variables, and is padded with `2^l - 1`.
+///
+/// Prover steps:
+/// 1. decompose `fi` into limbs, and commit to all limbs but the top ones
+/// 2. run a lookup check of the limbs into the range table
+///
+/// Verifier steps:
+/// 1. Extract the limb commitments from the proof and push them to the
+///    transcript
+/// 2. `verify` the lookup check proof, and generate the subclaims for
+///    polynomial evaluations
+pub trait RangeCheck<F, PCS>: LookupCheck<F, PCS>
+where
+    F: PrimeField,
+    PCS: PolynomialCommitmentScheme<F>,
+{
+    type RangeCheckSubClaim;
+    type RangeCheckProof: CanonicalSerialize + CanonicalDeserialize;
+
+    /// Initialize the system with a transcript
+    ///
+    /// This function is optional -- in the case where a RangeCheck is
+    /// an building block for a more complex protocol, the transcript
+    /// may be initialized by this complex protocol, and passed to the
+    /// RangeCheck prover/verifier.
+    fn init_transcript() -> Self::Transcript;
+
+    /// Inputs:
+    /// - pcs_param: PCS committing key
+    /// - fxs = (f1, ..., fk), the polynomials range checked
+    /// - num_bits: the number of bits `b` of the range
+    /// - limb_bits: the number of bits `l` of a limb
+    /// - transcript: the IOP transcript
+    ///
+    /// Outputs:
+    /// - a range check proof proving that the evaluations of fxs are in
+    ///   `[0, 2^b)`
+    /// - the committed limbs, i.e. all the limbs of `f1`, then of `f2`, etc.,
+    ///   but the top ones
+    /// - the multiplicity polynomial `m(y)` of the lookup check
+    /// - the witness fractional polynomial `h_f(x)` of the lookup check
+    /// - the table fractional polynomial `h_t(y)` of the lookup check
+    ///
+    /// Cost: O(k * b / l * N + 2^l)
+    #[allow(clippy::type_complexity)]
+    fn prove(
+        pcs_param: &PCS::ProverParam,
+        fxs: &[Self::MultilinearExtension],
+        num_bits: usize,
+        limb_bits: usize,
+        transcript: &mut Self::Transcript,
+    ) -> Result<
+        (
+            Self::RangeCheckProof,
+            Vec<Self::MultilinearExtension>,
+            Self::MultilinearExtension,
+            Self::MultilinearExtension,
+            Self::MultilinearExtension,
+        ),
+        PolyIOPErrors,
+    >;
+
+    /// Verify that the evaluations of `num_witnesses` polynomials (f1, ...,
+    /// fk) of `num_vars` variables are in `[0, 2^num_bits)`.
+    fn verify(
+        proof: &Self::RangeCheckProof,
+        num_witnesses: usize,
+        num_vars: usize,
+        num_bits: usize,
+        limb_bits: usize,
+        transcript: &mut Self::Transcript,
+    ) -> Result<Self::RangeCheckSubClaim, PolyIOPErrors>;
+}
+
+/// A range check subclaim consists of
+/// - the SubClaim from the LookupCheck
+/// - the layout of the limbs
+///
+/// The caller checks the expected evaluations of the lookup check subclaims
+/// against `witness_evaluation` and `table_evaluation`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct RangeCheckSubClaim<F: PrimeField> {
+    /// the SubClaim from the LookupCheck
+    pub lookup_check_sub_claim: LookupCheckSubClaim<F>,
+    /// the number of bits of a limb
+    pub limb_bits: usize,
+    /// the number of limbs
+    pub num_limbs: usize,
+    /// the number of bits of the top limb
+    pub top_bits: usize,
+}
+
+impl<F: PrimeField> RangeCheckSubClaim<F> {
+    /// The evaluation of the witness side of the lookup check at the point of
+    /// its subclaim, given the evaluations of `f1, ..., fk`, of the committed
+    /// limbs and of `h_f` at that point.
+    pub fn witness_evaluation(
+        &self,
+        fx_evals: &[F],
+        limb_evals: &[F],
+        frac_eval: F,
+    ) -> Result<F, PolyIOPErrors> {
+        let lookup_evals = eval_lookups(
+            fx_evals,
+            limb_evals,
+            self.limb_bits,
+            self.num_limbs,
+            self.top_bits,
+        )?;
+        self.lookup_check_sub_claim
+            .witness_evaluation(&lookup_evals, F::one(), frac_eval)
+    }
+
+    /// The evaluation of the table side of the lookup check at the point of
+    /// its subclaim, given the evaluations of `m` and `h_t` at that point.
+    pub fn table_evaluation(&self, multiplicity_eval: F, frac_eval: F) -> Result<F, PolyIOPErrors> {
+        let table_eval = eval_range_table(
+            &self.lookup_check_sub_claim.table_sub_claim.point,
+            self.limb_bits,
+        );
+        self.lookup_check_sub_claim
+            .table_evaluation(table_eval, multiplicity_eval, frac_eval)
+    }
+}
+
+/// A range check proof consists of
+/// - the lookup check proof
+/// - commitments to the limbs but the top ones
+#[derive(Clone, Debug, Default, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct RangeCheckProof<
+    F: PrimeField,
+    PCS: PolynomialCommitmentScheme<F>,
+    LC: LookupCheck<F, PCS>,
+> {
+    pub lookup_check_proof: LC::LookupCheckProof,
+    pub limb_comms: Vec<PCS::Commitment>,
+}
+
+impl<F, PCS, T> RangeCheck<F, PCS> for PolyIOP<F, T>
+where
+    F: PrimeField,
+    PCS: PolynomialCommitmentScheme<F, Polynomial = Arc<DenseMultilinearExtension<F>>>,
+    T: Transcript<F>,
+{
+    type RangeCheckSubClaim = RangeCheckSubClaim<F>;
+    type RangeCheckProof = RangeCheckProof<F, PCS, Self>;
+
+    fn init_transcript() -> Self::Transcript {
+        T::new(b"Initializing RangeCheck transcript")
+    }
+
+    fn prove(
+        pcs_param: &PCS::ProverParam,
+        fxs: &[Self::MultilinearExtension],
+        num_bits: usize,
+        limb_bits: usize,
+        transcript: &mut Self::Transcript,
+    ) -> Result<
+        (
+            Self::RangeCheckProof,
+            Vec<Self::MultilinearExtension>,
+            Self::MultilinearExtension,
+            Self::MultilinearExtension,
+            Self::MultilinearExtension,
+        ),
+        PolyIOPErrors,
+    > {
+        let start = start_timer!(|| "range_check prove");
+
+        if fxs.is_empty() {
+            return Err(PolyIOPErrors::InvalidParameters("fxs is empty".to_string()));
+        }
+        let (num_limbs, top_bits) = limb_layout::<F>(num_bits, limb_bits)?;
+        let top_scale = F::from(2u64).pow([(limb_bits - top_bits) as u64]);
+
+        // the limbs, of which all but the top ones are committed
+        let limbs = decompose(fxs, num_bits, limb_bits, num_limbs)?;
+        let committed_limbs: Vec<_> = limbs
+            .iter()
+            .flat_map(|l| l[..num_limbs - 1].iter().cloned())
+            .collect();
+        let limb_comms = committed_limbs
+            .iter()
+            .map(|l| PCS::commit(pcs_param, l))
+            .collect::<Result<Vec<_>, _>>()?;
+        for comm in limb_comms.iter() {
+            transcript.append_serializable_element(b"limb(x)", comm)?;
+        }
+
+        // look up all the limbs, and the scaled short top limbs
+        let mut lookups =
+            Vec::with_capacity(fxs.len() * num_lookups_per_witness(num_limbs, top_bits, limb_bits));
+        for l in limbs.iter() {
+            lookups.extend(l.iter().cloned());
+            if top_bits < limb_bits {
+                lookups.push(scale(&l[num_limbs - 1], top_scale));
+            }
+        }
+        let table = build_range_table(limb_bits.max(fxs[0].num_vars), limb_bits);
+        let (lookup_check_proof, multiplicity, witness_frac, table_frac) =
+            <Self as LookupCheck<F, PCS>>::prove(pcs_param, &lookups, None, &table, transcript)?;
+
+        end_timer!(start);
+        Ok((
+            RangeCheckProof {
+                lookup_check_proof,
+                limb_comms,
+            },
+            committed_limbs,
+            multiplicity,
+            witness_frac,
+            table_frac,
+        ))
+    }
+
+    fn verify(
+        proof: &Self::RangeCheckProof,
+        num_witnesses: usize,
+        num_vars: usize,
+        num_bits: usize,
+        limb_bits: usize,
+        transcript: &mut Self::Transcript,
+    ) -> Result<Self::RangeCheckSubClaim, PolyIOPErrors> {
+        let start = start_timer!(|| "range_check verify");
+
+        let (num_limbs, top_bits) = limb_layout::<F>(num_bits, limb_bits)?;
+        if num_witnesses == 0 || proof.limb_comms.len() != num_witnesses * (num_limbs - 1) {
+            return Err(PolyIOPErrors::InvalidProof(format!(
+                "got {} limb commitments for {} polynomials of {} limbs",
+                proof.limb_comms.len(),
+                num_witnesses,
+                num_limbs
+            )));
+        }
+
+        // update transcript
+        for comm in proof.limb_comms.iter() {
+            transcript.append_serializable_element(b"limb(x)", comm)?;
+        }
+
+        // invoke the lookup check on the proof
+        let aux_info = VPAuxInfo {
+            max_degree: num_witnesses * num_lookups_per_witness(num_limbs, top_bits, limb_bits) + 1,
+            num_variables: num_vars,
+            phantom: PhantomData,
+        };
+        let table_aux_info = VPAuxInfo {
+            max_degree: 2,
+            num_variables: limb_bits.max(num_vars),
+            phantom: PhantomData,
+        };
+        let lookup_check_sub_claim = <Self as LookupCheck<F, PCS>>::verify(
+            &proof.lookup_check_proof,
+            &aux_info,
+            &table_aux_info,
+            transcript,
+        )?;
+
+        end_timer!(start);
+        Ok(RangeCheckSubClaim {
+            lookup_check_sub_claim,
+            limb_bits,
+            num_limbs,
+            top_bits,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::RangeCheck;
+    use crate::{
+        pcs::{prelude::MultilinearKzgPCS, PolynomialCommitmentScheme},
+        poly_iop::{errors::PolyIOPErrors, PolyIOP},
+    };
+    use arithmetic::evaluate_opt;
+    use ark_bls12_381::{Bls12_381, Fr};
+    use ark_ff::PrimeField;
+    use ark_poly::DenseMultilinearExtension;
+    use ark_std::{rand::Rng, test_rng};
+    use std::sync::Arc;
+    use transcript::Transcript;
+
+    type Kzg = MultilinearKzgPCS<Bls12_381>;
+
+    /// Prove the range of `fxs` and check the subclaims against
+    /// `claimed_fxs`.
+    fn test_range_check_helper<F, PCS>(
+        pcs_param: &PCS::ProverParam,
+        fxs: &[Arc<DenseMultilinearExtension<F>>],
+        claimed_fxs: &[Arc<DenseMultilinearExtension<F>>],
+        num_bits: usize,
+        limb_bits: usize,
+    ) -> Result<(), PolyIOPErrors>
+    where
+        F: PrimeField,
+        PCS: PolynomialCommitmentScheme<F, Polynomial = Arc<DenseMultilinearExtension<F>>>,
+    {
+        // prover
+        let mut transcript = <PolyIOP<F> as RangeCheck<F, PCS>>::init_transcript();
+        transcript.append_message(b"testing", b"initializing transcript for testing")?;
+        let (proof, limbs, multiplicity, witness_frac, table_frac) =
+            <PolyIOP<F> as RangeCheck<F, PCS>>::prove(
+                pcs_param,
+                fxs,
+                num_bits,
+                limb_bits,
+                &mut transcript,
+            )?;
+
+        // verifier
+        let mut transcript = <PolyIOP<F> as RangeCheck<F, PCS>>::init_transcript();
+        transcript.append_message(b"testing", b"initializing transcript for testing")?;
+        let sub_claim = <PolyIOP<F> as RangeCheck<F, PCS>>::verify(
+            &proof,
+            fxs.len(),
+            fxs[0].num_vars,
+            num_bits,
+            limb_bits,
+            &mut transcript,
+        )?;
+
+        // check the subclaims
+        let lookup_sub_claim = &sub_claim.lookup_check_sub_claim;
+        let point = &lookup_sub_claim.witness_sub_claim.point;
+        let fx_evals: Vec<_> = claimed_fxs
+            .iter()
+            .map(|fx| evaluate_opt(fx, point))
+            .collect();
+        let limb_evals: Vec<_> = limbs.iter().map(|l| evaluate_opt(l, point)).collect();
+        if sub_claim.witness_evaluation(
+            &fx_evals,
+            &limb_evals,
+            evaluate_opt(&witness_frac, point),
+        )? != lookup_sub_claim.witness_sub_claim.expected_evaluation
+        {
+            return Err(PolyIOPErrors::InvalidVerifier(
+                "wrong witness subclaim".to_string(),
+            ));
+        }
+        let point = &lookup_sub_claim.table_sub_claim.point;
+        if sub_claim.table_evaluation(
+            evaluate_opt(&multiplicity, point),
+            evaluate_opt(&table_frac, point),
+        )? != lookup_sub_claim.table_sub_claim.expected_evaluation
+        {
+            return Err(PolyIOPErrors::InvalidVerifier(
+                "wrong table subclaim".to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// `num_witnesses` random polynomials of `nv` variables with evaluations
+    /// in `[0, 2^num_bits)`.
+    fn random_in_range<R: Rng>(
+        nv: usize,
+        num_bits: usize,
+        num_witnesses: usize,
+        rng: &mut R,
+    ) -> Vec<Arc<DenseMultilinearExtension<Fr>>> {
+        (0..num_witnesses)
+            .map(|_| {
+                Arc::new(DenseMultilinearExtension::from_evaluations_vec(
+                    nv,
+                    (0..1 << nv)
+                        .map(|_| Fr::from(rng.gen_range(0..1u64 << num_bits)))
+                        .collect(),
+                ))
+            })
+            .collect()
+    }
+
+    fn test_range_check(nv: usize, num_bits: usize, limb_bits: usize) -> Result<(), PolyIOPErrors> {
+        let mut rng = test_rng();
+
+        let max_nv = nv.max(limb_bits);
+        let srs = MultilinearKzgPCS::<Bls12_381>::gen_srs_for_testing(&mut rng, max_nv)?;
+        let (pcs_param, _) = MultilinearKzgPCS::<Bls12_381>::trim(&srs, None, Some(max_nv))?;
+
+        {
+            // good path: one and two witnesses
+            for num_witnesses in [1, 2] {
+                let fxs = random_in_range(nv, num_bits, num_witnesses, &mut rng);
+                test_range_check_helper::<Fr, Kzg>(&pcs_param, &fxs, &fxs, num_bits, limb_bits)?;
+            }
+        }
+
+        {
+            // good path: the bounds of the range
+            let fxs = vec![Arc::new(DenseMultilinearExtension::from_evaluations_vec(
+                nv,
+                (0..1u64 << nv)
+                    .map(|x| Fr::from((x % 2) * ((1 << num_bits) - 1)))
+                    .collect(),
+            ))];
+            test_range_check_helper::<Fr, Kzg>(&pcs_param, &fxs, &fxs, num_bits, limb_bits)?;
+        }
+
+        {
+            // bad path 1: a witness entry is out of the range
+            let mut fxs = random_in_range(nv, num_bits, 2, &mut rng);
+            Arc::make_mut(&mut fxs[1]).evaluations[0] = Fr::from(1u64 << num_bits);
+            assert!(test_range_check_helper::<Fr, Kzg>(
+                &pcs_param, &fxs, &fxs, num_bits, limb_bits,
+            )
+            .is_err());
+        }
+
+        {
+            // bad path 2: the subclaims are checked against other witnesses
+            let fxs = random_in_range(nv, num_bits, 2, &mut rng);
+            let other_fxs = random_in_range(nv, num_bits, 2, &mut rng);
+            assert!(test_range_check_helper::<Fr, Kzg>(
+                &pcs_param, &fxs, &other_fxs, num_bits, limb_bits,
+            )
+            .is_err());
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_trivial_polynomial() -> Result<(), PolyIOPErrors> {
+        let _trace = crate::trace_guard();
+        test_range_check(1, 1, 1)
+    }
+
+    #[test]
+    fn test_normal_polynomial() -> Result<(), PolyIOPErrors> {
+        let _trace = crate::trace_guard();
+        // limbs of 4 bits, the top one being shorter for 10 bits
+        test_range_check(5, 8, 4)?;
+        test_range_check(5, 10, 4)
+    }
+
+    #[test]
+    fn test_single_limb() -> Result<(), PolyIOPErrors> {
+        let _trace = crate::trace_guard();
+        // no committed limbs, with a range smaller than the table
+        test_range_check(5, 5, 5)?;
+        test_range_check(5, 3, 4)
+    }
+
+    #[test]
+    fn test_large_table() -> Result<(), PolyIOPErrors> {
+        let _trace = crate::trace_guard();
+        // a table with more variables than the witnesses
+        test_range_check(3, 12, 6)
+    }
+
+    #[test]
+    fn invalid_parameters_should_error() -> Result<(), PolyIOPErrors> {
+        let _trace = crate::trace_guard();
+        assert!(test_range_check(3, 0, 4).is_err());
+        assert!(test_range_check(3, 8, 0).is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,181 @@
+// Copyright (c) 2023 Espresso Systems (espressosys.com)
+// This file is part of the HyperPlonk library.
+
+// You should have received a copy of the MIT License
+// along with the HyperPlonk library. If not, see <https://mit-license.org/>.
+
+//! This module implements useful functions for the range check protocol.
+
+use crate::poly_iop::errors::PolyIOPErrors;
+use ark_ff::{BigInteger, PrimeField};
+use ark_poly::DenseMultilinearExtension;
+use ark_std::{end_timer, start_timer};
+use std::sync::Arc;
+
+/// The maximal number of bits of a limb, i.e. of the number of variables of
+/// the range table.
+pub(super) const MAX_LIMB_BITS: usize = 32;
+
+/// Check the parameters of a range check, and return the number of limbs of
+/// `limb_bits` bits of an `num_bits`-bit value, and the number of bits of the
+/// top limb.
+pub(super) fn limb_layout<F: PrimeField>(
+    num_bits: usize,
+    limb_bits: usize,
+) -> Result<(usize, usize), PolyIOPErrors> {
+    // a sum of limbs below 2^num_bits must not wrap around the modulus
+    if num_bits == 0 || num_bits >= F::MODULUS_BIT_SIZE as usize {
+        return Err(PolyIOPErrors::InvalidParameters(format!(
+            "cannot range check {} bits",
+            num_bits
+        )));
+    }
+    if limb_bits == 0 || limb_bits > MAX_LIMB_BITS {
+        return Err(PolyIOPErrors::InvalidParameters(format!(
+            "limbs of {} bits are not supported",
+            limb_bits
+        )));
+    }
+    let num_limbs = num_bits.div_ceil(limb_bits);
+    Ok((num_limbs, num_bits - (num_limbs - 1) * limb_bits))
+}
+
+/// The number of polynomials looked up per range checked polynomial, i.e.
+/// its limbs, and the top limb scaled by `2^{limb_bits - top_bits}` if the
+/// top limb is shorter than the others.
+pub(super) fn num_lookups_per_witness(
+    num_limbs: usize,
+    top_bits: usize,
+    limb_bits: usize,
+) -> usize {
+    num_limbs + (top_bits < limb_bits) as usize
+}
+
+/// Decompose each of `fxs` into `num_limbs` limbs of `limb_bits` bits, from
+/// the least significant one.
+///
+/// The caller needs to sanity-check the parameters with `limb_layout`.
+pub(super) fn decompose<F: PrimeField>(
+    fxs: &[Arc<DenseMultilinearExtension<F>>],
+    num_bits: usize,
+    limb_bits: usize,
+    num_limbs: usize,
+) -> Result<Vec<Vec<Arc<DenseMultilinearExtension<F>>>>, PolyIOPErrors> {
+    let start = start_timer!(|| "decompose f(x)");
+
+    let mut res = Vec::with_capacity(fxs.len());
+    for fx in fxs.iter() {
+        let mut limbs = vec![Vec::with_capacity(fx.evaluations.len()); num_limbs];
+        for f in fx.evaluations.iter() {
+            let bigint = f.into_bigint();
+            if bigint.num_bits() as usize > num_bits {
+                return Err(PolyIOPErrors::InvalidParameters(format!(
+                    "witness entry {} is not in the range of {} bits",
+                    f, num_bits
+                )));
+            }
+            for (j, limb) in limbs.iter_mut().enumerate() {
+                let value = (0..limb_bits)
+                    .filter(|&i| bigint.get_bit(j * limb_bits + i))
+                    .fold(0u64, |acc, i| acc | (1 << i));
+                limb.push(F::from(value));
+            }
+        }
+        res.push(
+            limbs
+                .into_iter()
+                .map(|l| {
+                    Arc::new(DenseMultilinearExtension::from_evaluations_vec(
+                        fx.num_vars,
+                        l,
+                    ))
+                })
+                .collect(),
+        );
+    }
+
+    end_timer!(start);
+    Ok(res)
+}
+
+/// Multiply the evaluations of `poly` by `scalar`.
+pub(super) fn scale<F: PrimeField>(
+    poly: &Arc<DenseMultilinearExtension<F>>,
+    scalar: F,
+) -> Arc<DenseMultilinearExtension<F>> {
+    Arc::new(DenseMultilinearExtension::from_evaluations_vec(
+        poly.num_vars,
+        poly.evaluations.iter().map(|e| *e * scalar).collect(),
+    ))
+}
+
+/// The range table of `num_vars` variables for limbs of `limb_bits` bits,
+/// i.e. `t(y) = y` for `y < 2^limb_bits`, padded with `2^limb_bits - 1`.
+pub(super) fn build_range_table<F: PrimeField>(
+    num_vars: usize,
+    limb_bits: usize,
+) -> Arc<DenseMultilinearExtension<F>> {
+    let max = (1u64 << limb_bits) - 1;
+    Arc::new(DenseMultilinearExtension::from_evaluations_vec(
+        num_vars,
+        (0..1u64 << num_vars).map(|y| F::from(y.min(max))).collect(),
+    ))
+}
+
+/// Evaluate the range table of `build_range_table` at `point`, i.e.
+///   t(y) = z(y) * \sum_{i < l} 2^i y_i + (1 - z(y)) * (2^l - 1)
+/// where `l = limb_bits` and `z(y) = \prod_{i >= l} (1 - y_i)` is one on the
+/// first `2^l` entries of the boolean hypercube and zero on the others.
+pub(super) fn eval_range_table<F: PrimeField>(point: &[F], limb_bits: usize) -> F {
+    let (low, high) = point.split_at(limb_bits.min(point.len()));
+    let value = low.iter().rev().fold(F::zero(), |acc, y| acc.double() + y);
+    let is_low: F = high.iter().map(|y| F::one() - y).product();
+    let max = F::from((1u64 << limb_bits) - 1);
+    is_low * value + (F::one() - is_low) * max
+}
+
+/// The evaluations of the polynomials looked up, given the evaluations of
+/// `f1, ..., fk` and of their limbs except the top ones, in the order of
+/// `decompose`. The top limb of `fi` is
+///   (fi - \sum_{j < m - 1} 2^{j * l} limb_j) / 2^{(m - 1) * l}
+/// where `m` is the number of limbs of `l = limb_bits` bits.
+pub(super) fn eval_lookups<F: PrimeField>(
+    fx_evals: &[F],
+    limb_evals: &[F],
+    limb_bits: usize,
+    num_limbs: usize,
+    top_bits: usize,
+) -> Result<Vec<F>, PolyIOPErrors> {
+    if limb_evals.len() != fx_evals.len() * (num_limbs - 1) {
+        return Err(PolyIOPErrors::InvalidParameters(format!(
+            "got {} limb evaluations for {} polynomials of {} limbs",
+            limb_evals.len(),
+            fx_evals.len(),
+            num_limbs
+        )));
+    }
+    let limb_base = F::from(2u64).pow([limb_bits as u64]);
+    let top_shift = limb_base.pow([(num_limbs - 1) as u64]);
+    let top_shift_inv = top_shift.inverse().ok_or(PolyIOPErrors::ShouldNotArrive)?;
+    let top_scale = F::from(2u64).pow([(limb_bits - top_bits) as u64]);
+
+    let mut res = Vec::with_capacity(
+        fx_evals.len() * num_lookups_per_witness(num_limbs, top_bits, limb_bits),
+    );
+    for (i, f) in fx_evals.iter().enumerate() {
+        let limbs = &limb_evals[i * (num_limbs - 1)..(i + 1) * (num_limbs - 1)];
+        let mut low = F::zero();
+        let mut base = F::one();
+        for limb in limbs.iter() {
+            low += base * limb;
+            base *= limb_base;
+            res.push(*limb);
+        }
+        let top = (*f - low) * top_shift_inv;
+        res.push(top);
+        if top_bits < limb_bits {
+            res.push(top * top_scale);
+        }
+    }
+    Ok(res)
+}
@@ -9,6 +9,7 @@
 use arithmetic::VirtualPolynomial;
 use ark_ff::PrimeField;
 use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use transcript::{Encode, Encoder};
 
 /// An IOP proof is a collections of
 /// - messages from prover to verifier at each round through the interactive
@@ -27,6 +28,12 @@ pub struct IOPProverMessage<F: PrimeField> {
     pub evaluations: Vec<F>,
 }
 
+impl<F: PrimeField> Encode for IOPProverMessage<F> {
+    fn encode<E: Encoder>(&self, encoder: &mut E) {
+        self.evaluations.encode(encoder);
+    }
+}
+
 /// Prover State of a PolyIOP.
 pub struct IOPProverState<F: PrimeField> {
     /// sampled randomness given by the verifier
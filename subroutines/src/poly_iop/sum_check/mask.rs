@@ -0,0 +1,254 @@
+// Copyright (c) 2023 Espresso Systems (espressosys.com)
+// This file is part of the HyperPlonk library.
+
+// You should have received a copy of the MIT License
+// along with the HyperPlonk library. If not, see <https://mit-license.org/>.
+
+//! Masking polynomials for the zero-knowledge sum check.
+//!
+//! Following section 4.1 of [XZZPS19](https://eprint.iacr.org/2019/317.pdf),
+//! the mask of a sum check over `n` variables is
+//! `g(x_1, ..., x_n) = g_1(x_1) + ... + g_n(x_n)`, where the `g_i` are random
+//! univariate polynomials of the degree `d` of the summed polynomial. The sum
+//! check runs on `f + rho * g`, so every round message of `f` is masked by the
+//! random `g_i`, and the verifier is left with a claim on `f` and the
+//! evaluations `g_i(r_i)` at the sum check point.
+//!
+//! The mask is committed to as a multilinear polynomial `M` in
+//! `log(d + 1) + log(n)` variables, whose restriction to `i` has the
+//! coefficients of `g_i` as monomial coefficients, so that
+//! `g_i(X) = M(X, X^2, X^4, ..., i)`. The evaluations of the `g_i` are thus
+//! openings of `M` that any multilinear PCS can check.
+
+use crate::poly_iop::{errors::PolyIOPErrors, structs::IOPProof};
+use ark_ff::PrimeField;
+use ark_poly::DenseMultilinearExtension;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_std::{log2, rand::Rng};
+use std::sync::Arc;
+
+/// A random mask `g(x) = \sum_i g_i(x_i)` for a zero-knowledge sum check.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SumCheckMask<F: PrimeField> {
+    /// the coefficients of each `g_i`, from the constant term
+    pub univariates: Vec<Vec<F>>,
+}
+
+/// The claimed evaluations of the multilinear encoding of a mask, output by
+/// the zero-knowledge sum check, that the caller checks against the
+/// commitment to the mask.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SumCheckMaskClaims<F: PrimeField> {
+    /// the points at which the mask is opened, one per variable
+    pub points: Vec<Vec<F>>,
+    /// the claimed evaluations
+    pub evaluations: Vec<F>,
+}
+
+/// A zero-knowledge sum check proof.
+#[derive(Clone, Debug, Default, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct ZkSumCheckProof<F: PrimeField> {
+    /// the sum of the mask over the hypercube
+    pub mask_sum: F,
+    /// the sum check proof of `f + rho * g`
+    pub sum_check_proof: IOPProof<F>,
+    /// `g_i(r_i)` for the sum check point `r`
+    pub mask_evaluations: Vec<F>,
+}
+
+impl<F: PrimeField> SumCheckMask<F> {
+    /// Sample a mask for a sum check over `num_vars` variables of a
+    /// polynomial of degree `degree`.
+    pub fn rand<R: Rng>(
+        num_vars: usize,
+        degree: usize,
+        rng: &mut R,
+    ) -> Result<Self, PolyIOPErrors> {
+        if num_vars == 0 || degree == 0 {
+            return Err(PolyIOPErrors::InvalidParameters(format!(
+                "cannot mask a sum check of {} variables and degree {}",
+                num_vars, degree
+            )));
+        }
+        let univariates = (0..num_vars)
+            .map(|_| (0..=degree).map(|_| F::rand(rng)).collect())
+            .collect();
+        Ok(Self { univariates })
+    }
+
+    /// The number of variables of the multilinear encoding of a mask for a
+    /// sum check over `num_vars` variables of a polynomial of degree
+    /// `degree`, which the PCS must support to commit to the mask.
+    pub fn encoding_num_vars(num_vars: usize, degree: usize) -> usize {
+        let (coeff_vars, index_vars) = mle_num_vars(num_vars, degree);
+        coeff_vars + index_vars
+    }
+
+    /// The number of variables of the mask.
+    pub fn num_vars(&self) -> usize {
+        self.univariates.len()
+    }
+
+    /// The degree of the mask in each variable.
+    pub fn degree(&self) -> usize {
+        self.univariates.first().map_or(0, |g| g.len() - 1)
+    }
+
+    /// The sum of the mask over the hypercube, i.e.
+    /// `2^{n-1} \sum_i (g_i(0) + g_i(1))`.
+    pub fn sum(&self) -> F {
+        let sum: F = self
+            .univariates
+            .iter()
+            .map(|g| eval_univariate(g, F::zero()) + eval_univariate(g, F::one()))
+            .sum();
+        sum * pow2::<F>(self.num_vars() - 1)
+    }
+
+    /// `g_i(x)`.
+    pub fn evaluate_univariate(&self, i: usize, x: F) -> F {
+        eval_univariate(&self.univariates[i], x)
+    }
+
+    /// `g_i(point_i)` for every variable.
+    pub fn evaluate_univariates(&self, point: &[F]) -> Vec<F> {
+        self.univariates
+            .iter()
+            .zip(point.iter())
+            .map(|(g, x)| eval_univariate(g, *x))
+            .collect()
+    }
+
+    /// The multilinear encoding of the mask, to be committed to.
+    pub fn to_mle(&self) -> Arc<DenseMultilinearExtension<F>> {
+        let (coeff_vars, index_vars) = mle_num_vars(self.num_vars(), self.degree());
+        let block = 1 << coeff_vars;
+        let mut evals = vec![F::zero(); block << index_vars];
+        for (g, chunk) in self.univariates.iter().zip(evals.chunks_mut(block)) {
+            chunk[..g.len()].copy_from_slice(g);
+            // from monomial coefficients to evaluations on the hypercube
+            for j in 0..coeff_vars {
+                for b in 0..block {
+                    if b >> j & 1 == 1 {
+                        let low = chunk[b ^ (1 << j)];
+                        chunk[b] += low;
+                    }
+                }
+            }
+        }
+        Arc::new(DenseMultilinearExtension::from_evaluations_vec(
+            coeff_vars + index_vars,
+            evals,
+        ))
+    }
+
+    /// The message of `g` in round `round`, i.e. the evaluations at
+    /// `0..num_evals` of
+    /// `\sum_{x_{round+1}, ...} g(r_0, ..., r_{round-1}, X, x_{round+1}, ...)`,
+    /// where `prefix = \sum_{j < round} g_j(r_j)`.
+    pub(crate) fn round_message(&self, round: usize, prefix: F, num_evals: usize) -> Vec<F> {
+        let num_vars = self.num_vars();
+        // every assignment of the remaining variables adds `prefix + g_i(X)`,
+        // and each `g_j` with `j > round` sums to `g_j(0) + g_j(1)` over half
+        // of them
+        let rest = num_vars - round - 1;
+        let mut constant = prefix * pow2::<F>(rest);
+        if rest > 0 {
+            let tail: F = self.univariates[round + 1..]
+                .iter()
+                .map(|g| eval_univariate(g, F::zero()) + eval_univariate(g, F::one()))
+                .sum();
+            constant += tail * pow2::<F>(rest - 1);
+        }
+        (0..num_evals)
+            .map(|x| {
+                constant
+                    + eval_univariate(&self.univariates[round], F::from(x as u64)) * pow2::<F>(rest)
+            })
+            .collect()
+    }
+}
+
+impl<F: PrimeField> SumCheckMaskClaims<F> {
+    /// The claims that the `g_i` of a mask of degree `degree` evaluate to
+    /// `evaluations` at `point`.
+    pub fn new(degree: usize, point: &[F], evaluations: Vec<F>) -> Self {
+        let (coeff_vars, index_vars) = mle_num_vars(point.len(), degree);
+        let points = point
+            .iter()
+            .enumerate()
+            .map(|(i, &r)| {
+                let mut res = Vec::with_capacity(coeff_vars + index_vars);
+                let mut power = r;
+                for _ in 0..coeff_vars {
+                    res.push(power);
+                    power.square_in_place();
+                }
+                res.extend((0..index_vars).map(|j| F::from((i >> j & 1) as u64)));
+                res
+            })
+            .collect();
+        Self {
+            points,
+            evaluations,
+        }
+    }
+}
+
+/// The number of variables of the coefficients and of the index of the `g_i`
+/// in the multilinear encoding of a mask.
+fn mle_num_vars(num_vars: usize, degree: usize) -> (usize, usize) {
+    (log2(degree + 1) as usize, log2(num_vars) as usize)
+}
+
+fn eval_univariate<F: PrimeField>(coeffs: &[F], x: F) -> F {
+    coeffs.iter().rev().fold(F::zero(), |acc, c| acc * x + c)
+}
+
+fn pow2<F: PrimeField>(exp: usize) -> F {
+    F::from(2u64).pow([exp as u64])
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use ark_bls12_381::Fr;
+    use ark_poly::{MultilinearExtension, Polynomial};
+    use ark_std::{test_rng, UniformRand, Zero};
+
+    #[test]
+    fn test_mask() -> Result<(), PolyIOPErrors> {
+        let mut rng = test_rng();
+        for (num_vars, degree) in [(1, 1), (3, 2), (5, 4)] {
+            let mask = SumCheckMask::<Fr>::rand(num_vars, degree, &mut rng)?;
+            assert_eq!(mask.degree(), degree);
+
+            // the sum over the hypercube, and the first round message
+            let evaluate = |x: &[Fr]| -> Fr { mask.evaluate_univariates(x).iter().sum() };
+            let sum: Fr = (0..1usize << num_vars)
+                .map(|b| {
+                    let x: Vec<_> = (0..num_vars)
+                        .map(|i| Fr::from((b >> i & 1) as u64))
+                        .collect();
+                    evaluate(&x)
+                })
+                .sum();
+            assert_eq!(mask.sum(), sum);
+            let message = mask.round_message(0, Fr::zero(), degree + 1);
+            assert_eq!(message[0] + message[1], sum);
+
+            // the multilinear encoding
+            let mle = mask.to_mle();
+            let point: Vec<_> = (0..num_vars).map(|_| Fr::rand(&mut rng)).collect();
+            let evaluations = mask.evaluate_univariates(&point);
+            let claims = SumCheckMaskClaims::new(degree, &point, evaluations.clone());
+            for (p, e) in claims.points.iter().zip(evaluations.iter()) {
+                assert_eq!(p.len(), mle.num_vars());
+                assert_eq!(mle.evaluate(p), *e);
+            }
+        }
+        assert!(SumCheckMask::<Fr>::rand(0, 2, &mut rng).is_err());
+        assert!(SumCheckMask::<Fr>::rand(2, 0, &mut rng).is_err());
+        Ok(())
+    }
+}
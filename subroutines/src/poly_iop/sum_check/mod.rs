@@ -14,22 +14,39 @@ use crate::poly_iop::{
 use arithmetic::{VPAuxInfo, VirtualPolynomial};
 use ark_ff::PrimeField;
 use ark_poly::DenseMultilinearExtension;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
 use ark_std::{end_timer, start_timer};
 use std::{fmt::Debug, sync::Arc};
-use transcript::IOPTranscript;
+use transcript::{Encode, Transcript};
 
+mod mask;
 mod prover;
 mod verifier;
 
+pub use mask::{SumCheckMask, SumCheckMaskClaims, ZkSumCheckProof};
+
 /// Trait for doing sum check protocols.
 pub trait SumCheck<F: PrimeField> {
     type VirtualPolynomial;
     type VPAuxInfo;
     type MultilinearExtension;
 
-    type SumCheckProof: Clone + Debug + Default + PartialEq;
+    type SumCheckProof: Clone
+        + Debug
+        + Default
+        + PartialEq
+        + CanonicalSerialize
+        + CanonicalDeserialize;
     type Transcript;
     type SumCheckSubClaim: Clone + Debug + Default + PartialEq;
+    type SumCheckMask;
+    type SumCheckMaskClaims: Clone + Debug + Default + PartialEq;
+    type ZkSumCheckProof: Clone
+        + Debug
+        + Default
+        + PartialEq
+        + CanonicalSerialize
+        + CanonicalDeserialize;
 
     /// Extract sum from the proof
     fn extract_sum(proof: &Self::SumCheckProof) -> F;
@@ -53,7 +70,7 @@ pub trait SumCheck<F: PrimeField> {
     fn prove_with_fixed_challenges(
         poly: &Self::VirtualPolynomial,
         transcript: &mut Self::Transcript,
-        challenges: &Vec<F>,
+        challenges: &[F],
     ) -> Result<Self::SumCheckProof, PolyIOPErrors>;
 
     /// Verify the claimed sum using the proof
@@ -63,6 +80,33 @@ pub trait SumCheck<F: PrimeField> {
         aux_info: &Self::VPAuxInfo,
         transcript: &mut Self::Transcript,
     ) -> Result<Self::SumCheckSubClaim, PolyIOPErrors>;
+
+    /// Generate a zero-knowledge proof of the sum of polynomial over
+    /// {0,1}^`num_vars`.
+    ///
+    /// The sum check runs on `f + rho * g`, where `g` is the random `mask`,
+    /// of the same degree as the polynomial, and `rho` is sampled after
+    /// `mask_commitment` and the sum of the mask are appended to the
+    /// transcript. The prover then opens the multilinear encoding of the mask
+    /// at the points of the claims returned by `verify_zk`.
+    fn prove_zk<C: CanonicalSerialize + Encode>(
+        poly: &Self::VirtualPolynomial,
+        mask: &Self::SumCheckMask,
+        mask_commitment: &C,
+        transcript: &mut Self::Transcript,
+    ) -> Result<Self::ZkSumCheckProof, PolyIOPErrors>;
+
+    /// Verify the claimed sum using a zero-knowledge proof.
+    ///
+    /// Returns the subclaim on the polynomial together with the claims on the
+    /// mask, which the caller checks against `mask_commitment`.
+    fn verify_zk<C: CanonicalSerialize + Encode>(
+        sum: F,
+        proof: &Self::ZkSumCheckProof,
+        aux_info: &Self::VPAuxInfo,
+        mask_commitment: &C,
+        transcript: &mut Self::Transcript,
+    ) -> Result<(Self::SumCheckSubClaim, Self::SumCheckMaskClaims), PolyIOPErrors>;
 }
 
 /// Trait for sum check protocol prover side APIs.
@@ -92,7 +136,6 @@ pub trait SumCheckVerifier<F: PrimeField> {
     type VPAuxInfo;
     type ProverMessage;
     type Challenge;
-    type Transcript;
     type SumCheckSubClaim;
 
     /// Initialize the verifier's state.
@@ -104,10 +147,10 @@ pub trait SumCheckVerifier<F: PrimeField> {
     /// challenges; and update the verifier's state accordingly. The actual
     /// verifications are deferred (in batch) to `check_and_generate_subclaim`
     /// at the last step.
-    fn verify_round_and_update_state(
+    fn verify_round_and_update_state<T: Transcript<F>>(
         &mut self,
         prover_msg: &Self::ProverMessage,
-        transcript: &mut Self::Transcript,
+        transcript: &mut T,
     ) -> Result<Self::Challenge, PolyIOPErrors>;
 
     /// This function verifies the deferred checks in the interactive version of
@@ -135,13 +178,16 @@ pub struct SumCheckSubClaim<F: PrimeField> {
     pub expected_evaluation: F,
 }
 
-impl<F: PrimeField> SumCheck<F> for PolyIOP<F> {
+impl<F: PrimeField, T: Transcript<F>, P> SumCheck<F> for PolyIOP<F, T, P> {
     type SumCheckProof = IOPProof<F>;
     type VirtualPolynomial = VirtualPolynomial<F>;
     type VPAuxInfo = VPAuxInfo<F>;
     type MultilinearExtension = Arc<DenseMultilinearExtension<F>>;
     type SumCheckSubClaim = SumCheckSubClaim<F>;
-    type Transcript = IOPTranscript<F>;
+    type SumCheckMask = SumCheckMask<F>;
+    type SumCheckMaskClaims = SumCheckMaskClaims<F>;
+    type ZkSumCheckProof = ZkSumCheckProof<F>;
+    type Transcript = T;
 
     fn extract_sum(proof: &Self::SumCheckProof) -> F {
         let start = start_timer!(|| "extract sum");
@@ -152,7 +198,7 @@ impl<F: PrimeField> SumCheck<F> for PolyIOP<F> {
 
     fn init_transcript() -> Self::Transcript {
         let start = start_timer!(|| "init transcript");
-        let res = IOPTranscript::<F>::new(b"Initializing SumCheck transcript");
+        let res = T::new(b"Initializing SumCheck transcript");
         end_timer!(start);
         res
     }
@@ -190,22 +236,30 @@ impl<F: PrimeField> SumCheck<F> for PolyIOP<F> {
     fn prove_with_fixed_challenges(
         poly: &Self::VirtualPolynomial,
         transcript: &mut Self::Transcript,
-        challenges: &Vec<F>,
+        challenges: &[F],
     ) -> Result<Self::SumCheckProof, PolyIOPErrors> {
         let start = start_timer!(|| "sum check prove");
 
+        if challenges.len() < poly.aux_info.num_variables {
+            return Err(PolyIOPErrors::InvalidParameters(format!(
+                "{} fixed challenges provided for {} rounds",
+                challenges.len(),
+                poly.aux_info.num_variables
+            )));
+        }
+
         transcript.append_serializable_element(b"aux info", &poly.aux_info)?;
 
         let mut prover_state = IOPProverState::prover_init(poly)?;
         let mut challenge = None;
         let mut prover_msgs = Vec::with_capacity(poly.aux_info.num_variables);
-        for round in 0..poly.aux_info.num_variables {
+        for &fixed_challenge in challenges.iter().take(poly.aux_info.num_variables) {
             let prover_msg =
                 IOPProverState::prove_round_and_update_state(&mut prover_state, &challenge)?;
             transcript.append_serializable_element(b"prover msg", &prover_msg)?;
             prover_msgs.push(prover_msg);
             challenge = Some(
-                transcript.get_and_append_fixed_challenge(b"Internal round", challenges[round])?,
+                transcript.get_and_append_fixed_challenge(b"Internal round", fixed_challenge)?,
             );
         }
         // pushing the last challenge point to the state
@@ -231,7 +285,13 @@ impl<F: PrimeField> SumCheck<F> for PolyIOP<F> {
         transcript.append_serializable_element(b"aux info", aux_info)?;
         let mut verifier_state = IOPVerifierState::verifier_init(aux_info);
         for i in 0..aux_info.num_variables {
-            let prover_msg = proof.proofs.get(i).expect("proof is incomplete");
+            let prover_msg = proof.proofs.get(i).ok_or_else(|| {
+                PolyIOPErrors::InvalidProof(format!(
+                    "proof is incomplete: {} rounds, expect {}",
+                    proof.proofs.len(),
+                    aux_info.num_variables
+                ))
+            })?;
             transcript.append_serializable_element(b"prover msg", prover_msg)?;
             IOPVerifierState::verify_round_and_update_state(
                 &mut verifier_state,
@@ -245,6 +305,116 @@ impl<F: PrimeField> SumCheck<F> for PolyIOP<F> {
         end_timer!(start);
         res
     }
+
+    fn prove_zk<C: CanonicalSerialize + Encode>(
+        poly: &Self::VirtualPolynomial,
+        mask: &Self::SumCheckMask,
+        mask_commitment: &C,
+        transcript: &mut Self::Transcript,
+    ) -> Result<Self::ZkSumCheckProof, PolyIOPErrors> {
+        let start = start_timer!(|| "zk sum check prove");
+
+        let num_vars = poly.aux_info.num_variables;
+        if mask.num_vars() != num_vars || mask.degree() != poly.aux_info.max_degree {
+            return Err(PolyIOPErrors::InvalidParameters(format!(
+                "mask of {} variables and degree {} for a polynomial of {} variables and degree {}",
+                mask.num_vars(),
+                mask.degree(),
+                num_vars,
+                poly.aux_info.max_degree
+            )));
+        }
+
+        let mask_sum = mask.sum();
+        transcript.append_serializable_element(b"mask commitment", mask_commitment)?;
+        transcript.append_field_element(b"mask sum", &mask_sum)?;
+        let rho = transcript.get_and_append_challenge(b"mask rho")?;
+
+        transcript.append_serializable_element(b"aux info", &poly.aux_info)?;
+
+        let mut prover_state = IOPProverState::prover_init(poly)?;
+        let mut challenge = None;
+        let mut prefix = F::zero();
+        let mut prover_msgs = Vec::with_capacity(num_vars);
+        for round in 0..num_vars {
+            let mut prover_msg =
+                IOPProverState::prove_round_and_update_state(&mut prover_state, &challenge)?;
+            if let Some(r) = challenge {
+                prefix += mask.evaluate_univariate(round - 1, r);
+            }
+            let mask_msg = mask.round_message(round, prefix, prover_msg.evaluations.len());
+            for (e, m) in prover_msg.evaluations.iter_mut().zip(mask_msg) {
+                *e += rho * m;
+            }
+            transcript.append_serializable_element(b"prover msg", &prover_msg)?;
+            prover_msgs.push(prover_msg);
+            challenge = Some(transcript.get_and_append_challenge(b"Internal round")?);
+        }
+        // pushing the last challenge point to the state
+        if let Some(p) = challenge {
+            prover_state.challenges.push(p)
+        };
+
+        let mask_evaluations = mask.evaluate_univariates(&prover_state.challenges);
+        transcript.append_serializable_element(b"mask evals", &mask_evaluations)?;
+
+        end_timer!(start);
+        Ok(ZkSumCheckProof {
+            mask_sum,
+            sum_check_proof: IOPProof {
+                point: prover_state.challenges,
+                proofs: prover_msgs,
+            },
+            mask_evaluations,
+        })
+    }
+
+    fn verify_zk<C: CanonicalSerialize + Encode>(
+        sum: F,
+        proof: &Self::ZkSumCheckProof,
+        aux_info: &Self::VPAuxInfo,
+        mask_commitment: &C,
+        transcript: &mut Self::Transcript,
+    ) -> Result<(Self::SumCheckSubClaim, Self::SumCheckMaskClaims), PolyIOPErrors> {
+        let start = start_timer!(|| "zk sum check verify");
+
+        if proof.mask_evaluations.len() != aux_info.num_variables {
+            return Err(PolyIOPErrors::InvalidProof(format!(
+                "{} mask evaluations, expect {}",
+                proof.mask_evaluations.len(),
+                aux_info.num_variables
+            )));
+        }
+
+        transcript.append_serializable_element(b"mask commitment", mask_commitment)?;
+        transcript.append_field_element(b"mask sum", &proof.mask_sum)?;
+        let rho = transcript.get_and_append_challenge(b"mask rho")?;
+
+        let subclaim = <Self as SumCheck<F>>::verify(
+            sum + rho * proof.mask_sum,
+            &proof.sum_check_proof,
+            aux_info,
+            transcript,
+        )?;
+        transcript.append_serializable_element(b"mask evals", &proof.mask_evaluations)?;
+
+        // remove the mask from the claim of `f + rho * g`
+        let mask_eval: F = proof.mask_evaluations.iter().sum();
+        let claims = SumCheckMaskClaims::new(
+            aux_info.max_degree,
+            &subclaim.point,
+            proof.mask_evaluations.clone(),
+        );
+
+        end_timer!(start);
+        Ok((
+            SumCheckSubClaim {
+                point: subclaim.point,
+                expected_evaluation: subclaim.expected_evaluation - rho * mask_eval,
+            },
+            claims,
+        ))
+    }
 }
 
 #[cfg(test)]
@@ -256,6 +426,7 @@ mod test {
     use ark_poly::{DenseMultilinearExtension, MultilinearExtension};
     use ark_std::test_rng;
     use std::sync::Arc;
+    use transcript::{IOPTranscript, KeccakTranscript};
 
     fn test_sumcheck(
         nv: usize,
@@ -325,6 +496,7 @@ mod test {
 
     #[test]
     fn test_trivial_polynomial() -> Result<(), PolyIOPErrors> {
+        let _trace = crate::trace_guard();
         let nv = 1;
         let num_multiplicands_range = (4, 13);
         let num_products = 5;
@@ -334,6 +506,7 @@ mod test {
     }
     #[test]
     fn test_normal_polynomial() -> Result<(), PolyIOPErrors> {
+        let _trace = crate::trace_guard();
         let nv = 12;
         let num_multiplicands_range = (4, 9);
         let num_products = 5;
@@ -342,7 +515,30 @@ mod test {
         test_sumcheck_internal(nv, num_multiplicands_range, num_products)
     }
     #[test]
+    fn test_keccak_transcript() -> Result<(), PolyIOPErrors> {
+        let _trace = crate::trace_guard();
+        type Keccak = PolyIOP<Fr, KeccakTranscript<Fr>>;
+
+        let mut rng = test_rng();
+        let (poly, asserted_sum) = VirtualPolynomial::rand(8, (3, 5), 3, &mut rng)?;
+        let mut transcript = <Keccak as SumCheck<Fr>>::init_transcript();
+        let proof = <Keccak as SumCheck<Fr>>::prove(&poly, &mut transcript)?;
+        let mut transcript = <Keccak as SumCheck<Fr>>::init_transcript();
+        let subclaim = <Keccak as SumCheck<Fr>>::verify(
+            asserted_sum,
+            &proof,
+            &poly.aux_info,
+            &mut transcript,
+        )?;
+        assert!(
+            poly.evaluate(&subclaim.point).unwrap() == subclaim.expected_evaluation,
+            "wrong subclaim"
+        );
+        Ok(())
+    }
+    #[test]
     fn zero_polynomial_should_error() {
+        let _trace = crate::trace_guard();
         let nv = 0;
         let num_multiplicands_range = (4, 13);
         let num_products = 5;
@@ -351,8 +547,104 @@ mod test {
         assert!(test_sumcheck_internal(nv, num_multiplicands_range, num_products).is_err());
     }
 
+    #[test]
+    fn test_zk_sumcheck() -> Result<(), PolyIOPErrors> {
+        let _trace = crate::trace_guard();
+        use crate::pcs::{prelude::MultilinearKzgPCS, PolynomialCommitmentScheme};
+        use ark_bls12_381::Bls12_381;
+        use ark_poly::Polynomial;
+        type Pcs = MultilinearKzgPCS<Bls12_381>;
+
+        let mut rng = test_rng();
+        let (poly, asserted_sum) = VirtualPolynomial::<Fr>::rand(6, (2, 4), 3, &mut rng)?;
+        let mask = SumCheckMask::rand(6, poly.aux_info.max_degree, &mut rng)?;
+        let mask_poly = mask.to_mle();
+        let pcs_srs = Pcs::gen_srs_for_testing(&mut rng, mask_poly.num_vars)?;
+        let (ck, vk) = Pcs::trim(&pcs_srs, None, Some(mask_poly.num_vars))?;
+        let mask_commitment = Pcs::commit(&ck, &mask_poly)?;
+
+        let mut transcript = <PolyIOP<Fr> as SumCheck<Fr>>::init_transcript();
+        let proof = <PolyIOP<Fr> as SumCheck<Fr>>::prove_zk(
+            &poly,
+            &mask,
+            &mask_commitment,
+            &mut transcript,
+        )?;
+        // the round messages are masked
+        let mut transcript = <PolyIOP<Fr> as SumCheck<Fr>>::init_transcript();
+        let plain_proof = <PolyIOP<Fr> as SumCheck<Fr>>::prove(&poly, &mut transcript)?;
+        assert_ne!(proof.sum_check_proof.proofs[0], plain_proof.proofs[0]);
+
+        let mut transcript = <PolyIOP<Fr> as SumCheck<Fr>>::init_transcript();
+        let (subclaim, mask_claims) = <PolyIOP<Fr> as SumCheck<Fr>>::verify_zk(
+            asserted_sum,
+            &proof,
+            &poly.aux_info,
+            &mask_commitment,
+            &mut transcript,
+        )?;
+        assert_eq!(
+            poly.evaluate(&subclaim.point)?,
+            subclaim.expected_evaluation
+        );
+        assert_eq!(mask_claims.points.len(), 6);
+        for (point, eval) in mask_claims
+            .points
+            .iter()
+            .zip(mask_claims.evaluations.iter())
+        {
+            let (opening, value) = Pcs::open(&ck, &mask_poly, point)?;
+            assert_eq!(value, *eval);
+            assert!(Pcs::verify(&vk, &mask_commitment, point, &value, &opening)?);
+        }
+
+        // wrong sum
+        let mut transcript = <PolyIOP<Fr> as SumCheck<Fr>>::init_transcript();
+        assert!(<PolyIOP<Fr> as SumCheck<Fr>>::verify_zk(
+            asserted_sum + Fr::from(1u64),
+            &proof,
+            &poly.aux_info,
+            &mask_commitment,
+            &mut transcript,
+        )
+        .is_err());
+
+        // a wrong mask evaluation shifts the claim on the polynomial
+        let mut bad_proof = proof.clone();
+        bad_proof.mask_evaluations[0] += Fr::from(1u64);
+        let mut transcript = <PolyIOP<Fr> as SumCheck<Fr>>::init_transcript();
+        let (subclaim, mask_claims) = <PolyIOP<Fr> as SumCheck<Fr>>::verify_zk(
+            asserted_sum,
+            &bad_proof,
+            &poly.aux_info,
+            &mask_commitment,
+            &mut transcript,
+        )?;
+        assert_ne!(
+            poly.evaluate(&subclaim.point)?,
+            subclaim.expected_evaluation
+        );
+        assert_ne!(
+            mask_poly.evaluate(&mask_claims.points[0]),
+            mask_claims.evaluations[0]
+        );
+
+        // a mask of the wrong degree
+        let mask = SumCheckMask::rand(6, poly.aux_info.max_degree + 1, &mut rng)?;
+        let mut transcript = <PolyIOP<Fr> as SumCheck<Fr>>::init_transcript();
+        assert!(<PolyIOP<Fr> as SumCheck<Fr>>::prove_zk(
+            &poly,
+            &mask,
+            &mask_commitment,
+            &mut transcript
+        )
+        .is_err());
+        Ok(())
+    }
+
     #[test]
     fn test_extract_sum() -> Result<(), PolyIOPErrors> {
+        let _trace = crate::trace_guard();
         let mut rng = test_rng();
         let mut transcript = <PolyIOP<Fr> as SumCheck<Fr>>::init_transcript();
         let (poly, asserted_sum) = VirtualPolynomial::<Fr>::rand(8, (3, 4), 3, &mut rng)?;
@@ -369,6 +661,7 @@ mod test {
     /// Test that the memory usage of shared-reference is linear to number of
     /// unique MLExtensions instead of total number of multiplicands.
     fn test_shared_reference() -> Result<(), PolyIOPErrors> {
+        let _trace = crate::trace_guard();
         let mut rng = test_rng();
         let ml_extensions: Vec<_> = (0..5)
             .map(|_| Arc::new(DenseMultilinearExtension::<Fr>::rand(8, &mut rng)))
@@ -14,7 +14,7 @@ use crate::poly_iop::{
 use arithmetic::VPAuxInfo;
 use ark_ff::PrimeField;
 use ark_std::{end_timer, start_timer};
-use transcript::IOPTranscript;
+use transcript::Transcript;
 
 #[cfg(feature = "parallel")]
 use rayon::iter::{IndexedParallelIterator, IntoParallelIterator, ParallelIterator};
@@ -23,7 +23,6 @@ impl<F: PrimeField> SumCheckVerifier<F> for IOPVerifierState<F> {
     type VPAuxInfo = VPAuxInfo<F>;
     type ProverMessage = IOPProverMessage<F>;
     type Challenge = F;
-    type Transcript = IOPTranscript<F>;
     type SumCheckSubClaim = SumCheckSubClaim<F>;
 
     /// Initialize the verifier's state.
@@ -47,10 +46,10 @@ impl<F: PrimeField> SumCheckVerifier<F> for IOPVerifierState<F> {
     /// challenges; and update the verifier's state accordingly. The actual
     /// verifications are deferred (in batch) to `check_and_generate_subclaim`
     /// at the last step.
-    fn verify_round_and_update_state(
+    fn verify_round_and_update_state<T: Transcript<F>>(
         &mut self,
         prover_msg: &Self::ProverMessage,
-        transcript: &mut Self::Transcript,
+        transcript: &mut T,
     ) -> Result<Self::Challenge, PolyIOPErrors> {
         let start =
             start_timer!(|| format!("sum check verify {}-th round and update state", self.round));
@@ -326,6 +325,7 @@ mod test {
 
     #[test]
     fn test_interpolation() -> Result<(), PolyIOPErrors> {
+        let _trace = crate::trace_guard();
         let mut prng = ark_std::test_rng();
 
         // test a polynomial with 20 known points, i.e., with degree 19
@@ -11,8 +11,9 @@ use std::fmt::Debug;
 use crate::poly_iop::{errors::PolyIOPErrors, sum_check::SumCheck, PolyIOP};
 use arithmetic::eq_eval;
 use ark_ff::PrimeField;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
 use ark_std::{end_timer, start_timer};
-use transcript::IOPTranscript;
+use transcript::{Encode, Transcript};
 
 /// A zero check IOP subclaim for `f(x)` consists of the following:
 ///   - the initial challenge vector r which is used to build eq(x, r) in
@@ -33,7 +34,12 @@ pub struct ZeroCheckSubClaim<F: PrimeField> {
 /// It is derived from SumCheck.
 pub trait ZeroCheck<F: PrimeField>: SumCheck<F> {
     type ZeroCheckSubClaim: Clone + Debug + Default + PartialEq;
-    type ZeroCheckProof: Clone + Debug + Default + PartialEq;
+    type ZeroCheckProof: Clone
+        + Debug
+        + Default
+        + PartialEq
+        + CanonicalSerialize
+        + CanonicalDeserialize;
 
     /// Initialize the system with a transcript
     ///
@@ -56,14 +62,35 @@ pub trait ZeroCheck<F: PrimeField>: SumCheck<F> {
         aux_info: &Self::VPAuxInfo,
         transcript: &mut Self::Transcript,
     ) -> Result<Self::ZeroCheckSubClaim, PolyIOPErrors>;
+
+    /// Generate a zero-knowledge proof that the polynomial vanishes on
+    /// {0,1}^`num_vars`, see `SumCheck::prove_zk`.
+    ///
+    /// The sum check runs on `f(x) * eq(x, r)`, so the degree of the mask is
+    /// the max degree of the polynomial plus one.
+    fn prove_zk<C: CanonicalSerialize + Encode>(
+        poly: &Self::VirtualPolynomial,
+        mask: &Self::SumCheckMask,
+        mask_commitment: &C,
+        transcript: &mut Self::Transcript,
+    ) -> Result<Self::ZkSumCheckProof, PolyIOPErrors>;
+
+    /// Verify a zero-knowledge proof, and return the subclaim on the
+    /// polynomial together with the claims on the mask.
+    fn verify_zk<C: CanonicalSerialize + Encode>(
+        proof: &Self::ZkSumCheckProof,
+        aux_info: &Self::VPAuxInfo,
+        mask_commitment: &C,
+        transcript: &mut Self::Transcript,
+    ) -> Result<(Self::ZeroCheckSubClaim, Self::SumCheckMaskClaims), PolyIOPErrors>;
 }
 
-impl<F: PrimeField> ZeroCheck<F> for PolyIOP<F> {
+impl<F: PrimeField, T: Transcript<F>, P> ZeroCheck<F> for PolyIOP<F, T, P> {
     type ZeroCheckSubClaim = ZeroCheckSubClaim<F>;
     type ZeroCheckProof = Self::SumCheckProof;
 
     fn init_transcript() -> Self::Transcript {
-        IOPTranscript::<F>::new(b"Initializing ZeroCheck transcript")
+        T::new(b"Initializing ZeroCheck transcript")
     }
 
     fn prove(
@@ -118,16 +145,74 @@ impl<F: PrimeField> ZeroCheck<F> for PolyIOP<F> {
             init_challenge: r,
         })
     }
+    fn prove_zk<C: CanonicalSerialize + Encode>(
+        poly: &Self::VirtualPolynomial,
+        mask: &Self::SumCheckMask,
+        mask_commitment: &C,
+        transcript: &mut Self::Transcript,
+    ) -> Result<Self::ZkSumCheckProof, PolyIOPErrors> {
+        let start = start_timer!(|| "zk zero check prove");
+
+        let length = poly.aux_info.num_variables;
+        let r = transcript.get_and_append_challenge_vectors(b"0check r", length)?;
+        let f_hat = poly.build_f_hat(r.as_ref())?;
+        let res = <Self as SumCheck<F>>::prove_zk(&f_hat, mask, mask_commitment, transcript);
+
+        end_timer!(start);
+        res
+    }
+
+    fn verify_zk<C: CanonicalSerialize + Encode>(
+        proof: &Self::ZkSumCheckProof,
+        fx_aux_info: &Self::VPAuxInfo,
+        mask_commitment: &C,
+        transcript: &mut Self::Transcript,
+    ) -> Result<(Self::ZeroCheckSubClaim, Self::SumCheckMaskClaims), PolyIOPErrors> {
+        let start = start_timer!(|| "zk zero check verify");
+
+        // the sum is checked to be zero by the sum check itself, since the
+        // first round message also holds the sum of the mask
+        let length = fx_aux_info.num_variables;
+        let r = transcript.get_and_append_challenge_vectors(b"0check r", length)?;
+
+        let mut hat_fx_aux_info = fx_aux_info.clone();
+        hat_fx_aux_info.max_degree += 1;
+        let (sum_subclaim, mask_claims) = <Self as SumCheck<F>>::verify_zk(
+            F::zero(),
+            proof,
+            &hat_fx_aux_info,
+            mask_commitment,
+            transcript,
+        )?;
+
+        let eq_x_r_eval = eq_eval(&sum_subclaim.point, &r)?;
+        let expected_evaluation = sum_subclaim.expected_evaluation / eq_x_r_eval;
+
+        end_timer!(start);
+        Ok((
+            ZeroCheckSubClaim {
+                point: sum_subclaim.point,
+                expected_evaluation,
+                init_challenge: r,
+            },
+            mask_claims,
+        ))
+    }
 }
 
 #[cfg(test)]
 mod test {
 
     use super::ZeroCheck;
-    use crate::poly_iop::{errors::PolyIOPErrors, PolyIOP};
+    use crate::{
+        pcs::{prelude::MultilinearKzgPCS, PolynomialCommitmentScheme},
+        poly_iop::{errors::PolyIOPErrors, sum_check::SumCheckMask, PolyIOP},
+    };
     use arithmetic::VirtualPolynomial;
-    use ark_bls12_381::Fr;
+    use ark_bls12_381::{Bls12_381, Fr};
+    use ark_poly::Polynomial;
     use ark_std::test_rng;
+    use transcript::{PoseidonTranscript, Transcript};
 
     fn test_zerocheck(
         nv: usize,
@@ -180,6 +265,7 @@ mod test {
 
     #[test]
     fn test_trivial_polynomial() -> Result<(), PolyIOPErrors> {
+        let _trace = crate::trace_guard();
         let nv = 1;
         let num_multiplicands_range = (4, 5);
         let num_products = 1;
@@ -188,6 +274,7 @@ mod test {
     }
     #[test]
     fn test_normal_polynomial() -> Result<(), PolyIOPErrors> {
+        let _trace = crate::trace_guard();
         let nv = 5;
         let num_multiplicands_range = (4, 9);
         let num_products = 5;
@@ -195,8 +282,96 @@ mod test {
         test_zerocheck(nv, num_multiplicands_range, num_products)
     }
 
+    #[test]
+    fn test_zk_zerocheck() -> Result<(), PolyIOPErrors> {
+        let _trace = crate::trace_guard();
+        type Pcs = MultilinearKzgPCS<Bls12_381>;
+
+        let mut rng = test_rng();
+        let poly = VirtualPolynomial::rand_zero(5, (2, 4), 3, &mut rng)?;
+        let mask = SumCheckMask::rand(5, poly.aux_info.max_degree + 1, &mut rng)?;
+        let mask_poly = mask.to_mle();
+        let pcs_srs = Pcs::gen_srs_for_testing(&mut rng, mask_poly.num_vars)?;
+        let (ck, _) = Pcs::trim(&pcs_srs, None, Some(mask_poly.num_vars))?;
+        let mask_commitment = Pcs::commit(&ck, &mask_poly)?;
+
+        let mut transcript = <PolyIOP<Fr> as ZeroCheck<Fr>>::init_transcript();
+        transcript.append_message(b"testing", b"initializing transcript for testing")?;
+        let proof = <PolyIOP<Fr> as ZeroCheck<Fr>>::prove_zk(
+            &poly,
+            &mask,
+            &mask_commitment,
+            &mut transcript,
+        )?;
+
+        let mut transcript = <PolyIOP<Fr> as ZeroCheck<Fr>>::init_transcript();
+        transcript.append_message(b"testing", b"initializing transcript for testing")?;
+        let (zero_subclaim, mask_claims) = <PolyIOP<Fr> as ZeroCheck<Fr>>::verify_zk(
+            &proof,
+            &poly.aux_info,
+            &mask_commitment,
+            &mut transcript,
+        )?;
+        assert_eq!(
+            poly.evaluate(&zero_subclaim.point)?,
+            zero_subclaim.expected_evaluation
+        );
+        for (point, eval) in mask_claims
+            .points
+            .iter()
+            .zip(mask_claims.evaluations.iter())
+        {
+            assert_eq!(mask_poly.evaluate(point), *eval);
+        }
+
+        // a polynomial that does not vanish
+        let (poly, _) = VirtualPolynomial::<Fr>::rand(5, (2, 4), 3, &mut rng)?;
+        let mut transcript = <PolyIOP<Fr> as ZeroCheck<Fr>>::init_transcript();
+        transcript.append_message(b"testing", b"initializing transcript for testing")?;
+        let proof = <PolyIOP<Fr> as ZeroCheck<Fr>>::prove_zk(
+            &poly,
+            &mask,
+            &mask_commitment,
+            &mut transcript,
+        )?;
+        let mut transcript = <PolyIOP<Fr> as ZeroCheck<Fr>>::init_transcript();
+        transcript.append_message(b"testing", b"initializing transcript for testing")?;
+        assert!(<PolyIOP<Fr> as ZeroCheck<Fr>>::verify_zk(
+            &proof,
+            &poly.aux_info,
+            &mask_commitment,
+            &mut transcript
+        )
+        .is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_poseidon_transcript() -> Result<(), PolyIOPErrors> {
+        let _trace = crate::trace_guard();
+        type Poseidon = PolyIOP<Fr, PoseidonTranscript<Fr>>;
+
+        let mut rng = test_rng();
+        let poly = VirtualPolynomial::rand_zero(5, (2, 4), 3, &mut rng)?;
+
+        let mut transcript = <Poseidon as ZeroCheck<Fr>>::init_transcript();
+        transcript.append_message(b"testing", b"initializing transcript for testing")?;
+        let proof = <Poseidon as ZeroCheck<Fr>>::prove(&poly, &mut transcript)?;
+
+        let mut transcript = <Poseidon as ZeroCheck<Fr>>::init_transcript();
+        transcript.append_message(b"testing", b"initializing transcript for testing")?;
+        let zero_subclaim =
+            <Poseidon as ZeroCheck<Fr>>::verify(&proof, &poly.aux_info, &mut transcript)?;
+        assert!(
+            poly.evaluate(&zero_subclaim.point)? == zero_subclaim.expected_evaluation,
+            "wrong subclaim"
+        );
+        Ok(())
+    }
+
     #[test]
     fn zero_polynomial_should_error() -> Result<(), PolyIOPErrors> {
+        let _trace = crate::trace_guard();
         let nv = 0;
         let num_multiplicands_range = (4, 13);
         let num_products = 5;
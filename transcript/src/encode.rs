@@ -0,0 +1,114 @@
+// Copyright (c) 2023 Espresso Systems (espressosys.com)
+// This file is part of the HyperPlonk library.
+
+// You should have received a copy of the MIT License
+// along with the HyperPlonk library. If not, see <https://mit-license.org/>.
+
+//! The structure of the elements given to `append_serializable_element`.
+//!
+//! `IOPTranscript` absorbs the arkworks serialization of an element, but a
+//! transcript that is recomputed by another verifier, such as an EVM contract
+//! or a circuit, needs to know which bytes are field elements, curve points
+//! and lengths. An element is thus also an `Encode`, which gives its parts to
+//! an `Encoder` in order:
+//!
+//! - a field element of any prime field, by value;
+//! - a curve point `(x, y)` as the base prime field elements of `x` then of
+//!   `y`, with the point at infinity as `(0, 0)`, as in the EVM precompiles;
+//! - a vector as its length, then its elements;
+//! - a fixed-size array or a tuple as its elements, without a length;
+//! - a hash as its bytes.
+
+use ark_ec::{short_weierstrass, twisted_edwards, AffineRepr};
+use ark_ff::{Field, PrimeField, Zero};
+
+/// Receives the parts of an element, see the module documentation.
+pub trait Encoder {
+    /// An integer, e.g. the length of a vector.
+    fn encode_u64(&mut self, n: u64);
+
+    /// An element of any prime field, e.g. a coordinate of a curve point.
+    fn encode_field_element<P: PrimeField>(&mut self, elem: &P);
+
+    /// A fixed-size byte string, e.g. a hash.
+    fn encode_bytes(&mut self, bytes: &[u8]);
+}
+
+/// An element whose parts can be absorbed one by one, see the module
+/// documentation.
+///
+/// `M` only tells apart the implementation for all prime fields from the
+/// others, so that a vector of field elements is an `Encode` in generic
+/// code; it is always inferred. The other elements use the default `()`.
+pub trait Encode<M = ()> {
+    /// Give the parts of `self` to `encoder`.
+    fn encode<E: Encoder>(&self, encoder: &mut E);
+}
+
+/// The marker of the `Encode` implementation of the prime fields.
+pub struct FieldMarker;
+
+/// Give the coordinates of `point` to `encoder`, with the point at infinity
+/// as `(0, 0)`.
+///
+/// This is the encoding of the affine points of the curve crates, for the
+/// types that only know their points as an `AffineRepr`, e.g. a
+/// `Commitment<E>` for any pairing `E`.
+pub fn encode_point<A: AffineRepr, E: Encoder>(point: &A, encoder: &mut E) {
+    let (x, y) = point
+        .xy()
+        .unwrap_or((A::BaseField::zero(), A::BaseField::zero()));
+    for coordinate in [x, y] {
+        for elem in coordinate.to_base_prime_field_elements() {
+            encoder.encode_field_element(&elem);
+        }
+    }
+}
+
+impl<P: PrimeField> Encode<FieldMarker> for P {
+    fn encode<E: Encoder>(&self, encoder: &mut E) {
+        encoder.encode_field_element(self);
+    }
+}
+
+impl<P: short_weierstrass::SWCurveConfig> Encode for short_weierstrass::Affine<P> {
+    fn encode<E: Encoder>(&self, encoder: &mut E) {
+        encode_point(self, encoder);
+    }
+}
+
+impl<P: twisted_edwards::TECurveConfig> Encode for twisted_edwards::Affine<P> {
+    fn encode<E: Encoder>(&self, encoder: &mut E) {
+        encode_point(self, encoder);
+    }
+}
+
+impl<M, T: Encode<M>> Encode<Vec<M>> for Vec<T> {
+    fn encode<E: Encoder>(&self, encoder: &mut E) {
+        encoder.encode_u64(self.len() as u64);
+        for elem in self.iter() {
+            elem.encode(encoder);
+        }
+    }
+}
+
+impl<M, T: Encode<M>, const N: usize> Encode<[M; N]> for [T; N] {
+    fn encode<E: Encoder>(&self, encoder: &mut E) {
+        for elem in self.iter() {
+            elem.encode(encoder);
+        }
+    }
+}
+
+impl<const N: usize> Encode for [u8; N] {
+    fn encode<E: Encoder>(&self, encoder: &mut E) {
+        encoder.encode_bytes(self);
+    }
+}
+
+impl<MA, MB, A: Encode<MA>, B: Encode<MB>> Encode<(MA, MB)> for (A, B) {
+    fn encode<E: Encoder>(&self, encoder: &mut E) {
+        self.0.encode(encoder);
+        self.1.encode(encoder);
+    }
+}
@@ -0,0 +1,301 @@
+// Copyright (c) 2023 Espresso Systems (espressosys.com)
+// This file is part of the HyperPlonk library.
+
+// You should have received a copy of the MIT License
+// along with the HyperPlonk library. If not, see <https://mit-license.org/>.
+
+//! A Keccak-256 transcript whose challenges can be reproduced by an EVM
+//! verifier.
+//!
+//! The transcript keeps a 32-byte `state` and a byte `buffer` of the messages
+//! absorbed since the last challenge. All hashes are `keccak256`, i.e. the
+//! EVM `KECCAK256` opcode. Labels and messages are absorbed as `enc(x) =
+//! be64(len(x)) || x`, where `be64` is an 8-byte big-endian length, so that
+//! absorbing is injective: `("ab", "c")` and `("a", "bc")` differ.
+//!
+//! - `new(label)`: `state = keccak256(label)`, `buffer = []`.
+//! - `append_message(label, msg)`: `buffer = buffer || enc(label) ||
+//!   enc(msg)`.
+//! - `append_field_element(label, f)`: `append_message(label, be(f))`, where
+//!   `be(f)` is the canonical (non-Montgomery) representation of `f` as a
+//!   big-endian integer padded to the size of the field's `BigInt` (32 bytes
+//!   for the BN254 and BLS12-381 scalar fields).
+//! - `append_serializable_element(label, s)`: `append_message(label,
+//!   evm(s))`, where `evm(s)` concatenates the `Encode` parts of `s`: a field
+//!   element `f` of any prime field is `be(f)`, a length is `be64(len)` and a
+//!   hash is its bytes. A BN254 G1 point `(x, y)` is thus `be32(x) ||
+//!   be32(y)`, as taken by the EVM precompiles, and the point at infinity is
+//!   `be32(0) || be32(0)`. A vector of points is `be64(len)` followed by the
+//!   points.
+//! - `get_and_append_challenge(label)`: `h = keccak256(state || buffer ||
+//!   enc(label))`, `state = h`, `buffer = []`, and the challenge is `h`
+//!   interpreted as a big-endian integer reduced modulo the field order. The
+//!   challenge is bound to all later challenges through `state`.
+//! - `get_and_append_fixed_challenge(label, c)`: as above, except that the
+//!   returned challenge is `c` and `buffer = enc(label) || enc(be(c))`, i.e.
+//!   `c` is then appended as `append_field_element(label, c)`.
+
+use crate::{Encode, Encoder, Transcript, TranscriptError};
+use ark_ff::{BigInteger, PrimeField};
+use ark_serialize::CanonicalSerialize;
+use sha3::{Digest, Keccak256};
+use std::marker::PhantomData;
+
+/// A Keccak-256 transcript, see the module documentation for the byte-level
+/// format.
+///
+/// As for `IOPTranscript`, a challenge cannot be generated from an empty
+/// transcript.
+#[derive(Clone)]
+pub struct KeccakTranscript<F: PrimeField> {
+    state: [u8; 32],
+    buffer: Vec<u8>,
+    is_empty: bool,
+    #[doc(hidden)]
+    phantom: PhantomData<F>,
+}
+
+impl<F: PrimeField> KeccakTranscript<F> {
+    /// Squeeze a new state out of the current state, the pending buffer and
+    /// the label; and reset the buffer.
+    fn squeeze(&mut self, label: &'static [u8]) -> Result<[u8; 32], TranscriptError> {
+        //  we need to reject when transcript is empty
+        if self.is_empty {
+            return Err(TranscriptError::InvalidTranscript(
+                "transcript is empty".to_string(),
+            ));
+        }
+
+        let mut hasher = Keccak256::new();
+        hasher.update(self.state);
+        hasher.update(&self.buffer);
+        hasher.update(encode(label));
+        self.state = hasher.finalize().into();
+        self.buffer.clear();
+        Ok(self.state)
+    }
+}
+
+/// `be64(len(bytes)) || bytes`
+fn encode(bytes: &[u8]) -> Vec<u8> {
+    let mut res = (bytes.len() as u64).to_be_bytes().to_vec();
+    res.extend_from_slice(bytes);
+    res
+}
+
+/// Concatenates the parts of an element, see `evm(s)` in the module
+/// documentation.
+struct EvmEncoder(Vec<u8>);
+
+impl Encoder for EvmEncoder {
+    fn encode_u64(&mut self, n: u64) {
+        self.0.extend_from_slice(&n.to_be_bytes());
+    }
+
+    fn encode_field_element<P: PrimeField>(&mut self, elem: &P) {
+        self.0.extend(elem.into_bigint().to_bytes_be());
+    }
+
+    fn encode_bytes(&mut self, bytes: &[u8]) {
+        self.0.extend_from_slice(bytes);
+    }
+}
+
+impl<F: PrimeField> Transcript<F> for KeccakTranscript<F> {
+    fn new(label: &'static [u8]) -> Self {
+        Self {
+            state: Keccak256::digest(label).into(),
+            buffer: vec![],
+            is_empty: true,
+            phantom: PhantomData,
+        }
+    }
+
+    fn append_message(&mut self, label: &'static [u8], msg: &[u8]) -> Result<(), TranscriptError> {
+        self.buffer.extend(encode(label));
+        self.buffer.extend(encode(msg));
+        self.is_empty = false;
+        Ok(())
+    }
+
+    fn append_field_element(
+        &mut self,
+        label: &'static [u8],
+        field_elem: &F,
+    ) -> Result<(), TranscriptError> {
+        self.append_message(label, &field_elem.into_bigint().to_bytes_be())
+    }
+
+    fn append_serializable_element<M, S: CanonicalSerialize + Encode<M>>(
+        &mut self,
+        label: &'static [u8],
+        group_elem: &S,
+    ) -> Result<(), TranscriptError> {
+        let mut encoder = EvmEncoder(vec![]);
+        group_elem.encode(&mut encoder);
+        self.append_message(label, &encoder.0)
+    }
+
+    fn get_and_append_challenge(&mut self, label: &'static [u8]) -> Result<F, TranscriptError> {
+        let digest = self.squeeze(label)?;
+        Ok(F::from_be_bytes_mod_order(&digest))
+    }
+
+    fn get_and_append_fixed_challenge(
+        &mut self,
+        label: &'static [u8],
+        challenge: F,
+    ) -> Result<F, TranscriptError> {
+        self.squeeze(label)?;
+        self.append_field_element(label, &challenge)?;
+        Ok(challenge)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::KeccakTranscript;
+    use crate::{Transcript, TranscriptError};
+    use ark_bls12_381::{Fr, G1Affine};
+    use ark_bn254::{Fr as BnFr, G1Affine as BnG1Affine};
+    use ark_ec::AffineRepr;
+    use ark_ff::{BigInteger, PrimeField};
+
+    fn to_hex(bytes: &[u8]) -> String {
+        bytes.iter().map(|b| format!("{:02x}", b)).collect()
+    }
+
+    fn challenge_hex<F: PrimeField>(challenge: F) -> String {
+        to_hex(&challenge.into_bigint().to_bytes_be())
+    }
+
+    #[test]
+    fn test_empty_transcript_should_error() {
+        let mut transcript = KeccakTranscript::<Fr>::new(b"hyperplonk");
+        assert!(transcript.get_and_append_challenge(b"alpha").is_err());
+    }
+
+    #[test]
+    fn test_field_element_encoding() -> Result<(), TranscriptError> {
+        // absorbing a field element is the same as absorbing its 32-byte
+        // big-endian encoding
+        let mut t1 = KeccakTranscript::<Fr>::new(b"hyperplonk");
+        t1.append_field_element(b"x", &Fr::from(0x0102u64))?;
+        let mut t2 = KeccakTranscript::<Fr>::new(b"hyperplonk");
+        let mut bytes = [0u8; 32];
+        bytes[30] = 0x01;
+        bytes[31] = 0x02;
+        t2.append_message(b"x", &bytes)?;
+        assert_eq!(
+            t1.get_and_append_challenge(b"alpha")?,
+            t2.get_and_append_challenge(b"alpha")?
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_domain_separation() -> Result<(), TranscriptError> {
+        let mut t1 = KeccakTranscript::<Fr>::new(b"hyperplonk");
+        t1.append_message(b"ab", b"c")?;
+        let mut t2 = KeccakTranscript::<Fr>::new(b"hyperplonk");
+        t2.append_message(b"a", b"bc")?;
+        assert_ne!(
+            t1.get_and_append_challenge(b"alpha")?,
+            t2.get_and_append_challenge(b"alpha")?
+        );
+
+        let mut t3 = KeccakTranscript::<Fr>::new(b"hyperplonk");
+        t3.append_message(b"ab", b"c")?;
+        let mut t4 = t3.clone();
+        assert_ne!(
+            t3.get_and_append_challenge(b"alpha")?,
+            t4.get_and_append_challenge(b"beta")?
+        );
+        Ok(())
+    }
+
+    /// Known-answer vectors for a contract port. Every challenge is printed
+    /// as a 32-byte big-endian integer.
+    #[test]
+    fn test_known_answers() -> Result<(), TranscriptError> {
+        let mut transcript = KeccakTranscript::<Fr>::new(b"hyperplonk");
+        transcript.append_message(b"testing", b"initializing transcript for testing")?;
+        let alpha = transcript.get_and_append_challenge(b"alpha")?;
+        assert_eq!(
+            challenge_hex(alpha),
+            "0bf5dc064aede05bf526a35b405697e679669165e3735863f8bbef5e3072101c"
+        );
+
+        // two challenges in a row, without any message in between
+        let beta = transcript.get_and_append_challenge(b"beta")?;
+        assert_eq!(
+            challenge_hex(beta),
+            "60a53af8484e7131847bdbf4573345b40ec77a8081ba41cae3160ff74cc2ce8c"
+        );
+
+        transcript.append_field_element(b"eval", &Fr::from(42u64))?;
+        transcript.append_field_element(b"eval", &-Fr::from(1u64))?;
+        let gammas = transcript.get_and_append_challenge_vectors(b"gamma", 2)?;
+        assert_eq!(
+            challenge_hex(gammas[0]),
+            "4125f66a462873501016ea4f27b12bb0af357098453810b7b81ece96aa3485f7"
+        );
+        assert_eq!(
+            challenge_hex(gammas[1]),
+            "4689a8c1b16d04f2871cc11b97aeaa63f32b463d35424a5d2bbf98f078aaa0f2"
+        );
+
+        // a G1 point is absorbed as its coordinates, 48 bytes each here
+        transcript.append_serializable_element(b"w", &G1Affine::generator())?;
+        let r = transcript.get_and_append_challenge(b"r")?;
+        assert_eq!(
+            challenge_hex(r),
+            "45a9a99be6dc729e9ced537fb45076256dc5283d3eef23e8e65fcf6919e808d0"
+        );
+
+        let fixed = transcript.get_and_append_fixed_challenge(b"fixed", Fr::from(7u64))?;
+        assert_eq!(fixed, Fr::from(7u64));
+        let s = transcript.get_and_append_challenge(b"s")?;
+        assert_eq!(
+            challenge_hex(s),
+            "04eb8ec0c58ca36c6aac5db565e8a61795ffa1cf64255a1e3593ebb0f091439b"
+        );
+
+        // over BN254, the curve of the EVM precompiles
+        let mut transcript = KeccakTranscript::<BnFr>::new(b"hyperplonk");
+        transcript.append_message(b"testing", b"initializing transcript for testing")?;
+        transcript.append_field_element(b"eval", &-BnFr::from(1u64))?;
+        let alpha: BnFr = transcript.get_and_append_challenge(b"alpha")?;
+        assert_eq!(
+            challenge_hex(alpha),
+            "0b76d52e7cf89315fb8762c06e07d3bfcd19b1c671bf31d483318a2e5c4ba0a5"
+        );
+
+        // the generator (1, 2) is absorbed as be32(1) || be32(2)
+        let g = BnG1Affine::generator();
+        let mut t1 = transcript.clone();
+        t1.append_serializable_element(b"w", &g)?;
+        let mut t2 = transcript.clone();
+        let mut bytes = [0u8; 64];
+        bytes[31] = 1;
+        bytes[63] = 2;
+        t2.append_message(b"w", &bytes)?;
+        let r = t1.get_and_append_challenge(b"r")?;
+        assert_eq!(r, t2.get_and_append_challenge(b"r")?);
+        assert_eq!(
+            challenge_hex(r),
+            "1d4082673009cbba3898814e588b30560b533fd62babbc987d72b1ed9d24edff"
+        );
+
+        // a vector of points is prefixed by its length
+        let points = vec![g, (g + g).into(), BnG1Affine::zero()];
+        t1.append_serializable_element(b"ws", &points)?;
+        let s = t1.get_and_append_challenge(b"s")?;
+        assert_eq!(
+            challenge_hex(s),
+            "1f160c67ebd4fe8b8432c830a4a92a9adb6af4832533415be5aa5512bb543432"
+        );
+
+        Ok(())
+    }
+}
@@ -9,14 +9,85 @@
 //! useful.
 //! TODO(ZZ): decide which APIs need to be public.
 
+mod encode;
 mod errors;
+mod keccak;
+mod poseidon;
+mod recording;
+pub use encode::{encode_point, Encode, Encoder, FieldMarker};
 pub use errors::TranscriptError;
+pub use keccak::KeccakTranscript;
+pub use poseidon::{pack_bytes, poseidon_config, PoseidonTranscript};
+pub use recording::{
+    diff, record, Divergence, RecordingTranscript, TranscriptEntry, TranscriptLog, TranscriptOp,
+};
 
 use ark_ff::PrimeField;
 use ark_serialize::CanonicalSerialize;
-use merlin::Transcript;
+use merlin::Transcript as MerlinTranscript;
 use std::marker::PhantomData;
 
+/// A Fiat-Shamir transcript over a prime field `F`.
+///
+/// The PolyIOPs and the polynomial commitment schemes are generic over this
+/// trait so that the hash function behind the challenges can be swapped, e.g.
+/// for a verifier that lives on-chain or inside a circuit. `IOPTranscript`
+/// is the default, Merlin-backed, implementation.
+pub trait Transcript<F: PrimeField>: Clone {
+    /// Create a new transcript, domain separated by `label`.
+    fn new(label: &'static [u8]) -> Self;
+
+    /// Append the message to the transcript.
+    fn append_message(&mut self, label: &'static [u8], msg: &[u8]) -> Result<(), TranscriptError>;
+
+    /// Append the field element to the transcript.
+    fn append_field_element(
+        &mut self,
+        label: &'static [u8],
+        field_elem: &F,
+    ) -> Result<(), TranscriptError> {
+        self.append_message(label, &to_bytes!(field_elem)?)
+    }
+
+    /// Append a serializable element (e.g., a commitment) to the transcript.
+    ///
+    /// The element is absorbed either through its serialization or through
+    /// its `Encode` parts, depending on the transcript.
+    fn append_serializable_element<M, S: CanonicalSerialize + Encode<M>>(
+        &mut self,
+        label: &'static [u8],
+        group_elem: &S,
+    ) -> Result<(), TranscriptError> {
+        self.append_message(label, &to_bytes!(group_elem)?)
+    }
+
+    /// Generate the challenge from the current transcript
+    /// and append it to the transcript.
+    fn get_and_append_challenge(&mut self, label: &'static [u8]) -> Result<F, TranscriptError>;
+
+    /// Advance the transcript as `get_and_append_challenge` would, but append
+    /// and return the given `challenge` instead of the derived one.
+    fn get_and_append_fixed_challenge(
+        &mut self,
+        label: &'static [u8],
+        challenge: F,
+    ) -> Result<F, TranscriptError>;
+
+    /// Generate a list of challenges from the current transcript
+    /// and append them to the transcript.
+    fn get_and_append_challenge_vectors(
+        &mut self,
+        label: &'static [u8],
+        len: usize,
+    ) -> Result<Vec<F>, TranscriptError> {
+        let mut res = vec![];
+        for _ in 0..len {
+            res.push(self.get_and_append_challenge(label)?)
+        }
+        Ok(res)
+    }
+}
+
 /// An IOP transcript consists of a Merlin transcript and a flag `is_empty` to
 /// indicate that if the transcript is empty.
 ///
@@ -28,58 +99,35 @@ use std::marker::PhantomData;
 /// `non-empty` transcript.
 #[derive(Clone)]
 pub struct IOPTranscript<F: PrimeField> {
-    transcript: Transcript,
+    transcript: MerlinTranscript,
     is_empty: bool,
     #[doc(hidden)]
     phantom: PhantomData<F>,
 }
 
-// TODO: Make this into a Trait
-impl<F: PrimeField> IOPTranscript<F> {
+impl<F: PrimeField> Transcript<F> for IOPTranscript<F> {
     /// Create a new IOP transcript.
-    pub fn new(label: &'static [u8]) -> Self {
+    fn new(label: &'static [u8]) -> Self {
         Self {
-            transcript: Transcript::new(label),
+            transcript: MerlinTranscript::new(label),
             is_empty: true,
             phantom: PhantomData,
         }
     }
 
     // Append the message to the transcript.
-    pub fn append_message(
-        &mut self,
-        label: &'static [u8],
-        msg: &[u8],
-    ) -> Result<(), TranscriptError> {
+    fn append_message(&mut self, label: &'static [u8], msg: &[u8]) -> Result<(), TranscriptError> {
         self.transcript.append_message(label, msg);
         self.is_empty = false;
         Ok(())
     }
 
-    // Append the message to the transcript.
-    pub fn append_field_element(
-        &mut self,
-        label: &'static [u8],
-        field_elem: &F,
-    ) -> Result<(), TranscriptError> {
-        self.append_message(label, &to_bytes!(field_elem)?)
-    }
-
-    // Append the message to the transcript.
-    pub fn append_serializable_element<S: CanonicalSerialize>(
-        &mut self,
-        label: &'static [u8],
-        group_elem: &S,
-    ) -> Result<(), TranscriptError> {
-        self.append_message(label, &to_bytes!(group_elem)?)
-    }
-
     // Generate the challenge from the current transcript
     // and append it to the transcript.
     //
     // The output field element is statistical uniform as long
     // as the field has a size less than 2^384.
-    pub fn get_and_append_challenge(&mut self, label: &'static [u8]) -> Result<F, TranscriptError> {
+    fn get_and_append_challenge(&mut self, label: &'static [u8]) -> Result<F, TranscriptError> {
         //  we need to reject when transcript is empty
         if self.is_empty {
             return Err(TranscriptError::InvalidTranscript(
@@ -94,7 +142,7 @@ impl<F: PrimeField> IOPTranscript<F> {
         Ok(challenge)
     }
 
-    pub fn get_and_append_fixed_challenge(
+    fn get_and_append_fixed_challenge(
         &mut self,
         label: &'static [u8],
         challenge: F,
@@ -118,7 +166,7 @@ impl<F: PrimeField> IOPTranscript<F> {
     //
     // The output field element are statistical uniform as long
     // as the field has a size less than 2^384.
-    pub fn get_and_append_challenge_vectors(
+    fn get_and_append_challenge_vectors(
         &mut self,
         label: &'static [u8],
         len: usize,
@@ -0,0 +1,379 @@
+// Copyright (c) 2023 Espresso Systems (espressosys.com)
+// This file is part of the HyperPlonk library.
+
+// You should have received a copy of the MIT License
+// along with the HyperPlonk library. If not, see <https://mit-license.org/>.
+
+//! A Poseidon sponge transcript over the scalar field, so that the challenges
+//! are cheap to recompute inside a circuit over the same field.
+//!
+//! Everything that enters the sponge is a list of native field elements.
+//! Byte strings are encoded with `pack(bytes) = [len(bytes)] || chunks`, where
+//! `bytes` is split into chunks of `(MODULUS_BIT_SIZE - 1) / 8` bytes (31
+//! bytes for the BN254 and BLS12-381 scalar fields), each read as a
+//! little-endian integer. No chunk can overflow the field, so the encoding is
+//! injective.
+//!
+//! - `new(label)`: a fresh sponge with `poseidon_config()` absorbs
+//!   `pack(label)`.
+//! - `append_message(label, msg)`: absorb `pack(label) || pack(msg)`.
+//! - `append_field_element(label, f)`: absorb `pack(label) || [f]`.
+//! - `append_serializable_element(label, s)`: absorb `pack(label) ||
+//!   native(s)`, where `native(s)` concatenates the `Encode` parts of `s`: a
+//!   length is `[len]`, a hash is `pack(hash)` and a field element `x` of a
+//!   prime field is `[x]` if the field is the native one. Otherwise, `x` is
+//!   split into limbs of `LIMB_BITS = 128` bits, least significant first:
+//!   `x = \sum_i x_i 2^{128 i}` with `0 <= x_i < 2^128`, one limb per 128
+//!   bits of the field's `BigInt`. A circuit recomposes `x` from its limbs
+//!   without decompressing a point. For instance a BN254 G1 point `(x, y)` on
+//!   the BN254 scalar field is `[x_0, x_1, y_0, y_1]`, a BLS12-381 G1 point
+//!   on the BLS12-381 scalar field is `[x_0, x_1, x_2, y_0, y_1, y_2]`, and a
+//!   Bandersnatch point on the BLS12-381 scalar field is `[x, y]`. The point
+//!   at infinity has the coordinates `(0, 0)`.
+//! - `get_and_append_challenge(label)`: absorb `pack(label)` and squeeze one
+//!   native field element.
+//! - `get_and_append_fixed_challenge(label, c)`: as above, then discard the
+//!   squeezed element and absorb `[c]`.
+
+use crate::{Encode, Encoder, Transcript, TranscriptError};
+use ark_crypto_primitives::sponge::{
+    poseidon::{find_poseidon_ark_and_mds, PoseidonConfig, PoseidonSponge},
+    Absorb, CryptographicSponge, FieldBasedCryptographicSponge,
+};
+use ark_ff::{BigInteger, PrimeField};
+use ark_serialize::CanonicalSerialize;
+
+/// Number of full rounds of the default configuration.
+const FULL_ROUNDS: usize = 8;
+/// Number of partial rounds of the default configuration.
+const PARTIAL_ROUNDS: usize = 57;
+/// S-box exponent of the default configuration.
+const ALPHA: u64 = 5;
+/// Rate of the default configuration, with a capacity of 1.
+const RATE: usize = 2;
+/// Size of the limbs of a non-native field element.
+const LIMB_BITS: usize = 128;
+
+/// The default Poseidon configuration: width 3 (rate 2, capacity 1), `x^5`
+/// S-box, 8 full and 57 partial rounds, with round constants and MDS matrix
+/// derived by the Grain LFSR of the Poseidon reference implementation.
+///
+/// These are the standard parameters for a ~255-bit field at the 128-bit
+/// security level. `x^5` is a permutation only if `gcd(5, p - 1) = 1`, which
+/// holds for the BN254 and BLS12-381 scalar fields.
+pub fn poseidon_config<F: PrimeField>() -> PoseidonConfig<F> {
+    let (ark, mds) = find_poseidon_ark_and_mds::<F>(
+        F::MODULUS_BIT_SIZE as u64,
+        RATE,
+        FULL_ROUNDS as u64,
+        PARTIAL_ROUNDS as u64,
+        0,
+    );
+    PoseidonConfig::new(FULL_ROUNDS, PARTIAL_ROUNDS, ALPHA, mds, ark, RATE, 1)
+}
+
+/// Encode a byte string as native field elements, see the module
+/// documentation.
+pub fn pack_bytes<F: PrimeField>(bytes: &[u8]) -> Vec<F> {
+    let chunk_size = ((F::MODULUS_BIT_SIZE - 1) / 8) as usize;
+    let mut res = Vec::with_capacity(1 + bytes.len().div_ceil(chunk_size));
+    res.push(F::from(bytes.len() as u64));
+    res.extend(bytes.chunks(chunk_size).map(F::from_le_bytes_mod_order));
+    res
+}
+
+/// Collects the parts of an element as native field elements, see
+/// `native(s)` in the module documentation.
+struct NativeEncoder<F: PrimeField>(Vec<F>);
+
+impl<F: PrimeField> Encoder for NativeEncoder<F> {
+    fn encode_u64(&mut self, n: u64) {
+        self.0.push(F::from(n));
+    }
+
+    fn encode_field_element<P: PrimeField>(&mut self, elem: &P) {
+        let bytes = elem.into_bigint().to_bytes_le();
+        if P::MODULUS.as_ref() == F::MODULUS.as_ref() {
+            self.0.push(F::from_le_bytes_mod_order(&bytes));
+        } else {
+            self.0
+                .extend(bytes.chunks(LIMB_BITS / 8).map(F::from_le_bytes_mod_order));
+        }
+    }
+
+    fn encode_bytes(&mut self, bytes: &[u8]) {
+        self.0.extend(pack_bytes::<F>(bytes));
+    }
+}
+
+/// A Poseidon transcript, see the module documentation for the encoding.
+///
+/// As for `IOPTranscript`, a challenge cannot be generated from an empty
+/// transcript.
+#[derive(Clone)]
+pub struct PoseidonTranscript<F: PrimeField + Absorb> {
+    sponge: PoseidonSponge<F>,
+    is_empty: bool,
+}
+
+impl<F: PrimeField + Absorb> PoseidonTranscript<F> {
+    /// Create a new transcript with a custom Poseidon configuration, e.g. one
+    /// that matches an existing circuit gadget.
+    pub fn new_with_config(label: &'static [u8], config: &PoseidonConfig<F>) -> Self {
+        let mut sponge = PoseidonSponge::new(config);
+        sponge.absorb(&pack_bytes::<F>(label));
+        Self {
+            sponge,
+            is_empty: true,
+        }
+    }
+
+    /// Absorb the label and squeeze a new field element.
+    fn squeeze(&mut self, label: &'static [u8]) -> Result<F, TranscriptError> {
+        //  we need to reject when transcript is empty
+        if self.is_empty {
+            return Err(TranscriptError::InvalidTranscript(
+                "transcript is empty".to_string(),
+            ));
+        }
+
+        self.sponge.absorb(&pack_bytes::<F>(label));
+        Ok(self.sponge.squeeze_native_field_elements(1)[0])
+    }
+
+    /// Absorb the label followed by `elems`.
+    fn absorb(&mut self, label: &'static [u8], elems: &[F]) {
+        let mut input = pack_bytes::<F>(label);
+        input.extend_from_slice(elems);
+        self.sponge.absorb(&input);
+        self.is_empty = false;
+    }
+}
+
+impl<F: PrimeField + Absorb> Transcript<F> for PoseidonTranscript<F> {
+    fn new(label: &'static [u8]) -> Self {
+        Self::new_with_config(label, &poseidon_config())
+    }
+
+    fn append_message(&mut self, label: &'static [u8], msg: &[u8]) -> Result<(), TranscriptError> {
+        self.absorb(label, &pack_bytes(msg));
+        Ok(())
+    }
+
+    fn append_field_element(
+        &mut self,
+        label: &'static [u8],
+        field_elem: &F,
+    ) -> Result<(), TranscriptError> {
+        self.absorb(label, &[*field_elem]);
+        Ok(())
+    }
+
+    fn append_serializable_element<M, S: CanonicalSerialize + Encode<M>>(
+        &mut self,
+        label: &'static [u8],
+        group_elem: &S,
+    ) -> Result<(), TranscriptError> {
+        let mut encoder = NativeEncoder(vec![]);
+        group_elem.encode(&mut encoder);
+        self.absorb(label, &encoder.0);
+        Ok(())
+    }
+
+    fn get_and_append_challenge(&mut self, label: &'static [u8]) -> Result<F, TranscriptError> {
+        self.squeeze(label)
+    }
+
+    fn get_and_append_fixed_challenge(
+        &mut self,
+        label: &'static [u8],
+        challenge: F,
+    ) -> Result<F, TranscriptError> {
+        self.squeeze(label)?;
+        self.sponge.absorb(&challenge);
+        Ok(challenge)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{pack_bytes, poseidon_config, PoseidonTranscript};
+    use crate::{Transcript, TranscriptError};
+    use ark_bls12_381::{Fr, G1Affine};
+    use ark_bn254::{Fr as BnFr, G1Affine as BnG1Affine};
+    use ark_crypto_primitives::sponge::{
+        poseidon::PoseidonSponge, CryptographicSponge, FieldBasedCryptographicSponge,
+    };
+    use ark_ec::AffineRepr;
+    use ark_ff::{BigInteger, Field, PrimeField};
+
+    #[test]
+    fn test_empty_transcript_should_error() {
+        let mut transcript = PoseidonTranscript::<Fr>::new(b"hyperplonk");
+        assert!(transcript.get_and_append_challenge(b"alpha").is_err());
+    }
+
+    #[test]
+    fn test_pack_bytes() {
+        assert_eq!(pack_bytes::<Fr>(b""), vec![Fr::from(0u64)]);
+        assert_eq!(
+            pack_bytes::<Fr>(&[1, 2]),
+            vec![Fr::from(2u64), Fr::from(0x0201u64)]
+        );
+        // 31 bytes per chunk
+        assert_eq!(pack_bytes::<Fr>(&[0u8; 32]).len(), 3);
+    }
+
+    /// The limbs of the coordinates of a BLS12-381 G1 point.
+    fn limbs(point: &G1Affine) -> Vec<Fr> {
+        let (x, y) = point.xy().unwrap();
+        [x, y]
+            .iter()
+            .flat_map(|c| {
+                let bytes = c.into_bigint().to_bytes_le();
+                bytes
+                    .chunks(16)
+                    .map(Fr::from_le_bytes_mod_order)
+                    .collect::<Vec<_>>()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_point_limbs() {
+        // a coordinate of 381 bits is three 128-bit limbs, least significant
+        // first
+        let g = G1Affine::generator();
+        let limbs = limbs(&g);
+        assert_eq!(limbs.len(), 6);
+        let two_128 = Fr::from(2u64).pow([128]);
+        let x = limbs[0] + limbs[1] * two_128 + limbs[2] * two_128 * two_128;
+        assert_eq!(
+            x,
+            Fr::from_le_bytes_mod_order(&g.x().unwrap().into_bigint().to_bytes_le())
+        );
+    }
+
+    #[test]
+    fn test_matches_sponge() -> Result<(), TranscriptError> {
+        // the transcript is exactly the documented sequence of sponge
+        // operations, which is what a circuit has to reproduce
+        let g = G1Affine::generator();
+        let points = vec![g, G1Affine::zero()];
+        let mut transcript = PoseidonTranscript::<Fr>::new(b"hyperplonk");
+        transcript.append_field_element(b"x", &Fr::from(42u64))?;
+        transcript.append_serializable_element(b"w", &g)?;
+        transcript.append_serializable_element(b"ws", &points)?;
+        let alpha = transcript.get_and_append_challenge(b"alpha")?;
+        transcript.get_and_append_fixed_challenge(b"fixed", Fr::from(7u64))?;
+        let betas = transcript.get_and_append_challenge_vectors(b"beta", 2)?;
+
+        let mut sponge = PoseidonSponge::new(&poseidon_config::<Fr>());
+        sponge.absorb(&pack_bytes::<Fr>(b"hyperplonk"));
+        sponge.absorb(&[pack_bytes::<Fr>(b"x"), vec![Fr::from(42u64)]].concat());
+        sponge.absorb(&[pack_bytes::<Fr>(b"w"), limbs(&g)].concat());
+        // a vector is prefixed by its length, and infinity is (0, 0)
+        sponge.absorb(
+            &[
+                pack_bytes::<Fr>(b"ws"),
+                vec![Fr::from(2u64)],
+                limbs(&g),
+                vec![Fr::from(0u64); 6],
+            ]
+            .concat(),
+        );
+        sponge.absorb(&pack_bytes::<Fr>(b"alpha"));
+        assert_eq!(alpha, sponge.squeeze_native_field_elements(1)[0]);
+        sponge.absorb(&pack_bytes::<Fr>(b"fixed"));
+        sponge.squeeze_native_field_elements(1);
+        sponge.absorb(&Fr::from(7u64));
+        for beta in betas {
+            sponge.absorb(&pack_bytes::<Fr>(b"beta"));
+            assert_eq!(beta, sponge.squeeze_native_field_elements(1)[0]);
+        }
+        Ok(())
+    }
+
+    fn challenge_hex<F: PrimeField>(challenge: F) -> String {
+        challenge
+            .into_bigint()
+            .to_bytes_be()
+            .iter()
+            .map(|b| format!("{:02x}", b))
+            .collect()
+    }
+
+    /// Known-answer vectors for a circuit port, with the parameters of
+    /// `poseidon_config`. Every challenge is printed as a 32-byte big-endian
+    /// integer.
+    #[test]
+    fn test_known_answers() -> Result<(), TranscriptError> {
+        // the first rate element of the permutation of the state [0, 1, 2]
+        let mut sponge = PoseidonSponge::new(&poseidon_config::<Fr>());
+        sponge.absorb(&vec![Fr::from(1u64), Fr::from(2u64)]);
+        assert_eq!(
+            challenge_hex(sponge.squeeze_native_field_elements(1)[0]),
+            "51f3e312c95343a896cfd8945ea82ba956c1118ce9b9859b6ea56637b4b1ddc4"
+        );
+
+        let mut transcript = PoseidonTranscript::<Fr>::new(b"hyperplonk");
+        transcript.append_message(b"testing", b"initializing transcript for testing")?;
+        transcript.append_field_element(b"eval", &-Fr::from(1u64))?;
+        let alpha = transcript.get_and_append_challenge(b"alpha")?;
+        assert_eq!(
+            challenge_hex(alpha),
+            "25ba6eb8d55cc451aa9b8415f84673688cd0c7b22ec6036c23e3ca89f745295a"
+        );
+        transcript.append_serializable_element(b"w", &G1Affine::generator())?;
+        let beta = transcript.get_and_append_challenge(b"beta")?;
+        assert_eq!(
+            challenge_hex(beta),
+            "3707c7b20cf46ae14b56930ec5c445e0c22dc5a42996d5f57c530d08e60d6e6e"
+        );
+
+        // over BN254, whose G1 coordinates are two limbs each
+        let mut sponge = PoseidonSponge::new(&poseidon_config::<BnFr>());
+        sponge.absorb(&vec![BnFr::from(1u64), BnFr::from(2u64)]);
+        assert_eq!(
+            challenge_hex(sponge.squeeze_native_field_elements(1)[0]),
+            "0fca49b798923ab0239de1c9e7a4a9a2210312b6a2f616d18b5a87f9b628ae29"
+        );
+
+        let mut transcript = PoseidonTranscript::<BnFr>::new(b"hyperplonk");
+        transcript.append_message(b"testing", b"initializing transcript for testing")?;
+        transcript.append_field_element(b"eval", &-BnFr::from(1u64))?;
+        let alpha = transcript.get_and_append_challenge(b"alpha")?;
+        assert_eq!(
+            challenge_hex(alpha),
+            "25af2b2fcbb4759e6d3bbf87d83c0c73a9cb363453aed75e85b2c1c473062d31"
+        );
+        transcript.append_serializable_element(b"w", &BnG1Affine::generator())?;
+        let beta = transcript.get_and_append_challenge(b"beta")?;
+        assert_eq!(
+            challenge_hex(beta),
+            "13e0aca9741cf2d6b36a415ca0730e69580750dd3f4e9d1d0a2c7fe61d6edd94"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_domain_separation() -> Result<(), TranscriptError> {
+        let mut t1 = PoseidonTranscript::<Fr>::new(b"hyperplonk");
+        t1.append_message(b"ab", b"c")?;
+        let mut t2 = PoseidonTranscript::<Fr>::new(b"hyperplonk");
+        t2.append_message(b"a", b"bc")?;
+        assert_ne!(
+            t1.get_and_append_challenge(b"alpha")?,
+            t2.get_and_append_challenge(b"alpha")?
+        );
+
+        let mut t3 = PoseidonTranscript::<Fr>::new(b"hyperplonk");
+        t3.append_message(b"ab", b"c")?;
+        let mut t4 = t3.clone();
+        assert_ne!(
+            t3.get_and_append_challenge(b"alpha")?,
+            t4.get_and_append_challenge(b"beta")?
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,391 @@
+// Copyright (c) 2023 Espresso Systems (espressosys.com)
+// This file is part of the HyperPlonk library.
+
+// You should have received a copy of the MIT License
+// along with the HyperPlonk library. If not, see <https://mit-license.org/>.
+
+//! Transcript recording, to find where a prover and a verifier diverge.
+//!
+//! Instantiate the protocol with `RecordingTranscript<F, T>` in place of `T`,
+//! run the prover and the verifier each inside `record`, and compare the two
+//! logs with `diff`:
+//!
+//! ```ignore
+//! type Debug = PolyIOP<Fr, RecordingTranscript<Fr, IOPTranscript<Fr>>>;
+//! let (proof, prover_log) = record(|| Debug::prove(&pk, &pub_input, &witnesses));
+//! let (res, verifier_log) = record(|| Debug::verify(&vk, &pub_input, &proof?));
+//! if let Some(divergence) = diff(&prover_log, &verifier_log) {
+//!     println!("{}", divergence);
+//! }
+//! ```
+//!
+//! Outside of `record` a `RecordingTranscript` behaves exactly like `T`.
+
+use crate::{Encode, Transcript, TranscriptError};
+use ark_ff::PrimeField;
+use ark_serialize::CanonicalSerialize;
+use std::{cell::RefCell, fmt, marker::PhantomData, panic::Location};
+
+thread_local! {
+    static LOG: RefCell<Option<Vec<TranscriptEntry>>> = const { RefCell::new(None) };
+}
+
+/// The kind of a transcript operation.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TranscriptOp {
+    /// A new transcript is created.
+    New,
+    /// A message is appended.
+    Message,
+    /// A field element is appended.
+    FieldElement,
+    /// A serializable element is appended.
+    SerializableElement,
+    /// A challenge is derived.
+    Challenge,
+    /// A fixed challenge is appended.
+    FixedChallenge,
+}
+
+/// A single recorded transcript operation.
+#[derive(Clone, Debug)]
+pub struct TranscriptEntry {
+    /// The operation.
+    pub op: TranscriptOp,
+    /// The label of the operation.
+    pub label: &'static [u8],
+    /// The appended bytes, or the compressed serialization of the challenge.
+    pub bytes: Vec<u8>,
+    /// The call site of the operation.
+    pub location: &'static Location<'static>,
+}
+
+impl TranscriptEntry {
+    /// Whether two entries are the same operation on the same data; the call
+    /// sites are not compared.
+    pub fn matches(&self, other: &Self) -> bool {
+        self.op == other.op && self.label == other.label && self.bytes == other.bytes
+    }
+}
+
+impl fmt::Display for TranscriptEntry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:?} b\"{}\" = 0x{} at {}",
+            self.op,
+            self.label.escape_ascii(),
+            self.bytes
+                .iter()
+                .map(|b| format!("{:02x}", b))
+                .collect::<String>(),
+            self.location
+        )
+    }
+}
+
+/// The operations recorded by `record`, in order.
+#[derive(Clone, Debug, Default)]
+pub struct TranscriptLog(pub Vec<TranscriptEntry>);
+
+/// The first point where two transcript logs differ.
+#[derive(Clone, Debug)]
+pub struct Divergence {
+    /// The index of the first differing entry.
+    pub index: usize,
+    /// The entry of the first log, or `None` if it ended first.
+    pub prover: Option<TranscriptEntry>,
+    /// The entry of the second log, or `None` if it ended first.
+    pub verifier: Option<TranscriptEntry>,
+}
+
+impl Divergence {
+    /// The label at which the transcripts diverged, taken from the first log
+    /// if it has an entry there.
+    pub fn label(&self) -> Option<&'static [u8]> {
+        self.prover
+            .as_ref()
+            .or(self.verifier.as_ref())
+            .map(|e| e.label)
+    }
+}
+
+impl fmt::Display for Divergence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "transcripts diverge at entry {}", self.index)?;
+        match &self.prover {
+            Some(e) => writeln!(f, "  prover:   {}", e)?,
+            None => writeln!(f, "  prover:   <end of transcript>")?,
+        }
+        match &self.verifier {
+            Some(e) => write!(f, "  verifier: {}", e),
+            None => write!(f, "  verifier: <end of transcript>"),
+        }
+    }
+}
+
+/// Run `f` and return its output together with the log of every operation on
+/// a `RecordingTranscript` made by `f` on the current thread.
+///
+/// The log starts empty, and the log of an enclosing `record` is restored
+/// when `f` returns or panics, so that a failed recording does not leak into
+/// later ones on the same thread.
+pub fn record<R>(f: impl FnOnce() -> R) -> (R, TranscriptLog) {
+    let guard = RecordGuard {
+        outer: LOG.with(|log| log.borrow_mut().replace(vec![])),
+    };
+    let res = f();
+    let entries = LOG.with(|log| log.borrow_mut().take());
+    drop(guard);
+    (res, TranscriptLog(entries.unwrap_or_default()))
+}
+
+/// Restores the log of the enclosing `record`, also when `f` panics.
+struct RecordGuard {
+    outer: Option<Vec<TranscriptEntry>>,
+}
+
+impl Drop for RecordGuard {
+    fn drop(&mut self) {
+        let outer = self.outer.take();
+        let _ = LOG.try_with(|log| *log.borrow_mut() = outer);
+    }
+}
+
+/// Align a prover log against a verifier log and return the first entry at
+/// which they differ, or `None` if they are identical.
+pub fn diff(prover: &TranscriptLog, verifier: &TranscriptLog) -> Option<Divergence> {
+    let len = prover.0.len().max(verifier.0.len());
+    (0..len).find_map(|index| {
+        let p = prover.0.get(index);
+        let v = verifier.0.get(index);
+        match (p, v) {
+            (Some(p), Some(v)) if p.matches(v) => None,
+            _ => Some(Divergence {
+                index,
+                prover: p.cloned(),
+                verifier: v.cloned(),
+            }),
+        }
+    })
+}
+
+/// Append an entry to the current log, if any.
+fn log(
+    op: TranscriptOp,
+    label: &'static [u8],
+    bytes: impl FnOnce() -> Vec<u8>,
+    location: &'static Location<'static>,
+) {
+    LOG.with(|log| {
+        if let Some(entries) = log.borrow_mut().as_mut() {
+            entries.push(TranscriptEntry {
+                op,
+                label,
+                bytes: bytes(),
+                location,
+            })
+        }
+    })
+}
+
+/// Serialize an element for the log.
+fn to_log_bytes<S: CanonicalSerialize>(elem: &S) -> Vec<u8> {
+    let mut buf = vec![];
+    // serialization into a vector cannot fail
+    let _ = elem.serialize_compressed(&mut buf);
+    buf
+}
+
+/// A transcript that forwards every operation to `T`, and logs it while
+/// inside `record`.
+#[derive(Clone)]
+pub struct RecordingTranscript<F: PrimeField, T: Transcript<F>> {
+    transcript: T,
+    #[doc(hidden)]
+    phantom: PhantomData<F>,
+}
+
+impl<F: PrimeField, T: Transcript<F>> Transcript<F> for RecordingTranscript<F, T> {
+    #[track_caller]
+    fn new(label: &'static [u8]) -> Self {
+        log(TranscriptOp::New, label, Vec::new, Location::caller());
+        Self {
+            transcript: T::new(label),
+            phantom: PhantomData,
+        }
+    }
+
+    #[track_caller]
+    fn append_message(&mut self, label: &'static [u8], msg: &[u8]) -> Result<(), TranscriptError> {
+        log(
+            TranscriptOp::Message,
+            label,
+            || msg.to_vec(),
+            Location::caller(),
+        );
+        self.transcript.append_message(label, msg)
+    }
+
+    #[track_caller]
+    fn append_field_element(
+        &mut self,
+        label: &'static [u8],
+        field_elem: &F,
+    ) -> Result<(), TranscriptError> {
+        log(
+            TranscriptOp::FieldElement,
+            label,
+            || to_log_bytes(field_elem),
+            Location::caller(),
+        );
+        self.transcript.append_field_element(label, field_elem)
+    }
+
+    #[track_caller]
+    fn append_serializable_element<M, S: CanonicalSerialize + Encode<M>>(
+        &mut self,
+        label: &'static [u8],
+        group_elem: &S,
+    ) -> Result<(), TranscriptError> {
+        log(
+            TranscriptOp::SerializableElement,
+            label,
+            || to_log_bytes(group_elem),
+            Location::caller(),
+        );
+        self.transcript
+            .append_serializable_element(label, group_elem)
+    }
+
+    #[track_caller]
+    fn get_and_append_challenge(&mut self, label: &'static [u8]) -> Result<F, TranscriptError> {
+        let location = Location::caller();
+        let challenge = self.transcript.get_and_append_challenge(label)?;
+        log(
+            TranscriptOp::Challenge,
+            label,
+            || to_log_bytes(&challenge),
+            location,
+        );
+        Ok(challenge)
+    }
+
+    #[track_caller]
+    fn get_and_append_fixed_challenge(
+        &mut self,
+        label: &'static [u8],
+        challenge: F,
+    ) -> Result<F, TranscriptError> {
+        let location = Location::caller();
+        let challenge = self
+            .transcript
+            .get_and_append_fixed_challenge(label, challenge)?;
+        log(
+            TranscriptOp::FixedChallenge,
+            label,
+            || to_log_bytes(&challenge),
+            location,
+        );
+        Ok(challenge)
+    }
+
+    #[track_caller]
+    fn get_and_append_challenge_vectors(
+        &mut self,
+        label: &'static [u8],
+        len: usize,
+    ) -> Result<Vec<F>, TranscriptError> {
+        let location = Location::caller();
+        let challenges = self
+            .transcript
+            .get_and_append_challenge_vectors(label, len)?;
+        for challenge in challenges.iter() {
+            log(
+                TranscriptOp::Challenge,
+                label,
+                || to_log_bytes(challenge),
+                location,
+            );
+        }
+        Ok(challenges)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{diff, record, RecordingTranscript, TranscriptOp, LOG};
+    use crate::{IOPTranscript, Transcript, TranscriptError};
+    use ark_bls12_381::Fr;
+
+    type Recording = RecordingTranscript<Fr, IOPTranscript<Fr>>;
+
+    fn run(x: u64) -> Result<Fr, TranscriptError> {
+        let mut transcript = Recording::new(b"hyperplonk");
+        transcript.append_message(b"testing", b"initializing transcript for testing")?;
+        transcript.get_and_append_challenge(b"alpha")?;
+        transcript.append_field_element(b"x", &Fr::from(x))?;
+        transcript.get_and_append_challenge(b"beta")
+    }
+
+    #[test]
+    fn test_same_as_inner_transcript() -> Result<(), TranscriptError> {
+        let (beta, _log) = record(|| run(1));
+
+        let mut transcript = IOPTranscript::<Fr>::new(b"hyperplonk");
+        transcript.append_message(b"testing", b"initializing transcript for testing")?;
+        transcript.get_and_append_challenge(b"alpha")?;
+        transcript.append_field_element(b"x", &Fr::from(1u64))?;
+        assert_eq!(beta?, transcript.get_and_append_challenge(b"beta")?);
+
+        // nothing is recorded outside of `record`
+        assert_eq!(run(1)?, record(|| run(1)).0?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_diff() -> Result<(), TranscriptError> {
+        let (_, prover_log) = record(|| run(1));
+        let (_, verifier_log) = record(|| run(1));
+        assert_eq!(prover_log.0.len(), 5);
+        assert_eq!(prover_log.0[2].op, TranscriptOp::Challenge);
+        assert!(diff(&prover_log, &verifier_log).is_none());
+
+        let (_, verifier_log) = record(|| run(2));
+        let divergence = diff(&prover_log, &verifier_log).unwrap();
+        assert_eq!(divergence.index, 3);
+        assert_eq!(divergence.label(), Some(&b"x"[..]));
+        assert!(divergence.to_string().contains("b\"x\""));
+        assert!(divergence.to_string().contains("recording.rs"));
+
+        // a truncated log diverges where it ends
+        let mut truncated = prover_log.clone();
+        truncated.0.pop();
+        let divergence = diff(&prover_log, &truncated).unwrap();
+        assert_eq!(divergence.index, 4);
+        assert!(divergence.verifier.is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn test_panic_does_not_leak() -> Result<(), TranscriptError> {
+        let res = std::panic::catch_unwind(|| {
+            record(|| {
+                let _ = run(1);
+                panic!("prover failed");
+            })
+        });
+        assert!(res.is_err());
+        assert!(LOG.with(|log| log.borrow().is_none()));
+
+        // an enclosing recording keeps its own entries
+        let ((), outer_log) = record(|| {
+            let _ = run(1);
+            let res = std::panic::catch_unwind(|| record(|| -> () { panic!("prover failed") }));
+            assert!(res.is_err());
+            let _ = run(2);
+        });
+        assert_eq!(outer_log.0.len(), 10);
+        Ok(())
+    }
+}